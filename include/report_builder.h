@@ -0,0 +1,33 @@
+/*
+ * C interface of report-builder, built with the `ffi` feature.
+ *
+ * Reports are described by a JSON array of commands, see the `commands` module documentation.
+ * All strings are NUL-terminated UTF-8.
+ */
+#ifndef REPORT_BUILDER_H
+#define REPORT_BUILDER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Builds a report from a JSON command stream and renders it as an HTML document.
+ * Returns the document, to be released with report_builder_free_string, or NULL on error. */
+char *report_builder_render(const char *commands);
+
+/* Builds a report from a JSON command stream and saves it to an HTML file.
+ * Returns 0 on success, or -1 on error. */
+int report_builder_save(const char *commands, const char *filename);
+
+/* The message of the last error raised on the calling thread, or NULL if there was none.
+ * The string is owned by the library and valid until the next call on the same thread. */
+const char *report_builder_last_error(void);
+
+/* Releases a string returned by report_builder_render. */
+void report_builder_free_string(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* REPORT_BUILDER_H */
@@ -0,0 +1,162 @@
+//! Derive macros for [report-builder](https://docs.rs/report-builder) tables and sections.
+//!
+//! Use them through the `derive` feature of `report-builder`, which re-exports them next to the
+//! traits they implement:
+//!
+//! ```rust,ignore
+//! use report_builder::table::{ReportSectionData, ReportTable, Table};
+//!
+//! #[derive(ReportTable, ReportSectionData)]
+//! #[report(title = "Run summary")]
+//! struct Run {
+//!     #[report(rename = "Run", link = "runs/{}.html")]
+//!     name: String,
+//!     #[report(format = "{:.1}%")]
+//!     identified: f64,
+//!     #[report(skip)]
+//!     raw: Vec<f64>,
+//! }
+//! ```
+//!
+//! Field attributes:
+//!
+//! - `rename = "Column"`: the column header (defaults to the field name)
+//! - `format = "{:.3}"`: a format string for the value (defaults to `"{}"`)
+//! - `link = "https://example.org/{}"`: a format string for a link target, given the value
+//! - `skip`: leave the field out
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// A field shown as a column, with its `#[report(...)]` attributes
+struct Column {
+    ident: syn::Ident,
+    header: String,
+    format: Option<LitStr>,
+    link: Option<LitStr>,
+}
+
+/// The `#[report(title = "...")]` attribute of a struct, if any
+fn struct_title(input: &DeriveInput) -> syn::Result<Option<LitStr>> {
+    let mut title = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("report")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                title = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `title = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(title)
+}
+
+/// The columns of a struct with named fields, in declaration order
+fn columns(input: &DeriveInput) -> syn::Result<Vec<Column>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "only structs can be shown as tables"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(&input.ident, "only structs with named fields can be shown as tables"));
+    };
+
+    let mut columns = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.clone().expect("named fields have identifiers");
+        let mut column = Column {
+            header: ident.to_string(),
+            ident,
+            format: None,
+            link: None,
+        };
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("report")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    column.header = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("format") {
+                    column.format = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("link") {
+                    column.link = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `format`, `link` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        if !skip {
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
+
+fn report_table(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let columns = columns(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let headers = columns.iter().map(|column| &column.header);
+    let cells = columns.iter().map(|column| {
+        let ident = &column.ident;
+        let text = match &column.format {
+            Some(format) => quote! { ::report_builder::__private::format!(#format, self.#ident) },
+            None => quote! { &self.#ident },
+        };
+        match &column.link {
+            Some(link) => quote! {
+                ::report_builder::table::link_cell(&::report_builder::__private::format!(#link, self.#ident), #text)
+            },
+            None => quote! { ::report_builder::table::text_cell(#text) },
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::report_builder::table::ReportTable for #name #ty_generics #where_clause {
+            fn headers() -> ::report_builder::__private::Vec<::report_builder::__private::String> {
+                ::report_builder::__private::vec![#(::report_builder::__private::String::from(#headers)),*]
+            }
+
+            fn cells(&self) -> ::report_builder::__private::Vec<::report_builder::__private::Markup> {
+                ::report_builder::__private::vec![#(#cells),*]
+            }
+        }
+    })
+}
+
+fn report_section_data(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let title = match struct_title(input)? {
+        Some(title) => title.value(),
+        None => name.to_string(),
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::report_builder::table::ReportSectionData for #name #ty_generics #where_clause {
+            fn title(&self) -> ::report_builder::__private::String {
+                ::report_builder::__private::String::from(#title)
+            }
+        }
+    })
+}
+
+/// Implements `report_builder::table::ReportTable`, making each field a column.
+#[proc_macro_derive(ReportTable, attributes(report))]
+pub fn derive_report_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    report_table(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Implements `report_builder::table::ReportSectionData`, titled by `#[report(title = "...")]`.
+/// The struct must also implement (or derive) `ReportTable`.
+#[proc_macro_derive(ReportSectionData, attributes(report))]
+pub fn derive_report_section_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    report_section_data(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
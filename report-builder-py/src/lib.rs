@@ -0,0 +1,283 @@
+//! Python bindings for [report-builder](https://docs.rs/report-builder).
+//!
+//! Exposes [`Report`], [`ReportSection`], [`Table`] and the plot helpers to Python, so Python
+//! pipelines produce exactly the same reports as the Rust tools. Build with
+//! [maturin](https://www.maturin.rs) (`maturin develop` or `maturin build --release`), then:
+//!
+//! ```python
+//! from report_builder import Report, ReportSection, Table, plot_roc
+//!
+//! report = Report("Redeem", "1.0", None, "Run QC")
+//! section = ReportSection("Scores")
+//! section.add_plot(plot_roc(scores, labels, "ROC"))
+//! section.add_table(Table(["Run", "Score"], [["run1", "0.9"]]))
+//! report.add_section(section)
+//! warnings = report.save_to_file("report.html")
+//! ```
+//!
+//! Invalid input raises `ValueError` (see [`ReportError`]) and failing to write a file raises
+//! `OSError`.
+
+use maud::PreEscaped;
+use plotly::Plot;
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use report_builder::table::Table;
+use report_builder::{plots, Report, ReportError, ReportSection};
+
+fn to_py_err(e: ReportError) -> PyErr {
+    match e {
+        ReportError::Io(e) => PyIOError::new_err(e.to_string()),
+        e => PyValueError::new_err(e.to_string()),
+    }
+}
+
+fn wrap_plot(result: Result<Plot, ReportError>) -> PyResult<PyPlot> {
+    result.map(|plot| PyPlot { plot }).map_err(to_py_err)
+}
+
+/// A Plotly figure, as returned by the plot helpers.
+///
+/// Plotly traces are not `Send`, so a plot stays on the Python thread that created it.
+#[pyclass(name = "Plot", unsendable)]
+struct PyPlot {
+    plot: Plot,
+}
+
+#[pymethods]
+impl PyPlot {
+    /// The figure as Plotly JSON
+    fn to_json(&self) -> String {
+        self.plot.to_json()
+    }
+}
+
+/// An interactive table, see [`Table`].
+#[pyclass(name = "Table")]
+struct PyTable {
+    table: Table,
+    columns: usize,
+}
+
+#[pymethods]
+impl PyTable {
+    #[new]
+    #[pyo3(signature = (headers, rows = Vec::new(), caption = None))]
+    fn new(headers: Vec<String>, rows: Vec<Vec<String>>, caption: Option<&str>) -> PyResult<Self> {
+        let columns = headers.len();
+        let mut table = Table::new().headers(headers);
+        if let Some(caption) = caption {
+            table = table.caption(caption);
+        }
+        let mut table = PyTable { table, columns };
+        for row in rows {
+            table.add_row(row)?;
+        }
+        Ok(table)
+    }
+
+    /// Adds a row, with one cell per header
+    fn add_row(&mut self, cells: Vec<String>) -> PyResult<()> {
+        if cells.len() != self.columns {
+            let message = format!("Expected one cell per header ({}), found {}", self.columns, cells.len());
+            return Err(PyValueError::new_err(message));
+        }
+        self.table = std::mem::take(&mut self.table).add_row(cells);
+        Ok(())
+    }
+
+    /// Sets the element ID of the table
+    fn set_id(&mut self, id: &str) {
+        self.table = std::mem::take(&mut self.table).id(id);
+    }
+
+    fn __len__(&self) -> usize {
+        self.table.len()
+    }
+}
+
+/// A section of a report, see [`ReportSection`].
+///
+/// Adding a section to a report (or to another section) moves it there, so it can only be added
+/// once.
+#[pyclass(name = "ReportSection")]
+struct PySection {
+    section: Option<ReportSection>,
+}
+
+impl PySection {
+    fn section_mut(&mut self) -> PyResult<&mut ReportSection> {
+        self.section
+            .as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("The section was already added to a report"))
+    }
+
+    fn take(&mut self) -> PyResult<ReportSection> {
+        self.section
+            .take()
+            .ok_or_else(|| PyRuntimeError::new_err("The section was already added to a report"))
+    }
+}
+
+#[pymethods]
+impl PySection {
+    #[new]
+    fn new(title: &str) -> Self {
+        PySection { section: Some(ReportSection::new(title)) }
+    }
+
+    /// Adds a block of HTML, inserted as is
+    fn add_content(&mut self, html: String) -> PyResult<()> {
+        self.section_mut()?.add_content(PreEscaped(html));
+        Ok(())
+    }
+
+    /// Adds a plot, e.g. from one of the `plot_*` functions
+    fn add_plot(&mut self, plot: PyRef<'_, PyPlot>) -> PyResult<()> {
+        self.section_mut()?.add_plot(plot.plot.clone());
+        Ok(())
+    }
+
+    fn add_table(&mut self, table: PyRef<'_, PyTable>) -> PyResult<()> {
+        self.section_mut()?.add_table(table.table.clone());
+        Ok(())
+    }
+
+    fn add_subsection(&mut self, mut subsection: PyRefMut<'_, PySection>) -> PyResult<()> {
+        let subsection = subsection.take()?;
+        self.section_mut()?.add_subsection(subsection);
+        Ok(())
+    }
+}
+
+/// A report, see [`Report`].
+#[pyclass(name = "Report")]
+struct PyReport {
+    report: Report,
+}
+
+#[pymethods]
+impl PyReport {
+    #[new]
+    #[pyo3(signature = (software_name, version, software_logo, title, toc = false, dark_mode_toggle = false))]
+    fn new(software_name: &str, version: &str, software_logo: Option<&str>, title: &str, toc: bool, dark_mode_toggle: bool) -> Self {
+        let report = Report::new(software_name, version, software_logo, title)
+            .with_toc(toc)
+            .with_dark_mode_toggle(dark_mode_toggle);
+        PyReport { report }
+    }
+
+    fn add_section(&mut self, mut section: PyRefMut<'_, PySection>) -> PyResult<()> {
+        self.report.add_section(section.take()?);
+        Ok(())
+    }
+
+    /// The report as an HTML document
+    fn render_to_string(&self) -> String {
+        self.report.render_to_string()
+    }
+
+    fn __str__(&self) -> String {
+        self.report.render_to_string()
+    }
+
+    /// Saves the report to an HTML file, returning the build warnings as strings
+    fn save_to_file(&self, filename: &str) -> PyResult<Vec<String>> {
+        let warnings = self.report.save_to_file(filename).map_err(to_py_err)?;
+        Ok(warnings.iter().map(ToString::to_string).collect())
+    }
+
+    /// Saves the report to a self-contained HTML file, returning the build warnings as strings
+    fn save_to_file_offline(&self, filename: &str) -> PyResult<Vec<String>> {
+        let warnings = self.report.save_to_file_offline(filename).map_err(to_py_err)?;
+        Ok(warnings.iter().map(ToString::to_string).collect())
+    }
+}
+
+/// See [`plots::plot_score_histogram`]
+#[pyfunction]
+fn plot_score_histogram(scores: Vec<f64>, labels: Vec<i32>, title: &str, x_title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_score_histogram(&scores, &labels, title, x_title))
+}
+
+/// See [`plots::plot_pp`]
+#[pyfunction]
+fn plot_pp(scores: Vec<f64>, labels: Vec<i32>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_pp(&scores, &labels, title))
+}
+
+/// See [`plots::plot_roc`]
+#[pyfunction]
+fn plot_roc(scores: Vec<f64>, labels: Vec<i32>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_roc(&scores, &labels, title))
+}
+
+/// See [`plots::plot_precision_recall`]
+#[pyfunction]
+fn plot_precision_recall(scores: Vec<f64>, labels: Vec<i32>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_precision_recall(&scores, &labels, title))
+}
+
+/// See [`plots::plot_boxplot`]
+#[pyfunction]
+fn plot_boxplot(scores: Vec<Vec<f64>>, filenames: Vec<String>, title: &str, x_title: &str, y_title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_boxplot(scores, filenames, title, x_title, y_title))
+}
+
+/// See [`plots::plot_scatter`]
+#[pyfunction]
+fn plot_scatter(x: Vec<Vec<f64>>, y: Vec<Vec<f64>>, labels: Vec<String>, title: &str, x_title: &str, y_title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_scatter(x, y, labels, title, x_title, y_title))
+}
+
+/// See [`plots::plot_splom`]
+#[pyfunction]
+#[pyo3(signature = (columns, color_by, title))]
+fn plot_splom(columns: Vec<(String, Vec<f64>)>, color_by: Option<Vec<String>>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_splom(&columns, color_by.as_deref(), title))
+}
+
+/// See [`plots::plot_strip`]
+#[pyfunction]
+fn plot_strip(groups: Vec<(String, Vec<f64>)>, jitter: f64, overlay_box: bool, title: &str, x_title: &str, y_title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_strip(&groups, jitter, overlay_box, title, x_title, y_title))
+}
+
+/// See [`plots::plot_cdf_compare`]
+#[pyfunction]
+fn plot_cdf_compare(a: (String, Vec<f64>), b: (String, Vec<f64>), title: &str, x_title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_cdf_compare((a.0.as_str(), a.1.as_slice()), (b.0.as_str(), b.1.as_slice()), title, x_title))
+}
+
+/// See [`plots::plot_bland_altman`]
+#[pyfunction]
+fn plot_bland_altman(x: Vec<f64>, y: Vec<f64>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_bland_altman(&x, &y, title))
+}
+
+/// See [`plots::plot_ma`]
+#[pyfunction]
+fn plot_ma(x: Vec<f64>, y: Vec<f64>, title: &str) -> PyResult<PyPlot> {
+    wrap_plot(plots::plot_ma(&x, &y, title))
+}
+
+#[pymodule]
+#[pyo3(name = "report_builder")]
+fn report_builder_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyReport>()?;
+    m.add_class::<PySection>()?;
+    m.add_class::<PyTable>()?;
+    m.add_class::<PyPlot>()?;
+    m.add_function(wrap_pyfunction!(plot_score_histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_pp, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_roc, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_precision_recall, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_boxplot, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_scatter, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_splom, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_strip, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_cdf_compare, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_bland_altman, m)?)?;
+    m.add_function(wrap_pyfunction!(plot_ma, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,371 @@
+//! Server-side aggregation of large datasets into compact summaries for plotting.
+//!
+//! Embedding every point of a 100M-row dataset in a report is not practical. The routines here
+//! reduce the data in Rust (binning, per-group summaries, quantile sketches) so only the summaries
+//! are embedded. Each returns an [`Aggregated`] value carrying an [`Aggregation`] description, which
+//! [`ReportSection::add_aggregated_plot`](crate::ReportSection::add_aggregated_plot) renders as a
+//! note below the figure so readers know the plot does not show raw points.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "plots")]
+use crate::plots::BinnedHistogram;
+use crate::stats;
+use crate::ReportError;
+#[cfg(feature = "plots")]
+use crate::stats::BinRule;
+
+/// A description of the aggregation applied to the data behind a plot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Aggregation {
+    /// The points were counted into histogram bins.
+    Binned { points: usize, bins: usize },
+    /// The points were summarized (count, mean, quartiles, extremes) per group.
+    GroupSummary { points: usize, groups: usize },
+    /// The points were compressed into a quantile sketch of at most `centroids` centroids.
+    QuantileSketch { points: usize, centroids: usize },
+}
+
+impl Aggregation {
+    /// The number of raw points that were aggregated.
+    pub fn points(&self) -> usize {
+        match *self {
+            Aggregation::Binned { points, .. }
+            | Aggregation::GroupSummary { points, .. }
+            | Aggregation::QuantileSketch { points, .. } => points,
+        }
+    }
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregation::Binned { points, bins } => {
+                write!(f, "Aggregated {} points into {} histogram bins", points, bins)
+            }
+            Aggregation::GroupSummary { points, groups } => {
+                write!(f, "Summarized {} points into {} groups", points, groups)
+            }
+            Aggregation::QuantileSketch { points, centroids } => {
+                write!(f, "Approximated {} points with a quantile sketch of {} centroids", points, centroids)
+            }
+        }
+    }
+}
+
+/// Aggregated data, along with a description of how it was aggregated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregated<T> {
+    pub data: T,
+    pub aggregation: Aggregation,
+}
+
+/// Count values into a histogram, with bins chosen by the given rule.
+///
+/// Non-finite values are ignored.
+///
+/// # Arguments
+///
+/// * `name` - The name of the histogram trace
+/// * `values` - The values to bin
+/// * `weights` - Optional weights, one per value
+/// * `rule` - The rule choosing the bin width, see [`stats::bin_width`]
+///
+/// # Returns
+///
+/// The binned histogram, ready for [`plot_binned_histogram`](crate::plots::plot_binned_histogram),
+/// or an error if weights are given but not one per value
+#[cfg(feature = "plots")]
+pub fn bin(name: &str, values: &[f64], weights: Option<&[f64]>, rule: BinRule) -> Result<Aggregated<BinnedHistogram>, ReportError> {
+    let width = stats::bin_width(values, rule);
+    let sorted = stats::sorted_finite(values);
+    let (start, end) = match (sorted.first(), sorted.last()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => (0.0, 0.0),
+    };
+    let bins = (((end - start) / width).floor() as usize + 1).max(1);
+    let edges: Vec<f64> = (0..=bins).map(|i| start + i as f64 * width).collect();
+
+    let mut histogram = BinnedHistogram::empty(name, edges)?;
+    histogram.accumulate(values, weights)?;
+    Ok(Aggregated {
+        data: histogram,
+        aggregation: Aggregation::Binned { points: sorted.len(), bins },
+    })
+}
+
+/// Summary statistics of the values in a single group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupSummary {
+    pub group: String,
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+}
+
+/// Summarize values per group, e.g. per sample or per run.
+///
+/// Non-finite values are ignored, and groups are returned sorted by name.
+///
+/// # Arguments
+///
+/// * `groups` - The group of each value
+/// * `values` - The values to summarize
+///
+/// # Returns
+///
+/// One summary per group, ready for [`plot_group_summaries`](crate::plots::plot_group_summaries),
+/// or an error if there is not one group per value
+pub fn group_by<K: AsRef<str>>(groups: &[K], values: &[f64]) -> Result<Aggregated<Vec<GroupSummary>>, ReportError> {
+    ReportError::check_len("groups", values.len(), groups.len())?;
+
+    let mut grouped: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for (group, &value) in groups.iter().zip(values) {
+        if value.is_finite() {
+            grouped.entry(group.as_ref()).or_default().push(value);
+        }
+    }
+
+    let points = grouped.values().map(Vec::len).sum();
+    let summaries: Vec<GroupSummary> = grouped
+        .into_iter()
+        .map(|(group, mut values)| {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            GroupSummary {
+                group: group.to_string(),
+                count: values.len(),
+                mean: stats::mean(&values),
+                min: values[0],
+                q1: stats::quantile(&values, 0.25),
+                median: stats::quantile(&values, 0.5),
+                q3: stats::quantile(&values, 0.75),
+                max: values[values.len() - 1],
+            }
+        })
+        .collect();
+
+    Ok(Aggregated {
+        aggregation: Aggregation::GroupSummary { points, groups: summaries.len() },
+        data: summaries,
+    })
+}
+
+/// A mergeable streaming quantile sketch (a merging t-digest).
+///
+/// Values are buffered and periodically merged into a bounded set of weighted centroids, which are
+/// kept small near the tails so extreme quantiles stay accurate. Memory use is independent of the
+/// number of values added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantileSketch {
+    compression: usize,
+    /// `(mean, weight)` pairs, sorted by mean
+    centroids: Vec<(f64, f64)>,
+    buffer: Vec<f64>,
+    count: usize,
+    min: f64,
+    max: f64,
+}
+
+impl Default for QuantileSketch {
+    fn default() -> Self {
+        Self::empty(100)
+    }
+}
+
+impl QuantileSketch {
+    /// Create an empty sketch. Higher `compression` keeps more centroids and is more accurate.
+    ///
+    /// # Returns
+    ///
+    /// The sketch, or an error if `compression` is below 10
+    pub fn new(compression: usize) -> Result<Self, ReportError> {
+        if compression < 10 {
+            return Err(ReportError::InvalidArgument(format!("Compression must be at least 10, found {}", compression)));
+        }
+        Ok(Self::empty(compression))
+    }
+
+    fn empty(compression: usize) -> Self {
+        QuantileSketch {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Add a value to the sketch. Non-finite values are ignored.
+    pub fn add(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+        if self.buffer.len() >= self.compression * 5 {
+            self.flush();
+        }
+    }
+
+    /// Merge another sketch into this one, e.g. sketches computed over chunks in parallel.
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.centroids.extend_from_slice(&other.centroids);
+        self.flush();
+    }
+
+    /// The number of values added to the sketch.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The smallest value added, or NaN if the sketch is empty.
+    pub fn min(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.min }
+    }
+
+    /// The largest value added, or NaN if the sketch is empty.
+    pub fn max(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.max }
+    }
+
+    /// The approximate `q`-th quantile (0 to 1), or NaN if the sketch is empty.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        let mut sketch = self.clone();
+        sketch.flush();
+        let centroids = &sketch.centroids;
+        if centroids.len() == 1 {
+            return centroids[0].0;
+        }
+
+        // Interpolate between the centroid means, placed at the midpoint of their cumulative weight
+        let total = self.count as f64;
+        let target = q.clamp(0.0, 1.0) * total;
+        let mut cumulative = 0.0;
+        let mut previous = (self.min, 0.0);
+        for &(mean, weight) in centroids {
+            let position = cumulative + weight / 2.0;
+            if target < position {
+                let fraction = if position > previous.1 { (target - previous.1) / (position - previous.1) } else { 0.0 };
+                return previous.0 + (mean - previous.0) * fraction;
+            }
+            previous = (mean, position);
+            cumulative += weight;
+        }
+        let fraction = if total > previous.1 { (target - previous.1) / (total - previous.1) } else { 1.0 };
+        previous.0 + (self.max - previous.0) * fraction
+    }
+
+    /// The number of centroids the sketch currently holds.
+    pub fn centroids(&self) -> usize {
+        self.centroids.len() + self.buffer.len()
+    }
+
+    /// A description of the aggregation, for the note rendered below plots built from the sketch.
+    pub fn aggregation(&self) -> Aggregation {
+        Aggregation::QuantileSketch {
+            points: self.count,
+            centroids: self.centroids(),
+        }
+    }
+
+    /// Merge the buffered values into the centroids, compressing them with the t-digest k1 scale
+    fn flush(&mut self) {
+        if self.buffer.is_empty() && self.centroids.len() <= self.compression {
+            return;
+        }
+        let mut all: Vec<(f64, f64)> = self.buffer.drain(..).map(|v| (v, 1.0)).collect();
+        all.append(&mut self.centroids);
+        all.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let total: f64 = all.iter().map(|c| c.1).sum();
+        let mut merged: Vec<(f64, f64)> = Vec::with_capacity(self.compression);
+        let mut cumulative = 0.0;
+        for (mean, weight) in all {
+            if let Some(last) = merged.last_mut() {
+                let q = (cumulative + (last.1 + weight) / 2.0) / total;
+                let limit = 4.0 * total * q * (1.0 - q) / self.compression as f64;
+                if last.1 + weight <= limit.max(1.0) {
+                    last.0 += (mean - last.0) * weight / (last.1 + weight);
+                    last.1 += weight;
+                    continue;
+                }
+                cumulative += last.1;
+            }
+            merged.push((mean, weight));
+        }
+        self.centroids = merged;
+    }
+}
+
+impl Extend<f64> for QuantileSketch {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.add(value);
+        }
+    }
+}
+
+impl FromIterator<f64> for QuantileSketch {
+    fn from_iter<I: IntoIterator<Item = f64>>(values: I) -> Self {
+        let mut sketch = QuantileSketch::default();
+        sketch.extend(values);
+        sketch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bin_and_group_by() {
+        let values: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        #[cfg(feature = "plots")]
+        {
+            let binned = bin("values", &values, None, BinRule::Width(10.0)).unwrap();
+            assert_eq!(binned.aggregation, Aggregation::Binned { points: 100, bins: 10 });
+            assert!(binned.data.counts.iter().all(|&c| c == 10.0));
+        }
+
+        let groups: Vec<&str> = values.iter().map(|&v| if v < 50.0 { "a" } else { "b" }).collect();
+        let summaries = group_by(&groups, &values).unwrap();
+        assert!(group_by(&groups[1..], &values).is_err());
+        assert_eq!(summaries.aggregation.to_string(), "Summarized 100 points into 2 groups");
+        assert_eq!(summaries.data[1].group, "b");
+        assert_eq!(summaries.data[1].median, 74.5);
+        assert_eq!(summaries.data[1].max, 99.0);
+    }
+
+    #[test]
+    fn test_quantile_sketch() {
+        let sketch: QuantileSketch = (0..100_000).map(|i| ((i * 7919) % 100_000) as f64).collect();
+        assert_eq!(sketch.count(), 100_000);
+        assert!(sketch.centroids() < 1_000);
+        for q in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            assert!((sketch.quantile(q) - q * 100_000.0).abs() < 500.0, "q = {}", q);
+        }
+
+        let mut halves: QuantileSketch = (0..50_000).map(|i| i as f64).collect();
+        halves.merge(&(50_000..100_000).map(|i| i as f64).collect());
+        assert!((halves.quantile(0.5) - 50_000.0).abs() < 500.0);
+        assert_eq!(halves.max(), 99_999.0);
+        assert!(QuantileSketch::new(5).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Unique anchors of the sections of a report.
+//!
+//! A section's anchor is its slugified title, nested below its parent's anchor, e.g.
+//! `quality-control--ms1`. A section whose title has no letters or digits is named by its position
+//! instead (`section-3`), and a section whose anchor is already taken gets a numeric suffix (`qc`,
+//! `qc-2`), so every section can be linked to and its element IDs are prefixed uniquely.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{slugify, ReportSection};
+
+/// The anchors of a section and its subsections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SectionAnchors {
+    /// The unique anchor of the section
+    pub(crate) anchor: String,
+    /// The anchor [`section_ref`](crate::numbering::section_ref) links the section by, made of
+    /// the titles of the section and its ancestors
+    pub(crate) path: String,
+    pub(crate) subsections: Vec<SectionAnchors>,
+}
+
+/// The anchors handed out so far in a report
+#[derive(Debug, Default)]
+pub(crate) struct Anchors {
+    used: BTreeSet<String>,
+}
+
+impl Anchors {
+    /// Hand out the anchors of a top-level section and its subsections, `position` being the
+    /// 1-based position of the section in the report
+    pub(crate) fn section(&mut self, section: &ReportSection, position: usize) -> SectionAnchors {
+        self.nested(section, position, "", "")
+    }
+
+    /// Hand out the anchors of a section below the given parent anchor and path, or at the top
+    /// level if they are empty
+    fn nested(&mut self, section: &ReportSection, position: usize, parent_anchor: &str, parent_path: &str) -> SectionAnchors {
+        let slug = slugify(&section.title);
+        let name = match slug.is_empty() {
+            true => format!("section-{}", position),
+            false => slug.clone(),
+        };
+        let (anchor, path) = match parent_anchor.is_empty() {
+            true => (name, slug),
+            false => (format!("{}--{}", parent_anchor, name), format!("{}--{}", parent_path, slug)),
+        };
+        let anchor = self.unique(anchor);
+        let subsections = section
+            .subsections
+            .iter()
+            .enumerate()
+            .map(|(i, subsection)| self.nested(subsection, i + 1, &anchor, &path))
+            .collect();
+        SectionAnchors { anchor, path, subsections }
+    }
+
+    /// The anchor, suffixed with the first free number from 2 if it is already taken
+    fn unique(&mut self, anchor: String) -> String {
+        if self.used.insert(anchor.clone()) {
+            return anchor;
+        }
+        (2..)
+            .map(|n| format!("{}-{}", anchor, n))
+            .find(|candidate| self.used.insert(candidate.clone()))
+            .expect("a free suffix")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_anchors() {
+        let mut qc = ReportSection::new("QC");
+        qc.add_subsection(ReportSection::new("MS1"));
+        let mut anchors = Anchors::default();
+        let first = anchors.section(&qc, 1);
+        let second = anchors.section(&qc, 2);
+        let unnamed = anchors.section(&ReportSection::new("???"), 3);
+        let suffixed = anchors.section(&ReportSection::new("QC 2"), 4);
+
+        assert_eq!((first.anchor.as_str(), first.subsections[0].anchor.as_str()), ("qc", "qc--ms1"));
+        assert_eq!((second.anchor.as_str(), second.subsections[0].anchor.as_str()), ("qc-2", "qc-2--ms1"));
+        assert_eq!(second.subsections[0].path, "qc--ms1");
+        assert_eq!((unnamed.anchor.as_str(), unnamed.path.as_str()), ("section-3", ""));
+        assert_eq!(suffixed.anchor, "qc-2-2");
+    }
+}
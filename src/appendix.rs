@@ -0,0 +1,202 @@
+//! Appendices and supplementary material.
+//!
+//! Sections created with [`ReportSection::appendix`] are rendered after the main content and
+//! lettered instead of numbered (Appendix A, B, ...), optionally collapsed until the reader opens
+//! them. A [`SupplementaryFiles`] block lists files shipped alongside the report, with a link to
+//! each and its checksum so readers can verify their copy:
+//!
+//! ```rust,ignore
+//! use report_builder::appendix::SupplementaryFiles;
+//!
+//! let mut appendix = ReportSection::appendix("Supplementary material", true);
+//! appendix.add(SupplementaryFiles::new().add_path("results/psms.tsv", "All PSMs before filtering")?);
+//! report.add_section(appendix);
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+use crate::components::Component;
+use crate::ReportSection;
+
+/// Whether a section belongs to the main content or to the appendices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SectionKind {
+    /// A section of the main content
+    #[default]
+    Main,
+    /// An appendix, rendered after the main content and collapsed until opened if `collapsed`
+    Appendix { collapsed: bool },
+}
+
+impl ReportSection {
+    /// Creates an appendix with the given title, rendered after the main sections with a letter
+    /// (Appendix A, B, ...) instead of a number.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the appendix.
+    /// * `collapsed` - Whether the appendix is collapsed until the reader opens it.
+    pub fn appendix(title: &str, collapsed: bool) -> Self {
+        let mut section = ReportSection::new(title);
+        section.kind = SectionKind::Appendix { collapsed };
+        section
+    }
+
+    /// Whether the section is an appendix.
+    pub fn is_appendix(&self) -> bool {
+        matches!(self.kind, SectionKind::Appendix { .. })
+    }
+}
+
+/// The letter of the appendix at the given 0-based position: A, B, ..., Z, AA, AB, ...
+pub(crate) fn appendix_letter(position: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = position + 1;
+    while n > 0 {
+        letters.push((b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// A file shipped alongside the report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplementaryFile {
+    /// The link to the file, usually a path relative to the report
+    pub href: String,
+    pub description: String,
+    /// The size of the file in bytes, if known
+    pub size: Option<u64>,
+    /// The hex-encoded SHA-256 checksum of the file, if known
+    pub sha256: Option<String>,
+}
+
+/// A list of supplementary files, rendered as a table linking each file with its size and
+/// checksum.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SupplementaryFiles {
+    files: Vec<SupplementaryFile>,
+}
+
+impl SupplementaryFiles {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file from already known values.
+    ///
+    /// # Arguments
+    ///
+    /// * `href` - The link to the file, e.g. a path relative to the report or a URL.
+    /// * `description` - What the file contains.
+    /// * `sha256` - The hex-encoded SHA-256 checksum of the file, if known.
+    pub fn add_file(mut self, href: &str, description: &str, sha256: Option<&str>) -> Self {
+        self.files.push(SupplementaryFile {
+            href: href.to_string(),
+            description: description.to_string(),
+            size: None,
+            sha256: sha256.map(|s| s.to_string()),
+        });
+        self
+    }
+
+    /// Adds a file on disk, linked by its path and with its size and SHA-256 checksum computed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, as linked from the report (usually relative to it).
+    /// * `description` - What the file contains.
+    ///
+    /// # Returns
+    ///
+    /// The list, or an IO error if the file cannot be read.
+    #[cfg(feature = "data-sources")]
+    pub fn add_path<P: AsRef<std::path::Path>>(mut self, path: P, description: &str) -> std::io::Result<Self> {
+        let fingerprint = crate::lineage::DataFingerprint::from_path(path.as_ref())?;
+        self.files.push(SupplementaryFile {
+            href: fingerprint.path,
+            description: description.to_string(),
+            size: Some(std::fs::metadata(path.as_ref())?.len()),
+            sha256: Some(fingerprint.sha256),
+        });
+        Ok(self)
+    }
+
+    /// The files, in the order they were added.
+    pub fn files(&self) -> &[SupplementaryFile] {
+        &self.files
+    }
+}
+
+/// Format a size in bytes with a binary unit, e.g. "1.5 MiB"
+fn format_size(bytes: u64) -> String {
+    let units = ["bytes", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} bytes", bytes)
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+impl Component for SupplementaryFiles {
+    fn render(&self) -> Markup {
+        html! {
+            table class="key-value supplementary-files" {
+                thead { tr { th { "File" } th { "Description" } th { "Size" } th { "SHA-256" } } }
+                tbody {
+                    @for file in &self.files {
+                        tr {
+                            th scope="row" { a href=(file.href) download { (file.href.rsplit(['/', '\\']).next().unwrap_or_default()) } }
+                            td { (file.description) }
+                            td { (file.size.map(format_size).unwrap_or_default()) }
+                            td { @if let Some(sha256) = &file.sha256 { code { (sha256) } } }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appendix_letter() {
+        let letters: Vec<String> = [0, 1, 25, 26, 27, 701, 702].into_iter().map(appendix_letter).collect();
+        assert_eq!(letters, vec!["A", "B", "Z", "AA", "AB", "ZZ", "AAA"]);
+    }
+
+    #[test]
+    fn test_supplementary_files() {
+        let files = SupplementaryFiles::new().add_file("data/psms.tsv", "All PSMs", Some("ab12"));
+        let html = files.render().into_string();
+        assert!(html.contains(r#"<th scope="row"><a href="data/psms.tsv" download>psms.tsv</a></th><td>All PSMs</td><td></td><td><code>ab12</code></td>"#));
+        assert_eq!(format_size(512), "512 bytes");
+        assert_eq!(format_size(1536 * 1024), "1.5 MiB");
+    }
+
+    #[cfg(feature = "data-sources")]
+    #[test]
+    fn test_supplementary_path() {
+        let path = std::env::temp_dir().join(format!("report-builder-supplementary-{}.txt", std::process::id()));
+        std::fs::write(&path, "abc").unwrap();
+        let files = SupplementaryFiles::new().add_path(&path, "Test").unwrap();
+        assert_eq!(files.files()[0].size, Some(3));
+        assert_eq!(files.files()[0].sha256.as_deref(), Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
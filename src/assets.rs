@@ -0,0 +1,283 @@
+//! Static JavaScript and CSS assets required by individual components, and the third-party
+//! libraries every report loads.
+//!
+//! Sections record the assets their components need, and the report includes each required asset
+//! exactly once in its `<head>`. Third-party [`Library`]s are referenced from CDNs by default, or
+//! embedded into the HTML with [`AssetMode::Inline`] so reports work without internet access.
+
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+use maud::{html, Markup, PreEscaped};
+use serde::{Deserialize, Serialize};
+
+/// A bundle of JavaScript and CSS needed by a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Asset {
+    /// Before/after image comparison slider.
+    ImageCompare,
+    /// Carousel of figures drawn one at a time.
+    Carousel,
+    /// CSV export of the data behind a plot's traces.
+    PlotData,
+    /// Drawing of lazy plots when they first become visible.
+    LazyPlots,
+    /// Client-side filtering of plots and tables by a threshold slider.
+    ThresholdSlider,
+    /// Report-level checkboxes showing or hiding groups in every plot and table.
+    GroupToggles,
+    /// Report-wide search box filtering every table and highlighting matching text.
+    ReportSearch,
+}
+
+impl Asset {
+    /// All assets.
+    pub const ALL: [Asset; 7] = [
+        Asset::ImageCompare,
+        Asset::Carousel,
+        Asset::PlotData,
+        Asset::LazyPlots,
+        Asset::ThresholdSlider,
+        Asset::GroupToggles,
+        Asset::ReportSearch,
+    ];
+
+    /// The JavaScript source of the asset.
+    pub fn script(&self) -> &'static str {
+        match self {
+            Asset::ImageCompare => include_str!("assets/image_compare.js"),
+            Asset::Carousel => include_str!("assets/carousel.js"),
+            Asset::PlotData => include_str!("assets/plot_data.js"),
+            Asset::LazyPlots => include_str!("assets/lazy_plots.js"),
+            Asset::ThresholdSlider => include_str!("assets/threshold_slider.js"),
+            Asset::GroupToggles => include_str!("assets/group_toggles.js"),
+            Asset::ReportSearch => include_str!("assets/report_search.js"),
+        }
+    }
+
+    /// The CSS source of the asset.
+    pub fn style(&self) -> &'static str {
+        match self {
+            Asset::ImageCompare => include_str!("assets/image_compare.css"),
+            Asset::Carousel => include_str!("assets/carousel.css"),
+            Asset::PlotData => include_str!("assets/plot_data.css"),
+            Asset::LazyPlots => include_str!("assets/lazy_plots.css"),
+            Asset::ThresholdSlider => include_str!("assets/threshold_slider.css"),
+            Asset::GroupToggles => include_str!("assets/group_toggles.css"),
+            Asset::ReportSearch => include_str!("assets/report_search.css"),
+        }
+    }
+
+    /// Render the asset as inline `<style>` and `<script>` elements.
+    pub(crate) fn render(&self) -> Markup {
+        html! {
+            style { (PreEscaped(self.style())) }
+            script { (PreEscaped(self.script())) }
+        }
+    }
+}
+
+/// A third-party JavaScript or CSS library loaded by every report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Library {
+    Plotly,
+    JQuery,
+    DataTables,
+    DataTablesStyle,
+    ColResize,
+    ColResizeStyle,
+    FileSaver,
+}
+
+impl Library {
+    /// All libraries, in the order they are loaded.
+    pub const ALL: [Library; 7] = [
+        Library::Plotly,
+        Library::JQuery,
+        Library::DataTables,
+        Library::DataTablesStyle,
+        Library::ColResize,
+        Library::ColResizeStyle,
+        Library::FileSaver,
+    ];
+
+    /// The CDN URL the library is loaded from.
+    pub fn url(&self) -> &'static str {
+        match self {
+            Library::Plotly => "https://cdn.plot.ly/plotly-latest.min.js",
+            Library::JQuery => "https://cdnjs.cloudflare.com/ajax/libs/jquery/3.6.4/jquery.min.js",
+            Library::DataTables => "https://cdn.datatables.net/1.13.4/js/jquery.dataTables.min.js",
+            Library::DataTablesStyle => "https://cdn.datatables.net/1.13.4/css/jquery.dataTables.min.css",
+            Library::ColResize => "https://cdn.datatables.net/colresize/1.0.0/dataTables.colResize.min.js",
+            Library::ColResizeStyle => "https://cdn.datatables.net/colResize/1.0.0/css/colResize.dataTables.min.css",
+            Library::FileSaver => "https://cdnjs.cloudflare.com/ajax/libs/FileSaver.js/2.0.5/FileSaver.min.js",
+        }
+    }
+
+    /// The file name the library is stored under in an asset directory.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Library::Plotly => "plotly.min.js",
+            Library::JQuery => "jquery.min.js",
+            Library::DataTables => "jquery.dataTables.min.js",
+            Library::DataTablesStyle => "jquery.dataTables.min.css",
+            Library::ColResize => "dataTables.colResize.min.js",
+            Library::ColResizeStyle => "colResize.dataTables.min.css",
+            Library::FileSaver => "FileSaver.min.js",
+        }
+    }
+
+    fn is_style(&self) -> bool {
+        self.file_name().ends_with(".css")
+    }
+
+    /// Load the library source from an asset directory, downloading it into the directory first if
+    /// it is missing and the `offline` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset_dir` - The directory holding the library files, see [`Library::file_name`].
+    ///
+    /// # Returns
+    ///
+    /// The library source, or an IO error if it is not in the directory and cannot be downloaded.
+    #[cfg(feature = "std")]
+    pub fn load(&self, asset_dir: &Path) -> io::Result<String> {
+        let path = asset_dir.join(self.file_name());
+        if path.exists() {
+            return std::fs::read_to_string(path);
+        }
+
+        #[cfg(feature = "offline")]
+        {
+            let source = ureq::get(self.url())
+                .call()
+                .map_err(io::Error::other)?
+                .into_string()?;
+            std::fs::create_dir_all(asset_dir)?;
+            std::fs::write(&path, &source)?;
+            Ok(source)
+        }
+        #[cfg(not(feature = "offline"))]
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "'{}' was not found; download {} into the asset directory or enable the `offline` feature",
+                path.display(),
+                self.url()
+            ),
+        ))
+    }
+
+    /// Render a reference to the library on its CDN.
+    pub(crate) fn render_cdn(&self) -> Markup {
+        html! {
+            @if self.is_style() {
+                link rel="stylesheet" href=(self.url());
+            } @else {
+                script src=(self.url()) {}
+            }
+        }
+    }
+
+    /// Render a reference to a local copy of the library.
+    #[cfg(feature = "std")]
+    fn render_local(&self, href: &str) -> Markup {
+        html! {
+            @if self.is_style() {
+                link rel="stylesheet" href=(href);
+            } @else {
+                script src=(href) {}
+            }
+        }
+    }
+
+    /// Render the library source inline.
+    #[cfg(feature = "std")]
+    fn render_inline(&self, source: &str) -> Markup {
+        html! {
+            @if self.is_style() {
+                style { (PreEscaped(source)) }
+            } @else {
+                // Prevent the source from closing the script element early
+                script { (PreEscaped(source.replace("</script", "<\\/script"))) }
+            }
+        }
+    }
+}
+
+/// Render references to all third-party libraries on their CDNs, for the report `<head>`.
+pub(crate) fn render_cdn_libraries() -> Markup {
+    html! {
+        @for library in Library::ALL {
+            (library.render_cdn())
+        }
+    }
+}
+
+/// How the third-party libraries are included in a report.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AssetMode {
+    /// Reference the libraries on their CDNs. Reports are small but need internet access to view.
+    #[default]
+    Cdn,
+    /// Embed the libraries into the HTML, loaded from (or downloaded into) an asset directory.
+    /// Reports are self-contained and can be viewed on air-gapped machines.
+    Inline { asset_dir: PathBuf },
+}
+
+#[cfg(feature = "std")]
+impl AssetMode {
+    /// Inline mode using the directory in the `REPORT_BUILDER_ASSET_DIR` environment variable, or
+    /// a `report-builder-assets` directory in the system temporary directory.
+    pub fn inline() -> Self {
+        let asset_dir = std::env::var_os("REPORT_BUILDER_ASSET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("report-builder-assets"));
+        AssetMode::Inline { asset_dir }
+    }
+
+    /// Render a third-party library for a `<head>`.
+    pub(crate) fn render_library(&self, library: Library) -> io::Result<Markup> {
+        Ok(match self {
+            AssetMode::Cdn => library.render_cdn(),
+            AssetMode::Inline { asset_dir } => library.render_inline(&library.load(asset_dir)?),
+        })
+    }
+
+    /// Render the third-party libraries for the report `<head>`.
+    pub(crate) fn render_libraries(&self) -> io::Result<Markup> {
+        let mut rendered = Vec::new();
+        for library in Library::ALL {
+            rendered.push(self.render_library(library)?);
+        }
+        Ok(html! {
+            @for markup in rendered {
+                (markup)
+            }
+        })
+    }
+
+    /// Render the third-party libraries for the `<head>` of several documents saved in one
+    /// directory. With [`AssetMode::Inline`], the libraries are copied once into its `assets`
+    /// subdirectory and referenced from there, rather than embedded into every document.
+    pub(crate) fn render_shared_libraries(&self, dir: &Path) -> io::Result<Markup> {
+        let AssetMode::Inline { asset_dir } = self else {
+            return Ok(render_cdn_libraries());
+        };
+        std::fs::create_dir_all(dir.join("assets"))?;
+        let mut rendered = Vec::new();
+        for library in Library::ALL {
+            std::fs::write(dir.join("assets").join(library.file_name()), library.load(asset_dir)?)?;
+            rendered.push(library.render_local(&format!("assets/{}", library.file_name())));
+        }
+        Ok(html! {
+            @for markup in rendered {
+                (markup)
+            }
+        })
+    }
+}
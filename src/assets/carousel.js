@@ -0,0 +1,48 @@
+// Carousel of figures: only the visible figure has a live Plotly instance
+function carouselShow(carousel, index) {
+    let figures = carousel.querySelectorAll('.carousel-figure');
+    let count = figures.length;
+    if (count === 0) {
+        return;
+    }
+    index = (index + count) % count;
+
+    let stage = carousel.querySelector('.carousel-stage');
+    let figure = JSON.parse(figures[index].textContent);
+    Plotly.purge(stage);
+    Plotly.newPlot(stage, figure.data, Object.assign({}, figure.layout, { autosize: true }), figure.config).then(function() {
+        if (window.applyGroupToggles) {
+            applyGroupToggles(stage);
+        }
+    });
+
+    carousel.dataset.index = index;
+    carousel.querySelector('.carousel-position').textContent = (index + 1) + ' / ' + count;
+    carousel.querySelector('.carousel-caption').textContent = figures[index].dataset.caption || '';
+}
+
+document.addEventListener('DOMContentLoaded', function() {
+    document.querySelectorAll('.carousel').forEach(function(carousel) {
+        let step = function(delta) {
+            carouselShow(carousel, parseInt(carousel.dataset.index || '0') + delta);
+        };
+        carousel.querySelector('.carousel-prev').addEventListener('click', function() { step(-1); });
+        carousel.querySelector('.carousel-next').addEventListener('click', function() { step(1); });
+        carousel.addEventListener('keydown', function(event) {
+            if (event.key === 'ArrowLeft') {
+                step(-1);
+            } else if (event.key === 'ArrowRight') {
+                step(1);
+            }
+        });
+        carouselShow(carousel, 0);
+    });
+});
+
+window.addEventListener('resize', function() {
+    document.querySelectorAll('.carousel-stage.js-plotly-plot').forEach(function(stage) {
+        if (stage.offsetParent !== null) {
+            Plotly.Plots.resize(stage);
+        }
+    });
+});
@@ -0,0 +1,65 @@
+// Group toggles: hide the traces and table rows of the unchecked groups in every plot and table
+(function() {
+    let hidden = [];
+
+    function isHidden(name) {
+        return name !== undefined && hidden.indexOf(String(name)) >= 0;
+    }
+
+    function applyToPlot(plotDiv) {
+        if (!plotDiv.data) {
+            return;
+        }
+        // Remember the visibility set by the figure, so shown groups get it back
+        if (!plotDiv.groupToggleVisible) {
+            plotDiv.groupToggleVisible = plotDiv.data.map(function(trace) {
+                return trace.visible === undefined ? true : trace.visible;
+            });
+        }
+        let groups = Array.from(document.querySelectorAll('.group-toggles input')).map(function(input) {
+            return input.value;
+        });
+        let traces = [];
+        let visible = [];
+        plotDiv.data.forEach(function(trace, i) {
+            let name = groups.indexOf(String(trace.name)) >= 0 ? trace.name : trace.legendgroup;
+            if (groups.indexOf(String(name)) >= 0) {
+                traces.push(i);
+                visible.push(isHidden(name) ? false : plotDiv.groupToggleVisible[i]);
+            }
+        });
+        if (traces.length > 0) {
+            Plotly.restyle(plotDiv, { visible: visible }, traces);
+        }
+    }
+
+    // Apply the current selection to one plot, e.g. one drawn after the page loaded, or to all
+    window.applyGroupToggles = function(plotDiv) {
+        if (plotDiv) {
+            applyToPlot(plotDiv);
+            return;
+        }
+        document.querySelectorAll('.js-plotly-plot').forEach(applyToPlot);
+        if (window.jQuery && jQuery.fn.dataTable) {
+            jQuery.fn.dataTable.tables({ api: true }).draw();
+        }
+    };
+
+    window.addEventListener('load', function() {
+        let inputs = Array.from(document.querySelectorAll('.group-toggles input'));
+        if (window.jQuery && jQuery.fn.dataTable) {
+            jQuery.fn.dataTable.ext.search.push(function(settings, data) {
+                return !data.some(function(cell) { return isHidden(cell.trim()); });
+            });
+        }
+        inputs.forEach(function(input) {
+            input.addEventListener('change', function() {
+                hidden = inputs.filter(function(i) { return !i.checked; }).map(function(i) { return i.value; });
+                applyGroupToggles();
+                if (window.announceStatus) {
+                    window.announceStatus('Group ' + input.value + (input.checked ? ' shown' : ' hidden'));
+                }
+            });
+        });
+    });
+})();
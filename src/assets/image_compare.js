@@ -0,0 +1,10 @@
+// Before/after image comparison slider: the range input reveals the "before" image up to its value
+document.addEventListener('input', function(event) {
+    if (!event.target.classList.contains('image-compare-slider')) {
+        return;
+    }
+    let container = event.target.closest('.image-compare');
+    let position = event.target.value;
+    container.querySelector('.image-compare-before').style.clipPath = 'inset(0 ' + (100 - position) + '% 0 0)';
+    container.querySelector('.image-compare-divider').style.left = position + '%';
+});
@@ -0,0 +1,47 @@
+// Lazy plots: the figure is embedded as JSON and only drawn the first time it becomes visible
+(function() {
+    function drawPlot(script) {
+        let plotDiv = document.querySelector(script.dataset.plot);
+        if (!plotDiv || plotDiv.data || !window.Plotly) {
+            return;
+        }
+        let figure = JSON.parse(script.textContent);
+        Plotly.newPlot(plotDiv, figure.data, figure.layout, figure.config).then(function() {
+            if (window.applyGroupToggles) {
+                applyGroupToggles(plotDiv);
+            }
+            // Let the plot's resize handler size it to the visible tab
+            window.dispatchEvent(new Event('resize'));
+        });
+    }
+
+    function observePlots() {
+        let scripts = Array.from(document.querySelectorAll('script.lazy-plot-figure'));
+        if (!('IntersectionObserver' in window)) {
+            scripts.forEach(drawPlot);
+            return;
+        }
+        // Plots in hidden tabs or collapsed subsections do not intersect until they are shown
+        let observer = new IntersectionObserver(function(entries) {
+            entries.forEach(function(entry) {
+                if (entry.isIntersecting) {
+                    observer.unobserve(entry.target);
+                    drawPlot(entry.target.lazyPlotFigure);
+                }
+            });
+        }, { rootMargin: '200px' });
+        scripts.forEach(function(script) {
+            let plotDiv = document.querySelector(script.dataset.plot);
+            if (plotDiv) {
+                plotDiv.lazyPlotFigure = script;
+                observer.observe(plotDiv);
+            }
+        });
+    }
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', observePlots);
+    } else {
+        observePlots();
+    }
+})();
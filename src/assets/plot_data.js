@@ -0,0 +1,35 @@
+function downloadPlotData(plotId) {
+    let plotDiv = document.getElementById(plotId);
+    if (!plotDiv || !plotDiv.data) {
+        return;
+    }
+    let columns = ['x', 'y', 'z', 'text'].filter(function(key) {
+        return plotDiv.data.some(function(trace) { return Array.isArray(trace[key]); });
+    });
+    let quote = function(value) {
+        if (value === null || value === undefined) {
+            return '';
+        }
+        let text = String(value);
+        return /[",\n]/.test(text) ? '"' + text.replace(/"/g, '""') + '"' : text;
+    };
+
+    let rows = [['trace'].concat(columns).join(',')];
+    plotDiv.data.forEach(function(trace, i) {
+        let name = trace.name || ('trace ' + i);
+        let length = Math.max.apply(null, columns.map(function(key) {
+            return Array.isArray(trace[key]) ? trace[key].length : 0;
+        }));
+        for (let j = 0; j < length; j++) {
+            let cells = columns.map(function(key) {
+                return Array.isArray(trace[key]) ? trace[key][j] : undefined;
+            });
+            rows.push([name].concat(cells).map(quote).join(','));
+        }
+    });
+
+    let title = (plotDiv.layout && plotDiv.layout.title && plotDiv.layout.title.text) || plotId;
+    let filename = title.toLowerCase().replace(/[^a-z0-9]+/g, '_').replace(/^_|_$/g, '') + '.csv';
+    let blob = new Blob([rows.join('\n')], { type: 'text/csv;charset=utf-8;' });
+    saveAs(blob, filename);
+}
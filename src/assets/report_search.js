@@ -0,0 +1,159 @@
+// Report search: filter every table and highlight matching text across all sections
+(function() {
+    let hits = [];
+    let current = -1;
+    let timer = null;
+
+    function clearHighlights() {
+        document.querySelectorAll('mark.search-hit').forEach(function(mark) {
+            let parent = mark.parentNode;
+            parent.replaceChild(document.createTextNode(mark.textContent), mark);
+            parent.normalize();
+        });
+        document.querySelectorAll('.tab[data-search-hits]').forEach(function(tab) {
+            tab.removeAttribute('data-search-hits');
+        });
+        hits = [];
+        current = -1;
+    }
+
+    // Text outside tables (searched through DataTables), plots, scripts and form controls
+    function textNodes(root) {
+        let walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT, {
+            acceptNode: function(node) {
+                if (!node.nodeValue.trim() || node.parentElement.closest('script, style, table, .plot-container, .js-plotly-plot, input, button, .visually-hidden')) {
+                    return NodeFilter.FILTER_REJECT;
+                }
+                return NodeFilter.FILTER_ACCEPT;
+            }
+        });
+        let nodes = [];
+        while (walker.nextNode()) {
+            nodes.push(walker.currentNode);
+        }
+        return nodes;
+    }
+
+    function highlight(root, query) {
+        let marks = [];
+        textNodes(root).forEach(function(node) {
+            let text = node.nodeValue;
+            let lower = text.toLowerCase();
+            let at = lower.indexOf(query);
+            if (at < 0) {
+                return;
+            }
+            let fragment = document.createDocumentFragment();
+            let start = 0;
+            while (at >= 0) {
+                fragment.appendChild(document.createTextNode(text.slice(start, at)));
+                let mark = document.createElement('mark');
+                mark.className = 'search-hit';
+                mark.textContent = text.slice(at, at + query.length);
+                fragment.appendChild(mark);
+                marks.push(mark);
+                start = at + query.length;
+                at = lower.indexOf(query, start);
+            }
+            fragment.appendChild(document.createTextNode(text.slice(start)));
+            node.parentNode.replaceChild(fragment, node);
+        });
+        return marks;
+    }
+
+    // Search the tables with DataTables, returning the number of matching rows per table element
+    function searchTables(query) {
+        let matches = new Map();
+        if (!(window.jQuery && jQuery.fn.dataTable)) {
+            return matches;
+        }
+        jQuery.fn.dataTable.tables({ api: true }).every(function() {
+            this.search(query).draw();
+            if (query) {
+                matches.set(this.table().node(), this.rows({ search: 'applied' }).count());
+            }
+        });
+        return matches;
+    }
+
+    function showHit(index) {
+        if (hits.length === 0) {
+            return;
+        }
+        current = (index + hits.length) % hits.length;
+        let hit = hits[current];
+        document.querySelectorAll('.search-hit-current').forEach(function(el) {
+            el.classList.remove('search-hit-current');
+        });
+        hit.classList.add('search-hit-current');
+        let tab = hit.closest('.tab-content');
+        if (tab && !tab.classList.contains('active') && document.querySelector("[data-tab='" + tab.id + "']")) {
+            showTab(tab.id);
+        }
+        hit.scrollIntoView({ block: 'center' });
+    }
+
+    function search(query) {
+        clearHighlights();
+        query = query.trim().toLowerCase();
+        let tables = searchTables(query);
+        let count = document.querySelector('.report-search-count');
+        if (!query) {
+            count.textContent = '';
+            return;
+        }
+
+        let sections = 0;
+        document.querySelectorAll('.tab-content').forEach(function(tab) {
+            let found = highlight(tab, query);
+            let rows = 0;
+            tables.forEach(function(n, table) {
+                if (n > 0 && tab.contains(table)) {
+                    rows += n;
+                    found.push(table.closest('.dataTables_wrapper') || table);
+                }
+            });
+            let total = found.filter(function(el) { return el.tagName === 'MARK'; }).length + rows;
+            if (total > 0) {
+                sections += 1;
+                let button = document.querySelector("[data-tab='" + tab.id + "']");
+                if (button) {
+                    button.setAttribute('data-search-hits', total);
+                }
+            }
+            hits = hits.concat(found);
+        });
+
+        let total = document.querySelectorAll('mark.search-hit').length;
+        tables.forEach(function(n) { total += n; });
+        count.textContent = total + (total === 1 ? ' match' : ' matches');
+        if (window.announceStatus) {
+            window.announceStatus(total + (total === 1 ? ' match' : ' matches') + ' in ' + sections + (sections === 1 ? ' section' : ' sections'));
+        }
+        // Jump to the first match, unless the open tab has one
+        let active = document.querySelector('.tab-content.active');
+        let first = hits.findIndex(function(hit) { return active && active.contains(hit); });
+        showHit(first >= 0 ? first : 0);
+    }
+
+    window.addEventListener('load', function() {
+        let input = document.getElementById('report-search-input');
+        if (!input) {
+            return;
+        }
+        input.addEventListener('input', function() {
+            clearTimeout(timer);
+            timer = setTimeout(function() { search(input.value); }, 250);
+        });
+        // Enter jumps to the next match, Shift+Enter to the previous one
+        input.addEventListener('keydown', function(event) {
+            if (event.key === 'Enter') {
+                event.preventDefault();
+                showHit(current + (event.shiftKey ? -1 : 1));
+            } else if (event.key === 'Escape') {
+                input.value = '';
+                search('');
+            }
+        });
+    });
+})();
@@ -0,0 +1,91 @@
+// Threshold sliders: re-filter the linked plots and tables each time the slider moves
+(function() {
+    function passes(value, threshold, keep) {
+        return keep === 'at-least' ? value >= threshold : value <= threshold;
+    }
+
+    // Filter the points of a plot, returning how many are kept
+    function filterPlot(plotDiv, values, threshold, keep) {
+        if (!plotDiv || !plotDiv.data) {
+            return 0;
+        }
+        // Keep the unfiltered traces, so the points come back when the threshold is relaxed
+        if (!plotDiv.thresholdOriginal) {
+            plotDiv.thresholdOriginal = plotDiv.data.map(function(trace) {
+                return { x: trace.x, y: trace.y, text: trace.text };
+            });
+        }
+        let update = { x: [], y: [], text: [] };
+        let kept = 0;
+        let traces = [];
+        plotDiv.thresholdOriginal.forEach(function(trace, i) {
+            if (!values[i] || !trace.x) {
+                return;
+            }
+            let keepPoint = function(_, j) { return passes(values[i][j], threshold, keep); };
+            update.x.push(Array.from(trace.x).filter(keepPoint));
+            kept += update.x[update.x.length - 1].length;
+            update.y.push(trace.y ? Array.from(trace.y).filter(keepPoint) : trace.y);
+            update.text.push(Array.isArray(trace.text) ? trace.text.filter(keepPoint) : trace.text);
+            traces.push(i);
+        });
+        if (traces.length > 0) {
+            Plotly.restyle(plotDiv, update, traces);
+        }
+        return kept;
+    }
+
+    function setUp(slider) {
+        let input = slider.querySelector('.threshold-input');
+        let output = slider.querySelector('.threshold-value');
+        let keep = slider.dataset.keep;
+        let plots = Array.from(slider.querySelectorAll('script.threshold-values')).map(function(script) {
+            return { selector: script.dataset.plot, values: JSON.parse(script.textContent) };
+        });
+        let tables = Array.from(slider.querySelectorAll('.threshold-table'));
+
+        if (tables.length > 0 && window.jQuery && jQuery.fn.dataTable) {
+            let ids = tables.map(function(table) { return table.dataset.table.slice(1); });
+            jQuery.fn.dataTable.ext.search.push(function(settings, data) {
+                let index = ids.indexOf(settings.nTable.id);
+                if (index < 0) {
+                    return true;
+                }
+                let value = parseFloat(data[parseInt(tables[index].dataset.column)]);
+                return isNaN(value) || passes(value, parseFloat(input.value), keep);
+            });
+        }
+
+        let apply = function() {
+            let threshold = parseFloat(input.value);
+            output.textContent = input.value;
+            let counts = [];
+            plots.forEach(function(plot) {
+                let kept = filterPlot(document.querySelector(plot.selector), plot.values, threshold, keep);
+                counts.push(kept + (kept === 1 ? ' point shown' : ' points shown'));
+            });
+            if (window.jQuery && jQuery.fn.dataTable) {
+                tables.forEach(function(table) {
+                    let selector = table.dataset.table;
+                    if (jQuery.fn.dataTable.isDataTable(selector)) {
+                        let rows = jQuery(selector).DataTable().draw().rows({ search: 'applied' }).count();
+                        counts.push(rows + (rows === 1 ? ' row matches' : ' rows match'));
+                    }
+                });
+            }
+            return counts;
+        };
+        input.addEventListener('input', function() {
+            let counts = apply();
+            if (window.announceStatus && counts.length > 0) {
+                let label = slider.querySelector('.threshold-control').firstChild.textContent.trim();
+                window.announceStatus(label + ' ' + input.value + ': ' + counts.join(', '));
+            }
+        });
+        apply();
+    }
+
+    window.addEventListener('load', function() {
+        document.querySelectorAll('.threshold-slider').forEach(setUp);
+    });
+})();
@@ -0,0 +1,74 @@
+//! Command-line tools for archives of reports.
+//!
+//! ```text
+//! report-builder index <DIR> [--title <TITLE>] [--output <FILE>]
+//! ```
+//!
+//! `index` (or its alias `aggregate`) scans `DIR` and its subdirectories for report manifests
+//! (see `Report::save_manifest`) and regenerates the landing page, with its table of reports and
+//! trend plots, at `DIR/index.html` or the given output file. The links between reports (see
+//! `Report::add_report_link`) are pointed to the linked reports, and links to report IDs missing
+//! from the archive are reported as warnings. Run it from a cron job to keep an archive browsable.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use report_builder::index::ReportIndex;
+
+const USAGE: &str = "Usage: report-builder index <DIR> [--title <TITLE>] [--output <FILE>]";
+
+/// The arguments of the `index` command
+struct IndexArgs {
+    dir: PathBuf,
+    title: String,
+    output: Option<PathBuf>,
+}
+
+fn parse_index_args(mut args: impl Iterator<Item = String>) -> Result<IndexArgs, String> {
+    let mut dir = None;
+    let mut title = "Reports".to_string();
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" => title = args.next().ok_or("--title requires a value")?,
+            "--output" => output = Some(PathBuf::from(args.next().ok_or("--output requires a value")?)),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{}'", arg)),
+            _ if dir.is_none() => dir = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+    let dir = dir.ok_or("Missing the archive directory")?;
+    Ok(IndexArgs { dir, title, output })
+}
+
+fn run_index(args: IndexArgs) -> Result<(), String> {
+    let index = ReportIndex::from_manifests(&args.dir, &args.title).map_err(|e| e.to_string())?;
+    let output = args.output.unwrap_or_else(|| args.dir.join("index.html"));
+    index.save_to_file(&output).map_err(|e| e.to_string())?;
+    index.link_reports().map_err(|e| e.to_string())?;
+    for link in index.broken_links() {
+        eprintln!("warning: '{}' ({}) links to the unknown report ID '{}'", link.title, link.report, link.target);
+    }
+    println!("Indexed {} reports into {}", index.entries().len(), output.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("index" | "aggregate") => parse_index_args(args).and_then(run_index),
+        Some("-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some(command) => Err(format!("Unknown command '{}'\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Logos of the software, institutions and sponsors behind a report.
+//!
+//! The software logo passed to [`Report::new`](crate::Report::new) is shown at the left of the
+//! banner; [`Report::add_logo`](crate::Report::add_logo) adds more, e.g. for collaboration
+//! reports, on either side:
+//!
+//! ```rust,ignore
+//! use report_builder::branding::LogoPlacement;
+//!
+//! report.add_logo("img/institute.png", "Institute", LogoPlacement::Left);
+//! report.add_logo("img/sponsor.svg", "Sponsor", LogoPlacement::Right);
+//! ```
+//!
+//! Local logos are resolved relative to the directory the report is saved to. Those found there
+//! when saving are embedded into the report as base64 `data:` URIs, so the HTML file can be shared
+//! without its images; missing ones are kept as paths and reported as
+//! [`WarningKind::MissingAsset`](crate::warnings::WarningKind::MissingAsset).
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+/// The side of the banner a logo is shown on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogoPlacement {
+    /// Left of the software name, after the software logo
+    #[default]
+    Left,
+    /// At the right end of the banner
+    Right,
+}
+
+/// A logo shown in the banner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Logo {
+    /// The path or URL of the image, local paths being relative to the output directory
+    pub src: String,
+    /// The text replacing the image for screen readers or when it cannot be loaded
+    pub alt: String,
+    pub placement: LogoPlacement,
+}
+
+impl Logo {
+    /// Creates a logo; local images are embedded when the report is saved, see [`inline_image`].
+    pub fn new(src: &str, alt: &str, placement: LogoPlacement) -> Self {
+        Logo {
+            src: src.to_string(),
+            alt: alt.to_string(),
+            placement,
+        }
+    }
+
+    /// Render the logo as an image, using its embedded version if there is one
+    pub(crate) fn render(&self, images: &BTreeMap<String, String>) -> Markup {
+        html! { img class="banner-logo" src=(image_src(&self.src, images)) alt=(self.alt); }
+    }
+}
+
+/// The `data:` URI embedding an image if there is one, or its source as given
+pub(crate) fn image_src<'a>(src: &'a str, images: &'a BTreeMap<String, String>) -> &'a str {
+    images.get(src).map_or(src, String::as_str)
+}
+
+/// Whether an image source points to a remote or embedded image rather than a local file
+#[cfg(feature = "std")]
+pub(crate) fn is_remote(src: &str) -> bool {
+    ["http://", "https://", "data:"].iter().any(|prefix| src.starts_with(prefix))
+}
+
+/// Embed a local image as a base64 `data:` URI, so the report does not depend on the file being
+/// shipped next to it.
+///
+/// # Arguments
+///
+/// * `output_dir` - The directory the report is saved to, which local paths are relative to.
+/// * `src` - The path or URL of the image.
+///
+/// # Returns
+///
+/// The `data:` URI, or `None` for URLs, `data:` URIs, files that cannot be read and files whose
+/// extension is not a known image type.
+#[cfg(feature = "std")]
+pub fn inline_image(output_dir: &Path, src: &str) -> Option<String> {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+
+    let extension = src.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    if is_remote(src) {
+        return None;
+    }
+    let bytes = std::fs::read(output_dir.join(src)).ok()?;
+    Some(format!("data:{};base64,{}", mime, BASE64_STANDARD.encode(bytes)))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_image() {
+        let dir = std::env::temp_dir().join(format!("report-builder-logo-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("img")).unwrap();
+        std::fs::write(dir.join("img/logo.svg"), "<svg/>").unwrap();
+        let inlined = inline_image(&dir, "img/logo.svg");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(inlined.as_deref(), Some("data:image/svg+xml;base64,PHN2Zy8+"));
+
+        assert_eq!(inline_image(&dir, "https://example.org/logo.png"), None);
+        assert_eq!(inline_image(&dir, "missing/logo.png"), None);
+        assert_eq!(inline_image(&dir, "logo.txt"), None);
+    }
+}
@@ -0,0 +1,278 @@
+//! Building reports from a stream of JSON commands.
+//!
+//! Tools written in other languages can construct a report without linking against Rust types by
+//! emitting a JSON array of commands, applied in order, and passing it to [`from_commands`] (or
+//! to the C functions in [`ffi`](crate::ffi)). The first command creates the report; each
+//! `section` starts a new top-level section, and each `subsection` a new subsection of the
+//! current section. Content, tables, plots and components go into the most recent of these.
+//!
+//! ```json
+//! [
+//!     { "command": "report", "software_name": "Redeem", "version": "1.0", "title": "Run QC" },
+//!     { "command": "section", "title": "Scores" },
+//!     { "command": "content", "html": "<p>Scores of the first run.</p>" },
+//!     { "command": "plot", "kind": "roc", "scores": [0.9, 0.8, 0.3], "labels": [1, 1, -1], "title": "ROC" },
+//!     { "command": "subsection", "title": "Top hits" },
+//!     { "command": "table", "headers": ["Run", "Score"], "rows": [["run1", "0.9"]] },
+//!     { "command": "component", "name": "banner-note", "params": { "text": "Hello" } }
+//! ]
+//! ```
+//!
+//! The command format is stable: commands and optional fields may be added, but existing ones
+//! keep their meaning.
+
+use maud::PreEscaped;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "plots")]
+use crate::plots;
+#[cfg(feature = "tables")]
+use crate::table::Table;
+use crate::{Report, ReportError, ReportSection};
+
+/// A command of the JSON command stream, tagged by its `command` field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Command {
+    /// Creates the report; must be the first command
+    Report {
+        software_name: String,
+        version: String,
+        #[serde(default)]
+        software_logo: Option<String>,
+        title: String,
+        #[serde(default)]
+        toc: bool,
+        #[serde(default)]
+        dark_mode_toggle: bool,
+    },
+    /// Starts a new top-level section
+    Section { title: String },
+    /// Starts a new subsection of the current top-level section
+    Subsection { title: String },
+    /// Adds a block of HTML, inserted as is
+    Content { html: String },
+    /// Adds an interactive table
+    #[cfg(feature = "tables")]
+    Table {
+        headers: Vec<String>,
+        #[serde(default)]
+        rows: Vec<Vec<String>>,
+        #[serde(default)]
+        caption: Option<String>,
+    },
+    /// Adds a plot built by one of the plot helpers
+    #[cfg(feature = "plots")]
+    Plot(PlotCommand),
+    /// Adds a component from the [`registry`](crate::registry)
+    Component {
+        name: String,
+        #[serde(default)]
+        params: Value,
+    },
+}
+
+/// A plot helper and its arguments, tagged by its `kind` field.
+#[cfg(feature = "plots")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PlotCommand {
+    /// See [`plots::plot_score_histogram`]
+    ScoreHistogram { scores: Vec<f64>, labels: Vec<i32>, title: String, x_title: String },
+    /// See [`plots::plot_pp`]
+    Pp { scores: Vec<f64>, labels: Vec<i32>, title: String },
+    /// See [`plots::plot_roc`]
+    Roc { scores: Vec<f64>, labels: Vec<i32>, title: String },
+    /// See [`plots::plot_precision_recall`]
+    PrecisionRecall { scores: Vec<f64>, labels: Vec<i32>, title: String },
+    /// See [`plots::plot_boxplot`]
+    Boxplot { scores: Vec<Vec<f64>>, filenames: Vec<String>, title: String, x_title: String, y_title: String },
+    /// See [`plots::plot_scatter`]
+    Scatter { x: Vec<Vec<f64>>, y: Vec<Vec<f64>>, labels: Vec<String>, title: String, x_title: String, y_title: String },
+    /// See [`plots::plot_bland_altman`]
+    BlandAltman { x: Vec<f64>, y: Vec<f64>, title: String },
+    /// See [`plots::plot_ma`]
+    Ma { x: Vec<f64>, y: Vec<f64>, title: String },
+}
+
+#[cfg(feature = "plots")]
+impl PlotCommand {
+    /// Build the plot
+    fn build(self) -> Result<plotly::Plot, ReportError> {
+        match self {
+            PlotCommand::ScoreHistogram { scores, labels, title, x_title } => {
+                plots::plot_score_histogram(&scores, &labels, &title, &x_title)
+            }
+            PlotCommand::Pp { scores, labels, title } => plots::plot_pp(&scores, &labels, &title),
+            PlotCommand::Roc { scores, labels, title } => plots::plot_roc(&scores, &labels, &title),
+            PlotCommand::PrecisionRecall { scores, labels, title } => plots::plot_precision_recall(&scores, &labels, &title),
+            PlotCommand::Boxplot { scores, filenames, title, x_title, y_title } => {
+                plots::plot_boxplot(scores, filenames, &title, &x_title, &y_title)
+            }
+            PlotCommand::Scatter { x, y, labels, title, x_title, y_title } => {
+                plots::plot_scatter(x, y, labels, &title, &x_title, &y_title)
+            }
+            PlotCommand::BlandAltman { x, y, title } => plots::plot_bland_altman(&x, &y, &title),
+            PlotCommand::Ma { x, y, title } => plots::plot_ma(&x, &y, &title),
+        }
+    }
+}
+
+/// The report being built, with the sections still open for content
+struct Builder {
+    report: Report,
+    section: Option<ReportSection>,
+    subsection: Option<ReportSection>,
+}
+
+impl Builder {
+    /// Close the current subsection, adding it to its section
+    fn close_subsection(&mut self) {
+        if let (Some(section), Some(subsection)) = (self.section.as_mut(), self.subsection.take()) {
+            section.add_subsection(subsection);
+        }
+    }
+
+    /// Close the current section and subsection, adding them to the report
+    fn close_section(&mut self) {
+        self.close_subsection();
+        if let Some(section) = self.section.take() {
+            self.report.add_section(section);
+        }
+    }
+
+    /// The innermost open section, which content goes into
+    fn target(&mut self) -> Result<&mut ReportSection, String> {
+        self.subsection
+            .as_mut()
+            .or(self.section.as_mut())
+            .ok_or_else(|| "content must follow a 'section' command".to_string())
+    }
+
+    fn apply(&mut self, command: Command) -> Result<(), String> {
+        match command {
+            Command::Report { .. } => return Err("the report was already created".to_string()),
+            Command::Section { title } => {
+                self.close_section();
+                self.section = Some(ReportSection::new(&title));
+            }
+            Command::Subsection { title } => {
+                if self.section.is_none() {
+                    return Err("'subsection' must follow a 'section' command".to_string());
+                }
+                self.close_subsection();
+                self.subsection = Some(ReportSection::new(&title));
+            }
+            Command::Content { html } => self.target()?.add_content(PreEscaped(html)),
+            #[cfg(feature = "tables")]
+            Command::Table { headers, rows, caption } => {
+                if let Some(row) = rows.iter().find(|row| row.len() != headers.len()) {
+                    return Err(format!("expected {} cells per row, found {}", headers.len(), row.len()));
+                }
+                let mut table = Table::new().headers(headers).add_rows(rows);
+                if let Some(caption) = caption {
+                    table = table.caption(&caption);
+                }
+                self.target()?.add_table(table);
+            }
+            #[cfg(feature = "plots")]
+            Command::Plot(plot) => {
+                let plot = plot.build().map_err(|e| e.to_string())?;
+                self.target()?.add_plot(plot);
+            }
+            Command::Component { name, params } => {
+                self.target()?.add_component(&name, &params).map_err(|e| e.to_string())?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds a report from a JSON array of commands, see the [module documentation](self).
+///
+/// # Arguments
+///
+/// * `json` - The commands, as a JSON array.
+///
+/// # Returns
+///
+/// The report, or [`ReportError::Serialization`] if the JSON is not a valid command stream and
+/// [`ReportError::InvalidCommand`] if a command cannot be applied.
+pub fn from_commands(json: &str) -> Result<Report, ReportError> {
+    let commands: Vec<Command> = serde_json::from_str(json)?;
+    build(commands)
+}
+
+/// Builds a report from commands, applied in order.
+pub fn build(commands: impl IntoIterator<Item = Command>) -> Result<Report, ReportError> {
+    let mut commands = commands.into_iter();
+    let report = match commands.next() {
+        Some(Command::Report { software_name, version, software_logo, title, toc, dark_mode_toggle }) => {
+            Report::new(&software_name, &version, software_logo.as_deref(), &title)
+                .with_toc(toc)
+                .with_dark_mode_toggle(dark_mode_toggle)
+        }
+        _ => {
+            let message = "the first command must be 'report'".to_string();
+            return Err(ReportError::InvalidCommand { index: 0, message });
+        }
+    };
+
+    let mut builder = Builder { report, section: None, subsection: None };
+    for (index, command) in commands.enumerate() {
+        builder
+            .apply(command)
+            .map_err(|message| ReportError::InvalidCommand { index: index + 1, message })?;
+    }
+    builder.close_section();
+    Ok(builder.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "plots", feature = "tables"))]
+    fn test_from_commands() {
+        let report = from_commands(
+            r#"[
+                { "command": "report", "software_name": "Redeem", "version": "1.0", "title": "Run QC" },
+                { "command": "section", "title": "Scores" },
+                { "command": "content", "html": "<p>First run</p>" },
+                { "command": "plot", "kind": "roc", "scores": [0.9, 0.8, 0.3], "labels": [1, 1, -1], "title": "ROC" },
+                { "command": "subsection", "title": "Top hits" },
+                { "command": "table", "headers": ["Run", "Score"], "rows": [["run1", "0.9"]] },
+                { "command": "section", "title": "Summary" }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(report.sections.len(), 2);
+        let html = report.render_to_string();
+        assert!(html.contains("<p>First run</p>"));
+        assert!(html.contains("Top hits"));
+        assert!(html.contains("<td>run1</td><td>0.9</td>"));
+        assert!(html.contains("ROC (AUC = 1.000)"));
+    }
+
+    #[test]
+    fn test_invalid_commands() {
+        let error = from_commands(r#"[{ "command": "section", "title": "Scores" }]"#).err().unwrap();
+        assert!(matches!(error, ReportError::InvalidCommand { index: 0, .. }));
+
+        let error = from_commands(
+            r#"[
+                { "command": "report", "software_name": "Redeem", "version": "1.0", "title": "Run QC" },
+                { "command": "content", "html": "<p>Orphan</p>" }
+            ]"#,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.to_string(), "Invalid command 1: content must follow a 'section' command");
+
+        assert!(matches!(from_commands(r#"[{ "command": "unknown" }]"#), Err(ReportError::Serialization(_))));
+    }
+}
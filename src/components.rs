@@ -0,0 +1,57 @@
+//! Reusable interactive components that can be added to a [`ReportSection`](crate::ReportSection).
+//!
+//! A component renders to a block of markup and declares the static [`Asset`]s (JavaScript and
+//! CSS) and third-party [`Library`]s it needs; the report includes each asset once, no matter how
+//! many components use it.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use maud::{html, Markup, PreEscaped};
+
+use crate::assets::{Asset, Library};
+
+#[cfg(feature = "plots")]
+pub mod carousel;
+pub mod image_compare;
+#[cfg(feature = "plots")]
+pub mod resource_usage;
+#[cfg(feature = "plots")]
+pub mod threshold_slider;
+
+#[cfg(feature = "plots")]
+pub use carousel::Carousel;
+pub use image_compare::ImageComparison;
+#[cfg(feature = "plots")]
+pub use resource_usage::{ResourceSample, ResourceUsage};
+#[cfg(feature = "plots")]
+pub use threshold_slider::{Keep, ThresholdSlider};
+
+/// A block of content with its own client-side assets.
+pub trait Component {
+    /// Render the component as HTML.
+    fn render(&self) -> Markup;
+
+    /// The static assets required by the component.
+    fn assets(&self) -> Vec<Asset> {
+        Vec::new()
+    }
+
+    /// The third-party libraries required by the component, see
+    /// [`ReportSection::render_fragment`](crate::ReportSection::render_fragment).
+    fn libraries(&self) -> Vec<Library> {
+        Vec::new()
+    }
+}
+
+/// Embed a JSON document in a `<script type="application/json">` element with the given class and
+/// extra attributes, escaping any `</` so the data cannot close the element early.
+#[cfg_attr(not(feature = "plots"), allow(dead_code))]
+pub(crate) fn json_script(class: &str, json: &str, attributes: &[(&str, &str)]) -> Markup {
+    let mut open_tag = format!(r#"<script type="application/json" class="{}""#, class);
+    for (name, value) in attributes {
+        open_tag.push_str(&format!(r#" {}="{}""#, name, html! { (value) }.into_string()));
+    }
+    open_tag.push('>');
+    PreEscaped(format!("{}{}</script>", open_tag, json.replace("</", "<\\/")))
+}
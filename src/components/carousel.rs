@@ -0,0 +1,94 @@
+use maud::{html, Markup};
+use plotly::Plot;
+
+use super::{json_script, Component};
+use crate::assets::{Asset, Library};
+
+/// A carousel cycling through related figures (e.g. one spectrum per sample), with previous/next
+/// buttons, keyboard arrow navigation and a position indicator.
+///
+/// Only the visible figure is drawn by Plotly; the others are embedded as JSON and drawn when
+/// navigated to, which keeps reports with many figures responsive.
+pub struct Carousel {
+    figures: Vec<(Plot, String)>,
+    height: u32,
+}
+
+impl Carousel {
+    /// Creates an empty carousel.
+    pub fn new() -> Self {
+        Carousel {
+            figures: Vec::new(),
+            height: 600,
+        }
+    }
+
+    /// Adds a figure to the carousel.
+    ///
+    /// # Arguments
+    ///
+    /// * `plot` - The figure to add.
+    /// * `caption` - A caption shown next to the position indicator while the figure is visible.
+    pub fn add_figure(mut self, plot: Plot, caption: &str) -> Self {
+        self.figures.push((plot, caption.to_string()));
+        self
+    }
+
+    /// Sets the height of the figures in pixels.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+impl Default for Carousel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for Carousel {
+    fn render(&self) -> Markup {
+        html! {
+            div class="carousel" tabindex="0" data-index="0" {
+                div class="carousel-stage" style=(format!("height: {}px;", self.height)) {}
+                @for (plot, caption) in &self.figures {
+                    (json_script("carousel-figure", &plot.to_json(), &[("data-caption", caption)]))
+                }
+                div class="carousel-controls" {
+                    button class="carousel-prev" aria-label="Previous figure" { "‹" }
+                    span class="carousel-position" aria-live="polite" {
+                        @if self.figures.is_empty() { "0 / 0" } @else { "1 / " (self.figures.len()) }
+                    }
+                    span class="carousel-caption" {}
+                    button class="carousel-next" aria-label="Next figure" { "›" }
+                }
+            }
+        }
+    }
+
+    fn assets(&self) -> Vec<Asset> {
+        vec![Asset::Carousel]
+    }
+
+    fn libraries(&self) -> Vec<Library> {
+        vec![Library::Plotly]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_carousel_render() {
+        let carousel = Carousel::new()
+            .add_figure(Plot::new(), "Sample 1")
+            .add_figure(Plot::new(), "Sample </script> 2");
+
+        let html = carousel.render().into_string();
+        assert_eq!(html.matches(r#"class="carousel-figure""#).count(), 2);
+        assert!(html.contains("1 / 2"));
+        assert!(!html.contains("Sample </script>"));
+    }
+}
@@ -0,0 +1,93 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use maud::{html, Markup};
+
+use super::Component;
+use crate::assets::Asset;
+
+/// A before/after comparison of two images with a draggable slider revealing one over the other.
+///
+/// Both images should have the same dimensions, e.g. a figure before and after normalization.
+pub struct ImageComparison {
+    before_src: String,
+    after_src: String,
+    before_label: String,
+    after_label: String,
+    caption: Option<String>,
+}
+
+impl ImageComparison {
+    /// Creates a new comparison between two images.
+    ///
+    /// # Arguments
+    ///
+    /// * `before_src` - The path, URL or data URI of the "before" image.
+    /// * `after_src` - The path, URL or data URI of the "after" image.
+    pub fn new(before_src: &str, after_src: &str) -> Self {
+        ImageComparison {
+            before_src: before_src.to_string(),
+            after_src: after_src.to_string(),
+            before_label: "Before".to_string(),
+            after_label: "After".to_string(),
+            caption: None,
+        }
+    }
+
+    /// Sets the labels shown on each side of the slider.
+    pub fn labels(mut self, before: &str, after: &str) -> Self {
+        self.before_label = before.to_string();
+        self.after_label = after.to_string();
+        self
+    }
+
+    /// Sets a caption shown below the images.
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = Some(caption.to_string());
+        self
+    }
+}
+
+impl Component for ImageComparison {
+    fn render(&self) -> Markup {
+        html! {
+            figure class="image-compare-figure" {
+                div class="image-compare" {
+                    img class="image-compare-after" src=(self.after_src) alt=(self.after_label);
+                    img class="image-compare-before" src=(self.before_src) alt=(self.before_label);
+                    div class="image-compare-divider" {}
+                    span class="image-compare-label before" { (self.before_label) }
+                    span class="image-compare-label after" { (self.after_label) }
+                    input type="range" class="image-compare-slider" min="0" max="100" value="50"
+                        aria-label=(format!("Slide to compare {} and {}", self.before_label, self.after_label));
+                }
+                @if let Some(ref caption) = self.caption {
+                    figcaption { (caption) }
+                }
+            }
+        }
+    }
+
+    fn assets(&self) -> Vec<Asset> {
+        vec![Asset::ImageCompare]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_comparison_render() {
+        let comparison = ImageComparison::new("raw.png", "normalized.png")
+            .labels("Raw", "Normalized")
+            .caption("Intensity normalization");
+
+        let html = comparison.render().into_string();
+        assert!(html.contains(r#"class="image-compare-before" src="raw.png""#));
+        assert!(html.contains(r#"class="image-compare-after" src="normalized.png""#));
+        assert!(html.contains("<figcaption>Intensity normalization</figcaption>"));
+        assert_eq!(comparison.assets(), vec![Asset::ImageCompare]);
+    }
+}
@@ -0,0 +1,148 @@
+use maud::{html, Markup, PreEscaped};
+use plotly::common::{Fill, Mode};
+use plotly::layout::{Axis, Layout};
+use plotly::{Configuration, Plot, Scatter};
+
+use super::Component;
+use crate::assets::Library;
+
+/// A sample of the resources used by a process, e.g. one line of a pipeline profiler's trace.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResourceSample {
+    /// Seconds since the start of the process
+    pub time: f64,
+    /// CPU usage in percent of one core, so multi-threaded processes can exceed 100
+    pub cpu_percent: f64,
+    /// Resident set size in bytes
+    pub rss_bytes: f64,
+    /// Read throughput in bytes per second
+    pub read_bytes_per_sec: f64,
+    /// Write throughput in bytes per second
+    pub write_bytes_per_sec: f64,
+}
+
+/// A summary of the resources used by a process over time: stacked CPU, memory and I/O plots
+/// sharing a time axis, and a table of the peak usage of each.
+pub struct ResourceUsage {
+    samples: Vec<ResourceSample>,
+    height: u32,
+}
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// A metric of the peak-usage table: its name, how to read it from a sample and how to format it
+type Metric = (&'static str, fn(&ResourceSample) -> f64, fn(f64) -> String);
+
+impl ResourceUsage {
+    /// Creates a summary of the given samples, in any order.
+    pub fn new(mut samples: Vec<ResourceSample>) -> Self {
+        samples.sort_by(|a, b| a.time.total_cmp(&b.time));
+        ResourceUsage { samples, height: 700 }
+    }
+
+    /// Sets the height of the plots in pixels.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// The stacked usage plots: CPU on top, memory in the middle and I/O at the bottom.
+    pub fn plot(&self) -> Plot {
+        let time: Vec<f64> = self.samples.iter().map(|s| s.time).collect();
+        let series = |value: fn(&ResourceSample) -> f64| self.samples.iter().map(value).collect::<Vec<f64>>();
+
+        let mut plot = Plot::new();
+        plot.add_trace(Scatter::new(time.clone(), series(|s| s.cpu_percent)).name("CPU").mode(Mode::Lines).fill(Fill::ToZeroY));
+        plot.add_trace(
+            Scatter::new(time.clone(), series(|s| s.rss_bytes / MIB))
+                .name("RSS")
+                .mode(Mode::Lines)
+                .fill(Fill::ToZeroY)
+                .y_axis("y2"),
+        );
+        plot.add_trace(Scatter::new(time.clone(), series(|s| s.read_bytes_per_sec / MIB)).name("Read").mode(Mode::Lines).y_axis("y3"));
+        plot.add_trace(Scatter::new(time, series(|s| s.write_bytes_per_sec / MIB)).name("Write").mode(Mode::Lines).y_axis("y3"));
+
+        let layout = Layout::new()
+            .title("Resource usage")
+            .x_axis(Axis::new().title("Time (s)"))
+            .y_axis(Axis::new().title("CPU (%)").domain(&[0.7, 1.0]))
+            .y_axis2(Axis::new().title("RSS (MiB)").domain(&[0.37, 0.63]).anchor("x"))
+            .y_axis3(Axis::new().title("I/O (MiB/s)").domain(&[0.0, 0.3]).anchor("x"));
+        plot.set_layout(layout);
+        plot.set_configuration(Configuration::new().responsive(true));
+        plot
+    }
+
+    /// The peak of each metric and the time it was reached, as (metric, peak, time) rows
+    fn peaks(&self) -> Vec<(&'static str, String, f64)> {
+        let metrics: [Metric; 4] = [
+            ("CPU", |s| s.cpu_percent, |v| format!("{:.1} %", v)),
+            ("Memory (RSS)", |s| s.rss_bytes, |v| format!("{:.1} MiB", v / MIB)),
+            ("Read", |s| s.read_bytes_per_sec, |v| format!("{:.1} MiB/s", v / MIB)),
+            ("Write", |s| s.write_bytes_per_sec, |v| format!("{:.1} MiB/s", v / MIB)),
+        ];
+        metrics
+            .iter()
+            .filter_map(|(name, value, format)| {
+                let peak = self.samples.iter().filter(|s| value(s).is_finite()).max_by(|a, b| value(a).total_cmp(&value(b)))?;
+                Some((*name, format(value(peak)), peak.time))
+            })
+            .collect()
+    }
+}
+
+impl Component for ResourceUsage {
+    fn render(&self) -> Markup {
+        let id = format!("resource-usage-{}", crate::ids::placeholder());
+        html! {
+            div class="resource-usage" {
+                @if self.samples.is_empty() {
+                    p { "No resource usage was recorded." }
+                } @else {
+                    div style=(format!("height: {}px;", self.height)) {
+                        (PreEscaped(self.plot().to_inline_html(Some(&id))))
+                    }
+                    table class="key-value" {
+                        thead { tr { th { "Peak usage" } th { "Value" } th { "Time (s)" } } }
+                        tbody {
+                            @for (name, peak, time) in self.peaks() {
+                                tr { th scope="row" { (name) } td { (peak) } td { (time) } }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn libraries(&self) -> Vec<Library> {
+        vec![Library::Plotly]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_usage() {
+        let sample = |time: f64, cpu_percent: f64, rss_mib: f64| ResourceSample {
+            time,
+            cpu_percent,
+            rss_bytes: rss_mib * MIB,
+            ..Default::default()
+        };
+        let usage = ResourceUsage::new(vec![sample(2.0, 350.0, 512.0), sample(1.0, 90.0, 1024.0), sample(3.0, f64::NAN, 256.0)]);
+
+        let json = crate::plots::to_json(&usage.plot());
+        assert_eq!(json["data"][0]["x"], serde_json::json!([1.0, 2.0, 3.0]));
+        assert_eq!(json["data"][1]["y"], serde_json::json!([1024.0, 512.0, 256.0]));
+        assert_eq!(json["layout"]["yaxis3"]["domain"], serde_json::json!([0.0, 0.3]));
+
+        let html = usage.render().into_string();
+        assert!(html.contains(r#"<th scope="row">CPU</th><td>350.0 %</td><td>2</td>"#));
+        assert!(html.contains(r#"<th scope="row">Memory (RSS)</th><td>1024.0 MiB</td><td>1</td>"#));
+        assert!(ResourceUsage::new(Vec::new()).render().into_string().contains("No resource usage"));
+    }
+}
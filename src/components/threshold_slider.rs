@@ -0,0 +1,200 @@
+use maud::{html, Markup, PreEscaped};
+use plotly::Plot;
+
+use super::{json_script, Component};
+use crate::assets::{Asset, Library};
+#[cfg(feature = "tables")]
+use crate::table::Table;
+
+/// Which side of the threshold a [`ThresholdSlider`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Keep {
+    /// Keep the points whose value is at most the threshold, e.g. a q-value cutoff.
+    #[default]
+    AtMost,
+    /// Keep the points whose value is at least the threshold, e.g. a minimum score.
+    AtLeast,
+}
+
+/// A slider bound to a numeric field (e.g. the q-value of each identification) that re-filters its
+/// linked plots and tables in the browser, turning a static report into a lightweight explorer.
+///
+/// Each linked plot comes with the field's value for every point of every trace; moving the slider
+/// restyles the traces with the points passing the threshold. Linked tables are filtered on one of
+/// their columns through a DataTables search.
+pub struct ThresholdSlider {
+    label: String,
+    min: f64,
+    max: f64,
+    step: f64,
+    value: f64,
+    keep: Keep,
+    height: u32,
+    plots: Vec<(Plot, Vec<Vec<f64>>)>,
+    #[cfg(feature = "tables")]
+    tables: Vec<(Table, usize)>,
+}
+
+impl ThresholdSlider {
+    /// Creates a slider over the given range, starting at the maximum.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The name of the field, e.g. "q-value".
+    /// * `min` - The lowest threshold.
+    /// * `max` - The highest threshold.
+    pub fn new(label: &str, min: f64, max: f64) -> Self {
+        ThresholdSlider {
+            label: label.to_string(),
+            min,
+            max,
+            step: (max - min) / 100.0,
+            value: max,
+            keep: Keep::default(),
+            height: 500,
+            plots: Vec::new(),
+            #[cfg(feature = "tables")]
+            tables: Vec::new(),
+        }
+    }
+
+    /// Sets the increment of the slider, one hundredth of the range by default.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets the initial threshold.
+    pub fn value(mut self, value: f64) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets which side of the threshold is kept.
+    pub fn keep(mut self, keep: Keep) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Sets the height of the linked plots in pixels.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Links a plot to the slider.
+    ///
+    /// # Arguments
+    ///
+    /// * `plot` - The plot to filter.
+    /// * `values` - The field's value for each point, one vector per trace in the order the traces
+    ///   were added. Traces without values are left unfiltered.
+    pub fn link_plot(mut self, plot: Plot, values: Vec<Vec<f64>>) -> Self {
+        self.plots.push((plot, values));
+        self
+    }
+
+    /// Links a table to the slider.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to filter.
+    /// * `column` - The index of the column holding the field. Rows whose cell is not a number are
+    ///   kept.
+    #[cfg(feature = "tables")]
+    pub fn link_table(mut self, table: Table, column: usize) -> Self {
+        self.tables.push((table, column));
+        self
+    }
+}
+
+impl Component for ThresholdSlider {
+    fn render(&self) -> Markup {
+        let keep = match self.keep {
+            Keep::AtMost => "at-most",
+            Keep::AtLeast => "at-least",
+        };
+        html! {
+            div class="threshold-slider" data-keep=(keep) {
+                label class="threshold-control" {
+                    (self.label) (if self.keep == Keep::AtMost { " ≤ " } else { " ≥ " })
+                    input type="range" class="threshold-input" min=(self.min) max=(self.max) step=(self.step) value=(self.value);
+                    output class="threshold-value" { (self.value) }
+                }
+                @for (plot, values) in &self.plots {
+                    @let plot_id = format!("plot-{}", crate::ids::placeholder());
+                    div class="threshold-plot" style=(format!("height: {}px;", self.height)) {
+                        (PreEscaped(plot.to_inline_html(Some(&plot_id))))
+                    }
+                    (json_script("threshold-values", &serde_json::to_string(values).unwrap_or_default(), &[("data-plot", &format!("#{plot_id}"))]))
+                }
+                (self.render_tables())
+            }
+        }
+    }
+
+    fn assets(&self) -> Vec<Asset> {
+        vec![Asset::ThresholdSlider]
+    }
+
+    fn libraries(&self) -> Vec<Library> {
+        #[allow(unused_mut)]
+        let mut libraries = vec![Library::Plotly];
+        #[cfg(feature = "tables")]
+        if let Some((table, _)) = self.tables.first() {
+            libraries.extend(table.libraries());
+        }
+        libraries
+    }
+}
+
+impl ThresholdSlider {
+    #[cfg(feature = "tables")]
+    fn render_tables(&self) -> Markup {
+        html! {
+            @for (table, column) in &self.tables {
+                div class="threshold-table" data-table=(format!("#{}", table.table_id())) data-column=(column) {
+                    (table.render())
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "tables"))]
+    fn render_tables(&self) -> Markup {
+        html! {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plotly::Scatter;
+
+    #[test]
+    fn test_threshold_slider() {
+        let mut plot = Plot::new();
+        plot.add_trace(Scatter::new(vec![1, 2, 3], vec![4, 5, 6]));
+        let slider = ThresholdSlider::new("q-value", 0.0, 0.1)
+            .step(0.001)
+            .value(0.01)
+            .link_plot(plot, vec![vec![0.001, 0.02, 0.05]]);
+
+        let html = slider.render().into_string();
+        assert!(html.contains(r#"<div class="threshold-slider" data-keep="at-most">"#));
+        assert!(html.contains(r#"min="0" max="0.1" step="0.001" value="0.01""#));
+        assert!(html.contains(r#"class="threshold-values""#) && html.contains("[[0.001,0.02,0.05]]"));
+        assert_eq!(slider.assets(), vec![Asset::ThresholdSlider]);
+    }
+
+    #[cfg(feature = "tables")]
+    #[test]
+    fn test_threshold_slider_table() {
+        let table = Table::new().id("psms").headers(["Peptide", "q-value"]).add_row(["PEPTIDE", "0.01"]);
+        let slider = ThresholdSlider::new("q-value", 0.0, 0.05).keep(Keep::AtLeast).link_table(table, 1);
+
+        let html = slider.render().into_string();
+        assert!(html.contains(r##"<div class="threshold-table" data-table="#psms" data-column="1">"##));
+        assert!(slider.libraries().contains(&Library::DataTables));
+    }
+}
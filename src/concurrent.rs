@@ -0,0 +1,131 @@
+//! Assembling a report from sections built on several threads.
+//!
+//! [`Report`] and [`ReportSection`] are `Send + Sync`, so sections can be built anywhere and moved
+//! between threads. A [`SectionCollector`] gathers them from any number of threads through a
+//! shared reference, and hands them to the report in a fixed order, however the threads finish.
+//!
+//! ```rust,ignore
+//! use report_builder::{concurrent::SectionCollector, Report, ReportSection};
+//!
+//! let collector = SectionCollector::new();
+//! std::thread::scope(|scope| {
+//!     for (i, run) in runs.iter().enumerate() {
+//!         let collector = &collector;
+//!         scope.spawn(move || collector.insert(i, build_run_section(run)).unwrap());
+//!     }
+//! });
+//! let mut report = Report::new("Redeem", "1.0", None, "Runs");
+//! report.add_collected(collector);
+//! ```
+
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::{Report, ReportError, ReportSection};
+
+/// A thread-safe collection of sections, ordered by the position each was inserted at.
+#[derive(Default)]
+pub struct SectionCollector {
+    sections: Mutex<BTreeMap<usize, ReportSection>>,
+    next: AtomicUsize,
+}
+
+impl SectionCollector {
+    /// Creates an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserves the next position, in the order the calls are made (e.g. before spawning the
+    /// thread that builds the section). Positions count up from 0.
+    pub fn reserve(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Adds a section at the given position, from any thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Where the section goes among the collected sections, e.g. the index of the
+    ///   input it was built from or a position from [`SectionCollector::reserve`].
+    /// * `section` - The section.
+    ///
+    /// # Returns
+    ///
+    /// A Result indicating success or [`ReportError::InvalidArgument`] if a section was already
+    /// collected at `position`, in which case that section is kept and `section` is dropped.
+    pub fn insert(&self, position: usize, section: ReportSection) -> Result<(), ReportError> {
+        match self.sections.lock().unwrap_or_else(PoisonError::into_inner).entry(position) {
+            Entry::Occupied(_) => Err(ReportError::InvalidArgument(format!(
+                "A section was already collected at position {}",
+                position
+            ))),
+            Entry::Vacant(entry) => {
+                entry.insert(section);
+                Ok(())
+            }
+        }
+    }
+
+    /// The number of sections collected so far.
+    pub fn len(&self) -> usize {
+        self.sections.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Returns true if no sections have been collected.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The collected sections, ordered by position.
+    pub fn into_sections(self) -> Vec<ReportSection> {
+        self.sections
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_values()
+            .collect()
+    }
+}
+
+impl Report {
+    /// Adds the sections gathered by a [`SectionCollector`], ordered by their positions.
+    pub fn add_collected(&mut self, collector: SectionCollector) {
+        for section in collector.into_sections() {
+            self.add_section(section);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Report>();
+        assert_send_sync::<ReportSection>();
+        assert_send_sync::<SectionCollector>();
+    }
+
+    #[test]
+    fn test_collect_from_threads() {
+        let collector = SectionCollector::new();
+        let first = collector.reserve();
+        std::thread::scope(|scope| {
+            for i in (1..8).rev() {
+                let collector = &collector;
+                scope.spawn(move || collector.insert(i, ReportSection::new(&format!("Run {}", i))).unwrap());
+            }
+        });
+        collector.insert(first, ReportSection::new("Summary")).unwrap();
+        assert!(matches!(collector.insert(first, ReportSection::new("Duplicate")), Err(ReportError::InvalidArgument(_))));
+        assert_eq!(collector.len(), 8);
+
+        let mut report = Report::new("Redeem", "1.0", None, "Runs");
+        report.add_collected(collector);
+        let titles: Vec<&str> = report.sections.iter().map(|section| section.title.as_str()).collect();
+        assert_eq!(titles, ["Summary", "Run 1", "Run 2", "Run 3", "Run 4", "Run 5", "Run 6", "Run 7"]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Cover pages for formal reports.
+//!
+//! A [`CoverPage`] set with [`Report::with_cover_page`](crate::Report::with_cover_page) fills the
+//! first screen of the report, and the first page when it is printed or exported to PDF, before
+//! the banner and the tab bar:
+//!
+//! ```rust,ignore
+//! use report_builder::cover::CoverPage;
+//!
+//! let cover = CoverPage::new("Method validation report")
+//!     .subtitle("LC-MS/MS quantification of plasma proteins")
+//!     .author("J. Doe")
+//!     .affiliation("Proteomics Core Facility")
+//!     .document_id("VAL-2024-017");
+//! let report = Report::new("Redeem", "1.0", None, "Validation").with_cover_page(cover);
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+/// The cover page of a report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverPage {
+    title: String,
+    subtitle: Option<String>,
+    authors: Vec<String>,
+    affiliations: Vec<String>,
+    logo: Option<String>,
+    date: Option<String>,
+    document_id: Option<String>,
+}
+
+impl CoverPage {
+    /// Creates a cover page with the given title and nothing else.
+    pub fn new(title: &str) -> Self {
+        CoverPage {
+            title: title.to_string(),
+            subtitle: None,
+            authors: Vec::new(),
+            affiliations: Vec::new(),
+            logo: None,
+            date: None,
+            document_id: None,
+        }
+    }
+
+    /// Sets a subtitle shown below the title.
+    pub fn subtitle(mut self, subtitle: &str) -> Self {
+        self.subtitle = Some(subtitle.to_string());
+        self
+    }
+
+    /// Adds an author; authors are listed in the order they were added.
+    pub fn author(mut self, author: &str) -> Self {
+        self.authors.push(author.to_string());
+        self
+    }
+
+    /// Adds an affiliation, e.g. an institute or company, listed below the authors.
+    pub fn affiliation(mut self, affiliation: &str) -> Self {
+        self.affiliations.push(affiliation.to_string());
+        self
+    }
+
+    /// Sets a logo shown above the title, e.g. of the organization issuing the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `logo` - The path or URL of the image.
+    pub fn logo(mut self, logo: &str) -> Self {
+        self.logo = Some(logo.to_string());
+        self
+    }
+
+    /// Sets the date of the document, e.g. "2024-03-01"; the date the report is rendered by default.
+    pub fn date(mut self, date: &str) -> Self {
+        self.date = Some(date.to_string());
+        self
+    }
+
+    /// Sets the identifier of the document, e.g. its number in a document management system.
+    pub fn document_id(mut self, document_id: &str) -> Self {
+        self.document_id = Some(document_id.to_string());
+        self
+    }
+
+    /// Render the cover page, with the given date if none was set
+    pub(crate) fn render(&self, default_date: Option<&str>) -> Markup {
+        html! {
+            section class="cover-page" {
+                @if let Some(logo) = &self.logo {
+                    img class="cover-logo" src=(logo) alt="Logo";
+                }
+                h1 class="cover-title" { (self.title) }
+                @if let Some(subtitle) = &self.subtitle {
+                    p class="cover-subtitle" { (subtitle) }
+                }
+                @if !self.authors.is_empty() {
+                    p class="cover-authors" { (self.authors.join(", ")) }
+                }
+                @for affiliation in &self.affiliations {
+                    p class="cover-affiliation" { (affiliation) }
+                }
+                dl class="cover-details" {
+                    @if let Some(date) = self.date.as_deref().or(default_date) {
+                        dt { "Date" } dd { (date) }
+                    }
+                    @if let Some(document_id) = &self.document_id {
+                        dt { "Document ID" } dd { (document_id) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover_page() {
+        let cover = CoverPage::new("Validation report").subtitle("Plasma <proteins>").author("A. Doe").author("B. Roe").affiliation("Core Facility").document_id("VAL-17");
+        let html = cover.render(Some("2024-03-01")).into_string();
+        assert!(html.starts_with(r#"<section class="cover-page"><h1 class="cover-title">Validation report</h1><p class="cover-subtitle">Plasma &lt;proteins&gt;</p>"#));
+        assert!(html.contains(r#"<p class="cover-authors">A. Doe, B. Roe</p><p class="cover-affiliation">Core Facility</p>"#));
+        assert!(html.contains("<dt>Date</dt><dd>2024-03-01</dd><dt>Document ID</dt><dd>VAL-17</dd>"));
+        assert!(cover.date("2023-12-24").render(Some("2024-03-01")).into_string().contains("<dd>2023-12-24</dd>"));
+    }
+}
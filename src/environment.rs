@@ -0,0 +1,156 @@
+//! Capture of the execution environment, for reproducibility.
+//!
+//! [`Environment::capture`] records the operating system, CPU, memory, host, toolchain and
+//! container of the running process, and renders them as a standardized "Environment" section:
+//!
+//! ```rust,ignore
+//! use report_builder::environment::Environment;
+//!
+//! let environment = Environment::capture().with_env_vars(&["SLURM_JOB_ID", "NXF_VER"]);
+//! report.add_section(environment.to_section());
+//! ```
+//!
+//! Environment variables may hold secrets, so only those explicitly listed are included.
+
+use maud::Markup;
+
+use crate::components::Component;
+use crate::ReportSection;
+
+/// The version of rustc the crate was compiled with, see `build.rs`
+const RUSTC_VERSION: &str = env!("REPORT_BUILDER_RUSTC_VERSION");
+
+/// Environment variables holding the digest of the container image the process runs in
+const IMAGE_DIGEST_VARS: [&str; 2] = ["CONTAINER_IMAGE_DIGEST", "IMAGE_DIGEST"];
+
+/// A description of the environment a report was generated in, as ordered key-value entries.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Environment {
+    entries: Vec<(String, String)>,
+}
+
+impl Environment {
+    /// Captures the environment of the running process: operating system, architecture, CPU,
+    /// memory, hostname, rustc and report-builder versions, and container. Details that cannot be
+    /// determined on the platform (e.g. the CPU model outside Linux) are left out.
+    ///
+    /// The digest of the container image is read from the `CONTAINER_IMAGE_DIGEST` or
+    /// `IMAGE_DIGEST` environment variable, e.g. set by the pipeline launching the container.
+    pub fn capture() -> Self {
+        let mut environment = Environment::default();
+        environment.push("Operating system", format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH));
+        if let Some(model) = read_proc_field("/proc/cpuinfo", "model name") {
+            environment.push("CPU", model);
+        }
+        if let Ok(cpus) = std::thread::available_parallelism() {
+            environment.push("Logical CPUs", cpus.to_string());
+        }
+        if let Some(memory) = read_proc_field("/proc/meminfo", "MemTotal").and_then(|total| format_memory(&total)) {
+            environment.push("Memory", memory);
+        }
+        if let Some(hostname) = hostname() {
+            environment.push("Hostname", hostname);
+        }
+        if !RUSTC_VERSION.is_empty() {
+            environment.push("Compiler", RUSTC_VERSION.to_string());
+        }
+        environment.push("report-builder", env!("CARGO_PKG_VERSION").to_string());
+
+        let in_container = ["/.dockerenv", "/run/.containerenv"].iter().any(|path| std::path::Path::new(path).exists());
+        environment.push("Container", if in_container { "yes" } else { "no" }.to_string());
+        if let Some(digest) = IMAGE_DIGEST_VARS.iter().find_map(|var| std::env::var(var).ok()) {
+            environment.push("Container image digest", digest);
+        }
+        environment
+    }
+
+    /// Adds the values of the given environment variables, skipping those that are not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowlist` - The names of the variables to include, e.g. `["SLURM_JOB_ID"]`.
+    pub fn with_env_vars(mut self, allowlist: &[&str]) -> Self {
+        for var in allowlist {
+            if let Ok(value) = std::env::var(var) {
+                self.push(&format!("${}", var), value);
+            }
+        }
+        self
+    }
+
+    /// Adds an entry, e.g. the version of a tool called by the pipeline.
+    pub fn with_entry(mut self, key: &str, value: &str) -> Self {
+        self.push(key, value.to_string());
+        self
+    }
+
+    /// The entries of the environment, in order.
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    /// Creates an "Environment" section listing the entries.
+    pub fn to_section(&self) -> ReportSection {
+        let mut section = ReportSection::new("Environment");
+        section.add(self.clone());
+        section
+    }
+
+    fn push(&mut self, key: &str, value: String) {
+        self.entries.push((key.to_string(), value));
+    }
+}
+
+impl Component for Environment {
+    fn render(&self) -> Markup {
+        crate::render_key_values(&self.entries)
+    }
+}
+
+/// The value of the first "key : value" line with the given key in a /proc file
+fn read_proc_field(path: &str, key: &str) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+/// Format a /proc/meminfo size, e.g. "16318360 kB" as "15.6 GiB"
+fn format_memory(total: &str) -> Option<String> {
+    let kib: f64 = total.trim_end_matches("kB").trim().parse().ok()?;
+    Some(format!("{:.1} GiB", kib / (1024.0 * 1024.0)))
+}
+
+/// The name of the host, from the environment or /etc/hostname
+fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        std::env::set_var("REPORT_BUILDER_ENVIRONMENT_TEST", "42");
+        let environment = Environment::capture()
+            .with_env_vars(&["REPORT_BUILDER_ENVIRONMENT_TEST", "REPORT_BUILDER_UNSET_VARIABLE"])
+            .with_entry("Pipeline", "v2.1");
+        let keys: Vec<&str> = environment.entries().iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys[0], "Operating system");
+        assert!(keys.contains(&"report-builder") && keys.contains(&"Container"));
+        assert!(!keys.contains(&"$REPORT_BUILDER_UNSET_VARIABLE"));
+        assert_eq!(environment.entries().last().unwrap(), &("Pipeline".to_string(), "v2.1".to_string()));
+
+        let html = environment.to_section().render_to_string();
+        assert!(html.contains("<h2>Environment</h2>"));
+        assert!(html.contains(r#"<th scope="row">$REPORT_BUILDER_ENVIRONMENT_TEST</th><td>42</td>"#));
+        assert_eq!(format_memory("16318360 kB").as_deref(), Some("15.6 GiB"));
+    }
+}
@@ -0,0 +1,136 @@
+//! The error type of the plot helpers and of saving reports.
+//!
+//! Invalid input (e.g. scores and labels of different lengths) is returned as a [`ReportError`]
+//! rather than panicking, so callers can skip a plot or report the problem:
+//!
+//! ```rust,ignore
+//! use report_builder::{plots::plot_roc, ReportError};
+//!
+//! match plot_roc(&scores, &labels, "ROC") {
+//!     Ok(plot) => section.add_plot(plot),
+//!     Err(ReportError::EmptyData(reason)) => section.add_content(html! { p { "No ROC curve: " (reason) } }),
+//!     Err(e) => return Err(e.into()),
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// An error building a plot or saving a report.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReportError {
+    /// Inputs that must be paired element by element have different lengths
+    LengthMismatch {
+        /// What was counted, e.g. "labels"
+        what: &'static str,
+        /// The length required by the other inputs
+        expected: usize,
+        found: usize,
+    },
+    /// A target/decoy label other than 1 (target) or -1 (decoy)
+    InvalidLabel(i32),
+    /// There is nothing to plot, e.g. no columns or no finite values
+    EmptyData(&'static str),
+    /// An argument outside its valid range
+    InvalidArgument(String),
+    /// A plot or document could not be converted to or from JSON
+    Serialization(String),
+    /// A command of a command stream (see `commands`) cannot be applied
+    InvalidCommand {
+        /// The position of the command in the stream
+        index: usize,
+        message: String,
+    },
+    /// Reading or writing a file failed
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+#[cfg_attr(not(feature = "plots"), allow(dead_code))]
+impl ReportError {
+    /// Returns a [`ReportError::LengthMismatch`] if `found` differs from `expected`.
+    pub(crate) fn check_len(what: &'static str, expected: usize, found: usize) -> Result<(), ReportError> {
+        if expected == found {
+            Ok(())
+        } else {
+            Err(ReportError::LengthMismatch { what, expected, found })
+        }
+    }
+
+    /// Checks there is one target/decoy label, 1 or -1, per score.
+    pub(crate) fn check_labels(n_scores: usize, labels: &[i32]) -> Result<(), ReportError> {
+        Self::check_len("labels", n_scores, labels.len())?;
+        match labels.iter().find(|&&label| label != 1 && label != -1) {
+            Some(&label) => Err(ReportError::InvalidLabel(label)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::LengthMismatch { what, expected, found } => {
+                write!(f, "Length mismatch: expected {} {}, found {}", expected, what, found)
+            }
+            ReportError::InvalidLabel(label) => {
+                write!(f, "Invalid label {}: labels must be 1 for targets and -1 for decoys", label)
+            }
+            ReportError::EmptyData(reason) => write!(f, "Nothing to plot: {}", reason),
+            ReportError::InvalidArgument(message) => write!(f, "Invalid argument: {}", message),
+            ReportError::Serialization(message) => write!(f, "Serialization failed: {}", message),
+            ReportError::InvalidCommand { index, message } => write!(f, "Invalid command {}: {}", index, message),
+            #[cfg(feature = "std")]
+            ReportError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReportError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ReportError {
+    fn from(e: std::io::Error) -> Self {
+        ReportError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ReportError {
+    fn from(e: serde_json::Error) -> Self {
+        ReportError::Serialization(e.to_string())
+    }
+}
+
+/// Lets `?` turn a report error into an IO error, e.g. in functions returning `io::Result`.
+#[cfg(feature = "std")]
+impl From<ReportError> for std::io::Error {
+    fn from(e: ReportError) -> Self {
+        match e {
+            ReportError::Io(e) => e,
+            e => std::io::Error::new(std::io::ErrorKind::InvalidInput, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_labels() {
+        assert!(ReportError::check_labels(2, &[1, -1]).is_ok());
+        let e = ReportError::check_labels(3, &[1, -1]).unwrap_err();
+        assert_eq!(e.to_string(), "Length mismatch: expected 3 labels, found 2");
+        let e = ReportError::check_labels(2, &[1, 0]).unwrap_err();
+        assert!(matches!(e, ReportError::InvalidLabel(0)));
+    }
+}
@@ -0,0 +1,172 @@
+//! A C ABI over the [JSON command stream](crate::commands).
+//!
+//! Build a shared or static library with the `ffi` feature, e.g.
+//! `cargo rustc --release --features ffi --crate-type cdylib`, and declare the functions from
+//! `include/report_builder.h`:
+//!
+//! ```c
+//! #include "report_builder.h"
+//!
+//! if (report_builder_save(commands_json, "report.html") != 0) {
+//!     fprintf(stderr, "%s\n", report_builder_last_error());
+//! }
+//! ```
+//!
+//! Panics inside the library (e.g. a table row with the wrong number of cells) are caught at the
+//! boundary and reported like any other error, as unwinding into C is undefined behavior.
+//!
+//! All strings are NUL-terminated UTF-8. Strings returned by the library are owned by the caller
+//! and must be released with [`report_builder_free_string`].
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+
+use crate::{commands, Report};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run the body of an exported function, returning `failure` with the panic message as the last
+/// error if it panics
+fn catch_panic<T>(failure: T, body: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or_else(|panic| {
+        let message = match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+            (Some(message), _) => message.to_string(),
+            (None, Some(message)) => message.clone(),
+            (None, None) => "unknown panic".to_string(),
+        };
+        set_last_error(format!("Internal error: {}", message));
+        failure
+    })
+}
+
+/// Read a C string argument and build the report it describes
+///
+/// # Safety
+///
+/// `commands` must be null or a valid NUL-terminated string.
+unsafe fn report_from(commands: *const c_char) -> Option<Report> {
+    if commands.is_null() {
+        set_last_error("The commands must not be null".to_string());
+        return None;
+    }
+    let result = CStr::from_ptr(commands)
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(|json| commands::from_commands(json).map_err(|e| e.to_string()));
+    result.map_err(set_last_error).ok()
+}
+
+/// Builds a report from a JSON command stream and renders it as an HTML document.
+///
+/// Returns the document, to be released with [`report_builder_free_string`], or null on error
+/// (see [`report_builder_last_error`]).
+///
+/// # Safety
+///
+/// `commands` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn report_builder_render(commands: *const c_char) -> *mut c_char {
+    catch_panic(std::ptr::null_mut(), || {
+        let Some(report) = report_from(commands) else {
+            return std::ptr::null_mut();
+        };
+        match CString::new(report.render_to_string()) {
+            Ok(html) => html.into_raw(),
+            Err(e) => {
+                set_last_error(e.to_string());
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Builds a report from a JSON command stream and saves it to an HTML file.
+///
+/// Returns 0 on success, or -1 on error (see [`report_builder_last_error`]).
+///
+/// # Safety
+///
+/// `commands` and `filename` must be null or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn report_builder_save(commands: *const c_char, filename: *const c_char) -> c_int {
+    catch_panic(-1, || {
+        if filename.is_null() {
+            set_last_error("The filename must not be null".to_string());
+            return -1;
+        }
+        let Some(report) = report_from(commands) else {
+            return -1;
+        };
+        let result = CStr::from_ptr(filename)
+            .to_str()
+            .map_err(|e| e.to_string())
+            .and_then(|filename| report.save_to_file(filename).map_err(|e| e.to_string()));
+        match result {
+            Ok(_) => 0,
+            Err(message) => {
+                set_last_error(message);
+                -1
+            }
+        }
+    })
+}
+
+/// The message of the last error raised on the calling thread, or null if there was none.
+///
+/// The string is owned by the library and valid until the next call on the same thread.
+#[no_mangle]
+pub extern "C" fn report_builder_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+/// Releases a string returned by the library.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this library that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn report_builder_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let commands = CString::new(
+            r#"[{ "command": "report", "software_name": "Redeem", "version": "1.0", "title": "Run QC" },
+                { "command": "section", "title": "Scores" }]"#,
+        )
+        .unwrap();
+        unsafe {
+            let html = report_builder_render(commands.as_ptr());
+            assert!(!html.is_null());
+            assert!(CStr::from_ptr(html).to_str().unwrap().contains("Run QC"));
+            report_builder_free_string(html);
+
+            let invalid = CString::new("[]").unwrap();
+            assert!(report_builder_render(invalid.as_ptr()).is_null());
+            let error = CStr::from_ptr(report_builder_last_error()).to_str().unwrap();
+            assert_eq!(error, "Invalid command 0: the first command must be 'report'");
+        }
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(-1, || -> c_int { panic!("Rows must have {} cells", 3) }), -1);
+        let error = unsafe { CStr::from_ptr(report_builder_last_error()) };
+        assert_eq!(error.to_str().unwrap(), "Internal error: Rows must have 3 cells");
+        assert_eq!(catch_panic(-1, || 0), 0);
+    }
+}
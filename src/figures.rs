@@ -0,0 +1,372 @@
+//! Export of a report's figures as static image files, e.g. for a manuscript.
+//!
+//! Every plot of the report is drawn by Plotly in a headless browser, as for
+//! [PDF export](crate::pdf), and saved as its own PNG, SVG or PDF file, named after its number
+//! and title. The figures are listed in a `figures.json` file next to them, with their captions,
+//! sections and input data fingerprints, for manuscript tooling to pick up:
+//!
+//! ```rust,ignore
+//! use report_builder::figures::FigureFormat;
+//!
+//! for figure in report.export_figures("figures", FigureFormat::Png, 300)? {
+//!     println!("{}: {}", figure.id, figure.path.display());
+//! }
+//! ```
+//!
+//! ```json
+//! {
+//!   "report": "QC Report",
+//!   "figures": [
+//!     {
+//!       "id": "figure-01-intensity-distribution",
+//!       "caption": "Intensity distribution",
+//!       "section": "QC / Intensities",
+//!       "lineage": [{ "path": "psms.tsv", "sha256": "9f86d0...", "rows": 120000 }],
+//!       "file": "figure-01-intensity-distribution.png"
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::assets::Library;
+use crate::lineage::DataFingerprint;
+use crate::pdf::{self, PdfOptions};
+use crate::schema::BlockData;
+use crate::{slugify, Report, ReportError, ReportSection};
+
+/// The size figures are drawn at when their layout does not set one, in CSS pixels
+const DEFAULT_SIZE: (u32, u32) = (800, 600);
+
+/// The resolution of a CSS pixel, in dots per inch
+const CSS_DPI: f64 = 96.0;
+
+/// The file listing the exported figures, written next to them
+pub const FIGURE_INDEX: &str = "figures.json";
+
+/// The file format of exported figures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FigureFormat {
+    /// A raster image, drawn at the requested resolution
+    Png,
+    /// A vector image, editable in Inkscape or Illustrator
+    Svg,
+    /// A vector PDF, one page the size of the figure
+    Pdf,
+}
+
+impl FigureFormat {
+    /// The extension of files in the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FigureFormat::Png => "png",
+            FigureFormat::Svg => "svg",
+            FigureFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// A figure written by [`Report::export_figures`], as listed in [`FIGURE_INDEX`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedFigure {
+    /// The name of the figure's file without its extension, e.g. "figure-02-intensity-distribution"
+    pub id: String,
+    /// The title of the plot, if it has one
+    pub caption: Option<String>,
+    /// The titles of the section holding the figure and of its parents, joined with " / "
+    pub section: String,
+    /// Fingerprints of the input files the figure was produced from
+    pub lineage: Vec<DataFingerprint>,
+    /// The file the figure was written to; listed by its name, relative to the index
+    #[serde(rename = "file", serialize_with = "serialize_file_name")]
+    pub path: PathBuf,
+    #[serde(skip)]
+    figure: Value,
+}
+
+/// Serialize a path as its file name
+fn serialize_file_name<S: serde::Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.file_name().unwrap_or_default().to_string_lossy())
+}
+
+/// A figure of a section: the section's path, the figure and its lineage
+type Figure<'a> = (String, &'a Value, &'a [DataFingerprint]);
+
+/// Collect the figures of a section and its subsections, in order of appearance
+fn collect_figures<'a>(section: &'a ReportSection, parent: &str, figures: &mut Vec<Figure<'a>>) {
+    let path = match parent.is_empty() {
+        true => section.title.clone(),
+        false => format!("{} / {}", parent, section.title),
+    };
+    for block in &section.content_blocks {
+        if let Some(BlockData::Plot { figure }) = &block.data {
+            figures.push((path.clone(), figure, &block.lineage));
+        }
+    }
+    for subsection in &section.subsections {
+        collect_figures(subsection, &path, figures);
+    }
+}
+
+/// The size the figure is drawn at: its layout's, or [`DEFAULT_SIZE`]
+fn figure_size(figure: &Value) -> (u32, u32) {
+    let size = |key: &str, default: u32| figure["layout"][key].as_f64().map_or(default, |v| v.round() as u32);
+    (size("width", DEFAULT_SIZE.0), size("height", DEFAULT_SIZE.1))
+}
+
+/// The page drawing a figure: as an image data URL in `pre#figure-image` for PNG and SVG, or as
+/// a static plot filling a page of its size for PDF
+fn figure_page(figure: &Value, format: FigureFormat, dpi: u32, plotly: &Markup) -> String {
+    let (width, height) = figure_size(figure);
+    let mut figure = figure.clone();
+    figure["layout"]["width"] = width.into();
+    figure["layout"]["height"] = height.into();
+    let figure = serde_json::to_string(&figure).unwrap_or_default().replace("</", "<\\/");
+    let script = match format {
+        FigureFormat::Pdf => "Plotly.newPlot('figure', figure.data, figure.layout, { staticPlot: true });".to_string(),
+        FigureFormat::Png | FigureFormat::Svg => format!(
+            "Plotly.toImage(figure, {{ format: '{}', width: {}, height: {}, scale: {} }})\
+             .then(function (url) {{ document.getElementById('figure-image').textContent = url; }});",
+            format.extension(),
+            width,
+            height,
+            dpi as f64 / CSS_DPI
+        ),
+    };
+    html! {
+        (DOCTYPE)
+        html {
+            head {
+                meta charset="utf-8";
+                (plotly)
+                style { (format!("@page {{ size: {}px {}px; margin: 0; }} body {{ margin: 0; }}", width, height)) }
+            }
+            body {
+                div id="figure" {}
+                pre id="figure-image" {}
+                script { (PreEscaped(format!("const figure = {};\n{}", figure, script))) }
+            }
+        }
+    }
+    .into_string()
+}
+
+/// Build the command printing the DOM of the page once its scripts have run
+fn dump_command(browser: &Path, page: &Path, options: &PdfOptions) -> Command {
+    let mut command = Command::new(browser);
+    command
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg(format!("--virtual-time-budget={}", options.render_delay_ms))
+        .arg("--dump-dom")
+        .args(&options.extra_args)
+        .arg(page);
+    command
+}
+
+/// The image written into `pre#figure-image` of a dumped page, decoded from its data URL
+fn decode_image(dom: &str) -> Option<Vec<u8>> {
+    let start = dom.find("<pre id=\"figure-image\">")? + "<pre id=\"figure-image\">".len();
+    let url = dom[start..].split("</pre>").next()?.trim();
+    if let Some(data) = url.strip_prefix("data:image/png;base64,") {
+        return BASE64_STANDARD.decode(data).ok();
+    }
+    let data = url.strip_prefix("data:image/svg+xml,")?.as_bytes();
+    let mut decoded = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match (data[i], data.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) => {
+                decoded.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Some(decoded)
+}
+
+impl Report {
+    /// The figures of the report, with the files they are exported to in a directory, in order of
+    /// appearance
+    fn figures(&self, dir: &Path, format: FigureFormat) -> Vec<ExportedFigure> {
+        let mut figures = Vec::new();
+        for section in &self.sections {
+            collect_figures(section, "", &mut figures);
+        }
+        figures
+            .into_iter()
+            .enumerate()
+            .map(|(i, (section, figure, lineage))| {
+                let caption = figure["layout"]["title"]["text"].as_str().filter(|title| !title.is_empty()).map(str::to_string);
+                let id = match caption.as_deref().map(slugify).filter(|slug| !slug.is_empty()) {
+                    Some(slug) => format!("figure-{:02}-{}", i + 1, slug),
+                    None => format!("figure-{:02}", i + 1),
+                };
+                let path = dir.join(format!("{}.{}", id, format.extension()));
+                ExportedFigure {
+                    id,
+                    caption,
+                    section,
+                    lineage: lineage.to_vec(),
+                    path,
+                    figure: figure.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes every figure of the report to its own file, drawn by a headless browser found on
+    /// the system, and lists them in [`FIGURE_INDEX`], see [`figures`](crate::figures).
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to write the figures to, created if missing.
+    /// * `format` - The file format of the figures.
+    /// * `dpi` - The resolution of PNG figures, e.g. 300 for print; vector formats ignore it.
+    ///
+    /// # Returns
+    ///
+    /// The figures written, in order of appearance, or [`ReportError::Io`] if no browser is found
+    /// or a figure cannot be drawn.
+    pub fn export_figures<P: AsRef<Path>>(&self, dir: P, format: FigureFormat, dpi: u32) -> Result<Vec<ExportedFigure>, ReportError> {
+        self.export_figures_with_options(dir, format, dpi, &PdfOptions::default())
+    }
+
+    /// Writes every figure of the report to its own file, with the given browser and rendering
+    /// options, see [`Report::export_figures`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to write the figures to, created if missing.
+    /// * `format` - The file format of the figures.
+    /// * `dpi` - The resolution of PNG figures, e.g. 300 for print; vector formats ignore it.
+    /// * `options` - The browser, render delay, extra arguments and Plotly source to use.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, dir, options)))]
+    pub fn export_figures_with_options<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        format: FigureFormat,
+        dpi: u32,
+        options: &PdfOptions,
+    ) -> Result<Vec<ExportedFigure>, ReportError> {
+        let browser = options.browser.clone().or_else(pdf::find_browser).ok_or_else(|| {
+            ReportError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No browser found to draw the figures; install Chromium or set REPORT_BUILDER_BROWSER",
+            ))
+        })?;
+        if browser.file_stem().is_some_and(|stem| stem.to_string_lossy().contains("wkhtmltopdf")) {
+            return Err(ReportError::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "wkhtmltopdf cannot draw figures; install Chromium or set REPORT_BUILDER_BROWSER",
+            )));
+        }
+
+        let dir = std::env::current_dir()?.join(dir);
+        std::fs::create_dir_all(&dir)?;
+        let plotly = options.asset_mode.render_library(Library::Plotly)?;
+        let figures = self.figures(&dir, format);
+        for figure in &figures {
+            let page = figure.path.with_extension("export.html");
+            std::fs::write(&page, figure_page(&figure.figure, format, dpi, &plotly))?;
+            let mut command = match format {
+                FigureFormat::Pdf => pdf::print_command(&browser, &page, &figure.path, options),
+                FigureFormat::Png | FigureFormat::Svg => dump_command(&browser, &page, options),
+            };
+            let output = command.output().map_err(ReportError::from);
+            std::fs::remove_file(&page)?;
+            let output = output?;
+            let drawn = match format {
+                FigureFormat::Pdf => output.status.success() && figure.path.exists(),
+                FigureFormat::Png | FigureFormat::Svg => match decode_image(&String::from_utf8_lossy(&output.stdout)) {
+                    Some(image) => {
+                        std::fs::write(&figure.path, image)?;
+                        true
+                    }
+                    None => false,
+                },
+            };
+            if !drawn {
+                return Err(ReportError::Io(io::Error::other(format!(
+                    "'{}' failed to draw figure '{}': {}",
+                    browser.display(),
+                    figure.id,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))));
+            }
+        }
+
+        let index = json!({ "report": self.title, "figures": figures });
+        std::fs::write(dir.join(FIGURE_INDEX), serde_json::to_string_pretty(&index).map_err(io::Error::from)?)?;
+
+        Ok(figures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plotly::layout::Layout;
+    use plotly::{Plot, Scatter};
+
+    #[test]
+    fn test_figures() {
+        let mut plot = Plot::new();
+        plot.add_trace(Scatter::new(vec![1, 2], vec![3, 4]));
+        plot.set_layout(Layout::new().title("Intensity distribution").width(1000));
+        let mut subsection = ReportSection::new("Details");
+        subsection.add_plot(Plot::new());
+        let mut section = ReportSection::new("QC");
+        section.add_plot_with_lineage(plot, vec![DataFingerprint::new("psms.tsv", "9f86d0", Some(2))]);
+        section.add_subsection(subsection);
+        let mut report = Report::new("Redeem", "1.0", None, "Figures");
+        report.add_section(section);
+
+        let figures = report.figures(Path::new("out"), FigureFormat::Svg);
+        assert_eq!(figures.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), ["figure-01-intensity-distribution", "figure-02"]);
+        assert_eq!(figures[0].caption.as_deref(), Some("Intensity distribution"));
+        assert_eq!(figures[1].section, "QC / Details");
+        assert_eq!(figures[1].path, Path::new("out/figure-02.svg"));
+        assert_eq!(
+            serde_json::to_value(&figures[0]).unwrap(),
+            json!({
+                "id": "figure-01-intensity-distribution",
+                "caption": "Intensity distribution",
+                "section": "QC",
+                "lineage": [{ "path": "psms.tsv", "sha256": "9f86d0", "rows": 2 }],
+                "file": "figure-01-intensity-distribution.svg"
+            })
+        );
+
+        let page = figure_page(&figures[0].figure, FigureFormat::Png, 300, &Library::Plotly.render_cdn());
+        assert!(page.contains("format: 'png', width: 1000, height: 600, scale: 3.125"));
+        assert!(page.contains("@page { size: 1000px 600px; margin: 0; }"));
+
+        let missing = PdfOptions { browser: Some(PathBuf::from("/nonexistent/chromium")), ..Default::default() };
+        let dir = std::env::temp_dir().join(format!("report-builder-figures-{}", std::process::id()));
+        assert!(report.export_figures_with_options(&dir, FigureFormat::Png, 300, &missing).is_err());
+        assert!(!dir.join("figure-01-intensity-distribution.export.html").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_decode_image() {
+        let dom = r#"<html><body><pre id="figure-image">data:image/svg+xml,%3Csvg%20width%3D%2210%22%3E%3C%2Fsvg%3E</pre></body></html>"#;
+        assert_eq!(decode_image(dom).unwrap(), br#"<svg width="10"></svg>"#);
+        let dom = r#"<pre id="figure-image">data:image/png;base64,iVBORw0K</pre>"#;
+        assert_eq!(decode_image(dom).unwrap(), b"\x89PNG\r\n");
+        assert_eq!(decode_image(r#"<pre id="figure-image"></pre>"#), None);
+    }
+}
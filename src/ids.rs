@@ -0,0 +1,106 @@
+//! Element IDs of plots, tables and components.
+//!
+//! Blocks are given a placeholder ID when they are added to a section, unique within the program.
+//! When the section is rendered, each placeholder is replaced by an ID numbered in order of
+//! appearance and prefixed by the section's anchor, e.g. `plot-qc-metrics-1`, so the IDs of a
+//! report depend only on its content and the same report renders to the same HTML on every run.
+//!
+//! Placeholders are kept in serialized reports, and numbered by a counter that restarts in every
+//! process, so a loaded report has its placeholders replaced by new ones (see [`renew`]) before
+//! more blocks are added to it.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+
+/// The start of a placeholder ID, followed by its number and [`END`]
+const START: &str = "__rbid";
+const END: &str = "__";
+
+/// A placeholder ID, unique within the program, replaced when the section holding it is rendered
+pub(crate) fn placeholder() -> String {
+    static COUNTER: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(1);
+    format!("{}{}{}", START, COUNTER.fetch_add(1, core::sync::atomic::Ordering::Relaxed), END)
+}
+
+/// Replace each placeholder of a text by its number's replacement, leaving anything else that
+/// starts like a placeholder as it is
+fn replace_placeholders(text: &str, mut replacement: impl FnMut(u64) -> String) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(START) {
+        let after = &rest[start + START.len()..];
+        let digits = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        let (Ok(n), true) = (after[..digits].parse::<u64>(), after[digits..].starts_with(END)) else {
+            replaced.push_str(&rest[..start + START.len()]);
+            rest = after;
+            continue;
+        };
+        replaced.push_str(&rest[..start]);
+        replaced.push_str(&replacement(n));
+        rest = &after[digits + END.len()..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// Give the placeholders of a serialized report new numbers from this program's counter, the
+/// same placeholder keeping the same replacement, so they cannot collide with placeholders
+/// created after the report is loaded
+pub(crate) fn renew(text: &str) -> String {
+    let mut renewed: BTreeMap<u64, String> = BTreeMap::new();
+    replace_placeholders(text, |n| renewed.entry(n).or_insert_with(placeholder).clone())
+}
+
+/// The IDs assigned to the placeholders of a report so far, so a placeholder rendered in several
+/// places keeps the same ID
+#[derive(Debug, Default)]
+pub(crate) struct ElementIds {
+    assigned: BTreeMap<u64, String>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl ElementIds {
+    /// Replace the placeholders of an HTML fragment, numbering the new ones after the IDs already
+    /// assigned with the same prefix
+    pub(crate) fn assign(&mut self, html: &str, prefix: &str) -> String {
+        replace_placeholders(html, |n| {
+            self.assigned
+                .entry(n)
+                .or_insert_with(|| {
+                    let count = self.counts.entry(String::from(prefix)).or_default();
+                    *count += 1;
+                    match prefix.is_empty() {
+                        true => format!("{}", count),
+                        false => format!("{}-{}", prefix, count),
+                    }
+                })
+                .clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_ids() {
+        let mut ids = ElementIds::default();
+        let html = r##"<div id="plot-__rbid7__"></div><a href="#plot-__rbid7__"></a><div id="table-__rbid3__"></div> __rbidx__"##;
+        assert_eq!(
+            ids.assign(html, "qc"),
+            r##"<div id="plot-qc-1"></div><a href="#plot-qc-1"></a><div id="table-qc-2"></div> __rbidx__"##
+        );
+        assert_eq!(ids.assign(r#"<div id="plot-__rbid9__"></div>"#, "qc"), r#"<div id="plot-qc-3"></div>"#);
+        assert_eq!(ids.assign(r#"<div id="plot-__rbid7__"></div>"#, "other"), r#"<div id="plot-qc-1"></div>"#);
+    }
+
+    #[test]
+    fn test_renew_placeholders() {
+        let renewed = renew(r##"["plot-__rbid1__", "#plot-__rbid1__", "table-__rbid2__", "__rbidx__"]"##);
+        let mut ids = ElementIds::default();
+        assert_eq!(ids.assign(&renewed, "qc"), r##"["plot-qc-1", "#plot-qc-1", "table-qc-2", "__rbidx__"]"##);
+        assert_ne!(renew(&renewed), renewed);
+    }
+}
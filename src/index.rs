@@ -0,0 +1,807 @@
+//! Landing pages listing many generated reports.
+//!
+//! A [`ReportIndex`] renders a searchable, sortable table of reports (date, sample set, status and
+//! a link), e.g. as the `index.html` of an archive directory:
+//!
+//! ```rust,ignore
+//! use report_builder::index::ReportIndex;
+//!
+//! let index = ReportIndex::from_manifests("/data/reports", "QC reports")?;
+//! index.save_to_file("/data/reports/index.html")?;
+//! ```
+//!
+//! Reports tagged with [`Report::add_tag`](crate::Report::add_tag) (e.g. by instrument or project)
+//! can be filtered with chips above the table.
+//!
+//! Built from [manifests](crate::manifest), the index also plots the trend of every numeric
+//! metadata entry (e.g. "Identified peptides") across the archive. The `report-builder index`
+//! command does the same from the command line, e.g. in a cron job keeping an archive browsable.
+//!
+//! Out-of-trend runs (by default, values more than 3 standard deviations from the mean of the
+//! previous 10 runs) are highlighted in the trend plots and listed in an "Anomalies" table, see
+//! [`find_anomalies`].
+//!
+//! Reports link to each other by ID (see [`Report::add_report_link`](crate::Report::add_report_link)),
+//! e.g. per-run reports to their batch overview. Indexing resolves the IDs through the manifests,
+//! [`ReportIndex::link_reports`] points the links to the right files, and links to IDs missing from
+//! the archive are listed in a "Broken links" table.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use maud::{html, Markup};
+#[cfg(feature = "plots")]
+use plotly::Plot;
+
+use crate::components::Component;
+use crate::manifest::{find_files, ReportManifest};
+use crate::stats::AnomalyRule;
+use crate::table::{link_cell, text_cell, Table};
+use crate::ReportError;
+
+/// A report listed in a [`ReportIndex`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReportEntry {
+    title: String,
+    link: String,
+    date: String,
+    sample_set: String,
+    status: String,
+    tags: Vec<(String, String)>,
+}
+
+impl ReportEntry {
+    /// Creates an entry linking to a report.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the report.
+    /// * `link` - The URL of the report, relative to the index page or absolute.
+    pub fn new(title: &str, link: &str) -> Self {
+        ReportEntry {
+            title: title.to_string(),
+            link: link.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the date the report was generated, e.g. "2024-05-01 12:00:00". Entries are listed
+    /// newest first, comparing dates as text.
+    pub fn date(mut self, date: &str) -> Self {
+        self.date = date.to_string();
+        self
+    }
+
+    /// Sets the sample set the report covers.
+    pub fn sample_set(mut self, sample_set: &str) -> Self {
+        self.sample_set = sample_set.to_string();
+        self
+    }
+
+    /// Sets the status of the run, e.g. "passed" or "failed".
+    pub fn status(mut self, status: &str) -> Self {
+        self.status = status.to_string();
+        self
+    }
+
+    /// Tags the entry with a label, e.g. its instrument, shown as a filter on the index page.
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Reads the title and generation date of a report saved by this crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the HTML file.
+    /// * `link` - The link to the report from the index page.
+    ///
+    /// # Returns
+    ///
+    /// The entry, without sample set or status, or an IO error if the file cannot be read.
+    pub fn from_file(path: &Path, link: &str) -> Result<Self, ReportError> {
+        let html = std::fs::read_to_string(path)?;
+        let between = |start: &str, end: &str| {
+            let from = html.find(start)? + start.len();
+            let to = html[from..].find(end)? + from;
+            Some(html[from..to].trim().to_string())
+        };
+        let title = between("<title>", "</title>").unwrap_or_else(|| link.to_string());
+        let date = between("Generated on: ", "</p>").unwrap_or_default();
+        Ok(ReportEntry { date, ..ReportEntry::new(&title, link) })
+    }
+
+    /// Creates an entry from the manifest of a report, with its tags, and the sample set and status
+    /// taken from its "Sample set" and "Status" metadata entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest` - The manifest of the report.
+    /// * `link` - The link to the report from the index page.
+    pub fn from_manifest(manifest: &ReportManifest, link: &str) -> Self {
+        ReportEntry::new(&manifest.title, link)
+            .date(&manifest.date)
+            .sample_set(manifest.metadata("Sample set").unwrap_or_default())
+            .status(manifest.metadata("Status").unwrap_or_default())
+            .tags(&manifest.tags)
+    }
+
+    fn tags(mut self, tags: &[(String, String)]) -> Self {
+        self.tags.extend_from_slice(tags);
+        self
+    }
+}
+
+/// A report whose numeric metadata entry is out of trend, see [`find_anomalies`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    /// The metadata key, e.g. "Identified peptides"
+    pub metric: String,
+    /// The title of the report
+    pub title: String,
+    /// The generation date of the report
+    pub date: String,
+    pub value: f64,
+    /// How far the value is from the trend, see [`AnomalyRule::detect`]
+    pub deviation: f64,
+}
+
+/// A link to a report ID that no report of the archive has, see [`ReportIndex::broken_links`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The title of the report the link is in
+    pub title: String,
+    /// The link to that report from the index page
+    pub report: String,
+    /// The ID linked to
+    pub target: String,
+    /// The text of the link
+    pub label: String,
+}
+
+/// A landing page listing reports in a searchable table.
+#[derive(Clone)]
+pub struct ReportIndex {
+    title: String,
+    entries: Vec<ReportEntry>,
+    anomalies: Vec<Anomaly>,
+    links: Vec<(PathBuf, Vec<(String, String)>)>,
+    broken_links: Vec<BrokenLink>,
+    #[cfg(feature = "plots")]
+    trends: Vec<Plot>,
+}
+
+impl ReportIndex {
+    /// Creates an empty index page with the given title.
+    pub fn new(title: &str) -> Self {
+        ReportIndex {
+            title: title.to_string(),
+            entries: Vec::new(),
+            anomalies: Vec::new(),
+            links: Vec::new(),
+            broken_links: Vec::new(),
+            #[cfg(feature = "plots")]
+            trends: Vec::new(),
+        }
+    }
+
+    /// Creates an index of the reports saved in a directory and its subdirectories, linked
+    /// relative to the directory (so the index is meant to be saved there), see
+    /// [`ReportEntry::from_file`]. Files named `index.html` are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The archive directory.
+    /// * `title` - The title of the index page.
+    ///
+    /// # Returns
+    ///
+    /// The index, or an IO error if the directory or a report cannot be read.
+    pub fn from_directory<P: AsRef<Path>>(dir: P, title: &str) -> Result<Self, ReportError> {
+        let dir = dir.as_ref();
+        let paths = find_files(dir, &|path| {
+            path.extension().is_some_and(|ext| ext == "html") && path.file_name().is_some_and(|name| name != "index.html")
+        })?;
+        let mut index = ReportIndex::new(title);
+        for path in paths {
+            index.add(ReportEntry::from_file(&path, &relative_link(dir, &path))?);
+        }
+        Ok(index)
+    }
+
+    /// Creates an index of the reports whose manifests are saved in a directory and its
+    /// subdirectories, linked relative to the directory, see [`ReportEntry::from_manifest`]. The
+    /// out-of-trend numeric metadata entries are listed as anomalies, and with the `plots`
+    /// feature, the index also plots the trend of each entry, see [`trend_plots`]. The links
+    /// between reports are resolved, see [`ReportIndex::link_reports`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The archive directory.
+    /// * `title` - The title of the index page.
+    ///
+    /// # Returns
+    ///
+    /// The index, or an error if the directory or a manifest cannot be read.
+    pub fn from_manifests<P: AsRef<Path>>(dir: P, title: &str) -> Result<Self, ReportError> {
+        Self::from_manifests_with_rule(dir, title, AnomalyRule::default())
+    }
+
+    /// Creates an index of the reports whose manifests are saved in a directory, like
+    /// [`ReportIndex::from_manifests`], detecting anomalies with the given rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The archive directory.
+    /// * `title` - The title of the index page.
+    /// * `rule` - How out-of-trend values are detected, e.g. [`AnomalyRule::Iqr`].
+    pub fn from_manifests_with_rule<P: AsRef<Path>>(dir: P, title: &str, rule: AnomalyRule) -> Result<Self, ReportError> {
+        let dir = dir.as_ref();
+        let manifests = ReportManifest::find(dir)?;
+        let mut index = ReportIndex::new(title);
+        for (path, manifest) in &manifests {
+            let report_path = path.parent().unwrap_or(dir).join(&manifest.report);
+            index.add(ReportEntry::from_manifest(manifest, &relative_link(dir, &report_path)));
+        }
+        (index.links, index.broken_links) = resolve_links(dir, &manifests);
+        let manifests: Vec<ReportManifest> = manifests.into_iter().map(|(_, manifest)| manifest).collect();
+        index.anomalies = find_anomalies(&manifests, rule);
+        #[cfg(feature = "plots")]
+        {
+            index.trends = trend_plots_with_rule(&manifests, rule);
+        }
+        Ok(index)
+    }
+
+    /// Adds a report to the index.
+    pub fn add(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    /// The reports listed in the index.
+    pub fn entries(&self) -> &[ReportEntry] {
+        &self.entries
+    }
+
+    /// Adds an out-of-trend value to the "Anomalies" table.
+    pub fn add_anomaly(&mut self, anomaly: Anomaly) {
+        self.anomalies.push(anomaly);
+    }
+
+    /// The out-of-trend values listed in the index.
+    pub fn anomalies(&self) -> &[Anomaly] {
+        &self.anomalies
+    }
+
+    /// The links to report IDs that no report of the archive has, found by
+    /// [`ReportIndex::from_manifests`] and listed in the "Broken links" table.
+    pub fn broken_links(&self) -> &[BrokenLink] {
+        &self.broken_links
+    }
+
+    /// Points the links between the indexed reports (see [`Report::add_report_link`](crate::Report::add_report_link))
+    /// to the files of the linked reports, rewriting the reports whose links changed in place.
+    /// Links to an ID that no report has are left as they are, see [`ReportIndex::broken_links`].
+    ///
+    /// # Returns
+    ///
+    /// The number of links pointed to a report, or [`ReportError::Io`] if a report cannot be read
+    /// or written.
+    pub fn link_reports(&self) -> Result<usize, ReportError> {
+        let mut count = 0;
+        for (path, links) in self.links.iter().filter(|(_, links)| !links.is_empty()) {
+            let original = std::fs::read_to_string(path)?;
+            let mut html = original.clone();
+            for (id, href) in links {
+                count += set_report_link(&mut html, id, href);
+            }
+            if html != original {
+                std::fs::write(path, html)?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Adds a plot below the table of reports, e.g. one of the [`trend_plots`].
+    #[cfg(feature = "plots")]
+    pub fn add_trend(&mut self, plot: Plot) {
+        self.trends.push(plot);
+    }
+
+    /// The distinct tags of the entries, sorted by key and value
+    fn tags(&self) -> Vec<&(String, String)> {
+        let mut tags: Vec<&(String, String)> = self.entries.iter().flat_map(|entry| &entry.tags).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Render the table of reports, newest first, with a "Tags" column if any report is tagged
+    fn render_table(&self) -> Table {
+        let mut entries: Vec<&ReportEntry> = self.entries.iter().collect();
+        entries.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.title.cmp(&b.title)));
+        let tagged = entries.iter().any(|entry| !entry.tags.is_empty());
+        let mut headers = vec!["Date", "Report", "Sample set", "Status"];
+        if tagged {
+            headers.push("Tags");
+        }
+        entries.into_iter().fold(Table::new().id("report-index").headers(headers), |table, entry| {
+            let mut cells = vec![
+                text_cell(&entry.date),
+                link_cell(&entry.link, &entry.title),
+                text_cell(&entry.sample_set),
+                html! { span class=(format!("status status-{}", crate::slugify(&entry.status))) { (entry.status) } },
+            ];
+            if tagged {
+                // Each tag ends with a ';', so the filters can match whole tags
+                cells.push(html! {
+                    @for (key, value) in &entry.tags {
+                        span class="tag" { (key) ": " (value) ";" } " "
+                    }
+                });
+            }
+            table.add_html_row(cells)
+        })
+    }
+
+    /// Render the "Anomalies" table, newest first, if any value is out of trend
+    fn render_anomalies(&self) -> Markup {
+        let mut anomalies: Vec<&Anomaly> = self.anomalies.iter().collect();
+        anomalies.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.metric.cmp(&b.metric)));
+        let table = anomalies.into_iter().fold(
+            Table::new().id("anomalies").headers(["Date", "Report", "Metric", "Value", "Deviation"]),
+            |table, anomaly| {
+                table.add_row([
+                    anomaly.date.clone(),
+                    anomaly.title.clone(),
+                    anomaly.metric.clone(),
+                    anomaly.value.to_string(),
+                    format!("{:+.2}", anomaly.deviation),
+                ])
+            },
+        );
+        html! {
+            @if !self.anomalies.is_empty() {
+                h2 { "Anomalies" }
+                (table.render())
+            }
+        }
+    }
+
+    /// Render the "Broken links" table, if any link could not be resolved
+    fn render_broken_links(&self) -> Markup {
+        let table = self.broken_links.iter().fold(
+            Table::new().id("broken-links").headers(["Report", "Linked ID", "Label"]),
+            |table, link| table.add_html_row([link_cell(&link.report, &link.title), text_cell(&link.target), text_cell(&link.label)]),
+        );
+        html! {
+            @if !self.broken_links.is_empty() {
+                h2 { "Broken links" }
+                (table.render())
+            }
+        }
+    }
+
+    /// Render the filter chips of the tags, narrowing the table to reports with any of the selected
+    /// values of each kind of tag
+    fn render_filters(&self) -> Markup {
+        let tags = self.tags();
+        html! {
+            @if !tags.is_empty() {
+                div class="tag-filters" {
+                    @for (key, value) in tags {
+                        button class="tag-chip" data-key=(key) data-value=(value) { (key) ": " (value) }
+                    }
+                }
+                script {
+                    (maud::PreEscaped(r#"
+                        jQuery(function($) {
+                            let selected = {};
+                            let escape = function(text) { return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&'); };
+                            $('.tag-chip').on('click', function() {
+                                let key = this.dataset.key, value = this.dataset.value;
+                                $(this).toggleClass('active');
+                                selected[key] = selected[key] || [];
+                                let i = selected[key].indexOf(value);
+                                if (i >= 0) {
+                                    selected[key].splice(i, 1);
+                                } else {
+                                    selected[key].push(value);
+                                }
+                                let pattern = Object.keys(selected).filter(function(k) { return selected[k].length > 0; }).map(function(k) {
+                                    return '(?=.*(' + selected[k].map(function(v) { return escape(k + ': ' + v + ';'); }).join('|') + '))';
+                                }).join('');
+                                $('#report-index').DataTable().column(4).search(pattern ? '^' + pattern : '', true, false).draw();
+                            });
+                        });
+                    "#))
+                }
+            }
+        }
+    }
+
+    /// Render the trend plots, and the Plotly library they need
+    #[cfg(feature = "plots")]
+    fn render_trends(&self) -> (Markup, Markup) {
+        if self.trends.is_empty() {
+            return (html! {}, html! {});
+        }
+        let library = crate::assets::Library::Plotly.render_cdn();
+        let plots = html! {
+            h2 { "Trends" }
+            @for (i, plot) in self.trends.iter().enumerate() {
+                div class="trend" { (maud::PreEscaped(plot.to_inline_html(Some(&format!("trend-{}", i))))) }
+            }
+        };
+        (library, plots)
+    }
+
+    #[cfg(not(feature = "plots"))]
+    fn render_trends(&self) -> (Markup, Markup) {
+        (html! {}, html! {})
+    }
+
+    /// Render the index page as HTML
+    fn render(&self) -> Markup {
+        let table = self.render_table();
+        let (trend_library, trends) = self.render_trends();
+        html! {
+            (maud::DOCTYPE)
+            html {
+                head {
+                    meta charset="utf-8";
+                    title { (self.title) }
+                    @for library in table.libraries() {
+                        (library.render_cdn())
+                    }
+                    (trend_library)
+                    style {
+                        "body { font-family: Arial, sans-serif; margin: 20px; }"
+                        ".status-passed { color: #2b8a3e; } .status-warning { color: #e67700; } .status-failed { color: #c92a2a; }"
+                        ".tag-filters { margin: 10px 0; } .tag-chip { margin: 0 6px 6px 0; padding: 2px 10px; border: 1px solid #999; border-radius: 12px; background: white; cursor: pointer; }"
+                        ".tag-chip.active { background: #1c7ed6; border-color: #1c7ed6; color: white; }"
+                    }
+                }
+                body {
+                    h1 { (self.title) }
+                    p { (self.entries.len()) " reports" }
+                    (self.render_filters())
+                    (table.render())
+                    (self.render_anomalies())
+                    (self.render_broken_links())
+                    (trends)
+                }
+            }
+        }
+    }
+
+    /// Renders the index page as an HTML document, without touching the disk.
+    pub fn render_to_string(&self) -> String {
+        self.render().into_string()
+    }
+
+    /// Saves the index page to an HTML file.
+    ///
+    /// # Returns
+    ///
+    /// [`ReportError::Io`] if the file cannot be written.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ReportError> {
+        std::fs::write(path, self.render_to_string())?;
+        Ok(())
+    }
+}
+
+/// The link to a file from a page in a directory, with `/` separators
+fn relative_link(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// The link from a report to another, both given relative to the same directory
+fn link_between(from: &str, to: &str) -> String {
+    let mut from_dirs: Vec<&str> = from.split('/').collect();
+    from_dirs.pop();
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dirs.iter().zip(&to_parts[..to_parts.len() - 1]).take_while(|(a, b)| a == b).count();
+    let mut link = "../".repeat(from_dirs.len() - common);
+    link.push_str(&to_parts[common..].join("/"));
+    link
+}
+
+/// The file of each report with links and the links resolved to the files of the reports with
+/// their IDs (the most recent one if several reports share an ID), relative to the report, and
+/// the links to IDs no report has
+#[allow(clippy::type_complexity)]
+fn resolve_links(dir: &Path, manifests: &[(PathBuf, ReportManifest)]) -> (Vec<(PathBuf, Vec<(String, String)>)>, Vec<BrokenLink>) {
+    let report_path = |path: &Path, manifest: &ReportManifest| path.parent().unwrap_or(dir).join(&manifest.report);
+    let mut targets: HashMap<&str, (&str, String)> = HashMap::new();
+    for (path, manifest) in manifests {
+        let Some(id) = &manifest.id else { continue };
+        if targets.get(id.as_str()).map_or(true, |(date, _)| *date <= manifest.date.as_str()) {
+            targets.insert(id, (&manifest.date, relative_link(dir, &report_path(path, manifest))));
+        }
+    }
+
+    let mut resolved = Vec::new();
+    let mut broken = Vec::new();
+    for (path, manifest) in manifests.iter().filter(|(_, manifest)| !manifest.links.is_empty()) {
+        let file = report_path(path, manifest);
+        let source = relative_link(dir, &file);
+        let mut links = Vec::new();
+        for (id, label) in &manifest.links {
+            match targets.get(id.as_str()) {
+                Some((_, target)) => links.push((id.clone(), link_between(&source, target))),
+                None => broken.push(BrokenLink {
+                    title: manifest.title.clone(),
+                    report: source.clone(),
+                    target: id.clone(),
+                    label: label.clone(),
+                }),
+            }
+        }
+        resolved.push((file, links));
+    }
+    (resolved, broken)
+}
+
+/// Point the report links to an ID in a report page to a file, returning the number of links.
+///
+/// The `href` of every tag carrying the ID is replaced, wherever it is among the tag's attributes.
+fn set_report_link(html: &mut String, id: &str, href: &str) -> usize {
+    const HREF: &str = r#" href=""#;
+    let escape = |text: &str| html! { (text) }.into_string();
+    let marker = format!(r#" data-report-id="{}""#, escape(id));
+    let href = escape(href);
+    let mut count = 0;
+    let mut from = 0;
+    while let Some(at) = html[from..].find(&marker).map(|i| from + i) {
+        // Attribute values are escaped, so the tag ends at the first `>`
+        let start = html[..at].rfind('<').unwrap_or(0);
+        let end = html[at..].find('>').map_or(html.len(), |i| at + i);
+        if let Some(value) = html[start..end].find(HREF).map(|i| start + i + HREF.len()) {
+            let value_end = html[value..end].find('"').map_or(end, |i| value + i);
+            html.replace_range(value..value_end, &href);
+            count += 1;
+        }
+        from = html[start..].find('>').map_or(html.len(), |i| start + i);
+    }
+    count
+}
+
+/// The series of every metadata entry that is numeric in at least two reports, as the key and
+/// the reports with their values, in order of generation date
+fn numeric_series(manifests: &[ReportManifest]) -> Vec<(&str, Vec<(&ReportManifest, f64)>)> {
+    let mut manifests: Vec<&ReportManifest> = manifests.iter().collect();
+    manifests.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut keys: Vec<&str> = Vec::new();
+    for (key, _) in manifests.iter().flat_map(|manifest| &manifest.metadata) {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+    keys.into_iter()
+        .map(|key| {
+            let series: Vec<(&ReportManifest, f64)> = manifests
+                .iter()
+                .filter_map(|&manifest| Some((manifest, manifest.metadata(key)?.trim().parse::<f64>().ok()?)))
+                .filter(|(_, value)| value.is_finite())
+                .collect();
+            (key, series)
+        })
+        .filter(|(_, series)| series.len() >= 2)
+        .collect()
+}
+
+/// Find the reports whose numeric metadata entries are out of trend, e.g. a run with far fewer
+/// identifications than the previous ones.
+///
+/// # Arguments
+///
+/// * `manifests` - The manifests of the reports, in any order.
+/// * `rule` - How out-of-trend values are detected, see [`AnomalyRule`].
+pub fn find_anomalies(manifests: &[ReportManifest], rule: AnomalyRule) -> Vec<Anomaly> {
+    numeric_series(manifests)
+        .into_iter()
+        .flat_map(|(key, series)| {
+            let values: Vec<f64> = series.iter().map(|&(_, value)| value).collect();
+            rule.detect(&values)
+                .into_iter()
+                .map(|(i, deviation)| Anomaly {
+                    metric: key.to_string(),
+                    title: series[i].0.title.clone(),
+                    date: series[i].0.date.clone(),
+                    value: values[i],
+                    deviation,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Plot the trend of every metadata entry that is numeric in at least two reports, e.g. the
+/// number of identifications of each run, in order of generation date. Out-of-trend values are
+/// highlighted with the default [`AnomalyRule`].
+///
+/// # Arguments
+///
+/// * `manifests` - The manifests of the reports, in any order.
+#[cfg(feature = "plots")]
+pub fn trend_plots(manifests: &[ReportManifest]) -> Vec<Plot> {
+    trend_plots_with_rule(manifests, AnomalyRule::default())
+}
+
+/// Plot the trend of every numeric metadata entry, like [`trend_plots`], highlighting the values
+/// flagged by the given rule.
+///
+/// # Arguments
+///
+/// * `manifests` - The manifests of the reports, in any order.
+/// * `rule` - How out-of-trend values are detected.
+#[cfg(feature = "plots")]
+pub fn trend_plots_with_rule(manifests: &[ReportManifest], rule: AnomalyRule) -> Vec<Plot> {
+    numeric_series(manifests)
+        .into_iter()
+        .filter_map(|(key, series)| {
+            let (dates, values): (Vec<&str>, Vec<f64>) = series.iter().map(|(manifest, value)| (manifest.date.as_str(), *value)).unzip();
+            let anomalies = rule.detect(&values);
+            if anomalies.is_empty() {
+                return crate::plots::plot_scatter([dates], [values], [key], key, "Date", key).ok();
+            }
+            let (anomaly_dates, anomaly_values): (Vec<&str>, Vec<f64>) = anomalies.iter().map(|&(i, _)| (dates[i], values[i])).unzip();
+            let plot = crate::plots::plot_scatter([dates, anomaly_dates], [values, anomaly_values], [key, "Anomalies"], key, "Date", key).ok()?;
+            let mut handle = crate::plots::PlotHandle::from(plot);
+            handle.trace(1)?.set("marker", serde_json::json!({ "color": "#c92a2a", "size": 14, "symbol": "circle-open", "line": { "width": 3 } }));
+            Some(handle.into())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Report, ReportSection};
+
+    #[test]
+    fn test_report_index() {
+        let mut index = ReportIndex::new("QC reports");
+        index.add(ReportEntry::new("Run 1", "run1.html").date("2024-01-01").sample_set("Plasma").status("passed"));
+        index.add(ReportEntry::new("Run 2", "run2.html").date("2024-02-01").status("Failed"));
+
+        let html = index.render_to_string();
+        assert!(html.contains("<title>QC reports</title>"));
+        assert!(html.contains("$('#report-index').DataTable("));
+        let (run1, run2) = (html.find(r#"href="run1.html""#).unwrap(), html.find(r#"href="run2.html""#).unwrap());
+        assert!(run2 < run1, "newest reports come first");
+        assert!(html.contains(r#"<span class="status status-failed">Failed</span>"#));
+        assert!(!html.contains("plotly"));
+    }
+
+    #[test]
+    fn test_from_manifests() {
+        let dir = std::env::temp_dir().join(format!("report-builder-index-manifests-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("2024")).unwrap();
+        for (i, peptides) in ["1200", "1350"].into_iter().enumerate() {
+            let mut report = Report::new("Redeem", "1.0", None, &format!("Run {}", i));
+            report.add_metadata("Status", "passed");
+            report.add_metadata("Identified peptides", peptides);
+            report.add_metadata("Operator", "jcs");
+            report.add_tag("instrument", if i == 0 { "Exploris" } else { "Astral" });
+            let manifest = ReportManifest { date: format!("2024-01-0{}", i + 1), ..report.manifest(&format!("run{}.html", i)) };
+            manifest.save(&dir.join(format!("2024/run{}.manifest.json", i))).unwrap();
+        }
+
+        let index = ReportIndex::from_manifests(&dir, "Archive").unwrap();
+        let expected = ReportEntry::new("Run 1", "2024/run1.html").date("2024-01-02").status("passed").tag("instrument", "Astral");
+        assert_eq!(index.entries()[1], expected);
+        let html = index.render_to_string();
+        assert!(html.contains(r#"<button class="tag-chip" data-key="instrument" data-value="Astral">instrument: Astral</button>"#));
+        assert!(html.contains(r#"<th>Tags</th>"#));
+        assert!(html.contains(r#"<span class="tag">instrument: Exploris;</span>"#));
+        #[cfg(feature = "plots")]
+        {
+            assert!(index.render_to_string().contains("<h2>Trends</h2>"));
+            assert_eq!(index.trends.len(), 1);
+            assert_eq!(crate::plots::to_json(&index.trends[0])["data"][0]["y"], serde_json::json!([1200.0, 1350.0]));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let peptides = [1200, 1250, 1210, 1240, 1230, 400, 1220];
+        let manifests: Vec<ReportManifest> = peptides
+            .iter()
+            .enumerate()
+            .map(|(i, peptides)| {
+                let mut report = Report::new("Redeem", "1.0", None, &format!("Run {}", i));
+                report.add_metadata("Identified peptides", peptides);
+                ReportManifest { date: format!("2024-01-0{}", i + 1), ..report.manifest("run.html") }
+            })
+            .collect();
+
+        let anomalies = find_anomalies(&manifests, AnomalyRule::default());
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!((anomalies[0].title.as_str(), anomalies[0].value), ("Run 5", 400.0));
+        assert!(anomalies[0].deviation < -3.0);
+
+        let mut index = ReportIndex::new("Archive");
+        index.add_anomaly(anomalies[0].clone());
+        let html = index.render_to_string();
+        assert!(html.contains("<h2>Anomalies</h2>"));
+        assert!(html.contains("<td>2024-01-06</td><td>Run 5</td><td>Identified peptides</td><td>400</td>"));
+        #[cfg(feature = "plots")]
+        {
+            let plot = crate::plots::to_json(&trend_plots(&manifests)[0]);
+            assert_eq!(plot["data"][1]["name"], "Anomalies");
+            assert_eq!(plot["data"][1]["y"], serde_json::json!([400.0]));
+        }
+    }
+
+    #[test]
+    fn test_report_links() {
+        let dir = std::env::temp_dir().join(format!("report_builder_index_links_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("batch-42/runs")).unwrap();
+        let mut overview = Report::new("Redeem", "1.0", None, "Batch 42").with_report_id("batch-42");
+        overview.add_report_link("run-1", "Run 1");
+        overview.add_report_link("run-2", "Run 2");
+        let mut run = Report::new("Redeem", "1.0", None, "Run 1").with_report_id("run-1");
+        run.add_report_link("batch-42", "Batch overview");
+        for (report, file) in [(&overview, "batch-42/overview.html"), (&run, "batch-42/runs/run1.html")] {
+            let path = dir.join(file);
+            report.save_to_file(path.to_str().unwrap()).unwrap();
+            report.save_manifest(path.to_str().unwrap()).unwrap();
+        }
+        assert!(std::fs::read_to_string(dir.join("batch-42/runs/run1.html")).unwrap().contains(
+            r##"<nav class="report-links" aria-label="Related reports"><a class="report-link" data-report-id="batch-42" href="#report-batch-42">Batch overview</a></nav>"##
+        ));
+
+        let index = ReportIndex::from_manifests(&dir, "Archive").unwrap();
+        let expected = BrokenLink {
+            title: "Batch 42".to_string(),
+            report: "batch-42/overview.html".to_string(),
+            target: "run-2".to_string(),
+            label: "Run 2".to_string(),
+        };
+        assert_eq!(index.broken_links(), [expected]);
+        assert!(index.render_to_string().contains(r#"<td><a href="batch-42/overview.html">Batch 42</a></td><td>run-2</td><td>Run 2</td>"#));
+
+        assert_eq!(index.link_reports().unwrap(), 2);
+        assert!(std::fs::read_to_string(dir.join("batch-42/runs/run1.html")).unwrap().contains(r#"data-report-id="batch-42" href="../overview.html""#));
+        let overview = std::fs::read_to_string(dir.join("batch-42/overview.html")).unwrap();
+        assert!(overview.contains(r#"data-report-id="run-1" href="runs/run1.html""#));
+        assert!(overview.contains(r##"data-report-id="run-2" href="#report-run-2""##));
+
+        // Reports whose links are already up to date are not rewritten
+        let past = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(86_400);
+        let run_path = dir.join("batch-42/runs/run1.html");
+        std::fs::File::options().write(true).open(&run_path).unwrap().set_modified(past).unwrap();
+        assert_eq!(ReportIndex::from_manifests(&dir, "Archive").unwrap().link_reports().unwrap(), 2);
+        assert_eq!(std::fs::metadata(&run_path).unwrap().modified().unwrap(), past);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut html = r#"<a href="" class="report-link" data-report-id="run-1">Run 1</a>"#.to_string();
+        assert_eq!(set_report_link(&mut html, "run-1", "runs/run1.html"), 1);
+        assert_eq!(html, r#"<a href="runs/run1.html" class="report-link" data-report-id="run-1">Run 1</a>"#);
+    }
+
+    #[test]
+    fn test_from_directory() {
+        let dir = std::env::temp_dir().join(format!("report-builder-index-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("2024")).unwrap();
+        let mut report = Report::new("Redeem", "1.0", None, "Run QC");
+        report.add_section(ReportSection::new("Scores"));
+        report.save_to_file(dir.join("2024/run.html").to_str().unwrap()).unwrap();
+        std::fs::write(dir.join("index.html"), "").unwrap();
+
+        let index = ReportIndex::from_directory(&dir, "Archive").unwrap();
+        assert_eq!(index.entries().len(), 1);
+        let entry = &index.entries()[0];
+        assert_eq!((entry.title.as_str(), entry.link.as_str()), ("Run QC", "2024/run.html"));
+        assert_eq!(entry.date.len(), "2024-01-01 00:00:00".len());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
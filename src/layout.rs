@@ -0,0 +1,165 @@
+//! Side-by-side layout of content blocks.
+//!
+//! By default every block of a section is stacked full-width. A [`Row`] places its blocks next to
+//! each other instead, e.g. two plots or a plot and its table, and wraps them onto one column on
+//! narrow screens:
+//!
+//! ```rust,ignore
+//! use report_builder::layout::Row;
+//!
+//! section.add_row(Row::new().add_plot(scores).add_table(summary));
+//! section.add_row(Row::grid(3).add_plot(a).add_plot(b).add_plot(c).add_plot(d));
+//! ```
+
+use alloc::format;
+
+use maud::{html, Markup};
+#[cfg(feature = "plots")]
+use plotly::Plot;
+
+use crate::components::Component;
+#[cfg(feature = "tables")]
+use crate::table::{Table, TableOptions};
+#[cfg(feature = "plots")]
+use crate::PlotOptions;
+use crate::ReportSection;
+
+/// Content blocks laid out side by side, see [`ReportSection::add_row`].
+///
+/// Blocks are added with the same methods as on a section; their assets, libraries and warnings
+/// are passed on to the section the row is added to.
+pub struct Row {
+    cells: ReportSection,
+    columns: Option<usize>,
+}
+
+impl Row {
+    /// Creates an empty row, with one equally wide column per block.
+    pub fn new() -> Self {
+        Row {
+            cells: ReportSection::new(""),
+            columns: None,
+        }
+    }
+
+    /// Creates an empty grid with the given number of columns, starting a new line every
+    /// `columns` blocks.
+    pub fn grid(columns: usize) -> Self {
+        Row {
+            cells: ReportSection::new(""),
+            columns: Some(columns.max(1)),
+        }
+    }
+
+    /// Adds a block of content (text, HTML, etc.).
+    pub fn add_content(mut self, content: Markup) -> Self {
+        self.cells.add_content(content);
+        self
+    }
+
+    /// Adds an interactive [`Component`], see [`ReportSection::add`].
+    pub fn add_interactive<C: Component>(mut self, component: C) -> Self {
+        self.cells.add(component);
+        self
+    }
+
+    /// Adds a Plotly plot, sized to the width of its cell.
+    #[cfg(feature = "plots")]
+    pub fn add_plot(mut self, plot: impl Into<Plot>) -> Self {
+        self.cells.add_plot(plot);
+        self
+    }
+
+    /// Adds a Plotly plot with the given options; the width is always that of its cell.
+    #[cfg(feature = "plots")]
+    pub fn add_plot_with_opts(mut self, plot: impl Into<Plot>, opts: PlotOptions) -> Self {
+        self.cells.add_plot_with_opts(plot, opts);
+        self
+    }
+
+    /// Adds an interactive table.
+    #[cfg(feature = "tables")]
+    pub fn add_table(mut self, table: Table) -> Self {
+        self.cells.add_table(table);
+        self
+    }
+
+    /// Adds an interactive table with the given DataTables settings.
+    #[cfg(feature = "tables")]
+    pub fn add_table_with_opts(mut self, table: Table, opts: TableOptions) -> Self {
+        self.cells.add_table_with_opts(table, opts);
+        self
+    }
+
+    /// The number of blocks in the row.
+    pub fn len(&self) -> usize {
+        self.cells.content_blocks.len()
+    }
+
+    /// Whether the row has no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.cells.content_blocks.is_empty()
+    }
+
+    /// Render the blocks in a grid container, one cell per block
+    fn render(&self) -> Markup {
+        let columns = self.columns.unwrap_or(self.len().max(1));
+        html! {
+            div class="layout-row" style=(format!("grid-template-columns: repeat({}, minmax(0, 1fr));", columns)) {
+                @for block in &self.cells.content_blocks {
+                    div class="layout-cell" {
+                        (block.render())
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for Row {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReportSection {
+    /// Adds blocks laid out side by side, e.g. two plots or a plot and a table, see [`Row`].
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The blocks of the row.
+    pub fn add_row(&mut self, mut row: Row) {
+        let title = self.title.clone();
+        self.log.absorb(&mut row.cells.log, &title);
+        self.assets.extend(row.cells.assets.iter().copied());
+        self.libraries.extend(row.cells.libraries.iter().copied());
+        self.add_content(row.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row() {
+        let mut section = ReportSection::new("Overview");
+        section.add_row(Row::new().add_content(html! { p { "Left" } }).add_content(html! { p { "Right" } }));
+        section.add_row(Row::grid(3).add_content(html! { p { "Only" } }));
+
+        let html = section.render_to_string();
+        assert!(html.contains(
+            r#"<div class="layout-row" style="grid-template-columns: repeat(2, minmax(0, 1fr));"><div class="layout-cell"><p>Left</p></div><div class="layout-cell"><p>Right</p></div></div>"#
+        ));
+        assert!(html.contains("repeat(3, minmax(0, 1fr))"));
+    }
+
+    #[cfg(feature = "plots")]
+    #[test]
+    fn test_row_plot_assets() {
+        let mut section = ReportSection::new("Overview");
+        section.add_row(Row::new().add_plot(Plot::new()).add_plot(Plot::new()));
+        assert!(section.libraries.contains(&crate::assets::Library::Plotly));
+        assert_eq!(section.render_to_string().matches(r#"class="layout-cell""#).count(), 2);
+    }
+}
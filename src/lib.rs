@@ -5,10 +5,40 @@
 //!
 //! ## Features
 //!
-//! - Create multi-section reports
-//! - Add interactive tables with sorting, searching, and CSV export
-//! - Include responsive Plotly charts
-//! - Customizable styling and layout
+//! - Multi-section reports with tabs, a table of contents, section numbering and appendices
+//! - Interactive tables with sorting, searching and CSV export (`table`)
+//! - Responsive Plotly charts, with helpers for QC, statistics and target-decoy plots (`plots`)
+//! - Reusable components, side-by-side layouts, themes, logos and cover pages (`components`,
+//!   `layout`, `theme`, `branding`, `cover`)
+//! - Output as a single HTML file, online or self-contained, as one page per section, streamed
+//!   section by section (`writer`), as fragments for other pages, or as PDF and figure files (`pdf`,
+//!   `figures`)
+//! - Archives of reports, with manifests, landing pages and links between reports (`manifest`,
+//!   `index`)
+//! - JSON export of report definitions (`schema`), and a JSON command stream with a C ABI for other
+//!   languages (`commands`, `ffi`)
+//!
+//! Each module documents its features in detail.
+//!
+//! ## Cargo features
+//!
+//! All but `derive`, `ffi` and `csv` are enabled by default; disable default features to embed simple HTML reports
+//! without pulling in Plotly and its dependencies.
+//!
+//! - `std`: saving reports to disk, embedded libraries, the component registry, statistics and
+//!   the "Generated on" timestamp. Without it the crate is `no_std` (with `alloc`), so the
+//!   document model (sections, content, tables, themes) can be built and rendered to a string in
+//!   constrained environments such as WASM or plugins.
+//! - `plots`: Plotly figures and the plot helpers in `plots` (`plotly`, `itertools-num`, `rand`)
+//! - `tables`: interactive DataTables tables in `table`
+//! - `pdf`: PDF export through a headless browser, see `pdf`, and static figure export with
+//!   `plots`, see `figures`
+//! - `derive` (not default): `#[derive(ReportTable, ReportSectionData)]` for building tables and
+//!   sections from structs, see `table::ReportTable`
+//! - `data-sources`: fingerprinting input data files with `DataFingerprint::from_path` (`sha2`, `hex`)
+//! - `ffi` (not default): a C ABI over the JSON command stream of `commands`, for building reports
+//!   from C, C++ or Java, see `ffi`
+//! - `csv` (not default): `Table::from_csv_path` for tables read from CSV/TSV files
 //!
 //! ## Usage
 //!
@@ -22,7 +52,7 @@
 //! Then, use the provided structs and methods to construct your report:
 //!
 //! ```rust,ignore
-//! use report_builder::{Report, ReportSection};
+//! use report_builder::{table::Table, Report, ReportSection};
 //! use maud::html;
 //! use plotly::Plot;
 //!
@@ -35,24 +65,277 @@
 //!     // Add a plot (assuming you have a Plot object)
 //!     let plot = Plot::new(); // Create and customize your plot
 //!     section.add_plot(plot);
+//! 
+//!     // Add a sortable, searchable table
+//!     let table = Table::new().headers(["Sample", "Score"]).add_row(["A", "0.9"]);
+//!     section.add_table(table);
 //!     
 //!     report.add_section(section);
 //!     report.save_to_file("report.html").unwrap();
 //! }
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+// Lets the derive macros refer to `::report_builder` from within this crate's own tests
+#[cfg(test)]
+extern crate self as report_builder;
+
+#[cfg(feature = "std")]
+pub mod aggregate;
+mod anchors;
+pub mod appendix;
+pub mod assets;
+pub mod branding;
+#[cfg(feature = "std")]
+pub mod commands;
+pub mod components;
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod cover;
+#[cfg(feature = "std")]
+pub mod environment;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(all(feature = "plots", feature = "pdf"))]
+pub mod figures;
+#[cfg(all(feature = "std", feature = "tables"))]
+pub mod index;
+mod ids;
+pub mod layout;
+pub mod lineage;
+#[cfg(feature = "std")]
+pub mod manifest;
+mod namespace;
+pub mod narrative;
+pub mod numbering;
+#[cfg(feature = "std")]
+pub mod notify;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(feature = "plots")]
 pub mod plots;
+#[cfg(all(feature = "std", feature = "plots"))]
+pub mod power;
+#[cfg(feature = "std")]
+pub mod registry;
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod stats;
+pub mod structured_data;
+#[cfg(feature = "tables")]
+pub mod table;
+pub mod template;
+#[cfg(feature = "std")]
+pub mod testing;
+pub mod theme;
+pub mod warnings;
+#[cfg(feature = "std")]
+pub mod writer;
+
+#[cfg(feature = "std")]
+pub use commands::from_commands;
+pub use error::ReportError;
+#[cfg(feature = "derive")]
+pub use report_builder_derive::{ReportSectionData, ReportTable};
 
-use chrono::Local;
+/// Items used by the code generated by the derive macros.
+#[cfg(feature = "tables")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
+    pub use maud::Markup;
+}
+
+#[cfg(feature = "plots")]
+use aggregate::Aggregation;
+use alloc::collections::{BTreeMap, BTreeSet};
+use appendix::SectionKind;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use anchors::{Anchors, SectionAnchors};
+use assets::{Asset, Library};
+use branding::{Logo, LogoPlacement};
+#[cfg(feature = "plots")]
+use base64::prelude::{Engine, BASE64_STANDARD};
+#[cfg(feature = "std")]
+use assets::AssetMode;
+#[cfg(feature = "std")]
+use manifest::ReportManifest;
+use components::Component;
+use cover::CoverPage;
+use ids::ElementIds;
+use lineage::DataFingerprint;
+use narrative::Metrics;
 use maud::{html, Markup, PreEscaped};
+use numbering::{RefTarget, SectionNumbering};
+#[cfg(feature = "plots")]
+use plotly::configuration::{ImageButtonFormats, ToImageButtonOptions};
+#[cfg(feature = "plots")]
 use plotly::Plot;
-use rand::{distributions::Alphanumeric, Rng};
-use std::io::Write;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tables")]
+use table::{Table, TableOptions};
+use theme::Theme;
+#[cfg(feature = "std")]
+use std::path::Path;
+use warnings::{BuildWarning, ReportBuilderLog, WarningKind};
+
+/// A single block of content in a section, with the input data it was produced from.
+#[derive(Serialize, Deserialize)]
+struct ContentBlock {
+    #[serde(with = "schema::markup")]
+    markup: Markup,
+    lineage: Vec<DataFingerprint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<schema::BlockData>,
+}
+
+impl ContentBlock {
+    fn new(markup: Markup) -> Self {
+        ContentBlock {
+            markup,
+            lineage: Vec::new(),
+            data: None,
+        }
+    }
+
+    /// Render the block, followed by its data lineage popover if any inputs are attached
+    fn render(&self) -> Markup {
+        html! {
+            (self.markup)
+            @if !self.lineage.is_empty() {
+                (lineage::render_lineage(&self.lineage))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "plots")]
+/// Sizing options for a plot added with [`ReportSection::add_plot_with_opts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotOptions {
+    /// Height of the figure in pixels. Ignored when `aspect` is set.
+    pub height: u32,
+    /// Height of the figure as a fraction of its width (e.g. 1.0 for a square heatmap).
+    pub aspect: Option<f64>,
+    /// Use the full page width instead of 80% of the window width.
+    pub full_width: bool,
+    /// The largest width of the figure in pixels, e.g. to keep a small plot legible on wide
+    /// screens. The figure is never wider than its container.
+    pub max_width: Option<u32>,
+    /// Show a slider below the figure to change the bin width of its histogram traces.
+    pub bin_slider: Option<BinSlider>,
+    /// Show a button exporting the data behind the figure's traces as CSV.
+    pub download_data: bool,
+    /// Also embed a static SVG snapshot of the figure, shown when JavaScript is disabled or
+    /// Plotly fails to load (e.g. the CDN is blocked), see [`plots::fallback`].
+    pub static_fallback: bool,
+    /// Embed the figure as JSON and only draw it the first time it becomes visible (e.g. when its
+    /// tab is opened), so reports with many plots load quickly.
+    pub lazy: bool,
+    /// The image downloaded by the camera button of the figure's toolbar, named after the plot's
+    /// title, and whether to add a "Download SVG" button. Plotly's defaults (a PNG named
+    /// "newplot") are kept when unset.
+    pub image_export: Option<ImageExport>,
+}
+
+#[cfg(feature = "plots")]
+/// The file format of a plot image downloaded from a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Svg,
+    Jpeg,
+    Webp,
+}
+
+#[cfg(feature = "plots")]
+/// The image downloaded from a plot's toolbar, see [`PlotOptions::image_export`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageExport {
+    /// The file format of the image.
+    pub format: ImageFormat,
+    /// The factor the figure's size is multiplied by, e.g. 3 for a print-quality PNG.
+    pub scale: usize,
+    /// Also show a "Download SVG" button next to the figure, e.g. for manuscripts.
+    pub svg_button: bool,
+}
+
+#[cfg(feature = "plots")]
+impl Default for ImageExport {
+    fn default() -> Self {
+        ImageExport {
+            format: ImageFormat::Png,
+            scale: 2,
+            svg_button: false,
+        }
+    }
+}
+
+#[cfg(feature = "plots")]
+/// The range of an interactive bin-width slider for histogram plots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinSlider {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    /// The bin width the slider starts at, matching the width the plot was built with.
+    pub initial: f64,
+}
+
+#[cfg(feature = "plots")]
+impl BinSlider {
+    /// Creates a slider ranging from a quarter to four times the given bin width, e.g. a width
+    /// computed with [`stats::bin_width`].
+    pub fn around(width: f64) -> Self {
+        BinSlider {
+            min: width / 4.0,
+            max: width * 4.0,
+            step: width / 4.0,
+            initial: width,
+        }
+    }
+}
+
+#[cfg(feature = "plots")]
+impl Default for PlotOptions {
+    fn default() -> Self {
+        PlotOptions {
+            height: 600,
+            aspect: None,
+            full_width: false,
+            max_width: None,
+            bin_slider: None,
+            download_data: true,
+            static_fallback: false,
+            lazy: false,
+            image_export: None,
+        }
+    }
+}
 
 /// Represents a section of the report, containing a title and multiple content blocks.
+#[derive(Serialize, Deserialize)]
 pub struct ReportSection {
     title: String,
-    content_blocks: Vec<Markup>, // Multiple content blocks (text or plots)
+    content_blocks: Vec<ContentBlock>, // Multiple content blocks (text or plots)
+    #[serde(default)]
+    log: ReportBuilderLog,
+    #[serde(default)]
+    assets: BTreeSet<Asset>,
+    #[serde(default)]
+    libraries: BTreeSet<Library>,
+    #[serde(default)]
+    subsections: Vec<ReportSection>,
+    #[serde(default)]
+    kind: SectionKind,
 }
 
 impl ReportSection {
@@ -65,72 +348,724 @@ impl ReportSection {
         ReportSection {
             title: title.to_string(),
             content_blocks: Vec::new(),
+            log: ReportBuilderLog::new(),
+            assets: BTreeSet::new(),
+            libraries: BTreeSet::new(),
+            subsections: Vec::new(),
+            kind: SectionKind::Main,
         }
     }
 
+    /// Adds a nested subsection, rendered after the section's own content as a collapsible block
+    /// with a heading one level below its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `subsection` - The ReportSection to nest in this section.
+    pub fn add_subsection(&mut self, mut subsection: ReportSection) {
+        self.log.absorb(&mut subsection.log, &subsection.title);
+        self.assets.extend(subsection.assets.iter().copied());
+        self.libraries.extend(subsection.libraries.iter().copied());
+        self.subsections.push(subsection);
+    }
+
     /// Adds a block of content (text, HTML, etc.) to the section.
     ///
     /// # Arguments
     ///
     /// * `content` - A Markup object representing the content to be added.
     pub fn add_content(&mut self, content: Markup) {
-        self.content_blocks.push(content);
+        self.content_blocks.push(ContentBlock::new(content));
+    }
+
+    /// Adds a block of content (e.g. a table) to the section, along with the input files it was
+    /// produced from.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - A Markup object representing the content to be added.
+    /// * `lineage` - Fingerprints of the input files behind the content.
+    pub fn add_content_with_lineage(&mut self, content: Markup, lineage: Vec<DataFingerprint>) {
+        self.content_blocks.push(ContentBlock { markup: content, lineage, data: None });
     }
 
     /// Adds a Plotly plot to the section, with responsive sizing.
     ///
     /// # Arguments
     ///
-    /// * `plot` - A Plot object to be added to the section.
-    pub fn add_plot(&mut self, plot: Plot) {
-        let plot_id: String = rand::thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(10)
-            .map(char::from)
-            .collect();
+    /// * `plot` - A Plot object (or [`plots::PlotHandle`]) to be added to the section.
+    #[cfg(feature = "plots")]
+    pub fn add_plot(&mut self, plot: impl Into<Plot>) {
+        self.add_plot_with_opts(plot, PlotOptions::default());
+    }
+
+    /// Adds a Plotly plot to the section, with the given sizing options.
+    ///
+    /// # Arguments
+    ///
+    /// * `plot` - A Plot object (or [`plots::PlotHandle`]) to be added to the section.
+    /// * `opts` - The height, aspect ratio and width of the figure.
+    #[cfg(feature = "plots")]
+    pub fn add_plot_with_opts(&mut self, plot: impl Into<Plot>, opts: PlotOptions) {
+        self.push_plot(plot.into(), opts, Vec::new());
+    }
+
+    /// Adds a Plotly plot to the section, along with the input files it was produced from.
+    ///
+    /// # Arguments
+    ///
+    /// * `plot` - A Plot object (or [`plots::PlotHandle`]) to be added to the section.
+    /// * `lineage` - Fingerprints of the input files behind the plot.
+    #[cfg(feature = "plots")]
+    pub fn add_plot_with_lineage(&mut self, plot: impl Into<Plot>, lineage: Vec<DataFingerprint>) {
+        self.push_plot(plot.into(), PlotOptions::default(), lineage);
+    }
+
+    /// Adds a plot built from aggregated data, with a note describing the aggregation below it.
+    ///
+    /// The aggregation is also recorded as a build warning, so it is listed in the report appendix.
+    ///
+    /// # Arguments
+    ///
+    /// * `plot` - A Plot object built from aggregated data, see [`aggregate`].
+    /// * `aggregation` - The aggregation applied to the data behind the plot.
+    #[cfg(feature = "plots")]
+    pub fn add_aggregated_plot(&mut self, plot: impl Into<Plot>, aggregation: &Aggregation) {
+        self.push_plot(plot.into(), PlotOptions::default(), Vec::new());
+        self.log.warn(WarningKind::DownsampledPlot, &aggregation.to_string());
+        if let Some(block) = self.content_blocks.last_mut() {
+            block.markup = html! {
+                (block.markup)
+                p class="aggregation-note" { (aggregation) }
+            };
+        }
+    }
+
+    #[cfg(feature = "plots")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(section = %self.title)))]
+    fn push_plot(&mut self, plot: Plot, opts: PlotOptions, lineage: Vec<DataFingerprint>) {
+        let figure = serde_json::from_str(&plot.to_json()).ok();
+        if let Some(figure) = &figure {
+            self.check_plot_values(figure);
+        }
+        self.libraries.insert(Library::Plotly);
+        if opts.lazy {
+            self.assets.insert(Asset::LazyPlots);
+        }
+        if opts.download_data {
+            self.assets.insert(Asset::PlotData);
+            self.libraries.insert(Library::FileSaver);
+        }
+        self.content_blocks.push(ContentBlock {
+            markup: Self::plot_markup(plot, &opts),
+            lineage,
+            data: figure.map(|figure| schema::BlockData::Plot { figure }),
+        });
+    }
+
+    /// Records a non-fatal issue raised while building this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The category of the warning.
+    /// * `message` - A description of what was changed or could not be done.
+    pub fn warn(&mut self, kind: WarningKind, message: &str) {
+        self.log.warn(kind, message);
+    }
+
+    /// Returns the log of non-fatal issues raised while building this section.
+    pub fn log(&self) -> &ReportBuilderLog {
+        &self.log
+    }
+
+    /// Warn about non-finite values that Plotly will silently skip when drawing the plot, given
+    /// its serialized figure
+    #[cfg(feature = "plots")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn check_plot_values(&mut self, plot_json: &serde_json::Value) {
+        let dropped = warnings::count_plot_nulls(plot_json);
+        if dropped > 0 {
+            let title = plot_json["layout"]["title"]["text"].as_str().unwrap_or("untitled");
+            self.log.warn(
+                WarningKind::DroppedNaN,
+                &format!("{} non-finite value(s) will not be drawn in plot '{}'", dropped, title),
+            );
+        }
+    }
+
+    /// Render a plot with its responsive resize script
+    #[cfg(feature = "plots")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn plot_markup(mut plot: Plot, opts: &PlotOptions) -> Markup {
+        let plot_id = format!("plot-{}", ids::placeholder());
+
+        // Downloaded images are named after the plot's title
+        let image_file = opts.image_export.map(|export| {
+            let title = plots::to_json(&plot)["layout"]["title"]["text"].as_str().map(slugify).unwrap_or_default();
+            let file_name = if title.is_empty() { "figure".to_string() } else { title };
+            let format = match export.format {
+                ImageFormat::Png => ImageButtonFormats::Png,
+                ImageFormat::Svg => ImageButtonFormats::Svg,
+                ImageFormat::Jpeg => ImageButtonFormats::Jpeg,
+                ImageFormat::Webp => ImageButtonFormats::Webp,
+            };
+            let options = ToImageButtonOptions::new().format(format).filename(&file_name).scale(export.scale);
+            plot.set_configuration(plot.configuration().clone().to_image_button_options(options));
+            file_name
+        });
+        let svg_button = opts.image_export.is_some_and(|export| export.svg_button);
+
+        let width_fraction = if opts.full_width { 0.95 } else { 0.8 };
+        let (mut container_style, height_js) = match opts.aspect {
+            Some(aspect) => (
+                String::new(),
+                format!("let height = width * {aspect}; plotDiv.style.height = height + 'px';"),
+            ),
+            None => (
+                format!("height: {}px;", opts.height),
+                format!("let height = {}; plotDiv.style.height = height + 'px';", opts.height),
+            ),
+        };
+        let max_width_js = match opts.max_width {
+            Some(max_width) => {
+                if !container_style.is_empty() {
+                    container_style.push(' ');
+                }
+                container_style.push_str(&format!("max-width: {max_width}px;"));
+                format!("width = Math.min(width, {max_width});")
+            }
+            None => String::new(),
+        };
 
-        self.content_blocks.push(html! {
-            div class="plot-wrapper" {
-                div id=(plot_id.clone()) class="plot-container" {
-                    (PreEscaped(plot.to_inline_html(Some(&plot_id))))
+        // Lazy plots are not drawn until they become visible, and cannot be resized before
+        let drawn = if opts.lazy { " && plotDiv.data" } else { "" };
+
+        let figure = plots::to_json(&plot);
+        let summary = plots::alt_text::describe(&figure);
+
+        // Drawn at the figure's height and a typical report width
+        let fallback = opts.static_fallback.then(|| {
+            let height = opts.aspect.map_or(opts.height, |aspect| (800.0 * aspect) as u32);
+            let svg = plots::fallback::render_svg(&figure, 800, height);
+            format!("data:image/svg+xml;base64,{}", BASE64_STANDARD.encode(svg))
+        });
+
+        html! {
+            div class="plot-wrapper" role="figure" aria-describedby=(format!("{plot_id}-summary")) {
+                p id=(format!("{plot_id}-summary")) class="visually-hidden plot-summary" { (summary) }
+                @if let Some(src) = &fallback {
+                    img id=(format!("{plot_id}-fallback")) class="plot-fallback" src=(src) alt=(summary);
+                }
+                @if opts.lazy {
+                    div id=(plot_id.clone()) class="plot-container lazy-plot" style=(container_style) {}
+                    (components::json_script("lazy-plot-figure", &plot.to_json(), &[("data-plot", &format!("#{plot_id}"))]))
+                } @else {
+                    // The plot ID goes to the Plotly div inside the container
+                    div id=(format!("{plot_id}-container")) class="plot-container" style=(container_style) {
+                        (PreEscaped(plot.to_inline_html(Some(&plot_id))))
+                    }
+                }
+                @if opts.download_data {
+                    button class="download-data-button" title="Download data (CSV)" onclick=(format!("downloadPlotData('{plot_id}')")) {
+                        "CSV"
+                    }
+                }
+                @if let (true, Some(file_name)) = (svg_button, &image_file) {
+                    button class="download-svg-button" title="Download SVG" onclick=(format!("Plotly.downloadImage('{plot_id}', {{ format: 'svg', filename: '{file_name}' }})")) {
+                        "SVG"
+                    }
+                }
+            }
+            @if let Some(slider) = opts.bin_slider {
+                div class="bin-slider" {
+                    label {
+                        "Bin width: "
+                        input type="range" min=(slider.min) max=(slider.max) step=(slider.step) value=(slider.initial)
+                            oninput=(format!("Plotly.restyle('{plot_id}', {{ 'xbins.size': parseFloat(this.value) }}); this.nextElementSibling.textContent = this.value;"));
+                        span { (slider.initial) }
+                    }
                 }
             }
             script {
                 (PreEscaped(format!(r#"
-                    function resizePlot() {{
-                        let plotDiv = document.getElementById('{plot_id}');
-                        if (plotDiv) {{
-                            let width = window.innerWidth * 0.8;
-                            Plotly.relayout(plotDiv, {{ width: width }});
+                    (function() {{
+                        function resizePlot() {{
+                            let plotDiv = document.getElementById('{plot_id}');
+                            if (plotDiv{drawn}) {{
+                                let width = window.innerWidth * {width_fraction};
+                                // Plots laid out side by side take the width of their cell
+                                let cell = plotDiv.closest('.layout-cell');
+                                if (cell && cell.clientWidth > 0) {{
+                                    width = cell.clientWidth;
+                                }}
+                                {max_width_js}
+                                {height_js}
+                                let wrapper = plotDiv.closest('.plot-wrapper');
+                                if (wrapper && wrapper.classList.contains('fullscreen')) {{
+                                    width = wrapper.clientWidth - 40;
+                                    height = wrapper.clientHeight - 40;
+                                    plotDiv.style.height = height + 'px';
+                                }}
+                                Plotly.relayout(plotDiv, {{ width: width, height: height }});
+                            }}
                         }}
-                    }}
-                    window.addEventListener('resize', resizePlot);
-                    resizePlot(); // Call initially
+                        window.addEventListener('resize', resizePlot);
+                        resizePlot(); // Call initially
+                    }})();
                 "#)))
             }
-        });
+            @if fallback.is_some() {
+                script {
+                    (PreEscaped(format!(r#"
+                        if (window.Plotly) {{
+                            document.getElementById('{plot_id}-fallback').remove();
+                        }}
+                    "#)))
+                }
+            }
+        }
+    }
+
+    /// Adds an interactive [`Component`] to the section, along with the assets it requires.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The component to be added to the section.
+    pub fn add<C: Component>(&mut self, component: C) {
+        self.assets.extend(component.assets());
+        self.libraries.extend(component.libraries());
+        self.content_blocks.push(ContentBlock::new(component.render()));
+    }
+
+    /// Adds the paragraphs of a narrative whose rules apply to the given metrics, see [`narrative`].
+    ///
+    /// # Arguments
+    ///
+    /// * `narrative` - The rules generating the text.
+    /// * `metrics` - The recorded metrics the rules are evaluated on.
+    pub fn add_narrative(&mut self, narrative: &narrative::Narrative, metrics: &narrative::Metrics) {
+        self.add_content(narrative.render(metrics));
+    }
+
+    /// Adds a two-column table of keys and values, e.g. a dump of structured parameters. Unlike
+    /// [`ReportSection::add_table`], the table is plain HTML, without sorting or searching.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The keys and values, formatted with their `Display` implementations.
+    pub fn add_key_value_table<I, K, V>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: ToString,
+        V: ToString,
+    {
+        let entries: Vec<(String, String)> = entries.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        self.add_content(render_key_values(&entries));
+    }
+
+    /// Adds an interactive table to the section.
+    ///
+    /// Each table initializes its own DataTables instance, so any number of tables can be sortable
+    /// and searchable in one report.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to be added to the section.
+    #[cfg(feature = "tables")]
+    pub fn add_table(&mut self, table: Table) {
+        let data = table.data();
+        self.add(table);
+        if let Some(block) = self.content_blocks.last_mut() {
+            block.data = Some(data);
+        }
+    }
+
+    /// Adds an interactive table to the section, with the given DataTables settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to be added to the section.
+    /// * `opts` - The page length, initial sort order, scrolling and column toggles of the table.
+    #[cfg(feature = "tables")]
+    pub fn add_table_with_opts(&mut self, table: Table, opts: TableOptions) {
+        self.add_table(table.options(opts));
+    }
+
+    /// Adds a component from the [`registry`] to the section.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the component was registered under.
+    /// * `params` - The JSON parameters passed to the component factory.
+    ///
+    /// # Returns
+    ///
+    /// A Result indicating success or [`ReportError::InvalidArgument`] if the component is unknown
+    /// or fails to build.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, params), fields(section = %self.title)))]
+    pub fn add_component(&mut self, name: &str, params: &serde_json::Value) -> Result<(), ReportError> {
+        let content = registry::build_component(name, params)?;
+        self.content_blocks.push(ContentBlock::new(content));
+        Ok(())
+    }
+
+    /// Renders the section as an HTML fragment, without touching the disk.
+    ///
+    /// The fragment relies on the report's scripts and styles (e.g. Plotly), see
+    /// [`Report::render_to_string`] for a complete document.
+    pub fn render_to_string(&self) -> String {
+        self.render().into_string()
+    }
+
+    /// Renders the section as a fragment to embed into another HTML page (e.g. an R Markdown or
+    /// Quarto document), with the assets and libraries it needs listed separately so the host page
+    /// can load each of them once, however many fragments it embeds.
+    ///
+    /// Unlike [`ReportSection::render_to_string`], the fragment does not rely on a report's
+    /// scripts, but it is drawn with the host page's styles.
+    pub fn render_fragment(&self) -> Fragment {
+        Fragment {
+            html: self.render_to_string(),
+            assets: self.assets.iter().copied().collect(),
+            libraries: self.libraries.iter().copied().collect(),
+            namespace: None,
+        }
+    }
+
+    /// Renders the section as a fragment to inject into another web application (e.g. a LIMS web
+    /// UI) without an iframe, see [`ReportSection::render_fragment`].
+    ///
+    /// Every element ID is prefixed with the namespace, the HTML is wrapped in a `div` with the
+    /// namespace as its class, and [`Fragment::head`] scopes the component styles to that `div`,
+    /// so neither the IDs nor the styles of the fragment collide with the host page's. Scripts
+    /// only use jQuery through the `jQuery` global, leaving the host free to bind `$`.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - A prefix unique to the fragment within the host page, e.g. "run-42". It is
+    ///   slugified to be a valid ID prefix and class name.
+    pub fn render_namespaced_fragment(&self, namespace: &str) -> Fragment {
+        let namespace = match slugify(namespace) {
+            slug if slug.is_empty() => "report".to_string(),
+            slug => slug,
+        };
+        let html = html! {
+            div class=(namespace) {
+                (PreEscaped(namespace::prefix_ids(&self.render_to_string(), &namespace)))
+            }
+        };
+        Fragment {
+            html: html.into_string(),
+            namespace: Some(namespace),
+            ..self.render_fragment()
+        }
     }
 
     /// Render the section as HTML
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(section = %self.title)))]
     fn render(&self) -> Markup {
+        let number = if self.is_appendix() { "A" } else { "1" };
+        let anchors = Anchors::default().section(self, 1);
+        let html = self.render_at(0, number, &anchors, None).into_string();
+        PreEscaped(ElementIds::default().assign(&html, &anchors.anchor))
+    }
+
+    /// The label shown before the title of the section: the letter of an appendix, or the number
+    /// of the section when `numbering` is set
+    fn heading_label(&self, depth: usize, number: &str, numbering: Option<&SectionNumbering>) -> Option<String> {
+        match (self.kind, numbering) {
+            (SectionKind::Appendix { .. }, _) if depth == 0 => Some(format!("Appendix {}:", number)),
+            (_, Some(numbering)) => Some(numbering.heading_label(number)),
+            (_, None) => None,
+        }
+    }
+
+    /// Render the title of the section, after its label if it has one
+    fn render_title(&self, label: Option<String>) -> Markup {
+        html! {
+            @if let Some(label) = label {
+                span class="section-number" { (label) } " "
+            }
+            (self.title)
+        }
+    }
+
+    /// Render the section and its subsections, with headings and anchors reflecting their nesting
+    ///
+    /// `number` is the number (or appendix letter) of the section, e.g. "2.1" or "A", used to label
+    /// its heading, and `anchors` are the anchors of the section and its subsections.
+    fn render_at(&self, depth: usize, number: &str, anchors: &SectionAnchors, numbering: Option<&SectionNumbering>) -> Markup {
+        let anchor = &anchors.anchor;
+        let body = html! {
+            @for block in &self.content_blocks {
+                (block.render())
+            }
+            @for ((i, subsection), anchors) in self.subsections.iter().enumerate().zip(&anchors.subsections) {
+                (subsection.render_at(depth + 1, &numbering::child_number(number, i + 1, numbering), anchors, numbering))
+            }
+        };
+        let title = self.render_title(self.heading_label(depth, number, numbering));
+
+        if depth == 0 {
+            if self.kind == (SectionKind::Appendix { collapsed: true }) {
+                return html! {
+                    details class="subsection appendix" id=(anchor) ontoggle="window.dispatchEvent(new Event('resize'))" {
+                        summary { h2 { (title) } }
+                        div class="subsection-body" { (body) }
+                    }
+                };
+            }
+            return html! {
+                div id=(anchor) {
+                    h2 { (title) }
+                    (body)
+                }
+            };
+        }
+        let heading = match depth {
+            1 => html! { h3 { (title) } },
+            2 => html! { h4 { (title) } },
+            3 => html! { h5 { (title) } },
+            _ => html! { h6 { (title) } },
+        };
+        html! {
+            // Plots drawn while collapsed need resizing when the subsection is expanded
+            details class="subsection" id=(anchor) open ontoggle="window.dispatchEvent(new Event('resize'))" {
+                summary { (heading) }
+                div class="subsection-body" { (body) }
+            }
+        }
+    }
+
+    /// Record the cross-reference targets of the section and its subsections, keyed by the anchor
+    /// references are created with: their unique anchors, the letter of an appendix, and the
+    /// numbers of sections when `numbering` is set. A reference matching several sections points
+    /// to the first one.
+    fn reference_targets(&self, depth: usize, number: &str, anchors: &SectionAnchors, numbering: Option<&SectionNumbering>, targets: &mut BTreeMap<String, RefTarget>) {
+        let label = match (self.kind, numbering) {
+            (SectionKind::Appendix { .. }, _) if depth == 0 => Some(format!("Appendix {}", number)),
+            (_, Some(numbering)) => Some(numbering.reference_label(number)),
+            (_, None) => None,
+        };
+        targets.entry(anchors.path.clone()).or_insert(RefTarget { anchor: anchors.anchor.clone(), label });
+        for ((i, subsection), anchors) in self.subsections.iter().enumerate().zip(&anchors.subsections) {
+            subsection.reference_targets(depth + 1, &numbering::child_number(number, i + 1, numbering), anchors, numbering, targets);
+        }
+    }
+
+    /// Render the table of contents entry of the section and its subsections
+    fn render_toc_entry(&self, tab_id: &str, depth: usize, number: &str, anchors: &SectionAnchors, numbering: Option<&SectionNumbering>) -> Markup {
+        html! {
+            li {
+                a href=(format!("#{}", anchors.anchor)) data-tab=(tab_id) {
+                    (self.render_title(self.heading_label(depth, number, numbering)))
+                }
+                @if !self.subsections.is_empty() {
+                    ul {
+                        @for ((i, subsection), anchors) in self.subsections.iter().enumerate().zip(&anchors.subsections) {
+                            (subsection.render_toc_entry(tab_id, depth + 1, &numbering::child_number(number, i + 1, numbering), anchors, numbering))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A section rendered for embedding into another HTML page, see
+/// [`ReportSection::render_fragment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// The section's HTML, to be placed in the body of the host page.
+    pub html: String,
+    /// The component assets the section needs, each listed once.
+    pub assets: Vec<Asset>,
+    /// The third-party libraries the section needs, each listed once and in load order.
+    pub libraries: Vec<Library>,
+    /// The namespace of the fragment's IDs and styles, see
+    /// [`ReportSection::render_namespaced_fragment`].
+    pub namespace: Option<String>,
+}
+
+impl Fragment {
+    /// Renders the libraries (referenced on their CDNs) and assets as elements for the `<head>` of
+    /// the host page, e.g. for hosts without their own dependency management.
+    pub fn head(&self) -> String {
         html! {
-            div {
-                h2 { (self.title) }
-                @for block in &self.content_blocks {
-                    (block)
+            @for library in &self.libraries {
+                (library.render_cdn())
+            }
+            @for asset in &self.assets {
+                @if let Some(namespace) = &self.namespace {
+                    style { (PreEscaped(namespace::scope_css(asset.style(), namespace))) }
+                    script { (PreEscaped(asset.script())) }
+                } @else {
+                    (asset.render())
+                }
+            }
+        }
+        .into_string()
+    }
+}
+
+/// Render keys and their values as a two-column table
+pub(crate) fn render_key_values(entries: &[(String, String)]) -> Markup {
+    html! {
+        table class="key-value" {
+            tbody {
+                @for (key, value) in entries {
+                    tr {
+                        th scope="row" { (key) }
+                        td { (value) }
+                    }
                 }
             }
         }
     }
 }
 
+/// Turn a title into an HTML anchor, e.g. "QC Metrics (MS1)" into "qc-metrics-ms1"
+fn slugify(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The time zone the timestamps of a report are shown in, see [`Report::with_time_zone`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeZone {
+    /// The time zone of the machine generating the report
+    #[default]
+    Local,
+    Utc,
+    /// A fixed offset from UTC in seconds, e.g. `-5 * 3600` for US Eastern Standard Time
+    Offset(i32),
+}
+
+#[cfg(feature = "std")]
+impl TimeZone {
+    /// A local time (see [`testing::now`]) in the time zone; offsets of a day or more fall back to UTC
+    fn convert(self, time: chrono::DateTime<chrono::FixedOffset>) -> chrono::DateTime<chrono::FixedOffset> {
+        let utc = chrono::FixedOffset::east_opt(0).unwrap_or_else(|| time.timezone());
+        match self {
+            TimeZone::Local => time,
+            TimeZone::Utc => time.with_timezone(&utc),
+            TimeZone::Offset(seconds) => time.with_timezone(&chrono::FixedOffset::east_opt(seconds).unwrap_or(utc)),
+        }
+    }
+}
+
+/// When an analysis ran and the span of its input data, as RFC 3339 timestamps, see
+/// [`Report::set_run_times`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RunTimes {
+    started: String,
+    finished: String,
+    acquired: Option<(String, String)>,
+}
+
+/// A duration in its two largest units, e.g. "2h 05m"
+#[cfg(feature = "std")]
+fn format_duration(duration: chrono::TimeDelta) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", minutes, seconds % 60),
+        3600..=86_399 => format!("{}h {:02}m", hours, minutes),
+        _ => format!("{}d {:02}h", days, hours),
+    }
+}
+
+/// The default format of the "Generated on" timestamp, see [`Report::with_timestamp_format`]
+#[cfg(feature = "std")]
+const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The part of a report shown by a rendered document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    /// All sections as tabs of a single document
+    Full,
+    /// The index page of a multi-page report, linking to one page per section
+    #[cfg(feature = "std")]
+    Index,
+    /// The page of a single section of a multi-page report
+    #[cfg(feature = "std")]
+    Section(usize),
+}
+
 /// Represents the entire report, containing multiple sections and metadata.
+///
+/// Besides its sections, a report carries what is shown around them: a cover page, the banner
+/// (logos, header fields, tags, run times and a timestamp in a chosen time zone and format), a
+/// "Run information" panel of metadata, and links to related reports. Options such as a table of
+/// contents, section numbering, a report-wide search box, group toggles, a theme with a dark mode
+/// toggle and custom CSS or JavaScript are set with the `with_*` and `add_*` methods.
+///
+/// A report is rendered to a string without touching the disk, or saved as a single file
+/// (referencing the JavaScript libraries on CDNs or embedding them), as one page per section, or
+/// section by section with a [`writer::ReportWriter`].
+#[derive(Serialize, Deserialize)]
 pub struct Report {
     software_name: String,
     version: String,
     software_logo: Option<String>,
     title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
     sections: Vec<ReportSection>,
+    #[serde(default)]
+    log: ReportBuilderLog,
+    #[serde(default)]
+    watermark: bool,
+    #[serde(default)]
+    toc: bool,
+    #[serde(default)]
+    theme: Theme,
+    #[serde(default)]
+    dark_mode_toggle: bool,
+    #[serde(default)]
+    group_toggles: Vec<String>,
+    #[serde(default)]
+    search: bool,
+    #[serde(default)]
+    structured_data: bool,
+    #[serde(default)]
+    key_metrics: Metrics,
+    #[serde(default)]
+    metadata: Vec<(String, String)>,
+    #[serde(default)]
+    header_fields: Vec<(String, String)>,
+    #[serde(default)]
+    logos: Vec<Logo>,
+    #[serde(default)]
+    tags: Vec<(String, String)>,
+    #[serde(default)]
+    report_id: Option<String>,
+    #[serde(default)]
+    report_links: Vec<(String, String)>,
+    #[serde(default)]
+    time_zone: TimeZone,
+    #[serde(default)]
+    timestamp_format: Option<String>,
+    #[serde(default)]
+    run_times: Option<RunTimes>,
+    #[serde(default)]
+    custom_css: Vec<String>,
+    #[serde(default)]
+    custom_js: Vec<String>,
+    #[serde(default)]
+    head_elements: Vec<String>,
+    #[serde(default)]
+    numbering: Option<SectionNumbering>,
+    #[serde(default)]
+    cover: Option<CoverPage>,
 }
 
 impl Report {
@@ -140,7 +1075,8 @@ impl Report {
     ///
     /// * `software_name` - The name of the software generating the report.
     /// * `version` - The version of the software.
-    /// * `software_logo` - An optional path to the software's logo image.
+    /// * `software_logo` - An optional path or URL of the software's logo image; local files are
+    ///   embedded when the report is saved, see [`branding::inline_image`].
     /// * `title` - The title of the report.
     pub fn new(
         software_name: &str,
@@ -151,235 +1087,1527 @@ impl Report {
         Report {
             software_name: software_name.to_string(),
             version: version.to_string(),
-            software_logo: software_logo.map(|s| s.to_string()),
+            software_logo: software_logo.map(|logo| logo.to_string()),
             title: title.to_string(),
+            description: None,
+            author: None,
             sections: Vec::new(),
+            log: ReportBuilderLog::new(),
+            watermark: false,
+            toc: false,
+            theme: Theme::default(),
+            dark_mode_toggle: false,
+            group_toggles: Vec::new(),
+            search: false,
+            structured_data: false,
+            key_metrics: Metrics::new(),
+            metadata: Vec::new(),
+            header_fields: Vec::new(),
+            logos: Vec::new(),
+            tags: Vec::new(),
+            report_id: None,
+            report_links: Vec::new(),
+            time_zone: TimeZone::default(),
+            timestamp_format: None,
+            run_times: None,
+            custom_css: Vec::new(),
+            custom_js: Vec::new(),
+            head_elements: Vec::new(),
+            numbering: None,
+            cover: None,
+        }
+    }
+
+    /// Shows a description below the report title, e.g. the purpose of the analysis or the study
+    /// it belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - The description, shown as a paragraph.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Shows the author of the report below its title.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - The name of the author, e.g. a person, team or pipeline.
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Starts the report with a cover page (title, subtitle, authors, affiliations, logo, date and
+    /// document ID), filling the first screen and the first printed or PDF page, as formal reports
+    /// require.
+    ///
+    /// # Arguments
+    ///
+    /// * `cover` - The cover page, see [`CoverPage`].
+    pub fn with_cover_page(mut self, cover: CoverPage) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+
+    /// Render the header below the banner: the report title, with its description and author
+    fn render_header(&self) -> Markup {
+        html! {
+            header class="report-header" {
+                h1 { (self.title) }
+                @if let Some(description) = &self.description {
+                    p class="report-description" { (description) }
+                }
+                @if let Some(author) = &self.author {
+                    p class="report-author" { "Author: " (author) }
+                }
+                @if !self.report_links.is_empty() {
+                    nav class="report-links" aria-label="Related reports" {
+                        @for (id, label) in &self.report_links {
+                            a class="report-link" data-report-id=(id) href=(format!("#report-{}", id)) { (label) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shows the timestamps of the report (its "Generated on" time, the date of its cover page and
+    /// of its plot watermarks, its manifest and structured data) in the given time zone instead of
+    /// that of the machine generating it, e.g. [`TimeZone::Utc`] for pipelines running in several
+    /// time zones.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_zone` - The time zone of the timestamps.
+    pub fn with_time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Sets the format of the "Generated on" timestamp in the banner, e.g. `"%Y-%m-%d %H:%M %:z"`
+    /// to show the UTC offset. The timestamp is also given in ISO 8601 format in the
+    /// `data-timestamp` attribute of the banner's `p.timestamp` element, for scripts and search.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - A [chrono format string](https://docs.rs/chrono/latest/chrono/format/strftime/index.html);
+    ///   invalid ones fall back to the default, `"%Y-%m-%d %H:%M:%S"`.
+    pub fn with_timestamp_format(mut self, format: &str) -> Self {
+        self.timestamp_format = Some(format.to_string());
+        self
+    }
+
+    /// The time the report is stamped with, in its time zone, see [`Report::with_time_zone`]
+    #[cfg(feature = "std")]
+    fn generated_at(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        self.time_zone.convert(testing::now())
+    }
+
+    /// The "Generated on" timestamp in the report's format, and in ISO 8601 format
+    #[cfg(feature = "std")]
+    fn timestamp(&self) -> (String, String) {
+        self.format_time(self.generated_at())
+    }
+
+    /// A time in the report's time zone and timestamp format, and in ISO 8601 format
+    #[cfg(feature = "std")]
+    fn format_time(&self, time: chrono::DateTime<chrono::FixedOffset>) -> (String, String) {
+        use core::fmt::Write;
+        let time = self.time_zone.convert(time);
+        let mut shown = String::new();
+        let format = self.timestamp_format.as_deref().unwrap_or(DEFAULT_TIMESTAMP_FORMAT);
+        if write!(shown, "{}", time.format(format)).is_err() {
+            shown = time.format(DEFAULT_TIMESTAMP_FORMAT).to_string();
+        }
+        (shown, time.to_rfc3339())
+    }
+
+    /// Shows when the analysis ran, with its duration, and optionally the span of time its input
+    /// data were acquired in, below the "Generated on" timestamp of the banner, so reviewers know
+    /// which acquisition window the report covers. Times are shown in the report's time zone and
+    /// timestamp format, see [`Report::with_time_zone`] and [`Report::with_timestamp_format`].
+    ///
+    /// # Arguments
+    ///
+    /// * `started` - When the analysis started, e.g. `Utc::now()` at the start of the pipeline.
+    /// * `finished` - When the analysis finished.
+    /// * `acquired` - The acquisition times of the first and last input files, if known.
+    #[cfg(feature = "std")]
+    pub fn set_run_times<T>(&mut self, started: T, finished: T, acquired: Option<(T, T)>)
+    where
+        T: Into<chrono::DateTime<chrono::FixedOffset>>,
+    {
+        let rfc3339 = |time: T| time.into().to_rfc3339();
+        self.run_times = Some(RunTimes {
+            started: rfc3339(started),
+            finished: rfc3339(finished),
+            acquired: acquired.map(|(first, last)| (rfc3339(first), rfc3339(last))),
+        });
+    }
+
+    /// Render the run and data acquisition times of the banner, see [`Report::set_run_times`]
+    #[cfg(feature = "std")]
+    fn render_run_times(&self) -> Markup {
+        let Some(times) = &self.run_times else {
+            return html! {};
+        };
+        let parse = |time: &str| chrono::DateTime::parse_from_rfc3339(time).ok();
+        let time = |time: chrono::DateTime<chrono::FixedOffset>| {
+            let (shown, iso) = self.format_time(time);
+            html! { time datetime=(iso) { (shown) } }
+        };
+        let run = parse(&times.started).zip(parse(&times.finished));
+        let acquired = times.acquired.as_ref().and_then(|(first, last)| parse(first).zip(parse(last)));
+        html! {
+            @if let Some((started, finished)) = run {
+                p class="run-times" { "Run: " (time(started)) " – " (time(finished)) " (" (format_duration(finished - started)) ")" }
+            }
+            @if let Some((first, last)) = acquired {
+                p class="acquisition-times" { "Data acquired: " (time(first)) " – " (time(last)) }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn render_run_times(&self) -> Markup {
+        html! {}
+    }
+
+    /// Shows a sticky table of contents next to the tab bar, listing all sections and subsections
+    /// and highlighting the one currently scrolled into view.
+    ///
+    /// # Arguments
+    ///
+    /// * `toc` - Whether to show the table of contents.
+    pub fn with_toc(mut self, toc: bool) -> Self {
+        self.toc = toc;
+        self
+    }
+
+    /// Sets the colors, fonts and plot template of the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The theme, e.g. [`Theme::dark`].
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Adds a button to the banner switching between the report's theme and the built-in dark
+    /// theme, including the plots' Plotly templates. The reader's choice is remembered by the browser.
+    ///
+    /// # Arguments
+    ///
+    /// * `toggle` - Whether to show the light/dark toggle.
+    pub fn with_dark_mode_toggle(mut self, toggle: bool) -> Self {
+        self.dark_mode_toggle = toggle;
+        self
+    }
+
+    /// Adds a checkbox per group (e.g. sample or condition) under the banner, showing or hiding the
+    /// group's traces in every plot and its rows in every table at once, e.g. to hide failed runs.
+    ///
+    /// Traces belong to a group when their name or legend group is the group's name, and table
+    /// rows when one of their cells is.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` - The names of the groups, in the order of their checkboxes.
+    pub fn with_group_toggles<I, S>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.group_toggles = groups.into_iter().map(|group| group.to_string()).collect();
+        self
+    }
+
+    /// Adds a search box to the banner, filtering every table and highlighting matching text in
+    /// all sections as the reader types, with the number of matches shown on each tab. The first
+    /// match is brought into view, and Enter jumps to the next one.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - Whether to show the search box.
+    pub fn with_search(mut self, search: bool) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Adds an entry to the collapsible "Run information" panel under the banner, e.g. the input
+    /// files, parameters, hostname, runtime or git commit of the run. Entries are listed in the
+    /// order they were first added; adding a key again replaces its value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the entry, e.g. "Git commit".
+    /// * `value` - The value, formatted with its `Display` implementation.
+    pub fn add_metadata<T: ToString>(&mut self, key: &str, value: T) {
+        let value = value.to_string();
+        match self.metadata.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.metadata.push((key.to_string(), value)),
+        }
+    }
+
+    /// Adds a field to the banner, shown next to the software name, e.g. the project, principal
+    /// investigator, instrument or batch ID. Fields are shown in the order they were first added;
+    /// adding a key again replaces its value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the field, e.g. "Project".
+    /// * `value` - The value, formatted with its `Display` implementation.
+    pub fn add_header_field<T: ToString>(&mut self, key: &str, value: T) {
+        let value = value.to_string();
+        match self.header_fields.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.header_fields.push((key.to_string(), value)),
+        }
+    }
+
+    /// Adds a logo to the banner, e.g. of an institution or sponsor of a collaboration report.
+    /// Logos are shown in the order they were added, left ones after the software logo.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The path or URL of the image; local files, relative to the output directory, are
+    ///   embedded when the report is saved, see [`branding::inline_image`].
+    /// * `alt` - A text alternative, e.g. the name of the institution.
+    /// * `placement` - The side of the banner the logo is shown on.
+    pub fn add_logo(&mut self, src: &str, alt: &str, placement: LogoPlacement) {
+        self.logos.push(Logo::new(src, alt, placement));
+    }
+
+    /// Tags the report with a label, e.g. its instrument, project or operator. Tags are shown in the
+    /// banner and saved in the [manifest](manifest), where the [index page](index) offers them as
+    /// filters. A key can have several values, e.g. for several projects.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The kind of label, e.g. "instrument".
+    /// * `value` - The label, e.g. "Orbitrap Astral".
+    pub fn add_tag(&mut self, key: &str, value: &str) {
+        let tag = (key.to_string(), value.to_string());
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    /// Identifies the report so other reports of the archive can link to it, e.g. "batch-42" for
+    /// a batch overview. The ID is saved in the [manifest](manifest), see [`Report::add_report_link`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID, unique within the archive.
+    pub fn with_report_id(mut self, id: &str) -> Self {
+        self.report_id = Some(id.to_string());
+        self
+    }
+
+    /// Links the report to another report of the archive by its ID, e.g. a per-run report back to
+    /// its batch overview and the overview to each run. Links are shown below the title and saved
+    /// in the [manifest](manifest); [`ReportIndex::link_reports`](index::ReportIndex::link_reports)
+    /// (run by the `report-builder index` command) points them to the linked reports' files and
+    /// lists the IDs no report of the archive has as broken links.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the linked report, see [`Report::with_report_id`].
+    /// * `label` - The text of the link, e.g. "Batch overview".
+    pub fn add_report_link(&mut self, id: &str, label: &str) {
+        let link = (id.to_string(), label.to_string());
+        if !self.report_links.contains(&link) {
+            self.report_links.push(link);
+        }
+    }
+
+    /// Adds a style sheet to the report, after the built-in styles so its rules take precedence,
+    /// e.g. to load corporate fonts or restyle the banner.
+    ///
+    /// # Arguments
+    ///
+    /// * `css` - The CSS rules, included as they are.
+    pub fn add_custom_css(&mut self, css: &str) {
+        self.custom_css.push(css.to_string());
+    }
+
+    /// Adds a script to the end of the report's body, so it runs once the sections are in the
+    /// document, e.g. to add behavior to the rendered components.
+    ///
+    /// # Arguments
+    ///
+    /// * `js` - The JavaScript source, included as it is.
+    pub fn add_custom_js(&mut self, js: &str) {
+        self.custom_js.push(js.to_string());
+    }
+
+    /// Adds an element to the report's `<head>`, after the built-in ones, e.g. a `<meta>` tag, a
+    /// font stylesheet `<link>` or an analytics snippet.
+    ///
+    /// # Arguments
+    ///
+    /// * `element` - The element, included as it is.
+    pub fn add_head_element(&mut self, element: Markup) {
+        self.head_elements.push(element.into_string());
+    }
+
+    /// Numbers the sections hierarchically (1, 1.1, 1.2.3) in their headings and the table of
+    /// contents, and labels the [cross-references](numbering::section_ref) to them with their
+    /// number, as regulatory documents require.
+    ///
+    /// # Arguments
+    ///
+    /// * `numbering` - How numbers are formatted, e.g. [`SectionNumbering::default`]; `None`
+    ///   turns numbering off.
+    pub fn with_section_numbering(mut self, numbering: Option<SectionNumbering>) -> Self {
+        self.numbering = numbering;
+        self
+    }
+
+    /// The unique anchors of the sections and their subsections, see [`anchors`]
+    pub(crate) fn section_anchors(&self) -> Vec<SectionAnchors> {
+        let mut anchors = Anchors::default();
+        self.sections.iter().enumerate().map(|(i, section)| anchors.section(section, i + 1)).collect()
+    }
+
+    /// The target of a cross-reference to every section and subsection, keyed by the anchor
+    /// references are created with, and labelled for appendices and, when sections are numbered,
+    /// for every section; references to other sections keep their titles
+    fn reference_targets(&self, anchors: &[SectionAnchors]) -> BTreeMap<String, RefTarget> {
+        let mut targets = BTreeMap::new();
+        for (i, (section, anchors)) in self.sections.iter().zip(anchors).enumerate() {
+            section.reference_targets(0, &self.section_number(i), anchors, self.numbering.as_ref(), &mut targets);
+        }
+        targets
+    }
+
+    /// The number of the section at the given index, or its letter if it is an appendix
+    fn section_number(&self, index: usize) -> String {
+        let main = self.sections[..index].iter().filter(|section| !section.is_appendix()).count();
+        if self.sections[index].is_appendix() {
+            appendix::appendix_letter(index - main)
+        } else {
+            (main + 1).to_string()
+        }
+    }
+
+    /// The label of the section at the given index in the tab bar, prefixed with its letter if it
+    /// is an appendix
+    fn tab_title(&self, index: usize) -> String {
+        let section = &self.sections[index];
+        if section.is_appendix() {
+            format!("Appendix {}: {}", self.section_number(index), section.title)
+        } else {
+            section.title.clone()
+        }
+    }
+
+    /// Render the section at the given index, numbered if enabled, with its cross-references
+    /// resolved and its element IDs assigned
+    fn render_section(&self, index: usize, anchors: &SectionAnchors, targets: &BTreeMap<String, RefTarget>, ids: &mut ElementIds) -> Markup {
+        let section = &self.sections[index];
+        let html = section.render_at(0, &self.section_number(index), anchors, self.numbering.as_ref()).into_string();
+        let html = ids.assign(&html, &anchors.anchor);
+        PreEscaped(numbering::resolve_references(&html, targets))
+    }
+
+    /// Render the table of contents, linking each section to its tab and anchor
+    fn render_toc(&self) -> Markup {
+        let anchors = self.section_anchors();
+        html! {
+            h3 { "Contents" }
+            ul {
+                @for (i, (section, anchors)) in self.sections.iter().zip(&anchors).enumerate() {
+                    (section.render_toc_entry(&format!("tab{}", i), 0, &self.section_number(i), anchors, self.numbering.as_ref()))
+                }
+            }
+        }
+    }
+
+    /// Stamps every plot with a small corner annotation of the software name, version and report
+    /// date, so figures downloaded as images keep their provenance.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to watermark the plots.
+    #[cfg(feature = "plots")]
+    pub fn watermark_plots(&mut self, enabled: bool) {
+        self.watermark = enabled;
+    }
+
+    /// The watermark text stamped on plots, see [`Report::watermark_plots`].
+    #[cfg(feature = "plots")]
+    fn watermark_text(&self) -> String {
+        format!("{} v{} · {}", self.software_name, self.version, self.generated_at().format("%Y-%m-%d"))
+    }
+
+    /// Adds a section to the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - A ReportSection to be added to the report.
+    pub fn add_section(&mut self, mut section: ReportSection) {
+        self.log.absorb(&mut section.log, &section.title);
+        // Appendices stay after the main content, whatever order sections are added in
+        let position = match section.is_appendix() {
+            true => self.sections.len(),
+            false => self.sections.iter().position(ReportSection::is_appendix).unwrap_or(self.sections.len()),
+        };
+        self.sections.insert(position, section);
+    }
+
+    /// Inserts a section at a position among the report's sections, shifting the following ones.
+    ///
+    /// Appendices stay after the main content: a main section is inserted no later than the first
+    /// appendix, and an appendix no earlier.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the section, from 0; positions past the end append it.
+    /// * `section` - A ReportSection to be inserted into the report.
+    pub fn insert_section(&mut self, index: usize, mut section: ReportSection) {
+        self.log.absorb(&mut section.log, &section.title);
+        let first_appendix = self.sections.iter().position(ReportSection::is_appendix).unwrap_or(self.sections.len());
+        let position = match section.is_appendix() {
+            true => index.clamp(first_appendix, self.sections.len()),
+            false => index.min(first_appendix),
+        };
+        self.sections.insert(position, section);
+    }
+
+    /// The first section with the given title, if any.
+    pub fn get_section(&self, title: &str) -> Option<&ReportSection> {
+        self.sections.iter().find(|section| section.title == title)
+    }
+
+    /// The first section with the given title, if any, e.g. to add content to a section another
+    /// plugin created.
+    ///
+    /// Warnings raised on the section once it is in the report are not listed in the report's
+    /// build warnings; raise them with [`Report::warn`] instead.
+    pub fn get_section_mut(&mut self, title: &str) -> Option<&mut ReportSection> {
+        self.sections.iter_mut().find(|section| section.title == title)
+    }
+
+    /// Removes the first section with the given title, if any. The warnings raised while building
+    /// it stay in the report's build warnings.
+    ///
+    /// # Returns
+    ///
+    /// The removed section, or `None` if no section has the title.
+    pub fn remove_section(&mut self, title: &str) -> Option<ReportSection> {
+        let index = self.sections.iter().position(|section| section.title == title)?;
+        Some(self.sections.remove(index))
+    }
+
+    /// Reorders the sections by title, e.g. to arrange sections contributed by several plugins.
+    ///
+    /// Sections not listed follow the listed ones in their current order, and appendices stay
+    /// after the main content. A title listed twice refers to the next section with that title.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The titles of the sections, in their new order.
+    ///
+    /// # Returns
+    ///
+    /// An error if a title matches no section, in which case the order is left unchanged.
+    pub fn reorder_sections(&mut self, order: &[&str]) -> Result<(), ReportError> {
+        let mut slots: Vec<Option<ReportSection>> = core::mem::take(&mut self.sections).into_iter().map(Some).collect();
+        let mut indices = Vec::with_capacity(slots.len());
+        for title in order {
+            let found = (0..slots.len()).find(|&i| !indices.contains(&i) && slots[i].as_ref().is_some_and(|s| s.title == *title));
+            match found {
+                Some(i) => indices.push(i),
+                None => {
+                    self.sections = slots.into_iter().flatten().collect();
+                    return Err(ReportError::InvalidArgument(format!("No section titled '{}' to reorder", title)));
+                }
+            }
+        }
+        let mut sections: Vec<ReportSection> = indices.into_iter().filter_map(|i| slots[i].take()).collect();
+        sections.extend(slots.into_iter().flatten());
+        sections.sort_by_key(ReportSection::is_appendix);
+        self.sections = sections;
+        Ok(())
+    }
+
+    /// Records a non-fatal issue raised while building the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The category of the warning.
+    /// * `message` - A description of what was changed or could not be done.
+    pub fn warn(&mut self, kind: WarningKind, message: &str) {
+        self.log.warn(kind, message);
+    }
+
+    /// Returns the non-fatal issues raised while building the report and its sections.
+    pub fn warnings(&self) -> &[BuildWarning] {
+        self.log.warnings()
+    }
+
+    /// Check that local assets referenced by the report exist, relative to the output directory
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    fn check_assets(&self, output_dir: &Path) -> Vec<BuildWarning> {
+        let mut warnings = Vec::new();
+        let logos = self.software_logo.iter().chain(self.logos.iter().map(|logo| &logo.src));
+        for logo in logos {
+            if !branding::is_remote(logo) && !output_dir.join(logo).exists() {
+                warnings.push(BuildWarning {
+                    kind: WarningKind::MissingAsset,
+                    message: format!("Logo '{}' was not found", logo),
+                    section: None,
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Embed the local logos found relative to the output directory, keyed by their path
+    #[cfg(feature = "std")]
+    fn embed_logos(&self, output_dir: &Path) -> BTreeMap<String, String> {
+        let logos = self.software_logo.iter().chain(self.logos.iter().map(|logo| &logo.src));
+        logos
+            .filter_map(|logo| Some((logo.clone(), branding::inline_image(output_dir, logo)?)))
+            .collect()
+    }
+
+    /// Renders the report as a complete HTML document, without touching the disk.
+    ///
+    /// The third-party libraries are referenced on their CDNs, and the "Build warnings" appendix
+    /// lists the warnings raised while building the report (asset checks need an output directory
+    /// and are only run by [`Report::save_to_file`], which also embeds local logos).
+    pub fn render_to_string(&self) -> String {
+        self.render().into_string()
+    }
+
+    /// Renders the report as a complete HTML document, including the third-party libraries as given.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset_mode` - Whether to reference the libraries on CDNs or embed them.
+    ///
+    /// # Returns
+    ///
+    /// The HTML document, or an IO error if a library cannot be loaded for embedding.
+    #[cfg(feature = "std")]
+    pub fn render_to_string_with_assets(&self, asset_mode: &AssetMode) -> std::io::Result<String> {
+        let libraries = asset_mode.render_libraries()?;
+        Ok(self.render_with(self.log.warnings(), libraries, &BTreeMap::new()).into_string())
+    }
+
+    /// Render the entire report as HTML
+    fn render(&self) -> Markup {
+        let libraries = assets::render_cdn_libraries();
+        self.render_with(self.log.warnings(), libraries, &BTreeMap::new())
+    }
+
+    /// Render the entire report as HTML, with the given third-party libraries in its head, the
+    /// given warnings in a "Build warnings" appendix and the given embedded logos, see
+    /// [`Report::embed_logos`]
+    fn render_with(&self, build_warnings: &[BuildWarning], libraries: Markup, images: &BTreeMap<String, String>) -> Markup {
+        self.render_page(build_warnings, libraries, images, Page::Full)
+    }
+
+    /// The file name of a section's page in a multi-page report, e.g. "02-qc-metrics.html"
+    #[cfg(feature = "std")]
+    fn page_file_name(&self, index: usize) -> String {
+        match slugify(&self.sections[index].title) {
+            slug if slug.is_empty() => format!("{:02}-section.html", index + 1),
+            slug => format!("{:02}-{}.html", index + 1, slug),
+        }
+    }
+
+    /// Render the navigation bar of a multi-page report, linking the index and every section page
+    #[cfg(feature = "std")]
+    fn render_page_links(&self, page: Page) -> Markup {
+        let class = |current: bool| if current { "tab active" } else { "tab" };
+        html! {
+            nav class="tabs" {
+                a class=(class(page == Page::Index)) href="index.html" { "Index" }
+                @for i in 0..self.sections.len() {
+                    a class=(class(page == Page::Section(i))) href=(self.page_file_name(i)) { (self.tab_title(i)) }
+                }
+            }
+        }
+    }
+
+    /// The main content of a page: the tab bar and the sections for the full report, or the
+    /// navigation bar and the index or one section for a page of a multi-page report
+    fn render_main(&self, build_warnings: &[BuildWarning], page: Page) -> Markup {
+        let anchors = self.section_anchors();
+        let targets = self.reference_targets(&anchors);
+        let mut ids = ElementIds::default();
+        match page {
+            Page::Full => html! {
+                div class="tabs" {
+                    @for i in 0..self.sections.len() {
+                        button class="tab" data-tab=(format!("tab{}", i)) onclick=(format!("showTab('tab{}')", i)) {
+                            (self.tab_title(i))
+                        }
+                    }
+                    @if !build_warnings.is_empty() {
+                        button class="tab" data-tab="tab-warnings" onclick="showTab('tab-warnings')" {
+                            "Build warnings (" (build_warnings.len()) ")"
+                        }
+                    }
+                }
+
+                @for i in 0..self.sections.len() {
+                    div id=(format!("tab{}", i)) class={@if i == 0 { "tab-content active" } @else { "tab-content" }} {
+                        (self.render_section(i, &anchors[i], &targets, &mut ids))
+                    }
+                }
+
+                @if !build_warnings.is_empty() {
+                    div id="tab-warnings" class="tab-content" {
+                        (warnings::render_warnings(build_warnings))
+                    }
+                }
+            },
+            #[cfg(feature = "std")]
+            Page::Index => html! {
+                (self.render_page_links(page))
+                div class="tab-content active" {
+                    h2 { (self.title) }
+                    ul class="page-index" {
+                        @for i in 0..self.sections.len() {
+                            li { a href=(self.page_file_name(i)) { (self.tab_title(i)) } }
+                        }
+                    }
+                    @if !build_warnings.is_empty() {
+                        (warnings::render_warnings(build_warnings))
+                    }
+                }
+            },
+            #[cfg(feature = "std")]
+            Page::Section(i) => html! {
+                (self.render_page_links(page))
+                div class="tab-content active" {
+                    (self.render_section(i, &anchors[i], &targets, &mut ids))
+                }
+            },
+        }
+    }
+
+    /// Render the contents of the `<head>` of a page: the title, the given third-party libraries and
+    /// assets, the report's scripts and styles, and any custom head elements and CSS
+    fn render_head(&self, libraries: Markup, assets: &BTreeSet<Asset>) -> Markup {
+        html! {
+            title { (self.title) }
+            (self.render_structured_data())
+            (libraries)
+
+            // JavaScript for tabs
+            script {
+                (PreEscaped(r#"
+                    function showTab(tabId) {
+                        document.querySelectorAll('.tab-content').forEach(function(tab) {
+                            tab.classList.remove('active');
+                        });
+
+                        document.querySelectorAll('.tab').forEach(function(tab) {
+                            tab.classList.remove('active');
+                        });
+
+                        document.getElementById(tabId).classList.add('active');
+                        document.querySelector(`[data-tab='${tabId}']`).classList.add('active');
+
+                        // Let plots that were drawn while hidden size themselves to the visible tab
+                        window.dispatchEvent(new Event('resize'));
+                        if (window.Plotly) {
+                            document.getElementById(tabId).querySelectorAll('.js-plotly-plot').forEach(function(plotDiv) {
+                                Plotly.Plots.resize(plotDiv);
+                            });
+                        }
+                    }
+                "#))
+            }
+
+            // JavaScript announcing what interactive filters changed to screen readers, through the
+            // live region at the top of the page. Announcements are debounced so dragging a slider
+            // is read out once it stops.
+            script {
+                (PreEscaped(r#"
+                    (function() {
+                        let timer = null;
+                        window.announceStatus = function(message) {
+                            let region = document.getElementById('report-status');
+                            if (!region) {
+                                return;
+                            }
+                            clearTimeout(timer);
+                            timer = setTimeout(function() {
+                                region.textContent = message;
+                            }, 400);
+                        };
+                    })();
+                "#))
+            }
+
+            // JavaScript for the table of contents: open the linked tab, and highlight the
+            // entry of the section currently scrolled into view
+            script {
+                (PreEscaped(r#"
+                    $(document).ready(function() {
+                        let links = Array.from(document.querySelectorAll('.toc a'));
+                        if (links.length === 0) {
+                            return;
+                        }
+
+                        function updateToc() {
+                            let current = null;
+                            links.forEach(function(link) {
+                                let target = document.getElementById(link.getAttribute('href').slice(1));
+                                if (target && target.offsetParent !== null && target.getBoundingClientRect().top <= 80) {
+                                    current = link;
+                                }
+                            });
+                            if (!current) {
+                                let active = document.querySelector('.tab-content.active');
+                                current = active ? document.querySelector(`.toc a[data-tab='${active.id}']`) : null;
+                            }
+                            links.forEach(function(link) {
+                                link.classList.toggle('active', link === current);
+                            });
+                        }
+
+                        links.forEach(function(link) {
+                            link.addEventListener('click', function(event) {
+                                event.preventDefault();
+                                showTab(link.dataset.tab);
+                                let target = document.getElementById(link.getAttribute('href').slice(1));
+                                if (target) {
+                                    if (target.tagName === 'DETAILS') {
+                                        target.open = true;
+                                    }
+                                    target.scrollIntoView({ behavior: 'smooth' });
+                                }
+                                updateToc();
+                            });
+                        });
+                        window.addEventListener('scroll', updateToc);
+                        updateToc();
+                    });
+                "#))
+            }
+
+            // JavaScript for full-screen plots and tables
+            script {
+                (PreEscaped(r#"
+                    function toggleFullscreen(wrapper) {
+                        wrapper.classList.toggle('fullscreen');
+                        document.body.classList.toggle('has-fullscreen', wrapper.classList.contains('fullscreen'));
+                        // Plots size themselves in their resize handlers, tables need their columns realigned
+                        window.dispatchEvent(new Event('resize'));
+                        if (window.jQuery && $.fn.dataTable) {
+                            $.fn.dataTable.tables({ visible: true, api: true }).columns.adjust();
+                        }
+                    }
+
+                    // Tables call this from their own initialization, once DataTables has built their wrapper
+                    function addExpandButton(wrapper) {
+                        let button = document.createElement('button');
+                        button.className = 'expand-button';
+                        button.title = 'Toggle full screen';
+                        button.innerHTML = '&#x26F6;';
+                        button.addEventListener('click', function() { toggleFullscreen(wrapper); });
+                        wrapper.appendChild(button);
+                    }
+
+                    $(document).ready(function() {
+                        document.querySelectorAll('.plot-wrapper').forEach(addExpandButton);
+
+                        document.addEventListener('keydown', function(event) {
+                            let open = document.querySelector('.fullscreen');
+                            if (event.key === 'Escape' && open) {
+                                toggleFullscreen(open);
+                            }
+                        });
+                    });
+                "#))
+            }
+
+            // Assets required by the components in this report
+            @for asset in assets {
+                (asset.render())
+            }
+
+            // CSS styles
+            // CSS for the table container
+            style {
+                (PreEscaped("
+                    .table-container {
+                        width: 100%;
+                        overflow-x: auto; /* Enable horizontal scrolling */
+                        white-space: nowrap; /* Prevent line breaks in cells */
+                        border: 1px solid #ddd; /* Optional: Add a border */
+                        padding: 10px;
+                    }
+                    table {
+                        width: 100%;
+                        border-collapse: collapse;
+                    }
+                    table.display {
+                        width: 100% 
+                        table-layout: fixed;
+                        border-collapse: collapse;
+                    }
+
+                    .dataTables_scrollHeadInner {
+                        width: 100% !important;
+                    }
+
+                    .dataTables_wrapper, .dataTables_wrapper .dataTables_info, .dataTables_wrapper .dataTables_length,
+                    .dataTables_wrapper .dataTables_filter, .dataTables_wrapper .dataTables_paginate .paginate_button {
+                        color: var(--text-color) !important;
+                    }
+                    table.dataTable tbody tr.odd, table.dataTable tbody tr.odd > .sorting_1 {
+                        background-color: var(--table-stripe-odd) !important;
+                    }
+                    table.dataTable tbody tr.even, table.dataTable tbody tr.even > .sorting_1 {
+                        background-color: var(--table-stripe-even) !important;
+                    }
+                "))
+            }
+
+            // CSS for the plot container
+            style {
+                (PreEscaped("
+                    .plot-wrapper {
+                        width: 100%;
+                        display: flex;
+                        justify-content: center;
+                        align-items: center;
+                        position: relative;
+                    }
+
+                    .expand-button {
+                        position: absolute;
+                        top: 5px;
+                        right: 5px;
+                        z-index: 10;
+                        border: 1px solid #ccc;
+                        border-radius: 4px;
+                        background: var(--background-color);
+                        cursor: pointer;
+                        opacity: 0.6;
+                    }
+                    .expand-button:hover {
+                        opacity: 1;
+                    }
+                    .download-svg-button {
+                        position: absolute;
+                        top: 5px;
+                        right: 85px;
+                        z-index: 10;
+                        border: 1px solid #ccc;
+                        border-radius: 4px;
+                        background: var(--background-color);
+                        cursor: pointer;
+                        opacity: 0.6;
+                        font-size: 11px;
+                    }
+                    .download-svg-button:hover {
+                        opacity: 1;
+                    }
+                    .dataTables_wrapper {
+                        position: relative;
+                    }
+                    .fullscreen {
+                        position: fixed;
+                        top: 0;
+                        left: 0;
+                        width: 100vw;
+                        height: 100vh;
+                        z-index: 1000;
+                        box-sizing: border-box;
+                        padding: 20px;
+                        background: var(--background-color);
+                        overflow: auto;
+                    }
+                    body.has-fullscreen {
+                        overflow: hidden;
+                    }
+
+                    .bin-slider {
+                        text-align: center;
+                        font-size: 14px;
+                        margin: 5px 0 15px 0;
+                    }
+                    .bin-slider input {
+                        vertical-align: middle;
+                        width: 200px;
+                    }
+
+                    .plot-fallback {
+                        display: block;
+                        max-width: 100%;
+                        margin: 0 auto;
+                    }
+
+                    .plot-container {
+                        width: 100%;
+                        // max-width: 1200px; /* Prevents it from getting too large */
+                        position: relative;
+                        overflow: hidden; /* Prevents content from spilling */
+                        // border: 1px solid #ccc; /* Optional: Helps visualize layout */
+                    }
+                "))
+            }
+
+            // CSS for the data lineage popovers
+            style {
+                (PreEscaped("
+                    .data-lineage {
+                        margin: 5px 0 15px 0;
+                        font-size: 13px;
+                        color: #555;
+                    }
+                    .data-lineage summary {
+                        cursor: pointer;
+                    }
+                    .data-lineage table {
+                        width: auto;
+                        margin-top: 5px;
+                    }
+                    .data-lineage th, .data-lineage td {
+                        padding: 2px 10px;
+                        text-align: left;
+                        border-bottom: 1px solid #eee;
+                    }
+                    .aggregation-note {
+                        margin: 5px 0 15px 0;
+                        font-size: 13px;
+                        font-style: italic;
+                        color: #555;
+                    }
+                "))
+            }
+
+            // CSS for the report
+            style {
+                (PreEscaped("
+                    body {
+                        font-family: var(--font-family);
+                        background-color: var(--background-color);
+                        color: var(--text-color);
+                    }
+                    .banner {
+                        display: flex;
+                        align-items: center;
+                        justify-content: space-between;
+                        padding: 15px;
+                        background: linear-gradient(135deg, var(--banner-start), var(--banner-end));
+                        border-radius: 12px;
+                        box-shadow: 0px 4px 6px rgba(0, 0, 0, 0.1);
+                        color: white;
+                        margin-bottom: 20px;
+                        max-width: 100%;
+                        overflow: hidden;
+                    }
+                    .banner img {
+                        max-height: 100px;
+                        width: auto;
+                        height: auto;
+                        margin-right: 15px;
+                    }
+                    .banner-text h2 {
+                        font-size: 36px;
+                        margin: 0;
+                        white-space: nowrap;
+                    }
+                    .banner-text p {
+                        font-size: 16px;
+                        margin: 0;
+                        opacity: 0.8;
+                    }
+                    .header-fields {
+                        display: flex;
+                        flex-wrap: wrap;
+                        gap: 8px 24px;
+                        flex: 1;
+                        justify-content: flex-end;
+                        margin: 0 15px;
+                    }
+                    .header-field dt {
+                        font-size: 12px;
+                        text-transform: uppercase;
+                        opacity: 0.8;
+                    }
+                    .header-field dd {
+                        margin: 0;
+                        font-size: 16px;
+                        font-weight: bold;
+                    }
+                    .visually-hidden {
+                        position: absolute;
+                        width: 1px;
+                        height: 1px;
+                        padding: 0;
+                        margin: -1px;
+                        overflow: hidden;
+                        clip: rect(0, 0, 0, 0);
+                        white-space: nowrap;
+                        border: 0;
+                    }
+                    .banner-logos {
+                        display: flex;
+                        align-items: center;
+                        margin-left: 15px;
+                    }
+                    .banner .tag {
+                        display: inline-block;
+                        margin: 4px 6px 0 0;
+                        padding: 1px 8px;
+                        border: 1px solid rgba(255, 255, 255, 0.6);
+                        border-radius: 10px;
+                        font-size: 13px;
+                    }
+                    .tabs {
+                        display: flex;
+                        border-bottom: 2px solid #ddd;
+                    }
+                    .tab {
+                        padding: 10px 20px;
+                        cursor: pointer;
+                        font-size: 16px;
+                        font-weight: bold;
+                        color: var(--text-color);
+                        opacity: 0.8;
+                        transition: 0.3s;
+                    }
+                    a.tab {
+                        text-decoration: none;
+                    }
+                    .tab:hover {
+                        opacity: 1;
+                    }
+                    .tab.active {
+                        border-bottom: 3px solid var(--primary-color);
+                        color: var(--primary-color);
+                        opacity: 1;
+                    }
+                    .theme-toggle {
+                        border: 1px solid rgba(255, 255, 255, 0.6);
+                        border-radius: 4px;
+                        background: transparent;
+                        color: white;
+                        font-size: 18px;
+                        cursor: pointer;
+                        margin-left: auto;
+                    }
+                    .tab-content {
+                        display: none;
+                        padding: 20px;
+                    }
+                    .tab-content.active {
+                        display: block;
+                    }
+                    .subsection {
+                        margin: 10px 0;
+                    }
+                    .report-header {
+                        margin-bottom: 20px;
+                    }
+                    .cover-page {
+                        min-height: 100vh;
+                        box-sizing: border-box;
+                        display: flex;
+                        flex-direction: column;
+                        justify-content: center;
+                        align-items: center;
+                        text-align: center;
+                        padding: 40px 20px;
+                        break-after: page;
+                    }
+                    .cover-logo {
+                        max-height: 120px;
+                        margin-bottom: 40px;
+                    }
+                    .cover-title {
+                        font-size: 2.4em;
+                        margin: 0 0 12px 0;
+                    }
+                    .cover-subtitle {
+                        font-size: 1.3em;
+                        color: #555;
+                        margin: 0 0 40px 0;
+                    }
+                    .cover-authors {
+                        font-size: 1.1em;
+                        margin: 0 0 6px 0;
+                    }
+                    .cover-affiliation {
+                        color: #666;
+                        margin: 0;
+                    }
+                    .cover-details {
+                        display: grid;
+                        grid-template-columns: auto auto;
+                        gap: 4px 12px;
+                        margin-top: 60px;
+                    }
+                    .cover-details dt {
+                        font-weight: bold;
+                        text-align: right;
+                    }
+                    .cover-details dd {
+                        margin: 0;
+                        text-align: left;
+                    }
+                    .report-header h1 {
+                        margin: 0 0 8px 0;
+                    }
+                    .report-description {
+                        margin: 0 0 6px 0;
+                        max-width: 900px;
+                    }
+                    .report-author {
+                        margin: 0;
+                        color: #666;
+                        font-size: 0.9em;
+                    }
+                    .report-links {
+                        display: flex;
+                        flex-wrap: wrap;
+                        gap: 12px;
+                        margin: 6px 0 0 0;
+                        font-size: 0.9em;
+                    }
+                    .run-info {
+                        margin-bottom: 20px;
+                    }
+                    .layout-row {
+                        display: grid;
+                        gap: 20px;
+                        align-items: start;
+                    }
+                    .layout-cell {
+                        min-width: 0;
+                    }
+                    @media (max-width: 900px) {
+                        .banner {
+                            flex-wrap: wrap;
+                        }
+                        .header-fields {
+                            justify-content: flex-start;
+                            margin: 10px 0 0 0;
+                            flex-basis: 100%;
+                        }
+                        .layout-row {
+                            grid-template-columns: minmax(0, 1fr) !important;
+                        }
+                    }
+                    .slot-unavailable {
+                        border: 1px dashed #b0b7c3;
+                        border-radius: 6px;
+                        background: #f7f8fa;
+                        color: #555;
+                        padding: 10px 14px;
+                    }
+                    .slot-unavailable-title {
+                        font-weight: bold;
+                        margin: 0 0 4px 0;
+                    }
+                    .narrative-warning {
+                        border-left: 4px solid #e0a800;
+                        background: #fff8e1;
+                        padding: 8px 12px;
+                    }
+                    .column-toggles {
+                        display: flex;
+                        flex-wrap: wrap;
+                        gap: 4px 14px;
+                        margin: 8px 0;
+                        font-size: 0.9em;
+                    }
+                    .run-info summary {
+                        cursor: pointer;
+                        font-weight: bold;
+                    }
+                    table.key-value {
+                        width: auto;
+                        margin: 10px 0;
+                    }
+                    table.key-value th, table.key-value td {
+                        padding: 4px 12px;
+                        border-bottom: 1px solid #ddd;
+                        text-align: left;
+                        vertical-align: top;
+                    }
+                    table.key-value td {
+                        white-space: pre-wrap;
+                        word-break: break-word;
+                    }
+                    .report-layout {
+                        display: flex;
+                        align-items: flex-start;
+                    }
+                    .report-main {
+                        flex: 1;
+                        min-width: 0;
+                    }
+                    .toc {
+                        position: sticky;
+                        top: 0;
+                        flex: 0 0 220px;
+                        max-height: 100vh;
+                        overflow-y: auto;
+                        padding: 10px;
+                        box-sizing: border-box;
+                        border-right: 1px solid #ddd;
+                        font-size: 14px;
+                    }
+                    .toc h3 {
+                        margin-top: 0;
+                    }
+                    .toc ul {
+                        list-style: none;
+                        padding-left: 12px;
+                        margin: 0;
+                    }
+                    .toc > ul {
+                        padding-left: 0;
+                    }
+                    .toc a {
+                        display: block;
+                        padding: 3px 0;
+                        color: var(--text-color);
+                        opacity: 0.8;
+                        text-decoration: none;
+                    }
+                    .toc a:hover {
+                        opacity: 1;
+                    }
+                    .toc a.active {
+                        opacity: 1;
+                        color: var(--primary-color);
+                        font-weight: bold;
+                    }
+                    .subsection > summary {
+                        cursor: pointer;
+                    }
+                    .subsection > summary > h3, .subsection > summary > h4,
+                    .subsection > summary > h5, .subsection > summary > h6 {
+                        display: inline;
+                    }
+                    .subsection-body {
+                        padding-left: 15px;
+                        border-left: 2px solid #eee;
+                    }
+                "))
+            }
+
+            // CSS custom properties of the theme, and the light/dark toggle
+            (theme::render_theme(&self.theme, self.dark_mode_toggle))
+
+            // CSS for printing (and PDF export): every section on its own pages, no controls
+            style {
+                (PreEscaped("
+                    @media print {
+                        .tabs, .toc, .theme-toggle, .group-toggles, .expand-button, .download-data-button, .bin-slider, .dataTables_filter, .dataTables_paginate, .dataTables_length {
+                            display: none !important;
+                        }
+                        .tab-content {
+                            display: block !important;
+                            break-before: page;
+                        }
+                        .tab-content:first-of-type {
+                            break-before: auto;
+                        }
+                        .plot-wrapper, tr, figure {
+                            break-inside: avoid;
+                        }
+                        .banner {
+                            box-shadow: none;
+                            -webkit-print-color-adjust: exact;
+                            print-color-adjust: exact;
+                        }
+                        .data-lineage {
+                            display: none;
+                        }
+                    }
+                "))
+            }
+
+            @for element in &self.head_elements {
+                (PreEscaped(element))
+            }
+            @for css in &self.custom_css {
+                style { (PreEscaped(css)) }
+            }
+        }
+    }
+
+    /// Render the top of the page body: the banner, the header and the run information panel,
+    /// with the given embedded logos
+    fn render_top(&self, images: &BTreeMap<String, String>) -> Markup {
+        // Without a clock (`std` feature) the banner has no timestamp
+        #[cfg(feature = "std")]
+        let timestamp = Some(self.timestamp());
+        #[cfg(not(feature = "std"))]
+        let timestamp: Option<(String, String)> = None;
+
+        html! {
+            // Announces the effect of filters and toggles to screen readers, see `announceStatus`
+            div id="report-status" class="visually-hidden" role="status" aria-live="polite" aria-atomic="true" {}
+
+            @if let Some(cover) = &self.cover {
+                (cover.render(timestamp.as_ref().and_then(|(_, iso)| iso.split('T').next())))
+            }
+
+            div class="banner" {
+                @if let Some(ref logo) = self.software_logo {
+                    img src=(branding::image_src(logo, images)) alt="Software Logo";
+                }
+                @for logo in self.logos.iter().filter(|logo| logo.placement == LogoPlacement::Left) {
+                    (logo.render(images))
+                }
+                div class="banner-text" {
+                    h2 { (self.software_name) " v" (self.version) }
+                    @if let Some((shown, iso)) = &timestamp {
+                        p class="timestamp" data-timestamp=(iso) { "Generated on: " (shown) }
+                    }
+                    (self.render_run_times())
+                    @if !self.tags.is_empty() {
+                        p class="tags" {
+                            @for (key, value) in &self.tags {
+                                span class="tag" { (key) ": " (value) }
+                            }
+                        }
+                    }
+                }
+                @if !self.header_fields.is_empty() {
+                    dl class="header-fields" {
+                        @for (key, value) in &self.header_fields {
+                            div class="header-field" {
+                                dt { (key) }
+                                dd { (value) }
+                            }
+                        }
+                    }
+                }
+                @if self.logos.iter().any(|logo| logo.placement == LogoPlacement::Right) {
+                    div class="banner-logos" {
+                        @for logo in self.logos.iter().filter(|logo| logo.placement == LogoPlacement::Right) {
+                            (logo.render(images))
+                        }
+                    }
+                }
+                @if self.search {
+                    div class="report-search" role="search" {
+                        input type="search" id="report-search-input" placeholder="Search report" aria-label="Search the report";
+                        span class="report-search-count" {}
+                    }
+                }
+                @if self.dark_mode_toggle {
+                    button class="theme-toggle" title="Toggle dark mode" onclick="setDarkMode(!document.body.classList.contains('dark-mode'))" {
+                        (PreEscaped("&#x25D0;"))
+                    }
+                }
+            }
+
+            (self.render_header())
+
+            @if !self.metadata.is_empty() {
+                details class="run-info" {
+                    summary { "Run information" }
+                    (render_key_values(&self.metadata))
+                }
+            }
+
+            @if !self.group_toggles.is_empty() {
+                div class="group-toggles" {
+                    span class="group-toggles-label" { "Groups:" }
+                    @for group in &self.group_toggles {
+                        label {
+                            input type="checkbox" value=(group) checked;
+                            " " (group)
+                        }
+                    }
+                }
+            }
         }
     }
 
-    /// Adds a section to the report.
-    ///
-    /// # Arguments
-    ///
-    /// * `section` - A ReportSection to be added to the report.
-    pub fn add_section(&mut self, section: ReportSection) {
-        self.sections.push(section);
+    /// Render the scripts at the end of the page body: the plot watermark and any custom JavaScript
+    fn render_scripts(&self) -> Markup {
+        #[cfg(feature = "plots")]
+        let watermark = self.watermark.then(|| plots::watermark_annotation_json(&self.watermark_text()));
+        #[cfg(not(feature = "plots"))]
+        let watermark: Option<String> = None;
+
+        html! {
+            @if let Some(watermark) = watermark {
+                script {
+                    (PreEscaped(format!(r#"
+                        window.addEventListener('load', function() {{
+                            let watermark = {};
+                            document.querySelectorAll('.js-plotly-plot').forEach(function(plotDiv) {{
+                                let annotations = (plotDiv.layout.annotations || []).concat([watermark]);
+                                Plotly.relayout(plotDiv, {{ annotations: annotations }});
+                            }});
+                        }});
+                    "#, watermark)))
+                }
+            }
+
+            @for js in &self.custom_js {
+                script { (PreEscaped(js)) }
+            }
+        }
     }
 
-    /// Render the entire report as HTML
-    fn render(&self) -> Markup {
-        let current_date = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    /// Render a page of the report as a complete HTML document, see [`Page`]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(title = %self.title, sections = self.sections.len())))]
+    fn render_page(&self, build_warnings: &[BuildWarning], libraries: Markup, images: &BTreeMap<String, String>, page: Page) -> Markup {
+        let mut assets: BTreeSet<Asset> = self
+            .sections
+            .iter()
+            .flat_map(|section| section.assets.iter().copied())
+            .collect();
+        if !self.group_toggles.is_empty() {
+            assets.insert(Asset::GroupToggles);
+        }
+        if self.search {
+            assets.insert(Asset::ReportSearch);
+        }
+
+        // The tab bar and the sections, shown next to the table of contents when it is enabled
+        let main = self.render_main(build_warnings, page);
 
         html! {
             (maud::DOCTYPE)
             html {
                 head {
-                    title { (self.title) }
-                    script src="https://cdn.plot.ly/plotly-latest.min.js" {}
-                    script src="https://cdnjs.cloudflare.com/ajax/libs/jquery/3.6.4/jquery.min.js" {}
-                    script src="https://cdn.datatables.net/1.13.4/js/jquery.dataTables.min.js" {}
-                    link rel="stylesheet" href="https://cdn.datatables.net/1.13.4/css/jquery.dataTables.min.css" {}
-                    script src="https://cdn.datatables.net/colresize/1.0.0/dataTables.colResize.min.js" {}
-                    link rel="stylesheet" href="https://cdn.datatables.net/colResize/1.0.0/css/colResize.dataTables.min.css" {}
-                    script src="https://cdnjs.cloudflare.com/ajax/libs/FileSaver.js/2.0.5/FileSaver.min.js" {}
-
-                    // JavaScript for DataTables and CSV export
-                    script {
-                        (PreEscaped(r#"
-                            $(document).ready(function() {
-                                let table = $('#dataTable').DataTable({
-                                    paging: true,
-                                    searching: true,
-                                    ordering: true,
-                                    scrollX: true,
-                                    autoWidth: false,  // Ensures DataTables doesn't override widths
-                                    colResize: {
-                                        enable: true,  // Enable column resizing
-                                        resizeTable: true
-                                    }
-                                });
-
-                                $('#downloadCsv').on('click', function() {
-                                    let csv = [];
-                                    let headers = [];
-                                    $('#dataTable thead th').each(function() {
-                                        headers.push($(this).text());
-                                    });
-                                    csv.push(headers.join(','));
-
-                                    $('#dataTable tbody tr').each(function() {
-                                        let row = [];
-                                        $(this).find('td').each(function() {
-                                            row.push('"' + $(this).text() + '"');
-                                        });
-                                        csv.push(row.join(','));
-                                    });
-
-                                    let csvContent = csv.join('\n');
-                                    let blob = new Blob([csvContent], { type: 'text/csv;charset=utf-8;' });
-                                    saveAs(blob, 'table_data.csv');
-                                });
-                            });
-                        "#))
-                    }
-
-                    // JavaScript for tabs
-                    script {
-                        (PreEscaped(r#"
-                            function showTab(tabId) {
-                                document.querySelectorAll('.tab-content').forEach(function(tab) {
-                                    tab.classList.remove('active');
-                                });
-                    
-                                document.querySelectorAll('.tab').forEach(function(tab) {
-                                    tab.classList.remove('active');
-                                });
-                    
-                                document.getElementById(tabId).classList.add('active');
-                                document.querySelector(`[data-tab='${tabId}']`).classList.add('active');
-                            }
-                        "#))
-                    }
-
-
-                    // CSS styles
-                    // CSS for the table container
-                    style {
-                        (PreEscaped("
-                            .table-container {
-                                width: 100%;
-                                overflow-x: auto; /* Enable horizontal scrolling */
-                                white-space: nowrap; /* Prevent line breaks in cells */
-                                border: 1px solid #ddd; /* Optional: Add a border */
-                                padding: 10px;
-                            }
-                            table {
-                                width: 100%;
-                                border-collapse: collapse;
-                            }
-                            table.display {
-                                width: 100% 
-                                table-layout: fixed;
-                                border-collapse: collapse;
-                            }
-
-                            .dataTables_scrollHeadInner {
-                                width: 100% !important;
-                            }
-                        "))
-                    }
-
-                    // CSS for the plot container
-                    style {
-                        (PreEscaped("
-                            .plot-wrapper {
-                                width: 100%;
-                                display: flex;
-                                justify-content: center;
-                                align-items: center;
-                                position: relative;
-                            }
-
-                            .plot-container {
-                                width: 100%;
-                                // max-width: 1200px; /* Prevents it from getting too large */
-                                height: 600px; /* Adjust as needed */
-                                position: relative;
-                                overflow: hidden; /* Prevents content from spilling */
-                                // border: 1px solid #ccc; /* Optional: Helps visualize layout */
-                            }
-                        "))
-                    }
-
-                    // CSS for the report
-                    style {
-                        (PreEscaped("
-                            body {
-                                font-family: Arial, sans-serif;
-                            }
-                            .banner {
-                                display: flex;
-                                align-items: center;
-                                justify-content: space-between;
-                                padding: 15px;
-                                background: linear-gradient(135deg, #4a90e2, #145da0);
-                                border-radius: 12px;
-                                box-shadow: 0px 4px 6px rgba(0, 0, 0, 0.1);
-                                color: white;
-                                margin-bottom: 20px;
-                                max-width: 100%;
-                                overflow: hidden;
-                            }
-                            .banner img {
-                                max-height: 100px;
-                                width: auto;
-                                height: auto;
-                                margin-right: 15px;
-                            }
-                            .banner-text h2 {
-                                font-size: 36px;
-                                margin: 0;
-                                white-space: nowrap;
-                            }
-                            .banner-text p {
-                                font-size: 16px;
-                                margin: 0;
-                                opacity: 0.8;
-                            }
-                            .tabs {
-                                display: flex;
-                                border-bottom: 2px solid #ddd;
-                            }
-                            .tab {
-                                padding: 10px 20px;
-                                cursor: pointer;
-                                font-size: 16px;
-                                font-weight: bold;
-                                color: #444;
-                                transition: 0.3s;
-                            }
-                            .tab:hover {
-                                color: #000;
-                            }
-                            .tab.active {
-                                border-bottom: 3px solid #007bff;
-                                color: #007bff;
-                            }
-                            .tab-content {
-                                display: none;
-                                padding: 20px;
-                            }
-                            .tab-content.active {
-                                display: block;
-                            }
-                        "))
-                    }
+                    (self.render_head(libraries, &assets))
                 }
 
                 body {
-                    div class="banner" {
-                        @if let Some(ref logo) = self.software_logo {
-                            img src=(logo) alt="Software Logo";
-                        }
-                        div class="banner-text" {
-                            h2 { (self.software_name) " v" (self.version) }
-                            p class="timestamp" { "Generated on: " (current_date) }
-                        }
-                    }
+                    (self.render_top(images))
 
-                    div class="tabs" {
-                        @for (i, section) in self.sections.iter().enumerate() {
-                            button class="tab" data-tab=(format!("tab{}", i)) onclick=(format!("showTab('tab{}')", i)) {
-                                (section.title.clone())
+                    // The table of contents links to the tabs of the full report
+                    @if self.toc && page == Page::Full {
+                        div class="report-layout" {
+                            nav class="toc" {
+                                (self.render_toc())
+                            }
+                            div class="report-main" {
+                                (main)
                             }
                         }
+                    } @else {
+                        (main)
                     }
 
-                    @for (i, section) in self.sections.iter().enumerate() {
-                        div id=(format!("tab{}", i)) class={@if i == 0 { "tab-content active" } @else { "tab-content" }} {
-                            (section.render())
-                        }
-                    }
+                    (self.render_scripts())
                 }
             }
         }
@@ -393,28 +2621,157 @@ impl Report {
     ///
     /// # Returns
     ///
-    /// A Result indicating success or an IO error.
-    pub fn save_to_file(&self, filename: &str) -> std::io::Result<()> {
-        let mut file = std::fs::File::create(filename)?;
-        file.write_all(self.render().into_string().as_bytes())?;
-        Ok(())
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`].
+    #[cfg(feature = "std")]
+    pub fn save_to_file(&self, filename: &str) -> Result<Vec<BuildWarning>, ReportError> {
+        self.save_to_file_with_assets(filename, &AssetMode::Cdn)
+    }
+
+    /// Saves the report to a self-contained HTML file, embedding Plotly, jQuery, DataTables and
+    /// FileSaver so it can be viewed without internet access.
+    ///
+    /// The libraries are read from the directory given by [`AssetMode::inline`], and downloaded
+    /// into it first when the `offline` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The name of the file to save the report to.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`]
+    /// if a library cannot be loaded or the file cannot be written.
+    #[cfg(feature = "std")]
+    pub fn save_to_file_offline(&self, filename: &str) -> Result<Vec<BuildWarning>, ReportError> {
+        self.save_to_file_with_assets(filename, &AssetMode::inline())
+    }
+
+    /// Saves the report to an HTML file, including the third-party libraries as given.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The name of the file to save the report to.
+    /// * `asset_mode` - Whether to reference the libraries on CDNs or embed them.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn save_to_file_with_assets(&self, filename: &str, asset_mode: &AssetMode) -> Result<Vec<BuildWarning>, ReportError> {
+        let output_dir = Path::new(filename).parent().unwrap_or(Path::new(""));
+        let mut build_warnings = self.log.warnings().to_vec();
+        build_warnings.extend(self.check_assets(output_dir));
+
+        let libraries = asset_mode.render_libraries()?;
+        let images = self.embed_logos(output_dir);
+        std::fs::write(filename, self.render_with(&build_warnings, libraries, &images).into_string())?;
+        Ok(build_warnings)
+    }
+
+    /// Summarizes the report for indexing, see [`manifest`].
+    ///
+    /// # Arguments
+    ///
+    /// * `report_file` - The report file, relative to the directory the manifest is saved to.
+    #[cfg(feature = "std")]
+    pub fn manifest(&self, report_file: &str) -> ReportManifest {
+        ReportManifest {
+            title: self.title.clone(),
+            software_name: self.software_name.clone(),
+            version: self.version.clone(),
+            date: self.generated_at().format(DEFAULT_TIMESTAMP_FORMAT).to_string(),
+            report: report_file.to_string(),
+            metadata: self.metadata.clone(),
+            tags: self.tags.clone(),
+            id: self.report_id.clone(),
+            links: self.report_links.clone(),
+        }
+    }
+
+    /// Saves the manifest of the report next to the report file, e.g. `run.manifest.json` for
+    /// `run.html`, so the report is picked up by [`ReportIndex::from_manifests`](index::ReportIndex::from_manifests)
+    /// and the `report-builder index` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The name of the report file, saved with e.g. [`Report::save_to_file`].
+    ///
+    /// # Returns
+    ///
+    /// The path of the manifest, or [`ReportError::Io`] if it cannot be written.
+    #[cfg(feature = "std")]
+    pub fn save_manifest(&self, filename: &str) -> Result<std::path::PathBuf, ReportError> {
+        let report_path = Path::new(filename);
+        let report_file = report_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let path = ReportManifest::path_for(report_path);
+        self.manifest(&report_file).save(&path)?;
+        Ok(path)
+    }
+
+    /// Saves the report as one HTML file per section plus an `index.html` linking them, so large
+    /// reports (e.g. with dozens of plots) stay light enough for browsers to open page by page.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory to save the pages to, created if missing.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`].
+    #[cfg(feature = "std")]
+    pub fn save_to_directory<P: AsRef<Path>>(&self, path: P) -> Result<Vec<BuildWarning>, ReportError> {
+        self.save_to_directory_with_assets(path, &AssetMode::Cdn)
+    }
+
+    /// Saves the report as one HTML file per section plus an `index.html`, including the
+    /// third-party libraries as given. With [`AssetMode::Inline`] the libraries are copied once
+    /// into an `assets` subdirectory shared by all pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory to save the pages to, created if missing.
+    /// * `asset_mode` - Whether to reference the libraries on CDNs or copy them next to the pages.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, path)))]
+    pub fn save_to_directory_with_assets<P: AsRef<Path>>(&self, path: P, asset_mode: &AssetMode) -> Result<Vec<BuildWarning>, ReportError> {
+        let dir = path.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut build_warnings = self.log.warnings().to_vec();
+        build_warnings.extend(self.check_assets(dir));
+
+        let libraries = asset_mode.render_shared_libraries(dir)?;
+        let images = self.embed_logos(dir);
+        let index = self.render_page(&build_warnings, libraries.clone(), &images, Page::Index);
+        std::fs::write(dir.join("index.html"), index.into_string())?;
+        for i in 0..self.sections.len() {
+            let page = self.render_page(&build_warnings, libraries.clone(), &images, Page::Section(i));
+            std::fs::write(dir.join(self.page_file_name(i)), page.into_string())?;
+        }
+        Ok(build_warnings)
     }
 }
 
-impl ToString for Report {
-    fn to_string(&self) -> String {
-        self.render().into_string()
+impl core::fmt::Display for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.render_to_string())
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
+    #[cfg(feature = "plots")]
     use crate::plots::plot_scatter;
+    #[cfg(feature = "plots")]
     use maud::html;
 
     #[test]
+    #[cfg(feature = "plots")]
     fn test_report() {
         let mut report = Report::new("Redeem", "1.0", Some("logo.png"), "My Report");
 
@@ -512,6 +2869,531 @@ mod tests {
 
         report.add_section(section2);
 
-        report.save_to_file("report.html").unwrap();
+        let html = report.render_to_string();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(r#"<img src="logo.png" alt="Software Logo">"#));
+        assert_eq!(html.matches(r#"class="plot-container""#).count(), 2);
+        assert!(report.sections[1].render_to_string().contains("Lorem ipsum"));
+    }
+
+    #[test]
+    #[cfg(feature = "tables")]
+    fn test_multiple_tables() {
+        let mut section = ReportSection::new("Tables");
+        for name in ["first", "second"] {
+            section.add_table(Table::new().id(name).headers(["Key", "Value"]).add_row([name, "1"]));
+        }
+
+        let html = section.render().into_string();
+        assert!(html.contains("$('#first').DataTable("));
+        assert!(html.contains("$('#second').DataTable("));
+        assert_eq!(html.matches("window.addExpandButton(table.table().container());").count(), 2);
+
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_section(section);
+        let html = report.render_to_string();
+        assert!(html.contains("function addExpandButton(wrapper)") && !html.contains("$('#dataTable')"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_save_inline_assets() {
+        let asset_dir = std::env::temp_dir().join(format!("report-builder-inline-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&asset_dir).unwrap();
+        for library in assets::Library::ALL {
+            std::fs::write(asset_dir.join(library.file_name()), format!("/* {} */", library.file_name())).unwrap();
+        }
+
+        let report = Report::new("Redeem", "1.0", None, "Offline Report");
+        let asset_mode = AssetMode::Inline { asset_dir: asset_dir.clone() };
+        let html = report.render_to_string_with_assets(&asset_mode).unwrap();
+        std::fs::remove_dir_all(&asset_dir).unwrap();
+
+        assert!(html.contains("<script>/* plotly.min.js */</script>"));
+        assert!(html.contains("<style>/* jquery.dataTables.min.css */</style>"));
+        assert!(!html.contains("cdn.plot.ly"));
+
+        let missing = AssetMode::Inline { asset_dir: asset_dir.join("missing") };
+        assert!(report.render_to_string_with_assets(&missing).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_save_to_directory() {
+        let mut report = Report::new("Redeem", "1.0", None, "Multi-page Report");
+        for title in ["Summary", "QC Metrics"] {
+            let mut section = ReportSection::new(title);
+            section.add_content(html! { p { (title) " content" } });
+            report.add_section(section);
+        }
+
+        let dir = std::env::temp_dir().join(format!("report-builder-directory-{}", std::process::id()));
+        report.save_to_directory(&dir).unwrap();
+        let index = std::fs::read_to_string(dir.join("index.html")).unwrap();
+        let page = std::fs::read_to_string(dir.join("02-qc-metrics.html")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(index.contains(r#"<li><a href="01-summary.html">Summary</a></li>"#));
+        assert!(index.contains(r#"<a class="tab active" href="index.html">Index</a>"#));
+        assert!(page.contains(r#"<a class="tab active" href="02-qc-metrics.html">QC Metrics</a>"#));
+        assert!(page.contains("QC Metrics content"));
+        assert!(!page.contains("Summary content"));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_watermark() {
+        let mut report = Report::new("Redeem", "1.2", None, "Watermarked");
+        assert!(!report.to_string().contains("Plotly.relayout(plotDiv"));
+        report.watermark_plots(true);
+        let html = report.to_string();
+        assert!(html.contains("Plotly.relayout(plotDiv"));
+        assert!(html.contains("Redeem v1.2 · "));
+    }
+
+    #[test]
+    fn test_subsections() {
+        let mut detail = ReportSection::new("Per-run detail");
+        detail.warn(WarningKind::Other, "Run 3 skipped");
+        let mut metrics = ReportSection::new("QC Metrics (MS1)");
+        metrics.add_subsection(detail);
+        let mut section = ReportSection::new("Quality Control");
+        section.add_subsection(metrics);
+
+        let html = section.render().into_string();
+        assert!(html.contains(r#"<div id="quality-control"><h2>Quality Control</h2>"#));
+        assert!(html.contains(r#"id="quality-control--qc-metrics-ms1" open ontoggle="window.dispatchEvent(new Event('resize'))"><summary><h3>QC Metrics (MS1)</h3>"#));
+        assert!(html.contains(r#"id="quality-control--qc-metrics-ms1--per-run-detail" open ontoggle="window.dispatchEvent(new Event('resize'))"><summary><h4>"#));
+        assert_eq!(section.log().warnings()[0].section.as_deref(), Some("Per-run detail"));
+    }
+
+    #[test]
+    fn test_toc() {
+        let mut section = ReportSection::new("Quality Control");
+        section.add_subsection(ReportSection::new("MS1"));
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_section(ReportSection::new("Summary"));
+        report.add_section(section);
+        assert!(!report.to_string().contains(r#"<nav class="toc">"#));
+
+        let html = report.with_toc(true).to_string();
+        assert!(html.contains(r#"<nav class="toc">"#));
+        assert!(html.contains(r##"<a href="#summary" data-tab="tab0">Summary</a>"##));
+        assert!(html.contains(r##"<a href="#quality-control--ms1" data-tab="tab1">MS1</a>"##));
+    }
+
+    #[test]
+    fn test_section_numbering() {
+        let mut section = ReportSection::new("Quality Control");
+        section.add_subsection(ReportSection::new("MS1"));
+        let mut summary = ReportSection::new("Summary");
+        summary.add_content(html! { p { "See " (numbering::section_ref(&["Quality Control", "MS1"])) } });
+        let mut report = Report::new("Redeem", "1.0", None, "My Report")
+            .with_toc(true)
+            .with_section_numbering(Some(SectionNumbering::default().heading_suffix(".")));
+        report.add_section(summary);
+        report.add_section(section);
+
+        let html = report.to_string();
+        assert!(html.contains(r#"<h2><span class="section-number">1.</span> Summary</h2>"#));
+        assert!(html.contains(r#"<summary><h3><span class="section-number">2.1.</span> MS1</h3>"#));
+        assert!(html.contains(r##"<a href="#quality-control--ms1" data-tab="tab1"><span class="section-number">2.1.</span> MS1</a>"##));
+        assert!(html.contains(r##"<a class="section-ref" href="#quality-control--ms1">Section 2.1</a>"##));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_duplicate_section_anchors() {
+        let plot = plot_scatter(&[vec![1.0, 2.0]], &[vec![3.0, 4.0]], ["a"], "Scatter", "x", "y").unwrap();
+        let mut report = Report::new("Redeem", "1.0", None, "My Report").with_toc(true);
+        for title in ["QC", "QC", "???"] {
+            let mut section = ReportSection::new(title);
+            section.add_subsection(ReportSection::new("MS1"));
+            section.add_content(html! { p { "See " (numbering::section_ref(&[title])) } });
+            section.add_plot(plot.clone());
+            report.add_section(section);
+        }
+
+        let html = report.to_string();
+        for anchor in ["qc", "qc--ms1", "qc-2", "qc-2--ms1", "section-3", "section-3--ms1"] {
+            assert_eq!(html.matches(&format!(r#" id="{}""#, anchor)).count(), 1, "{}", anchor);
+            assert!(html.contains(&format!(r##"<a href="#{}" data-tab="##, anchor)), "{}", anchor);
+        }
+        for plot_id in ["plot-qc-1", "plot-qc-2-1", "plot-section-3-1"] {
+            assert_eq!(html.matches(&format!(r#" id="{}""#, plot_id)).count(), 1, "{}", plot_id);
+        }
+        assert_eq!(html.matches(r##"<a class="section-ref" href="#qc">QC</a>"##).count(), 2);
+        assert!(html.contains(r##"<a class="section-ref" href="#section-3">???</a>"##));
+        assert!(!html.contains(r#" id="""#));
+    }
+
+    #[test]
+    fn test_appendix() {
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_section(ReportSection::appendix("Methods", false));
+        let mut summary = ReportSection::new("Summary");
+        summary.add_content(html! { p { "See " (numbering::section_ref(&["Raw data"])) } });
+        report.add_section(summary);
+        report.add_section(ReportSection::appendix("Raw data", true));
+
+        assert_eq!(report.sections.iter().map(|s| s.title.as_str()).collect::<Vec<_>>(), vec!["Summary", "Methods", "Raw data"]);
+        let html = report.to_string();
+        assert!(html.contains(r#"onclick="showTab('tab1')">Appendix A: Methods</button>"#));
+        assert!(html.contains(r#"<div id="methods"><h2><span class="section-number">Appendix A:</span> Methods</h2>"#));
+        assert!(html.contains(r#"<details class="subsection appendix" id="raw-data" ontoggle="window.dispatchEvent(new Event('resize'))"><summary><h2><span class="section-number">Appendix B:</span> Raw data</h2>"#));
+        assert!(html.contains(r##"<a class="section-ref" href="#raw-data">Appendix B</a>"##));
+    }
+
+    #[test]
+    fn test_arrange_sections() {
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        for title in ["Summary", "QC", "Identifications"] {
+            report.add_section(ReportSection::new(title));
+        }
+        report.add_section(ReportSection::appendix("Methods", false));
+        let titles = |report: &Report| report.sections.iter().map(|s| s.title.clone()).collect::<Vec<_>>();
+
+        report.insert_section(1, ReportSection::new("Overview"));
+        report.insert_section(10, ReportSection::new("Quantification"));
+        report.insert_section(0, ReportSection::appendix("Parameters", false));
+        assert_eq!(titles(&report), ["Summary", "Overview", "QC", "Identifications", "Quantification", "Parameters", "Methods"]);
+
+        report.get_section_mut("QC").unwrap().add_content(html! { p { "Added later" } });
+        assert_eq!(report.get_section("QC").unwrap().content_blocks.len(), 1);
+        assert_eq!(report.remove_section("Overview").unwrap().title, "Overview");
+        assert!(report.remove_section("Overview").is_none());
+
+        report.reorder_sections(&["Methods", "Identifications", "Summary"]).unwrap();
+        assert_eq!(titles(&report), ["Identifications", "Summary", "QC", "Quantification", "Methods", "Parameters"]);
+        assert!(report.reorder_sections(&["Summary", "Missing"]).is_err());
+        assert_eq!(titles(&report), ["Identifications", "Summary", "QC", "Quantification", "Methods", "Parameters"]);
+    }
+
+    #[test]
+    fn test_cover_page() {
+        let report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains(r#"<section class="cover-page">"#));
+
+        let html = report.with_cover_page(CoverPage::new("Validation").document_id("VAL-17")).to_string();
+        let cover = html.find(r#"<section class="cover-page"><h1 class="cover-title">Validation</h1>"#).unwrap();
+        assert!(cover < html.find(r#"<div class="banner">"#).unwrap());
+        assert!(html.contains("<dt>Document ID</dt><dd>VAL-17</dd>"));
+    }
+
+    #[test]
+    fn test_header_fields() {
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains(r#"<dl class="header-fields">"#));
+
+        report.add_header_field("Project", "PXD000001");
+        report.add_header_field("Batch ID", 7);
+        report.add_header_field("Project", "PXD000002");
+        let html = report.to_string();
+        assert!(html.contains(
+            r#"<dl class="header-fields"><div class="header-field"><dt>Project</dt><dd>PXD000002</dd></div><div class="header-field"><dt>Batch ID</dt><dd>7</dd></div></dl>"#
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_logos() {
+        let mut report = Report::new("Redeem", "1.0", Some("https://example.org/redeem.png"), "My Report");
+        report.add_logo("https://example.org/institute.png", "Institute", LogoPlacement::Left);
+        report.add_logo("img/sponsor.svg", "Sponsor", LogoPlacement::Right);
+
+        let html = report.to_string();
+        assert!(html.contains(r#"<img src="https://example.org/redeem.png" alt="Software Logo"><img class="banner-logo" src="https://example.org/institute.png" alt="Institute"><div class="banner-text">"#));
+        assert!(html.contains(r#"<div class="banner-logos"><img class="banner-logo" src="img/sponsor.svg" alt="Sponsor"></div>"#));
+        let warnings = report.check_assets(Path::new("."));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Logo 'img/sponsor.svg' was not found");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_save_embeds_logos() {
+        let dir = std::env::temp_dir().join(format!("report-builder-logos-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("redeem.svg"), "<svg/>").unwrap();
+        let mut report = Report::new("Redeem", "1.0", Some("redeem.svg"), "My Report");
+        report.add_logo("img/sponsor.svg", "Sponsor", LogoPlacement::Right);
+        assert!(report.to_string().contains(r#"<img src="redeem.svg" alt="Software Logo">"#));
+
+        let path = dir.join("report.html");
+        let warnings = report.save_to_file(path.to_str().unwrap()).unwrap();
+        let html = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(html.contains(r#"<img src="data:image/svg+xml;base64,PHN2Zy8+" alt="Software Logo">"#));
+        assert!(html.contains(r#"src="img/sponsor.svg" alt="Sponsor""#));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::MissingAsset);
+    }
+
+    #[test]
+    fn test_status_region() {
+        let html = Report::new("Redeem", "1.0", None, "My Report").to_string();
+        assert!(html.contains(r#"<div id="report-status" class="visually-hidden" role="status" aria-live="polite" aria-atomic="true"></div>"#));
+        assert!(html.contains("window.announceStatus = function(message)"));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains("Run information"));
+
+        report.add_metadata("Hostname", "node-1");
+        report.add_metadata("Threads", 8);
+        report.add_metadata("Hostname", "node-2");
+        let html = report.to_string();
+        assert!(html.contains(r#"<details class="run-info"><summary>Run information</summary><table class="key-value"><tbody><tr><th scope="row">Hostname</th><td>node-2</td></tr><tr><th scope="row">Threads</th><td>8</td></tr>"#));
+
+        report.add_tag("instrument", "Astral");
+        report.add_tag("instrument", "Astral");
+        assert_eq!(report.to_string().matches(r#"<span class="tag">instrument: Astral</span>"#).count(), 1);
+        #[cfg(feature = "std")]
+        assert_eq!(report.manifest("run.html").tags, vec![("instrument".to_string(), "Astral".to_string())]);
+
+        let mut section = ReportSection::new("Parameters");
+        section.add_key_value_table([("fdr", 0.01), ("min_length", 7.0)]);
+        assert!(section.render_to_string().contains(r#"<th scope="row">min_length</th><td>7</td>"#));
+    }
+
+    #[test]
+    fn test_custom_head_and_scripts() {
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_head_element(html! { meta name="robots" content="noindex"; });
+        report.add_custom_css("body { font-family: 'Corporate Sans'; }");
+        report.add_custom_js("console.log('loaded');");
+
+        let html = report.to_string();
+        let head_end = html.find("</head>").unwrap();
+        let meta = html.find(r#"<meta name="robots" content="noindex">"#).unwrap();
+        let css = html.find("<style>body { font-family: 'Corporate Sans'; }</style>").unwrap();
+        assert!(meta < css && css < head_end);
+        assert!(html.ends_with("<script>console.log('loaded');</script></body></html>"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_timestamp() {
+        let report = Report::new("Redeem", "1.0", None, "Plasma QC")
+            .with_time_zone(TimeZone::Offset(2 * 3600))
+            .with_timestamp_format("%d %b %Y %H:%M %:z");
+        let html = testing::deterministic(|| report.to_string());
+        assert!(html.contains(r#"<p class="timestamp" data-timestamp="2000-01-01T02:00:00+02:00">Generated on: 01 Jan 2000 02:00 +02:00</p>"#));
+        assert_eq!(testing::deterministic(|| report.manifest("run.html")).date, "2000-01-01 02:00:00");
+
+        let report = Report::new("Redeem", "1.0", None, "Plasma QC").with_time_zone(TimeZone::Utc).with_timestamp_format("%Q");
+        assert!(testing::deterministic(|| report.to_string()).contains("Generated on: 2000-01-01 00:00:00</p>"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_run_times() {
+        let time = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap();
+        let mut report = Report::new("Redeem", "1.0", None, "Plasma QC").with_time_zone(TimeZone::Utc);
+        report.set_run_times(
+            time("2024-05-01T12:00:00+02:00"),
+            time("2024-05-01T14:05:30+02:00"),
+            Some((time("2024-04-28T08:00:00Z"), time("2024-04-30T17:45:00Z"))),
+        );
+        let html = Report::from_json(&report.to_json().unwrap()).unwrap().to_string();
+        assert!(html.contains(
+            r#"<p class="run-times">Run: <time datetime="2024-05-01T10:00:00+00:00">2024-05-01 10:00:00</time> – <time datetime="2024-05-01T12:05:30+00:00">2024-05-01 12:05:30</time> (2h 05m)</p>"#
+        ));
+        assert!(html.contains(r#"<p class="acquisition-times">Data acquired: <time datetime="2024-04-28T08:00:00+00:00">2024-04-28 08:00:00</time> – "#));
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(42)), "42s");
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(90_061)), "1d 01h");
+    }
+
+    #[test]
+    fn test_report_header() {
+        let report = Report::new("Redeem", "1.0", None, "Plasma QC")
+            .with_description("Weekly QC of the plasma cohort.")
+            .with_author("Proteomics core");
+        let html = report.to_string();
+        assert!(html.contains(
+            r#"<header class="report-header"><h1>Plasma QC</h1><p class="report-description">Weekly QC of the plasma cohort.</p><p class="report-author">Author: Proteomics core</p></header>"#
+        ));
+        assert!(Report::new("Redeem", "1.0", None, "Plasma QC").to_string().contains(r#"<header class="report-header"><h1>Plasma QC</h1></header>"#));
+    }
+
+    #[test]
+    fn test_group_toggles() {
+        let report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains(r#"<div class="group-toggles">"#));
+
+        let report = report.with_group_toggles(["Run 1", "Run <2>"]);
+        let html = report.to_string();
+        assert!(html.contains(r#"<label><input type="checkbox" value="Run &lt;2&gt;" checked> Run &lt;2&gt;</label>"#));
+        assert!(html.contains("window.applyGroupToggles"));
+    }
+
+    #[test]
+    fn test_search() {
+        let report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains(r#"id="report-search-input""#));
+
+        let html = report.with_search(true).to_string();
+        assert!(html.contains(
+            r#"<div class="report-search" role="search"><input type="search" id="report-search-input" placeholder="Search report" aria-label="Search the report"><span class="report-search-count"></span></div>"#
+        ));
+        assert!(html.contains("jQuery.fn.dataTable.tables({ api: true }).every("));
+    }
+
+    #[test]
+    fn test_theme() {
+        let report = Report::new("Redeem", "1.0", None, "My Report");
+        assert!(!report.to_string().contains(r#"class="theme-toggle""#));
+
+        let html = report.with_theme(Theme::dark()).with_dark_mode_toggle(true).to_string();
+        assert!(html.contains(":root { --primary-color: #4dabf7;"));
+        assert!(html.contains(r#"class="theme-toggle""#));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_build_warnings() {
+        let mut report = Report::new("Redeem", "1.0", Some("missing_logo.png"), "My Report");
+
+        let mut plot = Plot::new();
+        plot.add_trace(plotly::Scatter::new(vec![1.0, 2.0, 3.0], vec![1.0, f64::NAN, 3.0]));
+        let mut section = ReportSection::new("Section 1");
+        section.add_plot(plot);
+        report.add_section(section);
+
+        let warnings = report.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::DroppedNaN);
+        assert_eq!(warnings[0].section.as_deref(), Some("Section 1"));
+        assert!(report.to_string().contains("<table><thead><tr><th>Kind</th><th>Section</th><th>Message</th>"));
+
+        let build_warnings = report.check_assets(&std::env::temp_dir());
+        assert_eq!(build_warnings[0].kind, WarningKind::MissingAsset);
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_bin_slider() {
+        let mut section = ReportSection::new("Section 1");
+        section.add_plot_with_opts(
+            Plot::new(),
+            PlotOptions {
+                bin_slider: Some(BinSlider::around(2.0)),
+                ..Default::default()
+            },
+        );
+
+        let html = section.render().into_string();
+        assert!(html.contains(r#"type="range" min="0.5" max="8" step="0.5" value="2""#));
+        assert!(html.contains("'xbins.size'"));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_plot_options() {
+        let mut section = ReportSection::new("Section 1");
+        section.add_plot_with_opts(
+            Plot::new(),
+            PlotOptions {
+                height: 300,
+                ..Default::default()
+            },
+        );
+        section.add_plot_with_opts(
+            Plot::new(),
+            PlotOptions {
+                aspect: Some(1.0),
+                full_width: true,
+                max_width: Some(500),
+                ..Default::default()
+            },
+        );
+
+        let html = section.render().into_string();
+        assert!(html.contains("style=\"height: 300px;\""));
+        assert!(html.contains("let height = width * 1;"));
+        assert!(html.contains("window.innerWidth * 0.95"));
+        assert!(html.contains("style=\"max-width: 500px;\"") && html.contains("width = Math.min(width, 500);"));
+        assert_eq!(html.matches("class=\"download-data-button\"").count(), 2);
+
+        section.add_plot_with_opts(Plot::new(), PlotOptions { download_data: false, ..Default::default() });
+        assert_eq!(section.render().into_string().matches("class=\"download-data-button\"").count(), 2);
+
+        let mut plot = Plot::new();
+        plot.set_layout(plotly::Layout::new().title("QC metrics (MS1)"));
+        let export = ImageExport { format: ImageFormat::Svg, scale: 3, svg_button: true };
+        section.add_plot_with_opts(plot, PlotOptions { image_export: Some(export), ..Default::default() });
+        let html = section.render().into_string();
+        assert!(html.contains(r#""toImageButtonOptions":{"format":"svg","filename":"qc-metrics-ms1","scale":3}"#));
+        assert!(html.contains(r#"onclick="Plotly.downloadImage('plot-section-1-4', { format: 'svg', filename: 'qc-metrics-ms1' })">SVG</button>"#));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_static_fallback() {
+        let plot = plot_scatter(&[vec![1.0, 2.0]], &[vec![3.0, 4.0]], ["a"], "Scatter", "x", "y").unwrap();
+        let mut section = ReportSection::new("Section 1");
+        section.add_plot(plot.clone());
+        assert!(!section.render().into_string().contains("plot-fallback"));
+        assert!(section.render().into_string().contains(
+            r#"<div class="plot-wrapper" role="figure" aria-describedby="plot-section-1-1-summary"><p id="plot-section-1-1-summary" class="visually-hidden plot-summary">Scatter plot &quot;Scatter&quot;. x axis &quot;x&quot;: 1 to 2."#
+        ));
+
+        section.add_plot_with_opts(plot, PlotOptions { static_fallback: true, ..Default::default() });
+        let html = section.render().into_string();
+        assert_eq!(html.matches(r#"class="plot-fallback" src="data:image/svg+xml;base64,"#).count(), 1);
+        assert!(html.contains("-fallback').remove();"));
+    }
+
+    #[test]
+    #[cfg(feature = "plots")]
+    fn test_lazy_plot() {
+        let mut section = ReportSection::new("Section 1");
+        section.add_plot_with_opts(Plot::new(), PlotOptions { lazy: true, ..Default::default() });
+        let html = section.render().into_string();
+        assert!(!html.contains("Plotly.newPlot("));
+        assert!(html.contains(r##"<script type="application/json" class="lazy-plot-figure" data-plot="#"##));
+        assert!(html.contains("if (plotDiv && plotDiv.data) {"));
+        assert!(section.assets.contains(&Asset::LazyPlots));
+    }
+
+    #[test]
+    #[cfg(all(feature = "plots", feature = "tables"))]
+    fn test_render_fragment() {
+        let mut subsection = ReportSection::new("Runs");
+        subsection.add_table(Table::new().headers(["Run"]).add_row(["run1"]));
+        let mut section = ReportSection::new("Scores");
+        section.add_plot(Plot::new());
+        section.add_plot(Plot::new());
+        section.add_subsection(subsection);
+
+        let fragment = section.render_fragment();
+        assert!(fragment.html.starts_with(r#"<div id="scores"><h2>Scores</h2>"#));
+        assert_eq!(fragment.assets, vec![Asset::PlotData]);
+        assert_eq!(fragment.libraries.len(), Library::ALL.len());
+        let head = fragment.head();
+        assert_eq!(head.matches("function downloadPlotData").count(), 1);
+        assert!(head.starts_with(r#"<script src="https://cdn.plot.ly/"#));
+
+        let fragment = ReportSection::new("Notes").render_fragment();
+        assert!(fragment.assets.is_empty() && fragment.libraries.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "plots", feature = "tables"))]
+    fn test_render_namespaced_fragment() {
+        let mut section = ReportSection::new("Scores");
+        section.add_table(Table::new().id("runs").headers(["Run"]));
+        section.add_plot(Plot::new());
+
+        let fragment = section.render_namespaced_fragment("Run 42");
+        assert_eq!(fragment.namespace.as_deref(), Some("run-42"));
+        assert!(fragment.html.starts_with(r#"<div class="run-42"><div id="run-42-scores">"#));
+        assert!(fragment.html.contains(r#"<table class="display" id="run-42-runs">"#));
+        assert!(fragment.html.contains("jQuery(function($) {"));
+        assert!(fragment.html.contains("$('#run-42-runs').DataTable("));
+        assert!(!fragment.html.contains(" id=\"runs\""));
+        assert!(fragment.head().contains(".run-42 .download-data-button {"));
     }
 }
@@ -0,0 +1,135 @@
+//! Input data fingerprints ("data lineage") attached to figures and tables.
+//!
+//! Each plot or table in a report can carry the list of input files it was produced from, with
+//! their SHA-256 checksum and row count. These are rendered in an expandable "Data lineage"
+//! popover below the figure and are kept in the JSON export of the report.
+
+#[cfg(feature = "data-sources")]
+use std::fs::File;
+#[cfg(feature = "data-sources")]
+use std::io::{BufReader, Read};
+#[cfg(feature = "data-sources")]
+use std::path::Path;
+
+use alloc::string::{String, ToString};
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "data-sources")]
+use sha2::{Digest, Sha256};
+
+/// A fingerprint of an input file used to produce a figure or table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataFingerprint {
+    pub path: String,
+    pub sha256: String,
+    pub rows: Option<usize>,
+}
+
+impl DataFingerprint {
+    /// Creates a fingerprint from already known values.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the input file.
+    /// * `sha256` - The hex-encoded SHA-256 checksum of the file.
+    /// * `rows` - The number of data rows in the file, if known.
+    pub fn new(path: &str, sha256: &str, rows: Option<usize>) -> Self {
+        DataFingerprint {
+            path: path.to_string(),
+            sha256: sha256.to_string(),
+            rows,
+        }
+    }
+
+    /// Computes the fingerprint of a file on disk, counting its lines as rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the input file.
+    ///
+    /// # Returns
+    ///
+    /// The fingerprint, or an IO error if the file cannot be read.
+    #[cfg(feature = "data-sources")]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path.as_ref())?);
+        let mut hasher = Sha256::new();
+        let mut buffer = [0u8; 64 * 1024];
+        let mut lines = 0;
+        let mut last_byte = b'\n';
+
+        loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+            lines += buffer[..n].iter().filter(|&&b| b == b'\n').count();
+            last_byte = buffer[n - 1];
+        }
+        // Count a final line that is not newline-terminated
+        if last_byte != b'\n' {
+            lines += 1;
+        }
+
+        Ok(DataFingerprint {
+            path: path.as_ref().display().to_string(),
+            sha256: hex::encode(hasher.finalize()),
+            rows: Some(lines),
+        })
+    }
+}
+
+/// Render an expandable "Data lineage" popover listing the given fingerprints.
+pub(crate) fn render_lineage(lineage: &[DataFingerprint]) -> Markup {
+    html! {
+        details class="data-lineage" {
+            summary { "Data lineage" }
+            table {
+                thead {
+                    tr {
+                        th { "File" }
+                        th { "SHA-256" }
+                        th { "Rows" }
+                    }
+                }
+                tbody {
+                    @for fingerprint in lineage {
+                        tr {
+                            td { (fingerprint.path) }
+                            td { code { (fingerprint.sha256) } }
+                            td {
+                                @if let Some(rows) = fingerprint.rows {
+                                    (rows)
+                                } @else {
+                                    "-"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "data-sources"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_from_path() {
+        let path = std::env::temp_dir().join(format!("report-builder-lineage-{}.tsv", std::process::id()));
+        std::fs::write(&path, "a\tb\n1\t2\n3\t4").unwrap();
+
+        let fingerprint = DataFingerprint::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(fingerprint.rows, Some(3));
+        assert_eq!(
+            fingerprint.sha256,
+            "4822cdf3a2f7780e94fb3b0a20473e7946556a8eb9dd42ab8e947626c4ca2856"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Manifests summarizing saved reports.
+//!
+//! A [`ReportManifest`] is a small JSON file saved next to a report (e.g. `run.manifest.json` for
+//! `run.html`) with its title, generation date and metadata, so archives of reports can be
+//! indexed and trended without parsing HTML, see [`Report::save_manifest`](crate::Report::save_manifest)
+//! and the `report-builder index` command.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ReportError;
+
+/// The extension of manifest files, replacing the `html` extension of their report.
+pub const MANIFEST_EXTENSION: &str = "manifest.json";
+
+/// A summary of a saved report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportManifest {
+    pub title: String,
+    pub software_name: String,
+    pub version: String,
+    /// When the report was generated, e.g. "2024-05-01 12:00:00"
+    pub date: String,
+    /// The report file, relative to the directory of the manifest
+    pub report: String,
+    /// The entries of the report's "Run information" panel, see
+    /// [`Report::add_metadata`](crate::Report::add_metadata)
+    #[serde(default)]
+    pub metadata: Vec<(String, String)>,
+    /// The labels of the report, see [`Report::add_tag`](crate::Report::add_tag)
+    #[serde(default)]
+    pub tags: Vec<(String, String)>,
+    /// The ID other reports link to the report by, see [`Report::with_report_id`](crate::Report::with_report_id)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// The reports linked from the report, as (ID, label) pairs, see
+    /// [`Report::add_report_link`](crate::Report::add_report_link)
+    #[serde(default)]
+    pub links: Vec<(String, String)>,
+}
+
+impl ReportManifest {
+    /// The path of the manifest of a report, e.g. `run.manifest.json` for `run.html`.
+    pub fn path_for(report_path: &Path) -> PathBuf {
+        report_path.with_extension(MANIFEST_EXTENSION)
+    }
+
+    /// The value of a metadata entry, if the report has one.
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Reads a manifest from a JSON file.
+    pub fn load(path: &Path) -> Result<Self, ReportError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the manifest to a JSON file.
+    pub fn save(&self, path: &Path) -> Result<(), ReportError> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reads all manifests in a directory and its subdirectories.
+    ///
+    /// # Returns
+    ///
+    /// The path of each manifest and its contents, or an error if a directory or manifest cannot
+    /// be read.
+    pub fn find<P: AsRef<Path>>(dir: P) -> Result<Vec<(PathBuf, Self)>, ReportError> {
+        let paths = find_files(dir.as_ref(), &|path| {
+            path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(MANIFEST_EXTENSION))
+        })?;
+        paths
+            .into_iter()
+            .map(|path| ReportManifest::load(&path).map(|manifest| (path, manifest)))
+            .collect()
+    }
+}
+
+/// The files in a directory and its subdirectories matching a predicate, sorted by path
+pub(crate) fn find_files(dir: &Path, matches: &dyn Fn(&Path) -> bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Report;
+
+    #[test]
+    fn test_save_manifest() {
+        let dir = std::env::temp_dir().join(format!("report-builder-manifest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("2024")).unwrap();
+        let mut report = Report::new("Redeem", "1.0", None, "Run QC");
+        report.add_metadata("Status", "passed");
+        let filename = dir.join("2024/run.html");
+        let path = report.save_manifest(filename.to_str().unwrap()).unwrap();
+        assert_eq!(path, dir.join("2024/run.manifest.json"));
+
+        let manifests = ReportManifest::find(&dir).unwrap();
+        assert_eq!(manifests.len(), 1);
+        let manifest = &manifests[0].1;
+        assert_eq!((manifest.title.as_str(), manifest.report.as_str()), ("Run QC", "run.html"));
+        assert_eq!(manifest.metadata("Status"), Some("passed"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
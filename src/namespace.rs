@@ -0,0 +1,97 @@
+//! Namespacing of rendered sections, so they can be injected into another web application without
+//! colliding with its element IDs and styles.
+//!
+//! Element IDs are prefixed with the namespace, along with the references to them in links and
+//! scripts, and the component styles are scoped to a container carrying the namespace as its
+//! class. See [`ReportSection::render_namespaced_fragment`](crate::ReportSection::render_namespaced_fragment).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The IDs of the elements in an HTML document, in order of appearance
+fn element_ids(html: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(" id=\"") {
+        rest = &rest[start + 5..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        if !rest[..end].is_empty() && !ids.contains(&&rest[..end]) {
+            ids.push(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    ids
+}
+
+/// Prefix every element ID of an HTML fragment, along with the references to it in links (`#id`)
+/// and in script calls taking it as their first argument (e.g. `getElementById('id')` or
+/// `$('#id')`).
+pub(crate) fn prefix_ids(html: &str, prefix: &str) -> String {
+    let mut prefixed = String::from(html);
+    for id in element_ids(html) {
+        let new_id = format!("{}-{}", prefix, id);
+        for (old, new) in [
+            (format!(" id=\"{}\"", id), format!(" id=\"{}\"", new_id)),
+            (format!("=\"#{}\"", id), format!("=\"#{}\"", new_id)),
+            (format!("('{}'", id), format!("('{}'", new_id)),
+            (format!("(\"{}\"", id), format!("(\"{}\"", new_id)),
+            (format!("('#{}'", id), format!("('#{}'", new_id)),
+        ] {
+            prefixed = prefixed.replace(&old, &new);
+        }
+    }
+    prefixed
+}
+
+/// Scope the rules of a style sheet to the descendants of the elements with the given class.
+///
+/// Only flat rules are scoped, as in the component assets; at-rules (e.g. `@media`) are kept as
+/// they are.
+pub(crate) fn scope_css(css: &str, class: &str) -> String {
+    let mut scoped = String::new();
+    let mut rest = css;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        let selectors = rest[..open].trim();
+        if selectors.starts_with('@') {
+            scoped.push_str(&rest[..=close]);
+        } else {
+            let selectors: Vec<String> = selectors
+                .split(',')
+                .map(|selector| format!(".{} {}", class, selector.trim()))
+                .collect();
+            scoped.push_str(&format!("{} {}\n", selectors.join(", "), &rest[open..=close]));
+        }
+        rest = &rest[close + 1..];
+    }
+    scoped.push_str(rest);
+    scoped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_ids() {
+        let html = r##"<div id="scores"><a href="#scores">Top</a><div id="abc"></div><script>Plotly.newPlot("abc", {}); $('#abc').hide(); downloadPlotData('abc')</script></div>"##;
+        assert_eq!(
+            prefix_ids(html, "lims"),
+            r##"<div id="lims-scores"><a href="#lims-scores">Top</a><div id="lims-abc"></div><script>Plotly.newPlot("lims-abc", {}); $('#lims-abc').hide(); downloadPlotData('lims-abc')</script></div>"##
+        );
+    }
+
+    #[test]
+    fn test_scope_css() {
+        let css = ".carousel { width: 100%; }\n.carousel-controls button, .carousel:hover { padding: 4px; }\n";
+        assert_eq!(
+            scope_css(css, "lims"),
+            ".lims .carousel { width: 100%; }\n.lims .carousel-controls button, .lims .carousel:hover { padding: 4px; }\n\n"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Narrative text generated from rules over recorded metrics, so a report's prose adapts to its
+//! results instead of being hardcoded.
+//!
+//! Metrics are recorded by name as they are computed, and each [`Rule`] adds a paragraph when its
+//! metric meets a condition (and optionally another one when it does not):
+//!
+//! ```rust,ignore
+//! use report_builder::narrative::{Condition, Metrics, Narrative, Rule};
+//!
+//! let mut metrics = Metrics::new();
+//! metrics.record("median_cv", 0.24);
+//!
+//! let narrative = Narrative::new()
+//!     .rule(
+//!         Rule::when("median_cv", Condition::Above(0.2))
+//!             .warning("The median CV is {value}, above the 20% target: check the sample preparation.")
+//!             .otherwise("The median CV of {value} is within the 20% target."),
+//!     );
+//! section.add_narrative(&narrative, &metrics);
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+/// Named numeric results of a run, e.g. the median CV or the number of identifications.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    values: BTreeMap<String, f64>,
+}
+
+impl Metrics {
+    /// Creates an empty set of metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the value of a metric, replacing any previous value.
+    pub fn record(&mut self, name: &str, value: f64) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    /// The value of a metric, if it was recorded.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+
+    /// Iterates over the metrics, in order of their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.values.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+/// A condition on the value of a metric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// Strictly greater than the bound
+    Above(f64),
+    /// Strictly less than the bound
+    Below(f64),
+    /// Within the bounds, inclusive
+    Between(f64, f64),
+    /// Outside the bounds
+    Outside(f64, f64),
+}
+
+impl Condition {
+    /// Whether the value meets the condition; NaN never does.
+    pub fn holds(&self, value: f64) -> bool {
+        match *self {
+            Condition::Above(bound) => value > bound,
+            Condition::Below(bound) => value < bound,
+            Condition::Between(low, high) => value >= low && value <= high,
+            Condition::Outside(low, high) => value < low || value > high,
+        }
+    }
+}
+
+/// How prominently a paragraph is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tone {
+    /// A plain paragraph
+    #[default]
+    Info,
+    /// A highlighted warning
+    Warning,
+}
+
+/// A paragraph added when a metric meets a condition, and optionally another when it does not.
+///
+/// The text may contain `{value}`, replaced by the metric's value. Rules whose metric was not
+/// recorded add nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    metric: String,
+    condition: Condition,
+    then: Option<(Tone, String)>,
+    otherwise: Option<(Tone, String)>,
+}
+
+impl Rule {
+    /// Creates a rule on a metric, with no text yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The name the metric is recorded under.
+    /// * `condition` - The condition the metric's value is tested against.
+    pub fn when(metric: &str, condition: Condition) -> Self {
+        Rule {
+            metric: metric.to_string(),
+            condition,
+            then: None,
+            otherwise: None,
+        }
+    }
+
+    /// Sets the paragraph added when the condition holds.
+    pub fn then(mut self, text: &str) -> Self {
+        self.then = Some((Tone::Info, text.to_string()));
+        self
+    }
+
+    /// Sets the warning added when the condition holds.
+    pub fn warning(mut self, text: &str) -> Self {
+        self.then = Some((Tone::Warning, text.to_string()));
+        self
+    }
+
+    /// Sets the paragraph added when the condition does not hold.
+    pub fn otherwise(mut self, text: &str) -> Self {
+        self.otherwise = Some((Tone::Info, text.to_string()));
+        self
+    }
+
+    /// Sets the warning added when the condition does not hold.
+    pub fn otherwise_warning(mut self, text: &str) -> Self {
+        self.otherwise = Some((Tone::Warning, text.to_string()));
+        self
+    }
+
+    /// The paragraph the rule adds for the given metrics, if any.
+    pub fn evaluate(&self, metrics: &Metrics) -> Option<(Tone, String)> {
+        let value = metrics.get(&self.metric)?;
+        let (tone, text) = if self.condition.holds(value) { self.then.as_ref() } else { self.otherwise.as_ref() }?;
+        Some((*tone, text.replace("{value}", &value.to_string())))
+    }
+}
+
+/// An ordered list of rules, rendered as the paragraphs of the rules that apply.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Narrative {
+    rules: Vec<Rule>,
+}
+
+impl Narrative {
+    /// Creates a narrative without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule; paragraphs are rendered in the order their rules were added.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// The paragraphs the rules add for the given metrics.
+    pub fn evaluate(&self, metrics: &Metrics) -> Vec<(Tone, String)> {
+        self.rules.iter().filter_map(|rule| rule.evaluate(metrics)).collect()
+    }
+
+    /// Render the paragraphs the rules add for the given metrics.
+    pub fn render(&self, metrics: &Metrics) -> Markup {
+        html! {
+            @for (tone, text) in self.evaluate(metrics) {
+                @match tone {
+                    Tone::Info => p class="narrative" { (text) },
+                    Tone::Warning => p class="narrative narrative-warning" role="note" { (text) },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_narrative() {
+        let mut metrics = Metrics::new();
+        metrics.record("median_cv", 0.25);
+        metrics.record("identifications", 12000.0);
+
+        let narrative = Narrative::new()
+            .rule(Rule::when("median_cv", Condition::Above(0.2)).warning("Median CV is {value}.").otherwise("CV is fine."))
+            .rule(Rule::when("identifications", Condition::Below(5000.0)).warning("Few identifications."))
+            .rule(Rule::when("missed_cleavages", Condition::Above(0.1)).then("Many missed cleavages."))
+            .rule(Rule::when("identifications", Condition::Between(10000.0, 20000.0)).then("{value} identifications."));
+
+        assert_eq!(
+            narrative.evaluate(&metrics),
+            vec![(Tone::Warning, "Median CV is 0.25.".to_string()), (Tone::Info, "12000 identifications.".to_string())]
+        );
+        let html = narrative.render(&metrics).into_string();
+        assert_eq!(
+            html,
+            r#"<p class="narrative narrative-warning" role="note">Median CV is 0.25.</p><p class="narrative">12000 identifications.</p>"#
+        );
+
+        metrics.record("median_cv", f64::NAN);
+        assert_eq!(narrative.evaluate(&metrics)[0], (Tone::Info, "CV is fine.".to_string()));
+    }
+}
@@ -0,0 +1,259 @@
+//! Compact notification payloads for announcing finished reports in chat tools.
+//!
+//! Pipelines usually want to post a short summary (title, status, a handful of headline metrics
+//! and a link to the full report) once a report has been written. This module turns a [`Report`]
+//! and a [`NotificationSummary`] into either a Slack Block Kit message or a Microsoft Teams
+//! Adaptive Card, both as JSON strings ready to be POSTed to an incoming webhook.
+
+use serde_json::{json, Value};
+
+use crate::Report;
+
+/// The chat platform to generate a notification payload for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationFormat {
+    /// Slack Block Kit message.
+    Slack,
+    /// Microsoft Teams Adaptive Card message.
+    Teams,
+}
+
+/// The overall outcome of the run that produced the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportStatus {
+    Success,
+    Warning,
+    Failure,
+}
+
+impl ReportStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            ReportStatus::Success => "Success",
+            ReportStatus::Warning => "Warning",
+            ReportStatus::Failure => "Failure",
+        }
+    }
+
+    fn emoji(&self) -> &'static str {
+        match self {
+            ReportStatus::Success => ":white_check_mark:",
+            ReportStatus::Warning => ":warning:",
+            ReportStatus::Failure => ":x:",
+        }
+    }
+
+    fn teams_color(&self) -> &'static str {
+        match self {
+            ReportStatus::Success => "Good",
+            ReportStatus::Warning => "Warning",
+            ReportStatus::Failure => "Attention",
+        }
+    }
+}
+
+/// The run-specific information to include in a notification alongside the report metadata.
+#[derive(Debug, Clone)]
+pub struct NotificationSummary {
+    pub status: ReportStatus,
+    /// Headline metrics as (name, value) pairs, shown in the order given. Slack messages show the
+    /// first 10 (the most a Slack section can hold) and a "+N more" line for the rest.
+    pub metrics: Vec<(String, String)>,
+    /// Optional URL of the published report.
+    pub link: Option<String>,
+}
+
+impl NotificationSummary {
+    /// Creates a summary with the given status, no metrics and no link.
+    pub fn new(status: ReportStatus) -> Self {
+        NotificationSummary {
+            status,
+            metrics: Vec::new(),
+            link: None,
+        }
+    }
+
+    /// Adds a headline metric to the summary.
+    pub fn metric(mut self, name: &str, value: &str) -> Self {
+        self.metrics.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the link to the published report.
+    pub fn link(mut self, url: &str) -> Self {
+        self.link = Some(url.to_string());
+        self
+    }
+}
+
+impl Report {
+    /// Renders a compact notification payload announcing this report.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The chat platform to generate the payload for.
+    /// * `summary` - The status, headline metrics and link to include.
+    ///
+    /// # Returns
+    ///
+    /// The JSON payload as a string, ready to be POSTed to an incoming webhook.
+    pub fn render_notification(
+        &self,
+        format: NotificationFormat,
+        summary: &NotificationSummary,
+    ) -> String {
+        let payload = match format {
+            NotificationFormat::Slack => self.slack_payload(summary),
+            NotificationFormat::Teams => self.teams_payload(summary),
+        };
+        payload.to_string()
+    }
+
+    fn slack_payload(&self, summary: &NotificationSummary) -> Value {
+        let mut blocks = vec![
+            json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": self.title },
+            }),
+            json!({
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "{} *{}* - {} v{}",
+                        summary.status.emoji(),
+                        summary.status.label(),
+                        self.software_name,
+                        self.version
+                    ),
+                },
+            }),
+        ];
+
+        if !summary.metrics.is_empty() {
+            // Slack allows at most 10 fields per section block
+            let fields: Vec<Value> = summary
+                .metrics
+                .iter()
+                .take(10)
+                .map(|(name, value)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", name, value) }))
+                .collect();
+            blocks.push(json!({ "type": "section", "fields": fields }));
+            let hidden = summary.metrics.len().saturating_sub(10);
+            if hidden > 0 {
+                blocks.push(json!({
+                    "type": "context",
+                    "elements": [{ "type": "mrkdwn", "text": format!("+{} more {} in the report", hidden, if hidden == 1 { "metric" } else { "metrics" }) }],
+                }));
+            }
+        }
+
+        if let Some(ref link) = summary.link {
+            blocks.push(json!({
+                "type": "actions",
+                "elements": [{
+                    "type": "button",
+                    "text": { "type": "plain_text", "text": "Open report" },
+                    "url": link,
+                }],
+            }));
+        }
+
+        json!({
+            "text": format!("{}: {}", self.title, summary.status.label()),
+            "blocks": blocks,
+        })
+    }
+
+    fn teams_payload(&self, summary: &NotificationSummary) -> Value {
+        let facts: Vec<Value> = summary
+            .metrics
+            .iter()
+            .map(|(name, value)| json!({ "title": name, "value": value }))
+            .collect();
+
+        let mut body = vec![
+            json!({
+                "type": "TextBlock",
+                "text": self.title,
+                "weight": "Bolder",
+                "size": "Medium",
+                "wrap": true,
+            }),
+            json!({
+                "type": "TextBlock",
+                "text": format!("{} - {} v{}", summary.status.label(), self.software_name, self.version),
+                "color": summary.status.teams_color(),
+                "wrap": true,
+            }),
+        ];
+        if !facts.is_empty() {
+            body.push(json!({ "type": "FactSet", "facts": facts }));
+        }
+
+        let mut card = json!({
+            "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+            "type": "AdaptiveCard",
+            "version": "1.4",
+            "body": body,
+        });
+        if let Some(ref link) = summary.link {
+            card["actions"] = json!([{ "type": "Action.OpenUrl", "title": "Open report", "url": link }]);
+        }
+
+        json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": card,
+            }],
+        })
+    }
+}
+
+/// Posts a notification payload to an incoming webhook URL.
+///
+/// # Arguments
+///
+/// * `url` - The Slack or Teams incoming webhook URL.
+/// * `payload` - The JSON payload, usually from [`Report::render_notification`].
+///
+/// # Returns
+///
+/// A Result indicating success or an IO error describing the failed request.
+#[cfg(feature = "webhook")]
+pub fn send_webhook(url: &str, payload: &str) -> std::io::Result<()> {
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .send_string(payload)
+        .map_err(std::io::Error::other)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_notification() {
+        let report = Report::new("Redeem", "1.0", None, "My Report");
+        let summary = NotificationSummary::new(ReportStatus::Success)
+            .metric("Precursors", "12,345")
+            .link("https://example.org/report.html");
+
+        let slack: Value = serde_json::from_str(&report.render_notification(NotificationFormat::Slack, &summary)).unwrap();
+        assert_eq!(slack["blocks"][0]["text"]["text"], "My Report");
+        assert_eq!(slack["blocks"][2]["fields"][0]["text"], "*Precursors*\n12,345");
+        assert_eq!(slack["blocks"][3]["elements"][0]["url"], "https://example.org/report.html");
+
+        let teams: Value = serde_json::from_str(&report.render_notification(NotificationFormat::Teams, &summary)).unwrap();
+        let card = &teams["attachments"][0]["content"];
+        assert_eq!(card["body"][2]["facts"][0]["title"], "Precursors");
+        assert_eq!(card["actions"][0]["url"], "https://example.org/report.html");
+
+        let summary = (0..12).fold(NotificationSummary::new(ReportStatus::Warning), |summary, i| summary.metric(&format!("Metric {}", i), "1"));
+        let slack: Value = serde_json::from_str(&report.render_notification(NotificationFormat::Slack, &summary)).unwrap();
+        assert_eq!(slack["blocks"][2]["fields"].as_array().unwrap().len(), 10);
+        assert_eq!(slack["blocks"][3]["elements"][0]["text"], "+2 more metrics in the report");
+    }
+}
@@ -0,0 +1,181 @@
+//! Hierarchical section numbering and cross-references.
+//!
+//! With [`Report::with_section_numbering`](crate::Report::with_section_numbering), sections are
+//! numbered 1, 2, ... and their subsections 1.1, 1.2.3, ... in their headings and in the table
+//! of contents. Links created with [`section_ref`] are labelled with the number of the section
+//! they point to (e.g. "Section 1.2"), or with its title when numbering is off:
+//!
+//! ```rust,ignore
+//! use report_builder::numbering::{section_ref, SectionNumbering};
+//!
+//! let report = Report::new("Redeem", "1.0", None, "Validation report")
+//!     .with_section_numbering(SectionNumbering::default().heading_suffix("."));
+//! section.add_content(html! { p { "Acceptance criteria are listed in " (section_ref(&["Methods", "Criteria"])) "." } });
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+/// How section numbers are formatted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionNumbering {
+    heading_prefix: String,
+    heading_suffix: String,
+    reference_prefix: String,
+    separator: String,
+}
+
+impl Default for SectionNumbering {
+    /// Headings numbered "1.2 Title", referenced as "Section 1.2".
+    fn default() -> Self {
+        SectionNumbering {
+            heading_prefix: String::new(),
+            heading_suffix: String::new(),
+            reference_prefix: "Section ".to_string(),
+            separator: ".".to_string(),
+        }
+    }
+}
+
+impl SectionNumbering {
+    /// Sets the text before the number in headings, e.g. "Chapter " for "Chapter 1.2 Title".
+    pub fn heading_prefix(mut self, prefix: &str) -> Self {
+        self.heading_prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the text after the number in headings, e.g. "." for "1.2. Title".
+    pub fn heading_suffix(mut self, suffix: &str) -> Self {
+        self.heading_suffix = suffix.to_string();
+        self
+    }
+
+    /// Sets the text before the number in cross-references, "Section " by default.
+    pub fn reference_prefix(mut self, prefix: &str) -> Self {
+        self.reference_prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the text between the levels of a number, "." by default.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// The number of a section, from the 1-based position of it and its ancestors.
+    pub fn number(&self, path: &[usize]) -> String {
+        path.iter().map(ToString::to_string).collect::<Vec<_>>().join(&self.separator)
+    }
+
+    /// The label of a section in its heading and the table of contents, e.g. "1.2".
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the section, see [`SectionNumbering::number`].
+    pub fn heading_label(&self, number: &str) -> String {
+        format!("{}{}{}", self.heading_prefix, number, self.heading_suffix)
+    }
+
+    /// The label of a cross-reference to a section, e.g. "Section 1.2".
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the section, see [`SectionNumbering::number`].
+    pub fn reference_label(&self, number: &str) -> String {
+        format!("{}{}", self.reference_prefix, number)
+    }
+}
+
+/// The number of the subsection at the given 1-based position below a section, e.g. "2.3" for the
+/// third subsection of section 2; empty when sections are not numbered
+pub(crate) fn child_number(parent: &str, position: usize, numbering: Option<&SectionNumbering>) -> String {
+    match numbering {
+        Some(numbering) => format!("{}{}{}", parent, numbering.separator, position),
+        None => String::new(),
+    }
+}
+
+/// A link to a section of the same report, labelled when the report is rendered.
+///
+/// # Arguments
+///
+/// * `path` - The titles of the section and its ancestors, from the top-level section down.
+pub fn section_ref(path: &[&str]) -> Markup {
+    let anchor = path.iter().map(|title| crate::slugify(title)).collect::<Vec<_>>().join("--");
+    html! {
+        a class="section-ref" href=(format!("#{}", anchor)) { (path.last().copied().unwrap_or_default()) }
+    }
+}
+
+/// The section a cross-reference points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RefTarget {
+    /// The unique anchor of the section, which may differ from the one the reference was created
+    /// with if several sections share a title
+    pub(crate) anchor: String,
+    /// The label replacing the text of the reference, if the section has one
+    pub(crate) label: Option<String>,
+}
+
+/// The opening tag of a cross-reference, up to its anchor
+const REF_START: &str = r##"<a class="section-ref" href="#"##;
+
+/// Point the cross-references in rendered HTML to their targets, keyed by the anchor the
+/// references were created with, and replace their text with the targets' labels. References to
+/// unknown anchors are left as they are.
+pub(crate) fn resolve_references(html: &str, targets: &BTreeMap<String, RefTarget>) -> String {
+    let mut resolved = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(REF_START) {
+        let after = &rest[start + REF_START.len()..];
+        let (Some(quote), Some(end)) = (after.find('"'), after.find("</a>")) else {
+            break;
+        };
+        let anchor = &after[..quote];
+        resolved.push_str(&rest[..start + REF_START.len()]);
+        match targets.get(anchor) {
+            Some(RefTarget { anchor, label: Some(label) }) => {
+                resolved.push_str(anchor);
+                resolved.push_str("\">");
+                resolved.push_str(&html! { (label) }.into_string());
+                resolved.push_str("</a>");
+                rest = &after[end + "</a>".len()..];
+            }
+            Some(RefTarget { anchor, label: None }) => {
+                resolved.push_str(anchor);
+                rest = &after[quote..];
+            }
+            None => rest = after,
+        }
+    }
+    resolved.push_str(rest);
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbering_labels() {
+        let numbering = SectionNumbering::default().heading_prefix("§").heading_suffix(".");
+        assert_eq!(numbering.heading_label(&numbering.number(&[1, 2, 3])), "§1.2.3.");
+        assert_eq!(numbering.reference_label("2"), "Section 2");
+        assert_eq!(child_number("A", 1, Some(&numbering)), "A.1");
+
+        let html = html! { p { "See " (section_ref(&["QC", "Mass accuracy"])) ", " (section_ref(&["Methods"])) " and " (section_ref(&["Missing"])) } }.into_string();
+        let targets = BTreeMap::from([
+            ("qc--mass-accuracy".to_string(), RefTarget { anchor: "qc--mass-accuracy".to_string(), label: Some("Section 1.1".to_string()) }),
+            ("methods".to_string(), RefTarget { anchor: "methods-2".to_string(), label: None }),
+        ]);
+        assert_eq!(
+            resolve_references(&html, &targets),
+            r##"<p>See <a class="section-ref" href="#qc--mass-accuracy">Section 1.1</a>, <a class="section-ref" href="#methods-2">Methods</a> and <a class="section-ref" href="#missing">Missing</a></p>"##
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Export of reports to PDF through a headless browser.
+//!
+//! The report is rendered to a temporary HTML file next to the output, printed by a headless
+//! Chromium (or Chrome/Edge, or `wkhtmltopdf`) after its scripts have drawn the plots, and removed
+//! again. The report's print stylesheet shows every section one after the other, each starting on
+//! a new page, and hides interactive controls.
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::assets::AssetMode;
+use crate::warnings::BuildWarning;
+use crate::{Report, ReportError};
+
+/// Browser executables searched for on the `PATH`, in order of preference.
+const BROWSERS: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+    "msedge",
+    "wkhtmltopdf",
+];
+
+/// Options for [`Report::save_to_pdf_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfOptions {
+    /// The browser used to print the report. When unset, the `REPORT_BUILDER_BROWSER` environment
+    /// variable is used, or the first browser found on the `PATH`, see [`find_browser`].
+    pub browser: Option<PathBuf>,
+    /// How long scripts are given to draw the plots before printing, in milliseconds.
+    pub render_delay_ms: u64,
+    /// Extra command-line arguments passed to the browser (e.g. `--no-sandbox` in containers).
+    pub extra_args: Vec<String>,
+    /// Whether to embed the JavaScript libraries, for printing without internet access.
+    pub asset_mode: AssetMode,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            browser: None,
+            render_delay_ms: 5000,
+            extra_args: Vec::new(),
+            asset_mode: AssetMode::Cdn,
+        }
+    }
+}
+
+/// Find a browser able to print PDFs, from the `REPORT_BUILDER_BROWSER` environment variable or
+/// the `PATH`.
+pub fn find_browser() -> Option<PathBuf> {
+    if let Some(browser) = std::env::var_os("REPORT_BUILDER_BROWSER") {
+        return Some(PathBuf::from(browser));
+    }
+    let path = std::env::var_os("PATH")?;
+    BROWSERS.iter().find_map(|name| {
+        std::env::split_paths(&path)
+            .flat_map(|dir| [dir.join(name), dir.join(format!("{}.exe", name))])
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Build the command printing `html` to `pdf` with the given browser
+pub(crate) fn print_command(browser: &Path, html: &Path, pdf: &Path, options: &PdfOptions) -> Command {
+    let mut command = Command::new(browser);
+    let is_wkhtmltopdf = browser
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().contains("wkhtmltopdf"));
+
+    if is_wkhtmltopdf {
+        command
+            .arg("--print-media-type")
+            .arg("--enable-local-file-access")
+            .arg("--javascript-delay")
+            .arg(options.render_delay_ms.to_string())
+            .args(&options.extra_args)
+            .arg(html)
+            .arg(pdf);
+    } else {
+        let mut print_to_pdf = OsString::from("--print-to-pdf=");
+        print_to_pdf.push(pdf);
+        command
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--no-pdf-header-footer")
+            .arg(format!("--virtual-time-budget={}", options.render_delay_ms))
+            .arg(print_to_pdf)
+            .args(&options.extra_args)
+            .arg(html);
+    }
+    command
+}
+
+impl Report {
+    /// Saves the report as a PDF, printed by a headless browser found on the system.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the PDF file to write.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building the report, or [`ReportError::Io`] if no
+    /// browser is found or printing fails.
+    pub fn save_to_pdf(&self, path: &str) -> Result<Vec<BuildWarning>, ReportError> {
+        self.save_to_pdf_with_options(path, &PdfOptions::default())
+    }
+
+    /// Saves the report as a PDF, with the given browser and rendering options.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the PDF file to write.
+    /// * `options` - The browser, render delay and extra arguments to use.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building the report, or [`ReportError::Io`] if no
+    /// browser is found or printing fails.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, options)))]
+    pub fn save_to_pdf_with_options(&self, path: &str, options: &PdfOptions) -> Result<Vec<BuildWarning>, ReportError> {
+        let browser = options.browser.clone().or_else(find_browser).ok_or_else(|| {
+            ReportError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No browser found to print the PDF; install Chromium or set REPORT_BUILDER_BROWSER",
+            ))
+        })?;
+
+        // Render next to the output, so relative paths (e.g. the logo) resolve the same way
+        let pdf = std::env::current_dir()?.join(path);
+        let html = pdf.with_extension("print.html");
+        let build_warnings = self.save_to_file_with_assets(&html.to_string_lossy(), &options.asset_mode)?;
+
+        let output = print_command(&browser, &html, &pdf, options).output();
+        std::fs::remove_file(&html)?;
+        let output = output?;
+        if !output.status.success() || !pdf.exists() {
+            return Err(ReportError::Io(io::Error::other(format!(
+                "'{}' failed to print the PDF: {}",
+                browser.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))));
+        }
+
+        Ok(build_warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_command() {
+        let options = PdfOptions::default();
+        let command = print_command(Path::new("/usr/bin/chromium"), Path::new("r.print.html"), Path::new("r.pdf"), &options);
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert!(args.contains(&"--headless".to_string()));
+        assert!(args.contains(&"--print-to-pdf=r.pdf".to_string()));
+        assert_eq!(args.last().unwrap(), "r.print.html");
+
+        let command = print_command(Path::new("/usr/bin/wkhtmltopdf"), Path::new("r.print.html"), Path::new("r.pdf"), &options);
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(args[..2], ["--print-media-type", "--enable-local-file-access"]);
+
+        let report = Report::new("Redeem", "1.0", None, "PDF Report");
+        let missing = PdfOptions { browser: Some(PathBuf::from("/nonexistent/chromium")), ..Default::default() };
+        let path = std::env::temp_dir().join(format!("report-builder-pdf-{}.pdf", std::process::id()));
+        assert!(report.save_to_pdf_with_options(path.to_str().unwrap(), &missing).is_err());
+        assert!(!path.with_extension("print.html").exists());
+    }
+}
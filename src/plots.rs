@@ -1,13 +1,531 @@
-use plotly::box_plot::BoxMean;
-use plotly::common::{DashType, Line, Marker, Mode, Orientation};
-use plotly::{Plot, Histogram, Scatter, BoxPlot};
-use plotly::layout::{Axis, Layout, Legend};
+//! Plotly figures for reports, built from plain Rust data.
+//!
+//! The helpers return a [`Plot`] (or a [`ReportError`] for invalid input) with the crate's default
+//! styling, see [`defaults`], ready for [`ReportSection::add_plot`](crate::ReportSection::add_plot).
+//! They cover:
+//!
+//! - distributions: histograms, box, strip, CDF and SPLOM plots, and scatter plots that switch to
+//!   density grids for large data ([`plot_scatter_with_opts`])
+//! - target-decoy QC: score histograms, P-P, ROC, precision-recall, calibration and score-rank
+//!   plots, with the FDR diagnostics of [`fdr`]
+//! - comparisons and trends: line plots with error bands, MA, Bland-Altman, forest, radar and UpSet
+//!   plots, dose-response fits and Kaplan-Meier curves, with significance brackets from
+//!   [`significance`]
+//! - compositions: sunburst and treemap plots of a [`Hierarchy`]
+//!
+//! Every plot added to a report is described for screen readers ([`alt_text`]) and can be given a
+//! static SVG snapshot for readers without JavaScript ([`fallback`]); [`snapshot`] compares plots
+//! in tests.
+
+pub mod alt_text;
+pub mod defaults;
+pub mod fallback;
+pub mod fdr;
+pub mod significance;
+pub mod snapshot;
+
+use plotly::box_plot::{BoxMean, BoxPoints};
+use plotly::common::{AxisSide, DashType, ErrorData, ErrorType, Fill, Line, LineShape, Marker, MarkerSymbol, Mode, Orientation};
+use plotly::histogram::{Bins, Cumulative, HistNorm};
+use plotly::{Bar, Configuration, Plot, Histogram, Scatter, ScatterPolar, BoxPlot};
+use plotly::layout::{Annotation, Axis, AxisType, BarMode, Layout, Legend, Shape, ShapeLine, ShapeType};
+use plotly::Trace;
 use itertools_num::linspace;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::BuildHasher;
+
+use crate::aggregate::{Aggregation, GroupSummary, QuantileSketch};
+use crate::error::ReportError;
+use crate::stats::{self, BinRule};
+
+/// Plotly's default qualitative color sequence, used where traces must share colors explicitly
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd",
+    "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf",
+];
+
+/// The color of targets in the target/decoy plots
+const TARGET_COLOR: &str = PALETTE[0];
+/// The color of decoys in the target/decoy plots
+const DECOY_COLOR: &str = PALETTE[1];
+
+/// A trace defined directly as Plotly JSON, for trace types not covered by the plotly crate
+/// (e.g. `splom`, `sunburst`, `treemap`, `violin`).
+#[derive(Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct JsonTrace(pub Value);
+
+impl JsonTrace {
+    pub fn new(trace: Value) -> Box<Self> {
+        Box::new(JsonTrace(trace))
+    }
+}
+
+impl Trace for JsonTrace {
+    fn to_json(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The Plotly JSON document of a plot (`data`, `layout` and `config`), e.g. to inspect it in tests
+/// without writing HTML, see [`snapshot`]
+pub fn to_json(plot: &Plot) -> Value {
+    serde_json::from_str(&plot.to_json()).expect("Plotly figures always serialize to valid JSON")
+}
+
+/// The corner annotation stamped on plots by [`add_watermark`]
+fn watermark_annotation(text: &str) -> Annotation {
+    Annotation::new()
+        .text(text)
+        .x_ref("paper")
+        .y_ref("paper")
+        .x(1.0)
+        .y(1.0)
+        .x_anchor(plotly::common::Anchor::Right)
+        .y_anchor(plotly::common::Anchor::Bottom)
+        .show_arrow(false)
+        .font(plotly::common::Font::new().size(10).color("#999999"))
+}
+
+/// The watermark annotation as JSON, for stamping plots in the browser
+pub(crate) fn watermark_annotation_json(text: &str) -> String {
+    serde_json::to_string(&watermark_annotation(text)).unwrap_or_default()
+}
+
+/// Stamp a plot with a small top-right annotation, e.g. the software name, version and date, so
+/// the figure keeps its provenance when exported as an image on its own
+///
+/// Reports can stamp all their plots at once with [`crate::Report::watermark_plots`].
+pub fn add_watermark(plot: &mut Plot, text: &str) {
+    let mut layout = plot.layout().clone();
+    layout.add_annotation(watermark_annotation(text));
+    plot.set_layout(layout);
+}
+
+/// An editable view of a plot's traces and layout, for tweaking a helper's output before adding it
+/// to a report (renaming a trace, changing one color, hiding a trace by default)
+///
+/// Converts back into a [`Plot`] with `Plot::from`, and can be passed directly to
+/// [`ReportSection::add_plot`](crate::ReportSection::add_plot).
+#[derive(Clone)]
+pub struct PlotHandle {
+    traces: Vec<Value>,
+    layout: Layout,
+    configuration: Configuration,
+}
+
+impl PlotHandle {
+    /// The number of traces in the plot
+    pub fn len(&self) -> usize {
+        self.traces.len()
+    }
+
+    /// Returns true if the plot has no traces
+    pub fn is_empty(&self) -> bool {
+        self.traces.is_empty()
+    }
+
+    /// The names of the traces, in drawing order (empty for unnamed traces)
+    pub fn trace_names(&self) -> Vec<&str> {
+        self.traces.iter().map(|trace| trace["name"].as_str().unwrap_or_default()).collect()
+    }
+
+    /// The trace at the given index, if any
+    pub fn trace(&mut self, index: usize) -> Option<TraceHandle<'_>> {
+        self.traces.get_mut(index).map(TraceHandle)
+    }
+
+    /// The first trace with the given name, if any
+    pub fn trace_named(&mut self, name: &str) -> Option<TraceHandle<'_>> {
+        self.traces.iter_mut().find(|trace| trace["name"] == name).map(TraceHandle)
+    }
+
+    /// The layout of the plot
+    pub fn layout_mut(&mut self) -> &mut Layout {
+        &mut self.layout
+    }
+}
+
+impl From<Plot> for PlotHandle {
+    fn from(plot: Plot) -> Self {
+        PlotHandle {
+            traces: plot
+                .data()
+                .iter()
+                .map(|trace| serde_json::from_str(&trace.to_json()).unwrap_or(Value::Null))
+                .collect(),
+            layout: plot.layout().clone(),
+            configuration: plot.configuration().clone(),
+        }
+    }
+}
+
+impl From<PlotHandle> for Plot {
+    fn from(handle: PlotHandle) -> Self {
+        let mut plot = Plot::new();
+        for trace in handle.traces {
+            plot.add_trace(JsonTrace::new(trace));
+        }
+        plot.set_layout(handle.layout);
+        plot.set_configuration(handle.configuration);
+        plot
+    }
+}
+
+/// An editable trace of a [`PlotHandle`]
+pub struct TraceHandle<'a>(&'a mut Value);
+
+impl TraceHandle<'_> {
+    /// Rename the trace, as shown in the legend and hover labels
+    pub fn name(self, name: &str) -> Self {
+        self.set("name", json!(name))
+    }
+
+    /// Set the color of the trace's markers and lines
+    pub fn color(self, color: &str) -> Self {
+        self.0["marker"]["color"] = json!(color);
+        if self.0["type"] == "scatter" || self.0["type"] == "scattergl" || self.0.get("line").is_some() {
+            self.0["line"]["color"] = json!(color);
+        }
+        self
+    }
+
+    /// Show or hide the trace. Hidden traces can still be toggled on from the legend.
+    pub fn visible(self, visible: bool) -> Self {
+        self.set("visible", if visible { json!(true) } else { json!("legendonly") })
+    }
+
+    /// Set the opacity of the trace, from 0 to 1
+    pub fn opacity(self, opacity: f64) -> Self {
+        self.set("opacity", json!(opacity))
+    }
+
+    /// Set any Plotly trace attribute
+    pub fn set(self, key: &str, value: Value) -> Self {
+        self.0[key] = value;
+        self
+    }
+}
+
+/// Which layout a composed figure uses, see [`PlotCompose`]
+#[derive(Debug, Clone)]
+pub enum LayoutSource {
+    /// The layout of the first plot added
+    First,
+    /// The layout of the last plot added
+    Last,
+    /// The layout of the plot at the given index
+    Index(usize),
+    /// A custom layout
+    Custom(Box<Layout>),
+}
+
+/// Combines the traces of several plots (e.g. from the helpers in this module) into one figure
+///
+/// For example, a histogram with a fitted density line, or a box plot with a strip overlay.
+/// Plots added with [`PlotCompose::overlay_secondary`] are drawn against a second y-axis on the right.
+#[derive(Clone)]
+pub struct PlotCompose {
+    plots: Vec<(Plot, bool)>,
+    layout: LayoutSource,
+    title: Option<String>,
+}
+
+impl Default for PlotCompose {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlotCompose {
+    pub fn new() -> Self {
+        PlotCompose {
+            plots: Vec::new(),
+            layout: LayoutSource::First,
+            title: None,
+        }
+    }
+
+    /// Add the traces of a plot, drawn against the primary y-axis
+    pub fn overlay(mut self, plot: Plot) -> Self {
+        self.plots.push((plot, false));
+        self
+    }
+
+    /// Add the traces of a plot, drawn against a secondary y-axis titled after the plot's own y-axis
+    pub fn overlay_secondary(mut self, plot: Plot) -> Self {
+        self.plots.push((plot, true));
+        self
+    }
+
+    /// Choose the layout of the composed figure (the first plot's layout by default)
+    pub fn layout(mut self, layout: LayoutSource) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Override the title of the composed figure
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Build the composed figure
+    ///
+    /// # Returns
+    ///
+    /// A Plot object containing the traces of all plots, in the order they were added, or an error
+    /// if no plots were added or the layout index is out of range
+    pub fn build(&self) -> Result<Plot, ReportError> {
+        if self.plots.is_empty() {
+            return Err(ReportError::EmptyData("no plots to compose"));
+        }
+
+        let mut plot = Plot::new();
+        let mut secondary_title = None;
+        for (source, secondary) in &self.plots {
+            for trace in source.data().iter() {
+                let mut trace: Value = serde_json::from_str(&trace.to_json())?;
+                if *secondary {
+                    trace["yaxis"] = json!("y2");
+                }
+                plot.add_trace(JsonTrace::new(trace));
+            }
+            if *secondary && secondary_title.is_none() {
+                let layout = serde_json::to_value(source.layout())?;
+                secondary_title = Some(layout["yaxis"]["title"]["text"].as_str().unwrap_or_default().to_string());
+            }
+        }
+
+        let mut layout = match &self.layout {
+            LayoutSource::First => self.plots[0].0.layout().clone(),
+            LayoutSource::Last => self.plots[self.plots.len() - 1].0.layout().clone(),
+            LayoutSource::Index(i) => self
+                .plots
+                .get(*i)
+                .ok_or_else(|| {
+                    ReportError::InvalidArgument(format!("Layout index {} out of range for {} plots", i, self.plots.len()))
+                })?
+                .0
+                .layout()
+                .clone(),
+            LayoutSource::Custom(layout) => layout.as_ref().clone(),
+        };
+        if let Some(y_title) = secondary_title {
+            layout = layout.y_axis2(Axis::new().title(y_title.as_str()).overlaying("y").side(AxisSide::Right));
+        }
+        if let Some(title) = &self.title {
+            layout = layout.title(title.as_str());
+        }
+        plot.set_layout(layout);
+
+        Ok(plot)
+    }
+}
+
+/// A numeric value accepted by the plot helpers, converted to `f64` only when the plot is built
+///
+/// Implemented for the primitive integer and float types and references to them. Other numeric
+/// types (e.g. decimals) can implement it to be plotted directly.
+pub trait Numeric: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_numeric {
+    ($($t:ty),*) => {
+        $(impl Numeric for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_numeric!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: Numeric> Numeric for &T {
+    fn to_f64(self) -> f64 {
+        (*self).to_f64()
+    }
+}
+
+/// A value on a plot axis: either a number or a category label
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum AxisValue {
+    Number(f64),
+    Category(String),
+}
+
+/// A value that can be placed on a plot axis, see [`AxisValue`]
+///
+/// Implemented for all [`Numeric`] types and for strings, so the same helper can plot numeric
+/// and categorical (e.g. per-sample) axes.
+pub trait IntoAxisValue {
+    fn into_axis_value(self) -> AxisValue;
+}
+
+impl<T: Numeric> IntoAxisValue for T {
+    fn into_axis_value(self) -> AxisValue {
+        AxisValue::Number(self.to_f64())
+    }
+}
+
+impl IntoAxisValue for String {
+    fn into_axis_value(self) -> AxisValue {
+        AxisValue::Category(self)
+    }
+}
+
+impl IntoAxisValue for &String {
+    fn into_axis_value(self) -> AxisValue {
+        AxisValue::Category(self.clone())
+    }
+}
+
+impl IntoAxisValue for &str {
+    fn into_axis_value(self) -> AxisValue {
+        AxisValue::Category(self.to_string())
+    }
+}
+
+impl IntoAxisValue for &&str {
+    fn into_axis_value(self) -> AxisValue {
+        AxisValue::Category(self.to_string())
+    }
+}
+
+/// Collect an iterable of iterables of axis values into owned series
+fn collect_axis_series<S, V>(series: S) -> Vec<Vec<AxisValue>>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator<Item = V>,
+    V: IntoAxisValue,
+{
+    series
+        .into_iter()
+        .map(|values| values.into_iter().map(IntoAxisValue::into_axis_value).collect())
+        .collect()
+}
+
+/// An axis with the given title, forced to be categorical if all its values are categories
+///
+/// Without this, Plotly would treat numeric-looking category labels (e.g. sample "1") as numbers.
+fn axis_for(series: &[Vec<AxisValue>], title: &str) -> Axis {
+    let axis = Axis::new().title(title);
+    let mut values = series.iter().flatten().peekable();
+    if values.peek().is_some() && values.all(|v| matches!(v, AxisValue::Category(_))) {
+        axis.type_(AxisType::Category)
+    } else {
+        axis
+    }
+}
+
+/// Convert a slice of numeric values to `f64`
+fn to_f64_vec<T: Numeric>(values: &[T]) -> Vec<f64> {
+    values.iter().map(|v| v.to_f64()).collect()
+}
+
+/// How the heights of histogram bars are normalized, see [`HistogramOptions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistogramNormalization {
+    /// The number of values in each bin
+    #[default]
+    Count,
+    /// The percentage of the trace's values in each bin
+    Percent,
+    /// The fraction of the trace's values in each bin
+    Probability,
+    /// The fraction of the trace's values in each bin divided by the bin width, so the area of
+    /// each trace is 1 and traces of different sizes can be compared
+    Density,
+}
+
+/// How the traces of a histogram are drawn together, see [`HistogramOptions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistogramMode {
+    /// Side by side within each bin
+    #[default]
+    Group,
+    /// On top of each other, semi-transparent
+    Overlay,
+    /// Stacked, so the bars show the total of all traces
+    Stack,
+}
+
+/// Options of [`plot_score_histogram_with_opts`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramOptions {
+    /// The rule choosing the bin width, e.g. [`BinRule::Count`] or [`BinRule::Width`]
+    pub bins: BinRule,
+    /// What the height of the bars shows; the y-axis is titled accordingly
+    pub normalization: HistogramNormalization,
+    pub mode: HistogramMode,
+    /// The opacity of the bars; by default 0.6 when overlaid and opaque otherwise
+    pub opacity: Option<f64>,
+    /// Show the cumulative distribution, each bar including all values up to its bin
+    pub cumulative: bool,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        HistogramOptions {
+            bins: BinRule::FreedmanDiaconis,
+            normalization: HistogramNormalization::Count,
+            mode: HistogramMode::Group,
+            opacity: None,
+            cumulative: false,
+        }
+    }
+}
+
+/// Plot a histogram of the scores for the targets and decoys, binned with the Freedman–Diaconis rule
+pub fn plot_score_histogram<T: Numeric>(scores: &[T], labels: &[i32], title: &str, x_title: &str) -> Result<Plot, ReportError> {
+    plot_score_histogram_with_bins(scores, labels, BinRule::FreedmanDiaconis, title, x_title)
+}
+
+/// Plot a histogram of the scores for the targets and decoys, with bins chosen by the given rule
+///
+/// The bin width is computed from all scores and applied explicitly to both traces, so targets
+/// and decoys are always binned identically.
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `bins` - The rule choosing the bin width, see [`stats::bin_width`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+///
+/// # Returns
+///
+/// A Plot object containing the histogram
+pub fn plot_score_histogram_with_bins<T: Numeric>(scores: &[T], labels: &[i32], bins: BinRule, title: &str, x_title: &str) -> Result<Plot, ReportError> {
+    plot_score_histogram_with_opts(scores, labels, HistogramOptions { bins, ..Default::default() }, title, x_title)
+}
 
-/// Plot a histogram of the scores for the targets and decoys
-pub fn plot_score_histogram(scores: &Vec<f64>, labels: &Vec<i32>, title: &str, x_title: &str) -> Result<Plot, String> {
-    assert_eq!(scores.len(), labels.len(), "Scores and labels must have the same length");
-    assert!(labels.iter().all(|&l| l == 1 || l == -1), "Labels must be 1 for targets and -1 for decoys");
+/// Plot a histogram of the scores for the targets and decoys, with the given binning,
+/// normalization and layout of the two traces
+///
+/// As with [`plot_score_histogram_with_bins`], both traces are binned identically.
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `opts` - The histogram options, see [`HistogramOptions`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+///
+/// # Returns
+///
+/// A Plot object containing the histogram
+pub fn plot_score_histogram_with_opts<T: Numeric>(scores: &[T], labels: &[i32], opts: HistogramOptions, title: &str, x_title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let scores = to_f64_vec(scores);
 
     let mut scores_target = Vec::new();
     let mut scores_decoy = Vec::new();
@@ -20,32 +538,218 @@ pub fn plot_score_histogram(scores: &Vec<f64>, labels: &Vec<i32>, title: &str, x
         }
     }
 
-    let trace_target = Histogram::new(scores_target).name("Target");
-    let trace_decoy = Histogram::new(scores_decoy).name("Decoy");
+    let width = stats::bin_width(&scores, opts.bins);
+    let finite = stats::sorted_finite(&scores);
+    let (start, end) = match (finite.first(), finite.last()) {
+        (Some(&min), Some(&max)) => (min, max + width),
+        _ => (0.0, width),
+    };
+
+    let (norm, y_title) = match opts.normalization {
+        HistogramNormalization::Count => (HistNorm::Default, "Count"),
+        HistogramNormalization::Percent => (HistNorm::Percent, "Percent"),
+        HistogramNormalization::Probability => (HistNorm::Probability, "Probability"),
+        HistogramNormalization::Density => (HistNorm::ProbabilityDensity, "Density"),
+    };
+    let y_title = if opts.cumulative { format!("Cumulative {}", y_title.to_lowercase()) } else { y_title.to_string() };
+    let opacity = opts.opacity.or((opts.mode == HistogramMode::Overlay).then_some(0.6));
+
+    let mut plot = Plot::new();
+    for (name, values, color) in [("Target", scores_target, TARGET_COLOR), ("Decoy", scores_decoy, DECOY_COLOR)] {
+        let mut trace = Histogram::new(values).name(name).x_bins(Bins::new(start, end, width)).marker(Marker::new().color(color));
+        if opts.normalization != HistogramNormalization::Count {
+            trace = trace.hist_norm(norm.clone());
+        }
+        if let Some(opacity) = opacity {
+            trace = trace.opacity(opacity);
+        }
+        if opts.cumulative {
+            trace = trace.cumulative(Cumulative::new().enabled(true));
+        }
+        plot.add_trace(trace);
+    }
 
-    let layout = Layout::new()
+    let mut layout = defaults::base_layout()
         .title(title)
         .x_axis(plotly::layout::Axis::new().title(x_title))
-        .y_axis(plotly::layout::Axis::new().title("Density"));
+        .y_axis(plotly::layout::Axis::new().title(y_title));
+    match opts.mode {
+        HistogramMode::Group => {}
+        HistogramMode::Overlay => layout = layout.bar_mode(BarMode::Overlay),
+        HistogramMode::Stack => layout = layout.bar_mode(BarMode::Stack),
+    }
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// Histogram bins computed outside of Plotly, e.g. aggregated server-side from data too large to
+/// embed in the report
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinnedHistogram {
+    pub name: String,
+    /// The bin edges in ascending order, one more than the number of bins
+    pub edges: Vec<f64>,
+    /// The (possibly weighted) count of each bin
+    pub counts: Vec<f64>,
+}
+
+impl BinnedHistogram {
+    /// Create a histogram from pre-computed bin edges and counts
+    ///
+    /// # Returns
+    ///
+    /// The histogram, or an error if there is not exactly one more edge than counts, there are no
+    /// bins, or the edges are not strictly increasing
+    pub fn new(name: &str, edges: Vec<f64>, counts: Vec<f64>) -> Result<Self, ReportError> {
+        ReportError::check_len("edges", counts.len() + 1, edges.len())?;
+        if counts.is_empty() {
+            return Err(ReportError::InvalidArgument("A histogram needs at least two edges".to_string()));
+        }
+        if !edges.windows(2).all(|w| w[0] < w[1]) {
+            return Err(ReportError::InvalidArgument("Edges must be strictly increasing".to_string()));
+        }
+        Ok(BinnedHistogram {
+            name: name.to_string(),
+            edges,
+            counts,
+        })
+    }
 
+    /// Create an empty histogram over the given bin edges, to be filled with [`BinnedHistogram::accumulate`]
+    ///
+    /// # Returns
+    ///
+    /// The histogram, or an error if there are fewer than two edges or they are not strictly increasing
+    pub fn empty(name: &str, edges: Vec<f64>) -> Result<Self, ReportError> {
+        let counts = vec![0.0; edges.len().saturating_sub(1)];
+        Self::new(name, edges, counts)
+    }
+
+    /// Add values to the histogram, each counting for its weight (or 1 if no weights are given).
+    /// Values outside the edges and non-finite values are ignored; the last bin includes its upper edge.
+    ///
+    /// This can be called repeatedly to aggregate data in chunks.
+    ///
+    /// # Returns
+    ///
+    /// A Result indicating success or an error if weights are given but not one per value
+    pub fn accumulate(&mut self, values: &[f64], weights: Option<&[f64]>) -> Result<(), ReportError> {
+        if let Some(weights) = weights {
+            ReportError::check_len("weights", values.len(), weights.len())?;
+        }
+        let (Some(&first), Some(&last), Some(last_bin)) = (self.edges.first(), self.edges.last(), self.counts.len().checked_sub(1)) else {
+            return Ok(());
+        };
+        for (i, &value) in values.iter().enumerate() {
+            if !value.is_finite() || value < first || value > last {
+                continue;
+            }
+            let bin = (self.edges.partition_point(|&edge| edge <= value) - 1).min(last_bin);
+            self.counts[bin] += weights.map_or(1.0, |w| w[i]);
+        }
+        Ok(())
+    }
+}
+
+/// Plot pre-binned histograms as overlaid bar traces, so only the bins are embedded in the report
+///
+/// # Arguments
+///
+/// * `histograms` - The histograms to overlay, see [`BinnedHistogram`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing the histograms
+pub fn plot_binned_histogram(histograms: &[BinnedHistogram], title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError> {
     let mut plot = Plot::new();
-    plot.add_trace(trace_target);
-    plot.add_trace(trace_decoy);
+    for histogram in histograms {
+        let centers: Vec<f64> = histogram.edges.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect();
+        let widths: Vec<f64> = histogram.edges.windows(2).map(|w| w[1] - w[0]).collect();
+        plot.add_trace(JsonTrace::new(json!({
+            "type": "bar",
+            "name": histogram.name,
+            "x": centers,
+            "y": histogram.counts,
+            "width": widths,
+            "opacity": if histograms.len() > 1 { 0.6 } else { 1.0 },
+        })));
+    }
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title))
+        .y_axis(Axis::new().title(y_title))
+        .bar_mode(BarMode::Overlay)
+        .bar_gap(0.0);
     plot.set_layout(layout);
 
     Ok(plot)
 }
 
+/// Plot per-group summaries as box plots drawn from precomputed quartiles, so no raw points are
+/// embedded in the report
+///
+/// # Arguments
+///
+/// * `summaries` - The group summaries, see [`crate::aggregate::group_by`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing one box per group
+pub fn plot_group_summaries(summaries: &[GroupSummary], title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError> {
+    let mut plot = Plot::new();
+    plot.add_trace(JsonTrace::new(json!({
+        "type": "box",
+        "name": y_title,
+        "x": summaries.iter().map(|s| s.group.as_str()).collect::<Vec<_>>(),
+        "q1": summaries.iter().map(|s| s.q1).collect::<Vec<_>>(),
+        "median": summaries.iter().map(|s| s.median).collect::<Vec<_>>(),
+        "q3": summaries.iter().map(|s| s.q3).collect::<Vec<_>>(),
+        "lowerfence": summaries.iter().map(|s| s.min).collect::<Vec<_>>(),
+        "upperfence": summaries.iter().map(|s| s.max).collect::<Vec<_>>(),
+        "mean": summaries.iter().map(|s| s.mean).collect::<Vec<_>>(),
+        "text": summaries.iter().map(|s| format!("n = {}", s.count)).collect::<Vec<_>>(),
+        "boxmean": true,
+    })));
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title))
+        .y_axis(Axis::new().title(y_title))
+        .show_legend(false);
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
 
+/// Collect an iterable of iterables of values (e.g. `&[Vec<f64>]`) into owned series
+fn collect_series<S, V>(series: S) -> Vec<Vec<f64>>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator<Item = V>,
+    V: Numeric,
+{
+    series
+        .into_iter()
+        .map(|values| values.into_iter().map(Numeric::to_f64).collect())
+        .collect()
+}
 
-fn ecdf(data: &mut Vec<f64>) -> (Vec<f64>, Vec<f64>) {
-    data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+fn ecdf(data: &mut [f64]) -> (Vec<f64>, Vec<f64>) {
+    data.sort_by(f64::total_cmp);
     let n = data.len() as f64;
     let y: Vec<f64> = (1..=data.len()).map(|i| i as f64 / n).collect();
-    (data.clone(), y)
+    (data.to_vec(), y)
 }
 
-fn interpolate_ecdf(x: &Vec<f64>, y: &Vec<f64>, x_seq: &Vec<f64>) -> Vec<f64> {
+fn interpolate_ecdf(x: &[f64], y: &[f64], x_seq: &[f64]) -> Vec<f64> {
     x_seq.iter().map(|&xi| {
         let idx = x.iter().position(|&xv| xv >= xi).unwrap_or(x.len() - 1);
         y[idx]
@@ -59,7 +763,7 @@ fn interpolate_ecdf(x: &Vec<f64>, y: &Vec<f64>, x_seq: &Vec<f64>) -> Vec<f64> {
 // }
 
 /// Estimate the proportion of null hypotheses (π₀).
-fn estimate_pi0(labels: &Vec<i32>) -> f64 {
+fn estimate_pi0(labels: &[i32]) -> f64 {
     let count_decoys = labels.iter().filter(|&&l| l == -1).count() as f64;
     let count_targets = labels.iter().filter(|&&l| l == 1).count() as f64;
     count_decoys / count_targets
@@ -76,9 +780,9 @@ fn estimate_pi0(labels: &Vec<i32>) -> f64 {
 /// # Returns
 /// 
 /// A Plot object containing the P-P plot
-pub fn plot_pp(scores: &Vec<f64>, labels: &Vec<i32>, title: &str) -> Result<Plot, String> {
-    assert_eq!(scores.len(), labels.len(), "Scores and labels must have the same length");
-    assert!(labels.iter().all(|&l| l == 1 || l == -1), "Labels must be 1 for targets and -1 for decoys");
+pub fn plot_pp<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let scores = to_f64_vec(scores);
 
     let mut scores_target = Vec::new();
     let mut scores_decoy = Vec::new();
@@ -91,6 +795,10 @@ pub fn plot_pp(scores: &Vec<f64>, labels: &Vec<i32>, title: &str) -> Result<Plot
         }
     }
 
+    if scores_target.is_empty() || scores_decoy.is_empty() {
+        return Err(ReportError::EmptyData("both targets and decoys are required"));
+    }
+
     let (x_target, y_target) = ecdf(&mut scores_target);
     let (x_decoy, y_decoy) = ecdf(&mut scores_decoy);
 
@@ -125,7 +833,7 @@ pub fn plot_pp(scores: &Vec<f64>, labels: &Vec<i32>, title: &str) -> Result<Plot
     plot.add_trace(reference_line);
     plot.add_trace(pi0_line);
     plot.set_layout(
-        Layout::new()
+        defaults::base_layout()
             .title(title)
             .x_axis(plotly::layout::Axis::new().title("Decoy ECDF"))
             .y_axis(plotly::layout::Axis::new().title("Target ECDF")),
@@ -134,70 +842,1578 @@ pub fn plot_pp(scores: &Vec<f64>, labels: &Vec<i32>, title: &str) -> Result<Plot
     Ok(plot)
 }
 
-/// Generate a box plot of the scores/intensities for each file
-/// 
+/// Draw a classifier performance curve, with the score threshold of each point on hover
+fn curve_trace(curve: &stats::Curve, name: String) -> Box<Scatter<f64, f64>> {
+    let thresholds: Vec<String> = curve.thresholds.iter().map(|t| format!("Threshold: {:.4}", t)).collect();
+    Scatter::new(curve.x.clone(), curve.y.clone())
+        .name(name)
+        .mode(Mode::Lines)
+        .line(Line::new().shape(LineShape::Hv).color(PALETTE[0]))
+        .text_array(thresholds)
+}
+
+/// Generate the receiver operating characteristic (ROC) curve of target/decoy scores, with the
+/// area under the curve in the legend and the chance diagonal for reference
+///
 /// # Arguments
-/// 
-/// * `scores` - A vector of vectors where each inner vector contains the scores/intensities for a file
-/// * `filenames` - A vector of filenames corresponding to the scores
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
 /// * `title` - The title of the plot
-/// * `x_title` - The title of the x-axis
-/// * `y_title` - The title of the y-axis
-/// 
+///
 /// # Returns
-/// 
-/// A Plot object containing the box plot
-pub fn plot_boxplot(scores: &Vec<Vec<f64>>, filenames: Vec<String>, title: &str, x_title: &str, y_title: &str) -> Result<Plot, String> {
-    assert_eq!(scores.len(), filenames.len(), "Scores and filenames must have the same length");
+///
+/// A Plot object containing the ROC curve
+pub fn plot_roc<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let curve = stats::roc_curve(&to_f64_vec(scores), labels)
+        .ok_or(ReportError::EmptyData("both targets and decoys must have finite scores"))?;
+
+    let chance = Scatter::new(vec![0.0, 1.0], vec![0.0, 1.0])
+        .name("Chance")
+        .mode(Mode::Lines)
+        .line(Line::new().color("gray").dash(DashType::Dash));
 
     let mut plot = Plot::new();
-    for (i, s) in scores.iter().enumerate() {
-        let trace = BoxPlot::new_xy(
-            vec![filenames[i].clone(); s.len()],
-            s.to_vec()).name(filenames[i].clone()).box_mean(BoxMean::True);
-        plot.add_trace(trace);
-    }
-    
-    let layout = Layout::new()
-        .title(title)
-        .x_axis(Axis::new().title(x_title).tick_angle(45.0))
-        .y_axis(Axis::new().title(y_title))
-        .show_legend(false);
-    
-    plot.set_layout(layout);
+    plot.add_trace(curve_trace(&curve, format!("ROC (AUC = {:.3})", curve.auc)));
+    plot.add_trace(chance);
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("False positive rate").range(vec![0.0, 1.0]))
+            .y_axis(Axis::new().title("True positive rate").range(vec![0.0, 1.05])),
+    );
 
     Ok(plot)
 }
 
+/// Generate the precision-recall curve of target/decoy scores, with the average precision in the
+/// legend and the target fraction (the precision of random scores) for reference
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the precision-recall curve
+pub fn plot_precision_recall<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let curve = stats::precision_recall_curve(&to_f64_vec(scores), labels)
+        .ok_or(ReportError::EmptyData("targets must have finite scores"))?;
 
-pub fn plot_scatter(x: &Vec<Vec<f64>>, y: &Vec<Vec<f64>>, labels: Vec<String>, title: &str, x_title: &str, y_title: &str) -> Result<Plot, String> {
-    assert_eq!(x.len(), y.len(), "X and Y must have the same length");
-
-    // Check to see how large the data is, if there's a large amount of data we should use web_gl_mode. We can look at one of the arrays to see how many points there are
-    let web_gl_mode = x[0].len() > 10_000;
+    let target_fraction = labels.iter().filter(|&&l| l == 1).count() as f64 / labels.len() as f64;
+    let chance = Scatter::new(vec![0.0, 1.0], vec![target_fraction, target_fraction])
+        .name(format!("Chance (target fraction = {:.3})", target_fraction))
+        .mode(Mode::Lines)
+        .line(Line::new().color("gray").dash(DashType::Dash));
 
     let mut plot = Plot::new();
-    for (i, (x_i, y_i)) in x.iter().zip(y.iter()).enumerate() {
-        let trace = Scatter::new(x_i.to_vec(), y_i.to_vec()).name(labels[i].clone()).mode(Mode::Markers).marker(Marker::new().size(10)).web_gl_mode(true);
-        plot.add_trace(trace);
+    plot.add_trace(curve_trace(&curve, format!("Precision-recall (AP = {:.3})", curve.auc)));
+    plot.add_trace(chance);
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("Recall").range(vec![0.0, 1.0]))
+            .y_axis(Axis::new().title("Precision").range(vec![0.0, 1.05])),
+    );
+
+    Ok(plot)
+}
+
+/// Generate a calibration (reliability) curve of predicted target probabilities: the observed
+/// fraction of targets against the mean predicted probability in each bin, with the predicted
+/// probabilities of targets and decoys histogrammed below
+///
+/// # Arguments
+///
+/// * `predicted_probs` - The predicted probability of being a target, between 0 and 1
+/// * `labels` - 1 for targets and -1 for decoys, one per probability
+/// * `n_bins` - The number of equal-width probability bins
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the calibration curve, with the expected calibration error (the
+/// bin-size weighted mean distance to the diagonal) in the legend
+pub fn plot_calibration<T: Numeric>(predicted_probs: &[T], labels: &[i32], n_bins: usize, title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(predicted_probs.len(), labels)?;
+    if n_bins == 0 {
+        return Err(ReportError::InvalidArgument("The number of bins must be positive".to_string()));
+    }
+    let pairs: Vec<(f64, i32)> = to_f64_vec(predicted_probs)
+        .into_iter()
+        .zip(labels.iter().copied())
+        .filter(|(p, _)| (0.0..=1.0).contains(p))
+        .collect();
+    if pairs.is_empty() {
+        return Err(ReportError::EmptyData("no predicted probability is between 0 and 1"));
     }
 
-    let layout = Layout::new()
-        .title(title)
-        .x_axis(Axis::new().title(x_title))
-        .y_axis(Axis::new().title(y_title))
-        .legend(Legend::new().orientation(Orientation::Vertical));
+    // (sum of predictions, number of targets, count) per bin
+    let mut bins = vec![(0.0, 0usize, 0usize); n_bins];
+    for &(p, label) in &pairs {
+        let bin = &mut bins[((p * n_bins as f64) as usize).min(n_bins - 1)];
+        bin.0 += p;
+        bin.1 += (label == 1) as usize;
+        bin.2 += 1;
+    }
+    let (mean_predicted, observed): (Vec<f64>, Vec<f64>) = bins
+        .iter()
+        .filter(|bin| bin.2 > 0)
+        .map(|&(sum, targets, count)| (sum / count as f64, targets as f64 / count as f64))
+        .unzip();
+    let calibration_error = bins
+        .iter()
+        .map(|&(sum, targets, _)| (sum - targets as f64).abs())
+        .sum::<f64>()
+        / pairs.len() as f64;
 
-    plot.set_layout(layout);
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(mean_predicted, observed)
+            .name(format!("Calibration (ECE = {:.3})", calibration_error))
+            .mode(Mode::LinesMarkers)
+            .line(Line::new().color(TARGET_COLOR)),
+    );
+    plot.add_trace(
+        Scatter::new(vec![0.0, 1.0], vec![0.0, 1.0])
+            .name("Perfectly calibrated")
+            .mode(Mode::Lines)
+            .line(Line::new().color("gray").dash(DashType::Dash)),
+    );
+    let width = 1.0 / n_bins as f64;
+    for (name, label, color) in [("Target", 1, TARGET_COLOR), ("Decoy", -1, DECOY_COLOR)] {
+        let values: Vec<f64> = pairs.iter().filter(|(_, l)| *l == label).map(|(p, _)| *p).collect();
+        plot.add_trace(
+            Histogram::new(values)
+                .name(name)
+                .x_bins(Bins::new(0.0, 1.0 + width / 2.0, width))
+                .marker(Marker::new().color(color))
+                .opacity(0.7)
+                .y_axis("y2"),
+        );
+    }
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .bar_mode(BarMode::Overlay)
+            .x_axis(Axis::new().title("Mean predicted probability").range(vec![0.0, 1.0]))
+            .y_axis(Axis::new().title("Fraction of targets").range(vec![0.0, 1.05]).domain(&[0.3, 1.0]))
+            .y_axis2(Axis::new().title("Count").domain(&[0.0, 0.22]).anchor("x")),
+    );
 
     Ok(plot)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
+/// Generate the scores sorted from best to worst against their rank, with targets and decoys
+/// colored as in [`plot_score_histogram`]
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing one marker trace for targets and one for decoys. Non-finite scores
+/// are left out.
+pub fn plot_score_rank<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let mut ranked: Vec<(f64, i32)> = to_f64_vec(scores).into_iter().zip(labels.iter().copied()).filter(|(s, _)| s.is_finite()).collect();
+    if ranked.is_empty() {
+        return Err(ReportError::EmptyData("scores must be finite"));
+    }
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let web_gl_mode = ranked.len() > 10_000;
+
+    let mut plot = Plot::new();
+    for (name, label, color) in [("Target", 1, TARGET_COLOR), ("Decoy", -1, DECOY_COLOR)] {
+        let (rank, score): (Vec<usize>, Vec<f64>) = ranked
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, l))| *l == label)
+            .map(|(i, (s, _))| (i + 1, *s))
+            .unzip();
+        plot.add_trace(
+            Scatter::new(rank, score)
+                .name(name)
+                .mode(Mode::Markers)
+                .marker(Marker::new().size(4).color(color))
+                .web_gl_mode(web_gl_mode),
+        );
+    }
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("Rank"))
+            .y_axis(Axis::new().title("Score")),
+    );
+
+    Ok(plot)
+}
+
+/// Generate a dose-response curve: the measured responses against concentration on a log axis,
+/// overlaid with a fitted four-parameter logistic and its 95% confidence band
+///
+/// # Arguments
+///
+/// * `conc` - The concentration of each measurement
+/// * `response` - The response of each measurement
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis, e.g. "Concentration (nM)"
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object with the EC50, Hill slope and limit of detection in the legend, or an error if
+/// the lengths differ or the curve cannot be fitted, see [`stats::fit_four_parameter_logistic`]
+pub fn plot_dose_response<T: Numeric>(conc: &[T], response: &[T], title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_len("responses", conc.len(), response.len())?;
+    let (conc, response) = (to_f64_vec(conc), to_f64_vec(response));
+    let fit = stats::fit_four_parameter_logistic(&conc, &response)
+        .ok_or(ReportError::EmptyData("at least five points with distinct positive concentrations are needed for a 4PL fit"))?;
+
+    let positive = || conc.iter().copied().filter(|c| c.is_finite() && *c > 0.0);
+    let (low, high) = (positive().fold(f64::INFINITY, f64::min).log10(), positive().fold(f64::NEG_INFINITY, f64::max).log10());
+    let grid: Vec<f64> = linspace(low, high, 200).map(|x| 10f64.powf(x)).collect();
+    let (band_low, band_high): (Vec<f64>, Vec<f64>) = grid.iter().map(|&c| fit.confidence_interval(c)).unzip();
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(grid.clone(), band_low)
+            .name("95% CI")
+            .legend_group("ci")
+            .show_legend(false)
+            .mode(Mode::Lines)
+            .line(Line::new().width(0.0)),
+    );
+    plot.add_trace(
+        Scatter::new(grid.clone(), band_high)
+            .name("95% CI")
+            .legend_group("ci")
+            .mode(Mode::Lines)
+            .fill(Fill::ToNextY)
+            .fill_color("rgba(31, 119, 180, 0.2)")
+            .line(Line::new().width(0.0)),
+    );
+    let fitted: Vec<f64> = grid.iter().map(|&c| fit.predict(c)).collect();
+    plot.add_trace(
+        Scatter::new(grid, fitted)
+            .name(format!("4PL fit (EC50 = {:.4}, Hill = {:.2})", fit.ec50, fit.hill))
+            .mode(Mode::Lines)
+            .line(Line::new().color(PALETTE[0])),
+    );
+    plot.add_trace(
+        Scatter::new(conc, response)
+            .name("Measurements")
+            .mode(Mode::Markers)
+            .marker(Marker::new().color(PALETTE[1])),
+    );
+    let y_range = (fit.bottom.min(fit.top), fit.bottom.max(fit.top));
+    for (name, value, dash) in [("EC50", Some(fit.ec50), DashType::Dash), ("LOD", fit.lod(), DashType::Dot)] {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            plot.add_trace(
+                Scatter::new(vec![value, value], vec![y_range.0, y_range.1])
+                    .name(format!("{} = {:.4}", name, value))
+                    .mode(Mode::Lines)
+                    .line(Line::new().color("gray").dash(dash)),
+            );
+        }
+    }
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title(x_title).type_(AxisType::Log))
+            .y_axis(Axis::new().title(y_title)),
+    );
+
+    Ok(plot)
+}
+
+/// Generate a box plot of the scores/intensities for each file
+/// 
+/// # Arguments
+/// 
+/// * `scores` - The scores/intensities of each file, as any iterable of iterables (e.g. `&[Vec<f64>]` or an iterator of slices)
+/// * `filenames` - The filenames corresponding to the scores
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+/// 
+/// # Returns
+/// 
+/// A Plot object containing the box plot
+pub fn plot_boxplot<S, V, N>(scores: S, filenames: impl IntoIterator<Item = N>, title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator<Item = V>,
+    V: Numeric,
+    N: Into<String>,
+{
+    let scores = collect_series(scores);
+    let filenames: Vec<String> = filenames.into_iter().map(Into::into).collect();
+    ReportError::check_len("filenames", scores.len(), filenames.len())?;
+
+    let mut plot = Plot::new();
+    for (s, filename) in scores.into_iter().zip(filenames) {
+        let trace = BoxPlot::new_xy(
+            vec![filename.clone(); s.len()],
+            s).name(filename).box_mean(BoxMean::True);
+        plot.add_trace(trace);
+    }
+    
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title).tick_angle(45.0))
+        .y_axis(Axis::new().title(y_title))
+        .show_legend(false);
+    
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// Builds box (or violin) plots from streams of values too large to hold in memory
+///
+/// Each group is reduced to a [`QuantileSketch`] as it is consumed, so only the sketch is kept and
+/// the quartiles, whiskers and densities drawn are approximations.
+#[derive(Debug, Clone, Default)]
+pub struct BoxPlotBuilder {
+    groups: Vec<(String, QuantileSketch)>,
+    violin: bool,
+}
+
+impl BoxPlotBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder with a single group consumed from a stream
+    pub fn from_stream<T: Numeric>(name: &str, values: impl Iterator<Item = T>) -> Self {
+        Self::new().add_stream(name, values)
+    }
+
+    /// Add a group consumed from a stream
+    pub fn add_stream<T: Numeric>(self, name: &str, values: impl Iterator<Item = T>) -> Self {
+        self.add_sketch(name, values.map(Numeric::to_f64).collect())
+    }
+
+    /// Add a group from an already computed sketch, e.g. merged from chunks processed in parallel
+    pub fn add_sketch(mut self, name: &str, sketch: QuantileSketch) -> Self {
+        self.groups.push((name.to_string(), sketch));
+        self
+    }
+
+    /// Draw violins (the approximate density of each group) around the boxes
+    pub fn violin(mut self, violin: bool) -> Self {
+        self.violin = violin;
+        self
+    }
+
+    /// The aggregation applied to the data, for [`crate::ReportSection::add_aggregated_plot`]
+    pub fn aggregation(&self) -> Aggregation {
+        Aggregation::QuantileSketch {
+            points: self.groups.iter().map(|(_, sketch)| sketch.count()).sum(),
+            centroids: self.groups.iter().map(|(_, sketch)| sketch.centroids()).sum(),
+        }
+    }
+
+    /// Build the plot
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the plot
+    /// * `x_title` - The title of the x-axis
+    /// * `y_title` - The title of the y-axis
+    ///
+    /// # Returns
+    ///
+    /// A Plot object containing one box (and violin) per group
+    pub fn build(&self, title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError> {
+        let mut plot = Plot::new();
+        for (i, (name, sketch)) in self.groups.iter().enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            let (q1, median, q3) = (sketch.quantile(0.25), sketch.quantile(0.5), sketch.quantile(0.75));
+            let iqr = q3 - q1;
+
+            if self.violin {
+                // The density between successive percentiles is inversely proportional to their spacing
+                let percentiles: Vec<f64> = (0..=100).map(|p| sketch.quantile(p as f64 / 100.0)).collect();
+                let densities: Vec<f64> = percentiles
+                    .windows(2)
+                    .map(|w| if w[1] > w[0] { 0.01 / (w[1] - w[0]) } else { 0.0 })
+                    .collect();
+                let max_density = densities.iter().cloned().fold(0.0, f64::max);
+                if max_density > 0.0 {
+                    let centers: Vec<f64> = percentiles.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect();
+                    let half_widths: Vec<f64> = densities.iter().map(|d| 0.4 * d / max_density).collect();
+                    let x: Vec<f64> = half_widths.iter().map(|w| i as f64 - w)
+                        .chain(half_widths.iter().rev().map(|w| i as f64 + w))
+                        .collect();
+                    let y: Vec<f64> = centers.iter().chain(centers.iter().rev()).copied().collect();
+                    plot.add_trace(JsonTrace::new(json!({
+                        "type": "scatter",
+                        "x": x,
+                        "y": y,
+                        "fill": "toself",
+                        "mode": "lines",
+                        "line": { "color": color, "width": 1 },
+                        "name": name,
+                        "hoverinfo": "skip",
+                        "showlegend": false,
+                    })));
+                }
+            }
+
+            plot.add_trace(JsonTrace::new(json!({
+                "type": "box",
+                "name": name,
+                "x": [i],
+                "q1": [q1],
+                "median": [median],
+                "q3": [q3],
+                "lowerfence": [(q1 - 1.5 * iqr).max(sketch.min())],
+                "upperfence": [(q3 + 1.5 * iqr).min(sketch.max())],
+                "width": if self.violin { 0.15 } else { 0.6 },
+                "marker": { "color": color },
+                "text": [format!("n = {}", sketch.count())],
+            })));
+        }
+
+        let layout = defaults::base_layout()
+            .title(title)
+            .x_axis(
+                Axis::new()
+                    .title(x_title)
+                    .tick_values((0..self.groups.len()).map(|i| i as f64).collect())
+                    .tick_text(self.groups.iter().map(|(name, _)| name.clone()).collect()),
+            )
+            .y_axis(Axis::new().title(y_title))
+            .show_legend(false);
+        plot.set_layout(layout);
+
+        Ok(plot)
+    }
+}
+
+
+/// How [`plot_scatter_with_opts`] draws the points of each trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScatterMode {
+    /// Markers, or a density grid when the plot has more than [`ScatterOptions::density_threshold`] points
+    #[default]
+    Auto,
+    /// One marker per point
+    Markers,
+    /// The number of points in each cell of a grid, counted in Rust so only the grid is embedded
+    /// in the report; empty cells are transparent and the others translucent, so the grids of
+    /// several traces show through each other
+    Density,
+}
+
+/// Options of [`plot_scatter_with_opts`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScatterOptions {
+    pub mode: ScatterMode,
+    /// The number of points (across all traces) above which [`ScatterMode::Auto`] draws density grids
+    pub density_threshold: usize,
+    /// The number of grid cells along each axis of a density grid
+    pub density_bins: usize,
+    /// Draw markers with WebGL, much faster for large traces; by default for traces of more than
+    /// 10,000 points
+    pub web_gl: Option<bool>,
+}
+
+impl Default for ScatterOptions {
+    fn default() -> Self {
+        ScatterOptions {
+            mode: ScatterMode::Auto,
+            density_threshold: 200_000,
+            density_bins: 200,
+            web_gl: None,
+        }
+    }
+}
+
+/// The numbers of each series, or `None` if any value is a category
+fn numeric_series(series: &[Vec<AxisValue>]) -> Option<Vec<Vec<f64>>> {
+    series
+        .iter()
+        .map(|values| {
+            values
+                .iter()
+                .map(|value| match value {
+                    AxisValue::Number(number) => Some(*number),
+                    AxisValue::Category(_) => None,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// One heatmap per trace counting its points in a grid shared by all traces, with empty cells
+/// left out and the others translucent, so overlapping traces stay visible through each other
+fn density_traces(x: &[Vec<f64>], y: &[Vec<f64>], labels: &[String], bins: usize) -> Vec<Value> {
+    let bins = bins.max(1);
+    let points = || {
+        x.iter().zip(y).flat_map(|(x_i, y_i)| x_i.iter().zip(y_i)).filter(|(x, y)| x.is_finite() && y.is_finite())
+    };
+    let range = |values: Vec<f64>| {
+        let (min, max) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| (min.min(v), max.max(v)));
+        let width = if max > min { (max - min) / bins as f64 } else { 1.0 };
+        (if min.is_finite() { min } else { 0.0 }, width)
+    };
+    let (x_min, x_width) = range(points().map(|(x, _)| *x).collect());
+    let (y_min, y_width) = range(points().map(|(_, y)| *y).collect());
+    let centers = |min: f64, width: f64| -> Vec<f64> { (0..bins).map(|i| min + (i as f64 + 0.5) * width).collect() };
+    let cell = |value: f64, min: f64, width: f64| (((value - min) / width).floor().max(0.0) as usize).min(bins - 1);
+
+    x.iter()
+        .zip(y)
+        .zip(labels)
+        .enumerate()
+        .map(|(i, ((x_i, y_i), label))| {
+            let mut counts = vec![vec![0_usize; bins]; bins];
+            for (x, y) in x_i.iter().zip(y_i).filter(|(x, y)| x.is_finite() && y.is_finite()) {
+                counts[cell(*y, y_min, y_width)][cell(*x, x_min, x_width)] += 1;
+            }
+            let z: Vec<Vec<Option<usize>>> = counts.into_iter().map(|row| row.into_iter().map(|n| (n > 0).then_some(n)).collect()).collect();
+            let color = PALETTE[i % PALETTE.len()];
+            json!({
+                "type": "heatmap",
+                "name": label,
+                "x": centers(x_min, x_width),
+                "y": centers(y_min, y_width),
+                "z": z,
+                "colorscale": [[0, with_alpha(color, 0.2)], [1, with_alpha(color, 0.7)]],
+                "showscale": false,
+                "showlegend": true,
+                "hovertemplate": format!("{}: %{{z}} points<extra></extra>", label),
+            })
+        })
+        .collect()
+}
+
+/// Generate a scatter plot with one trace per label
+///
+/// Plots of more than 200,000 points are drawn as density grids, see [`plot_scatter_with_opts`].
+///
+/// # Arguments
+///
+/// * `x` - The x values of each trace, as any iterable of iterables (e.g. `&[Vec<f64>]` or an iterator of slices).
+///   Values may be numbers or category labels, see [`IntoAxisValue`]
+/// * `y` - The y values of each trace, numbers or category labels
+/// * `labels` - The name of each trace
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing the scatter plot
+pub fn plot_scatter<X, Y, VX, VY, N>(x: X, y: Y, labels: impl IntoIterator<Item = N>, title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError>
+where
+    X: IntoIterator,
+    X::Item: IntoIterator<Item = VX>,
+    VX: IntoAxisValue,
+    Y: IntoIterator,
+    Y::Item: IntoIterator<Item = VY>,
+    VY: IntoAxisValue,
+    N: Into<String>,
+{
+    plot_scatter_with_opts(x, y, labels, ScatterOptions::default(), title, x_title, y_title)
+}
+
+/// Generate a scatter plot with one trace per label, drawn as markers or, for millions of points,
+/// as density grids counted in Rust, so the report embeds a fixed number of cells instead of every
+/// point
+///
+/// # Arguments
+///
+/// * `x` - The x values of each trace, numbers or category labels, see [`plot_scatter`]
+/// * `y` - The y values of each trace, numbers or category labels
+/// * `labels` - The name of each trace
+/// * `opts` - How the points are drawn, see [`ScatterOptions`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing the scatter plot, or an error if there is not one label per trace, a
+/// trace has different numbers of x and y values, or density grids are requested for categorical
+/// values. [`ScatterMode::Auto`]
+/// draws categorical values as markers.
+pub fn plot_scatter_with_opts<X, Y, VX, VY, N>(
+    x: X,
+    y: Y,
+    labels: impl IntoIterator<Item = N>,
+    opts: ScatterOptions,
+    title: &str,
+    x_title: &str,
+    y_title: &str,
+) -> Result<Plot, ReportError>
+where
+    X: IntoIterator,
+    X::Item: IntoIterator<Item = VX>,
+    VX: IntoAxisValue,
+    Y: IntoIterator,
+    Y::Item: IntoIterator<Item = VY>,
+    VY: IntoAxisValue,
+    N: Into<String>,
+{
+    let x = collect_axis_series(x);
+    let y = collect_axis_series(y);
+    let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+    ReportError::check_len("y series", x.len(), y.len())?;
+    ReportError::check_len("labels", x.len(), labels.len())?;
+    for (x_i, y_i) in x.iter().zip(&y) {
+        ReportError::check_len("y values", x_i.len(), y_i.len())?;
+    }
+    let (x_axis, y_axis) = (axis_for(&x, x_title), axis_for(&y, y_title));
+
+    let density = match opts.mode {
+        ScatterMode::Markers => None,
+        ScatterMode::Auto if x.iter().map(Vec::len).sum::<usize>() <= opts.density_threshold => None,
+        ScatterMode::Auto => numeric_series(&x).zip(numeric_series(&y)),
+        ScatterMode::Density => match numeric_series(&x).zip(numeric_series(&y)) {
+            Some(numbers) => Some(numbers),
+            None => return Err(ReportError::InvalidArgument("Density grids need numeric x and y values".to_string())),
+        },
+    };
+
+    let mut plot = Plot::new();
+    if let Some((x, y)) = density {
+        for trace in density_traces(&x, &y, &labels, opts.density_bins) {
+            plot.add_trace(JsonTrace::new(trace));
+        }
+    } else {
+        // Large traces are drawn with WebGL unless told otherwise
+        let web_gl_mode = opts.web_gl.unwrap_or_else(|| x.iter().map(Vec::len).max().unwrap_or(0) > 10_000);
+        for ((x_i, y_i), label) in x.into_iter().zip(y).zip(labels) {
+            let trace = Scatter::new(x_i, y_i).name(label).mode(Mode::Markers).marker(Marker::new().size(10)).web_gl_mode(web_gl_mode);
+            plot.add_trace(trace);
+        }
+    }
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(x_axis)
+        .y_axis(y_axis)
+        .legend(Legend::new().orientation(Orientation::Vertical));
+
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// A shaded band around a line, e.g. a confidence interval or ± one standard deviation, see
+/// [`plot_lines`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorBand {
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+impl ErrorBand {
+    /// Creates a band from its lower and upper bounds at each x value.
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Self {
+        ErrorBand { lower, upper }
+    }
+
+    /// Creates a band of the given error above and below each value, e.g. the standard deviation.
+    pub fn symmetric(values: &[f64], error: &[f64]) -> Self {
+        ErrorBand {
+            lower: values.iter().zip(error).map(|(v, e)| v - e).collect(),
+            upper: values.iter().zip(error).map(|(v, e)| v + e).collect(),
+        }
+    }
+}
+
+/// A `#rrggbb` color as CSS `rgba()` with the given opacity
+fn with_alpha(color: &str, alpha: f64) -> String {
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap_or(0);
+    format!("rgba({}, {}, {}, {})", channel(1), channel(3), channel(5), alpha)
+}
+
+/// Generate a line plot of one or more series over a shared x axis, each with an optional shaded
+/// error band, e.g. TIC chromatograms, learning curves or a QC metric drifting over time
+///
+/// # Arguments
+///
+/// * `x` - The x values shared by all series, e.g. retention times or epochs
+/// * `y_series` - The y values of each series, as any iterable of iterables (e.g. `&[Vec<f64>]`)
+/// * `labels` - The name of each series
+/// * `bands` - The error band of each series, `None` for series without one; an empty slice draws
+///   no bands
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing one line per series, each drawn over its band, or an error if a
+/// series, band or the labels do not match the x values or the number of series
+pub fn plot_lines<X, S, V, N>(
+    x: &[X],
+    y_series: S,
+    labels: impl IntoIterator<Item = N>,
+    bands: &[Option<ErrorBand>],
+    title: &str,
+    x_title: &str,
+    y_title: &str,
+) -> Result<Plot, ReportError>
+where
+    X: Numeric,
+    S: IntoIterator,
+    S::Item: IntoIterator<Item = V>,
+    V: Numeric,
+    N: Into<String>,
+{
+    let x = to_f64_vec(x);
+    let y_series = collect_series(y_series);
+    let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+    ReportError::check_len("labels", y_series.len(), labels.len())?;
+    if !bands.is_empty() {
+        ReportError::check_len("bands", y_series.len(), bands.len())?;
+    }
+    for y in &y_series {
+        ReportError::check_len("y values", x.len(), y.len())?;
+    }
+    for band in bands.iter().flatten() {
+        ReportError::check_len("band lower bounds", x.len(), band.lower.len())?;
+        ReportError::check_len("band upper bounds", x.len(), band.upper.len())?;
+    }
+
+    let mut plot = Plot::new();
+    for (i, (y, label)) in y_series.into_iter().zip(labels).enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        if let Some(Some(band)) = bands.get(i) {
+            plot.add_trace(
+                Scatter::new(x.clone(), band.lower.clone())
+                    .name(format!("{} band", label))
+                    .legend_group(&label)
+                    .mode(Mode::Lines)
+                    .line(Line::new().width(0.0))
+                    .show_legend(false),
+            );
+            plot.add_trace(
+                Scatter::new(x.clone(), band.upper.clone())
+                    .name(format!("{} band", label))
+                    .legend_group(&label)
+                    .mode(Mode::Lines)
+                    .line(Line::new().width(0.0))
+                    .fill(Fill::ToNextY)
+                    .fill_color(with_alpha(color, 0.2))
+                    .show_legend(false),
+            );
+        }
+        plot.add_trace(
+            Scatter::new(x.clone(), y)
+                .name(&label)
+                .legend_group(&label)
+                .mode(Mode::Lines)
+                .line(Line::new().color(color)),
+        );
+    }
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title))
+        .y_axis(Axis::new().title(y_title));
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// Generate a scatter plot matrix (SPLOM) for pairwise exploration of several features
+///
+/// # Arguments
+///
+/// * `columns` - The features to plot, as (name, values) pairs of equal length
+/// * `color_by` - An optional group label for each row, drawn as separately colored traces
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the scatter plot matrix
+pub fn plot_splom(columns: &[(String, Vec<f64>)], color_by: Option<&[String]>, title: &str) -> Result<Plot, ReportError> {
+    let Some((_, first)) = columns.first() else {
+        return Err(ReportError::EmptyData("at least one column is required"));
+    };
+    let n_rows = first.len();
+    for (_, values) in columns {
+        ReportError::check_len("rows", n_rows, values.len())?;
+    }
+    if let Some(groups) = color_by {
+        ReportError::check_len("color_by labels", n_rows, groups.len())?;
+    }
+
+    // One trace per group, so each group gets its own color and legend entry
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    match color_by {
+        Some(labels) => {
+            for (i, label) in labels.iter().enumerate() {
+                match groups.iter_mut().find(|(name, _)| name == label) {
+                    Some((_, rows)) => rows.push(i),
+                    None => groups.push((label.clone(), vec![i])),
+                }
+            }
+        }
+        None => groups.push((String::new(), (0..n_rows).collect())),
+    }
+
+    let mut plot = Plot::new();
+    for (name, rows) in &groups {
+        let dimensions: Vec<Value> = columns
+            .iter()
+            .map(|(label, values)| json!({
+                "label": label,
+                "values": rows.iter().map(|&i| values[i]).collect::<Vec<f64>>(),
+            }))
+            .collect();
+        plot.add_trace(JsonTrace::new(json!({
+            "type": "splom",
+            "name": name,
+            "dimensions": dimensions,
+            "showupperhalf": false,
+            "diagonal": { "visible": false },
+            "marker": { "size": 4, "opacity": 0.6 },
+        })));
+    }
+
+    let size = (250 * columns.len()).max(500);
+    let layout = defaults::base_layout()
+        .title(title)
+        .height(size)
+        .show_legend(color_by.is_some())
+        .drag_mode(plotly::layout::DragMode::Select);
+
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// A node of a hierarchy, e.g. a taxon with its sub-taxa or a directory with its contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchyNode {
+    pub label: String,
+    /// The value of the node itself, excluding its children
+    pub value: f64,
+    pub children: Vec<HierarchyNode>,
+}
+
+impl HierarchyNode {
+    pub fn new(label: &str, value: f64) -> Self {
+        HierarchyNode {
+            label: label.to_string(),
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// Add a child node
+    pub fn child(mut self, child: HierarchyNode) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+/// A flattened hierarchy in Plotly's (ids, labels, parents, values) form
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Hierarchy {
+    pub ids: Vec<String>,
+    pub labels: Vec<String>,
+    /// The id of each node's parent, or an empty string for root nodes
+    pub parents: Vec<String>,
+    pub values: Vec<f64>,
+}
+
+impl Hierarchy {
+    /// Create a hierarchy from (labels, parents, values) triples, where labels are unique and
+    /// double as ids
+    ///
+    /// # Returns
+    ///
+    /// The hierarchy, or an error if the three vectors have different lengths
+    pub fn new(labels: Vec<String>, parents: Vec<String>, values: Vec<f64>) -> Result<Self, ReportError> {
+        ReportError::check_len("parents", labels.len(), parents.len())?;
+        ReportError::check_len("values", labels.len(), values.len())?;
+        Ok(Hierarchy {
+            ids: labels.clone(),
+            labels,
+            parents,
+            values,
+        })
+    }
+
+    /// Flatten a tree, using the path from the root as the id of each node
+    pub fn from_tree(root: &HierarchyNode) -> Self {
+        fn visit(node: &HierarchyNode, parent_id: &str, hierarchy: &mut Hierarchy) {
+            let id = if parent_id.is_empty() {
+                node.label.clone()
+            } else {
+                format!("{}/{}", parent_id, node.label)
+            };
+            hierarchy.ids.push(id.clone());
+            hierarchy.labels.push(node.label.clone());
+            hierarchy.parents.push(parent_id.to_string());
+            hierarchy.values.push(node.value);
+            for child in &node.children {
+                visit(child, &id, hierarchy);
+            }
+        }
+
+        let mut hierarchy = Hierarchy::default();
+        visit(root, "", &mut hierarchy);
+        hierarchy
+    }
+}
+
+fn hierarchy_plot(trace_type: &str, hierarchy: &Hierarchy, title: &str) -> Plot {
+    let mut plot = Plot::new();
+    plot.add_trace(JsonTrace::new(json!({
+        "type": trace_type,
+        "ids": hierarchy.ids,
+        "labels": hierarchy.labels,
+        "parents": hierarchy.parents,
+        "values": hierarchy.values,
+        "branchvalues": "remainder",
+    })));
+    plot.set_layout(defaults::base_layout().title(title));
+    plot
+}
+
+/// Generate a sunburst plot of a hierarchical composition
+///
+/// # Arguments
+///
+/// * `hierarchy` - The hierarchy to plot, see [`Hierarchy::new`] and [`Hierarchy::from_tree`]
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the sunburst plot
+pub fn plot_sunburst(hierarchy: &Hierarchy, title: &str) -> Result<Plot, ReportError> {
+    Ok(hierarchy_plot("sunburst", hierarchy, title))
+}
+
+/// Generate a treemap of a hierarchical composition
+///
+/// # Arguments
+///
+/// * `hierarchy` - The hierarchy to plot, see [`Hierarchy::new`] and [`Hierarchy::from_tree`]
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the treemap
+pub fn plot_treemap(hierarchy: &Hierarchy, title: &str) -> Result<Plot, ReportError> {
+    Ok(hierarchy_plot("treemap", hierarchy, title))
+}
+
+/// How the values of each metric are scaled before drawing a radar chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarNormalization {
+    /// Plot the raw values
+    None,
+    /// Scale each metric to [0, 1] using its minimum and maximum across series
+    MinMax,
+    /// Divide each metric by its maximum absolute value across series
+    Max,
+}
+
+/// Generate a radar (polar) chart with one polygon per series over a common set of metrics
+///
+/// # Arguments
+///
+/// * `categories` - The names of the metrics, one per axis of the chart
+/// * `series` - The polygons to draw as (name, values) pairs, with one value per category
+/// * `normalization` - How each metric is scaled, so metrics with different units are comparable
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the radar chart
+pub fn plot_radar(categories: &[String], series: &[(String, Vec<f64>)], normalization: RadarNormalization, title: &str) -> Result<Plot, ReportError> {
+    if categories.is_empty() {
+        return Err(ReportError::EmptyData("at least one category is required"));
+    }
+    for (_, values) in series {
+        ReportError::check_len("values per series", categories.len(), values.len())?;
+    }
+
+    let scale = |c: usize, value: f64| -> f64 {
+        let column = series.iter().map(|(_, values)| values[c]);
+        match normalization {
+            RadarNormalization::None => value,
+            RadarNormalization::MinMax => {
+                let min = column.clone().fold(f64::INFINITY, f64::min);
+                let max = column.fold(f64::NEG_INFINITY, f64::max);
+                if max > min { (value - min) / (max - min) } else { 0.5 }
+            }
+            RadarNormalization::Max => {
+                let max = column.fold(0.0, |acc: f64, v| acc.max(v.abs()));
+                if max > 0.0 { value / max } else { 0.0 }
+            }
+        }
+    };
+
+    // Repeat the first category to close each polygon
+    let mut theta = categories.to_vec();
+    theta.push(categories[0].clone());
+
+    let mut plot = Plot::new();
+    for (name, values) in series {
+        let mut r: Vec<f64> = values.iter().enumerate().map(|(c, &v)| scale(c, v)).collect();
+        r.push(r[0]);
+        let trace = ScatterPolar::new(theta.clone(), r)
+            .name(name)
+            .mode(Mode::LinesMarkers)
+            .fill(Fill::ToSelf);
+        plot.add_trace(trace);
+    }
+
+    plot.set_layout(defaults::base_layout().title(title));
+
+    Ok(plot)
+}
+
+/// Generate a strip plot showing every individual point per group, spread horizontally with
+/// jitter, optionally overlaid on box plots
+///
+/// # Arguments
+///
+/// * `groups` - The groups to plot as (name, values) pairs
+/// * `jitter` - The maximum horizontal offset of the points, as a fraction of the group spacing (0 to 1)
+/// * `overlay_box` - Whether to draw a box plot behind the points of each group
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+/// * `y_title` - The title of the y-axis
+///
+/// # Returns
+///
+/// A Plot object containing the strip plot
+pub fn plot_strip(groups: &[(String, Vec<f64>)], jitter: f64, overlay_box: bool, title: &str, x_title: &str, y_title: &str) -> Result<Plot, ReportError> {
+    if !(0.0..=1.0).contains(&jitter) {
+        return Err(ReportError::InvalidArgument(format!("Jitter must be between 0 and 1, got {}", jitter)));
+    }
+
+    // Seeded so that the same data always produces the same figure
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let mut plot = Plot::new();
+    for (i, (name, values)) in groups.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        let center = i as f64;
+
+        if overlay_box {
+            let trace = BoxPlot::new_xy(vec![center; values.len()], values.to_vec())
+                .name(name)
+                .legend_group(name)
+                .show_legend(false)
+                .box_points(BoxPoints::False)
+                .marker(Marker::new().color(color))
+                .hover_info(plotly::common::HoverInfo::Skip);
+            plot.add_trace(trace);
+        }
+
+        let x: Vec<f64> = values
+            .iter()
+            .map(|_| center + rng.gen_range(-0.5..=0.5) * jitter * 0.8)
+            .collect();
+        let trace = Scatter::new(x, values.to_vec())
+            .name(name)
+            .legend_group(name)
+            .mode(Mode::Markers)
+            .marker(Marker::new().color(color).size(7).opacity(0.7));
+        plot.add_trace(trace);
+    }
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(
+            Axis::new()
+                .title(x_title)
+                .tick_values((0..groups.len()).map(|i| i as f64).collect())
+                .tick_text(groups.iter().map(|(name, _)| name.clone()).collect())
+                .zero_line(false),
+        )
+        .y_axis(Axis::new().title(y_title))
+        .show_legend(false);
+
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// Generate an overlay of the empirical CDFs of two samples, shading their maximum gap and
+/// annotating the two-sample Kolmogorov–Smirnov statistic and p-value
+///
+/// # Arguments
+///
+/// * `a` - The first sample, as (name, values)
+/// * `b` - The second sample, as (name, values)
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+///
+/// # Returns
+///
+/// A Plot object containing the ECDF comparison
+pub fn plot_cdf_compare<T: Numeric>(a: (&str, &[T]), b: (&str, &[T]), title: &str, x_title: &str) -> Result<Plot, ReportError> {
+    let (a, b) = ((a.0, to_f64_vec(a.1)), (b.0, to_f64_vec(b.1)));
+    let ks = stats::ks_two_sample(&a.1, &b.1).ok_or(ReportError::EmptyData("both samples must contain finite values"))?;
+
+    let mut plot = Plot::new();
+    for (i, (name, values)) in [a, b].into_iter().enumerate() {
+        let sorted = stats::sorted_finite(&values);
+        let n = sorted.len() as f64;
+        let y: Vec<f64> = (1..=sorted.len()).map(|k| k as f64 / n).collect();
+        let trace = Scatter::new(sorted, y)
+            .name(name)
+            .mode(Mode::Lines)
+            .line(Line::new().shape(LineShape::Hv).color(PALETTE[i]));
+        plot.add_trace(trace);
+    }
+
+    let gap = Scatter::new(vec![ks.location, ks.location], vec![ks.cdf_a, ks.cdf_b])
+        .name("Maximum gap")
+        .mode(Mode::Lines)
+        .line(Line::new().color("rgba(214, 39, 40, 0.4)").width(8.0));
+    plot.add_trace(gap);
+
+    let annotation = Annotation::new()
+        .text(format!("KS D = {:.4}<br>p = {:.3e}", ks.statistic, ks.p_value))
+        .x_ref("paper")
+        .y_ref("paper")
+        .x(0.02)
+        .y(0.98)
+        .show_arrow(false)
+        .align(plotly::layout::HAlign::Left);
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title))
+        .y_axis(Axis::new().title("Cumulative fraction").range(vec![0.0, 1.0]))
+        .annotations(vec![annotation]);
+
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// Scatter the differences against the averages of paired measurements, with the mean difference
+/// (bias) and the 95% limits of agreement drawn as horizontal lines
+fn agreement_plot(averages: Vec<f64>, differences: Vec<f64>, title: &str, x_title: &str, y_title: &str) -> Plot {
+    let bias = stats::mean(&differences);
+    let sd = stats::std_dev(&differences);
+    let x_min = averages.iter().copied().fold(f64::INFINITY, f64::min);
+    let x_max = averages.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(averages, differences)
+            .name("Pairs")
+            .mode(Mode::Markers)
+            .marker(Marker::new().opacity(0.6)),
+    );
+
+    let lines = [
+        ("Bias", bias, DashType::Solid),
+        ("Upper LoA", bias + 1.96 * sd, DashType::Dash),
+        ("Lower LoA", bias - 1.96 * sd, DashType::Dash),
+    ];
+    for (name, level, dash) in lines {
+        plot.add_trace(
+            Scatter::new(vec![x_min, x_max], vec![level, level])
+                .name(format!("{} = {:.3}", name, level))
+                .mode(Mode::Lines)
+                .line(Line::new().color("red").dash(dash)),
+        );
+    }
+
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title))
+        .y_axis(Axis::new().title(y_title));
+    plot.set_layout(layout);
+
+    plot
+}
+
+/// Generate a Bland–Altman plot comparing two methods measuring the same samples
+///
+/// # Arguments
+///
+/// * `x` - The measurements of the first method
+/// * `y` - The measurements of the second method, paired with `x`
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the mean vs difference scatter with bias and limits-of-agreement lines
+pub fn plot_bland_altman<T: Numeric>(x: &[T], y: &[T], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_len("y values", x.len(), y.len())?;
+    let (x, y) = (to_f64_vec(x), to_f64_vec(y));
+
+    let (averages, differences): (Vec<f64>, Vec<f64>) = x
+        .iter()
+        .zip(y.iter())
+        .filter(|(a, b)| a.is_finite() && b.is_finite())
+        .map(|(a, b)| ((a + b) / 2.0, a - b))
+        .unzip();
+
+    Ok(agreement_plot(averages, differences, title, "Mean of methods", "Difference (x - y)"))
+}
+
+/// Generate an MA plot (log ratio vs mean log intensity) comparing two replicates or conditions
+///
+/// # Arguments
+///
+/// * `x` - The intensities of the first replicate
+/// * `y` - The intensities of the second replicate, paired with `x`
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the MA scatter with bias and limits-of-agreement lines. Pairs with a
+/// non-positive intensity are left out, as their log ratio is undefined.
+pub fn plot_ma<T: Numeric>(x: &[T], y: &[T], title: &str) -> Result<Plot, ReportError> {
+    ReportError::check_len("y values", x.len(), y.len())?;
+    let (x, y) = (to_f64_vec(x), to_f64_vec(y));
+
+    let (a_values, m_values): (Vec<f64>, Vec<f64>) = x
+        .iter()
+        .zip(y.iter())
+        .filter(|(a, b)| **a > 0.0 && **b > 0.0 && a.is_finite() && b.is_finite())
+        .map(|(a, b)| (0.5 * (a.log2() + b.log2()), a.log2() - b.log2()))
+        .unzip();
+
+    Ok(agreement_plot(a_values, m_values, title, "A = mean log2 intensity", "M = log2 ratio"))
+}
+
+/// Generate a forest plot of per-group effect sizes with their confidence intervals, e.g. the
+/// fold changes of a differential analysis or the studies of a meta-analysis
+///
+/// # Arguments
+///
+/// * `estimates` - The effect size of each group
+/// * `ci_low` - The lower bound of each confidence interval
+/// * `ci_high` - The upper bound of each confidence interval
+/// * `labels` - The name of each group, listed from top to bottom
+/// * `null_value` - The effect of no difference, drawn as a dashed vertical line (e.g. 0 for a
+///   difference or a log ratio, 1 for a ratio)
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis
+///
+/// # Returns
+///
+/// A Plot object containing one row per group, or an error if the inputs have different lengths
+pub fn plot_forest<T: Numeric, N: Into<String>>(
+    estimates: &[T],
+    ci_low: &[T],
+    ci_high: &[T],
+    labels: impl IntoIterator<Item = N>,
+    null_value: f64,
+    title: &str,
+    x_title: &str,
+) -> Result<Plot, ReportError> {
+    let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+    ReportError::check_len("lower bounds", estimates.len(), ci_low.len())?;
+    ReportError::check_len("upper bounds", estimates.len(), ci_high.len())?;
+    ReportError::check_len("labels", estimates.len(), labels.len())?;
+    let (estimates, low, high) = (to_f64_vec(estimates), to_f64_vec(ci_low), to_f64_vec(ci_high));
+
+    // A categorical axis draws its first category at the bottom, so rows are added bottom-up
+    let rows: Vec<usize> = (0..estimates.len()).rev().collect();
+    let error = ErrorData::new(ErrorType::Data)
+        .symmetric(false)
+        .array(rows.iter().map(|&i| high[i] - estimates[i]).collect())
+        .array_minus(rows.iter().map(|&i| estimates[i] - low[i]).collect());
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(rows.iter().map(|&i| estimates[i]).collect(), rows.iter().map(|&i| labels[i].clone()).collect())
+            .name("Estimate")
+            .mode(Mode::Markers)
+            .marker(Marker::new().symbol(MarkerSymbol::Square).size(9).color(PALETTE[0]))
+            .error_x(error.color(PALETTE[0]))
+            .show_legend(false),
+    );
+
+    let mut layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title).zero_line(false))
+        .y_axis(Axis::new().type_(AxisType::Category).auto_margin(true))
+        .height((40 * estimates.len()).max(300) + 150);
+    layout.add_shape(
+        Shape::new()
+            .shape_type(ShapeType::Line)
+            .x_ref("x")
+            .y_ref("paper")
+            .x0(null_value)
+            .y0(0.0)
+            .x1(null_value)
+            .y1(1.0)
+            .line(ShapeLine::new().color("gray").dash(DashType::Dash)),
+    );
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// A round step (1, 2 or 5 times a power of ten) splitting `range` into about `count` intervals
+fn nice_step(range: f64, count: usize) -> f64 {
+    let raw = range / count as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].iter().map(|m| m * magnitude).find(|step| *step >= raw).unwrap_or(raw)
+}
+
+/// Generate Kaplan–Meier survival curves, one per group, with censored subjects marked and the
+/// number of subjects at risk listed below the plot
+///
+/// # Arguments
+///
+/// * `times` - The follow-up time of each subject
+/// * `events` - Whether each subject's follow-up ended with the event (`true`) or was censored
+/// * `groups` - The group of each subject; groups are drawn in the order they first appear
+/// * `log_rank` - Whether to test the groups for a difference in survival and show the log-rank
+///   p-value, see [`stats::log_rank_test`]
+/// * `title` - The title of the plot
+/// * `x_title` - The title of the x-axis, e.g. "Months"
+///
+/// # Returns
+///
+/// A Plot object containing the step survival curves, or an error if the inputs have different
+/// lengths or no subject has a finite time
+pub fn plot_kaplan_meier<T: Numeric, S: AsRef<str>>(
+    times: &[T],
+    events: &[bool],
+    groups: &[S],
+    log_rank: bool,
+    title: &str,
+    x_title: &str,
+) -> Result<Plot, ReportError> {
+    ReportError::check_len("events", times.len(), events.len())?;
+    ReportError::check_len("groups", times.len(), groups.len())?;
+    let times = to_f64_vec(times);
+    let max_time = times.iter().copied().filter(|t| t.is_finite()).fold(f64::NEG_INFINITY, f64::max);
+    if max_time == f64::NEG_INFINITY {
+        return Err(ReportError::EmptyData("survival times"));
+    }
+
+    let mut names: Vec<&str> = Vec::new();
+    for group in groups {
+        if !names.contains(&group.as_ref()) {
+            names.push(group.as_ref());
+        }
+    }
+    let members = |name: &str| -> (Vec<f64>, Vec<bool>) {
+        times
+            .iter()
+            .zip(events)
+            .zip(groups)
+            .filter(|((t, _), group)| t.is_finite() && group.as_ref() == name)
+            .map(|((t, e), _)| (*t, *e))
+            .unzip()
+    };
+
+    let step = nice_step(max_time, 5);
+    let ticks: Vec<f64> = (0..).map(|i| i as f64 * step).take_while(|t| *t <= max_time + step * 1e-9).collect();
+
+    let mut plot = Plot::new();
+    let mut layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().title(x_title).tick_values(ticks.clone()).range(vec![0.0, max_time]))
+        .y_axis(Axis::new().title("Survival probability").range(vec![0.0, 1.05]))
+        .margin(plotly::layout::Margin::new().left(110).bottom(90 + 18 * (names.len() + 1)));
+    layout.add_annotation(at_risk_annotation("Number at risk", None, 0));
+
+    for (i, name) in names.iter().enumerate() {
+        let (group_times, group_events) = members(name);
+        let curve = stats::kaplan_meier(&group_times, &group_events);
+        let color = PALETTE[i % PALETTE.len()];
+
+        // Extend the last step to the end of the group's follow-up
+        let (mut x, mut y) = (curve.times.clone(), curve.survival.clone());
+        let last_time = group_times.iter().copied().fold(0.0, f64::max);
+        if last_time > *x.last().unwrap_or(&0.0) {
+            x.push(last_time);
+            y.push(*y.last().unwrap_or(&1.0));
+        }
+        plot.add_trace(
+            Scatter::new(x, y)
+                .name(format!("{} (n={})", name, group_times.len()))
+                .legend_group(*name)
+                .mode(Mode::Lines)
+                .line(Line::new().shape(LineShape::Hv).color(color)),
+        );
+        if !curve.censored.is_empty() {
+            let (x, y): (Vec<f64>, Vec<f64>) = curve.censored.into_iter().unzip();
+            plot.add_trace(
+                Scatter::new(x, y)
+                    .name(format!("{} (censored)", name))
+                    .legend_group(*name)
+                    .show_legend(false)
+                    .mode(Mode::Markers)
+                    .marker(Marker::new().symbol(MarkerSymbol::LineNSOpen).size(10).color(color)),
+            );
+        }
+
+        layout.add_annotation(at_risk_annotation(name, None, i + 1).font(plotly::common::Font::new().color(color)));
+        for &tick in &ticks {
+            let at_risk = group_times.iter().filter(|t| **t >= tick).count();
+            layout.add_annotation(at_risk_annotation(&at_risk.to_string(), Some(tick), i + 1));
+        }
+    }
+
+    if log_rank {
+        if let Some(result) = stats::log_rank_test(&times, events, groups) {
+            layout.add_annotation(
+                Annotation::new()
+                    .text(format!("Log-rank p = {:.3}", result.p_value))
+                    .x_ref("paper")
+                    .y_ref("paper")
+                    .x(0.98)
+                    .y(0.98)
+                    .x_anchor(plotly::common::Anchor::Right)
+                    .y_anchor(plotly::common::Anchor::Top)
+                    .show_arrow(false),
+            );
+        }
+    }
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+/// A cell of the at-risk table below a survival plot: a row label left of the plot area when
+/// `time` is `None`, or a count below the given time, on the given row
+fn at_risk_annotation(text: &str, time: Option<f64>, row: usize) -> Annotation {
+    let annotation = match time {
+        Some(time) => Annotation::new().x_ref("x").x(time).x_anchor(plotly::common::Anchor::Center),
+        None => Annotation::new().x_ref("paper").x(0.0).x_anchor(plotly::common::Anchor::Right),
+    };
+    annotation
+        .text(text)
+        .y_ref("paper")
+        .y(0.0)
+        .y_anchor(plotly::common::Anchor::Top)
+        .y_shift(-60.0 - 18.0 * row as f64)
+        .show_arrow(false)
+}
+
+/// Generate an UpSet plot of the overlap between sets, e.g. the identifications shared across
+/// runs, for more sets than a Venn diagram can show
+///
+/// Each column is an intersection: the elements in exactly the sets marked by the connected dots
+/// of the matrix below it, with its size drawn as a bar above. The size of each set is drawn as a
+/// bar left of its row. Sets are listed from the largest at the top, and intersections from the
+/// largest on the left.
+///
+/// # Arguments
+///
+/// * `sets` - The elements of each set, by name
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the intersection bars, the membership matrix and the set-size bars, or
+/// an error if all sets are empty
+pub fn plot_upset<S: BuildHasher, E: BuildHasher>(sets: &HashMap<String, HashSet<String, E>, S>, title: &str) -> Result<Plot, ReportError> {
+    let mut names: Vec<(&str, usize)> = sets.iter().map(|(name, elements)| (name.as_str(), elements.len())).collect();
+    names.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    // The sets each element belongs to, as indices into `names`
+    let mut memberships: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (name, _)) in names.iter().enumerate() {
+        for element in &sets[*name] {
+            memberships.entry(element.as_str()).or_default().push(i);
+        }
+    }
+    if memberships.is_empty() {
+        return Err(ReportError::EmptyData("all sets are empty"));
+    }
+    let mut counts: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+    for members in memberships.into_values() {
+        *counts.entry(members).or_default() += 1;
+    }
+    let mut intersections: Vec<(Vec<usize>, usize)> = counts.into_iter().collect();
+    intersections.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.len().cmp(&b.0.len())).then(a.0.cmp(&b.0)));
+
+    // Sets are drawn on rows n-1 (top) to 0 (bottom), intersections on columns 0 to k-1
+    let (n, k) = (names.len(), intersections.len());
+    let row = |set: usize| (n - 1 - set) as f64;
+    let columns: Vec<f64> = (0..k).map(|j| j as f64).collect();
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Bar::new(columns.clone(), intersections.iter().map(|(_, count)| *count).collect())
+            .name("Intersection size")
+            .marker(Marker::new().color(PALETTE[0]))
+            .show_legend(false),
+    );
+    plot.add_trace(
+        Bar::new(names.iter().map(|(_, size)| *size).collect(), (0..n).map(row).collect())
+            .name("Set size")
+            .orientation(Orientation::Horizontal)
+            .marker(Marker::new().color(PALETTE[0]))
+            .x_axis("x2")
+            .y_axis("y2")
+            .show_legend(false),
+    );
+    plot.add_trace(
+        Scatter::new(columns.iter().flat_map(|&x| std::iter::repeat(x).take(n)).collect(), columns.iter().flat_map(|_| (0..n).map(row)).collect())
+            .mode(Mode::Markers)
+            .marker(Marker::new().size(12).color("#dddddd"))
+            .y_axis("y2")
+            .hover_info(plotly::common::HoverInfo::Skip)
+            .show_legend(false),
+    );
+    for (j, (members, count)) in intersections.iter().enumerate() {
+        let label = members.iter().map(|&set| names[set].0).collect::<Vec<_>>().join(" ∩ ");
+        plot.add_trace(
+            Scatter::new(vec![j as f64; members.len()], members.iter().map(|&set| row(set)).collect())
+                .name(format!("{} ({})", label, count))
+                .mode(Mode::LinesMarkers)
+                .marker(Marker::new().size(12).color(PALETTE[0]))
+                .line(Line::new().color(PALETTE[0]).width(3.0))
+                .y_axis("y2")
+                .show_legend(false),
+        );
+    }
+
+    let largest_set = names.first().map_or(0, |(_, size)| *size) as f64;
+    let layout = defaults::base_layout()
+        .title(title)
+        .x_axis(Axis::new().domain(&[0.25, 1.0]).range(vec![-0.5, k as f64 - 0.5]).show_tick_labels(false).show_grid(false).zero_line(false))
+        .y_axis(Axis::new().title("Intersection size").domain(&[0.4, 1.0]))
+        .x_axis2(Axis::new().title("Set size").domain(&[0.0, 0.2]).anchor("y2").range(vec![largest_set * 1.1, 0.0]))
+        .y_axis2(
+            Axis::new()
+                .domain(&[0.0, 0.35])
+                .anchor("x")
+                .range(vec![-0.5, n as f64 - 0.5])
+                .tick_values((0..n).map(row).collect())
+                .tick_text(names.iter().map(|(name, _)| name.to_string()).collect())
+                .show_grid(false)
+                .zero_line(false),
+        )
+        .height(300 + 30 * n);
+    plot.set_layout(layout);
+
+    Ok(plot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
     fn test_plot_boxplot() {
         let scores = vec![
             vec![1.0, 2.0, 3.0, 4.0, 5.0],
@@ -215,16 +2431,17 @@ mod tests {
 
         let plot = plot_boxplot(&scores, filenames, title, x_title, y_title).unwrap();
 
-        plot.write_html("test_plot_boxplot.html");
-
-        // assert_eq!(plot.
-        // assert_eq!(plot.layout.title, Some(title.to_string()));
-        // assert_eq!(plot.layout.x_axis, Some(Axis::new().title(x_title).tick_angle(45.0)));
-        // assert_eq!(plot.layout.y_axis, Some(Axis::new().title(y_title)));
+        let json = to_json(&plot);
+        assert_eq!(json["data"].as_array().unwrap().len(), 3);
+        assert_eq!(json["data"][1]["name"], "file2");
+        assert_eq!(json["data"][1]["y"], json!([6.0, 7.0, 8.0, 9.0, 10.0]));
+        assert_eq!(json["layout"]["title"]["text"], title);
+        assert_eq!(json["layout"]["xaxis"]["title"]["text"], x_title);
+        assert_eq!(json["layout"]["xaxis"]["tickangle"], 45.0);
+        assert_eq!(json["layout"]["yaxis"]["title"]["text"], y_title);
     }
 
     #[test]
-    #[should_panic(expected = "Scores and filenames must have the same length")]
     fn test_plot_boxplot_mismatched_lengths() {
         let scores = vec![
             vec![1.0, 2.0, 3.0, 4.0, 5.0],
@@ -239,7 +2456,8 @@ mod tests {
         let x_title = "Filenames";
         let y_title = "Scores";
 
-        plot_boxplot(&scores, filenames, title, x_title, y_title).unwrap();
+        let result = plot_boxplot(&scores, filenames, title, x_title, y_title);
+        assert!(matches!(result, Err(ReportError::LengthMismatch { what: "filenames", expected: 2, found: 3 })));
     }
 
     #[test]
@@ -265,7 +2483,427 @@ mod tests {
 
         let plot = plot_scatter(&x, &y, labels, title, x_title, y_title).unwrap();
 
-        plot.write_html("test_plot_scatter.html");
+        let json = to_json(&plot);
+        assert_eq!(json["data"].as_array().unwrap().len(), 3);
+        assert_eq!(json["data"][2]["type"], "scatter");
+        assert_eq!(json["data"][2]["x"], json!([1.0, 12.0, 13.0, 14.0, 15.0]));
+        assert_eq!(json["data"][2]["mode"], "markers");
+        assert_eq!(json["layout"]["title"]["text"], title);
+    }
+
+    #[test]
+    fn test_plot_scatter_from_iterators() {
+        let x = (0..3).map(|i| (0..5).map(move |j| (i * j) as f64));
+        let y = [[1.0, 2.0, 3.0, 4.0, 5.0].as_slice(); 3];
+        let plot = plot_scatter(x, y, ["a", "b", "c"], "Scatter", "X", "Y").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][2]["x"], json!([0.0, 2.0, 4.0, 6.0, 8.0]));
+        assert_eq!(json["data"][1]["name"], "b");
+    }
+
+    #[test]
+    fn test_plot_scatter_density() {
+        let x = vec![vec![0.0, 0.1, 0.9, 1.0, f64::NAN], vec![0.5, 0.5]];
+        let y = vec![vec![0.0, 0.1, 0.9, 1.0, 2.0], vec![0.5, 0.6]];
+        let opts = ScatterOptions { density_threshold: 5, density_bins: 2, ..Default::default() };
+        let json = to_json(&plot_scatter_with_opts(&x, &y, ["Targets", "Decoys"], opts, "Scores", "X", "Y").unwrap());
+        assert_eq!(json["data"][0]["type"], "heatmap");
+        assert_eq!(json["data"][0]["x"], json!([0.25, 0.75]));
+        assert_eq!(json["data"][0]["z"], json!([[2, null], [null, 2]]));
+        assert_eq!(json["data"][1]["z"], json!([[null, null], [null, 2]]));
+        assert_eq!(json["data"][1]["colorscale"][1][1], "rgba(255, 127, 14, 0.7)");
+        let result = plot_scatter_with_opts(&x, [&y[0][..4], &y[1][..]], ["Targets", "Decoys"], opts, "Scores", "X", "Y");
+        assert!(matches!(result, Err(ReportError::LengthMismatch { what: "y values", expected: 5, found: 4 })));
+        let result = plot_scatter_with_opts(&x, &y, ["Targets"], opts, "Scores", "X", "Y");
+        assert!(matches!(result, Err(ReportError::LengthMismatch { what: "labels", expected: 2, found: 1 })));
+
+        let opts = ScatterOptions { mode: ScatterMode::Markers, web_gl: Some(true), ..opts };
+        assert_eq!(to_json(&plot_scatter_with_opts(&x, &y, ["Targets", "Decoys"], opts, "Scores", "X", "Y").unwrap())["data"][0]["type"], "scattergl");
+        let opts = ScatterOptions { mode: ScatterMode::Density, ..opts };
+        assert!(plot_scatter_with_opts([["A"]], [[1.0]], ["Runs"], opts, "Scores", "X", "Y").is_err());
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_plot_splom() {
+        let columns = vec![
+            ("score".to_string(), vec![1.0, 2.0, 3.0, 4.0]),
+            ("mass_error".to_string(), vec![0.1, -0.2, 0.3, 0.0]),
+            ("rt_delta".to_string(), vec![5.0, 1.0, 2.0, 3.0]),
+        ];
+        let groups = vec!["target".to_string(), "decoy".to_string(), "target".to_string(), "target".to_string()];
+
+        let plot = plot_splom(&columns, Some(&groups), "Feature matrix").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"].as_array().unwrap().len(), 2);
+        assert_eq!(json["data"][0]["type"], "splom");
+        assert_eq!(json["data"][0]["name"], "target");
+        assert_eq!(json["data"][0]["dimensions"][2]["values"], json!([5.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_plot_sunburst_from_tree() {
+        let tree = HierarchyNode::new("Bacteria", 0.0)
+            .child(HierarchyNode::new("Firmicutes", 40.0).child(HierarchyNode::new("Bacilli", 10.0)))
+            .child(HierarchyNode::new("Proteobacteria", 25.0));
+
+        assert!(Hierarchy::new(vec!["Bacteria".to_string()], Vec::new(), vec![1.0]).is_err());
+        let hierarchy = Hierarchy::from_tree(&tree);
+        assert_eq!(hierarchy.ids, vec!["Bacteria", "Bacteria/Firmicutes", "Bacteria/Firmicutes/Bacilli", "Bacteria/Proteobacteria"]);
+        assert_eq!(hierarchy.parents, vec!["", "Bacteria", "Bacteria/Firmicutes", "Bacteria"]);
+
+        let plot = plot_sunburst(&hierarchy, "Composition").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["type"], "sunburst");
+        assert_eq!(json["data"][0]["values"], json!([0.0, 40.0, 10.0, 25.0]));
+
+        let plot = plot_treemap(&hierarchy, "Composition").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["type"], "treemap");
+    }
+
+    #[test]
+    fn test_plot_radar_min_max() {
+        let categories = vec!["ID rate".to_string(), "Mass accuracy".to_string(), "RT stability".to_string()];
+        let series = vec![
+            ("sample1".to_string(), vec![0.8, 2.0, 10.0]),
+            ("sample2".to_string(), vec![0.6, 4.0, 10.0]),
+        ];
+
+        let plot = plot_radar(&categories, &series, RadarNormalization::MinMax, "QC fingerprint").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["r"], json!([1.0, 0.0, 0.5, 1.0]));
+        assert_eq!(json["data"][1]["r"], json!([0.0, 1.0, 0.5, 0.0]));
+        assert_eq!(json["data"][0]["theta"][3], "ID rate");
+    }
+
+    #[test]
+    fn test_plot_strip() {
+        let groups = vec![
+            ("control".to_string(), vec![1.0, 1.2, 0.9]),
+            ("treated".to_string(), vec![2.0, 2.4, 1.8, 2.2]),
+        ];
+
+        let plot = plot_strip(&groups, 0.5, true, "Strip", "Group", "Value").unwrap();
+        let json = to_json(&plot);
+
+        // A box and a strip trace per group
+        assert_eq!(json["data"].as_array().unwrap().len(), 4);
+        let x = json["data"][3]["x"].as_array().unwrap();
+        assert!(x.iter().all(|v| (v.as_f64().unwrap() - 1.0).abs() <= 0.2));
+        assert_eq!(json["layout"]["xaxis"]["ticktext"], json!(["control", "treated"]));
+
+        // Jitter is deterministic
+        let again = plot_strip(&groups, 0.5, true, "Strip", "Group", "Value").unwrap();
+        assert_eq!(plot.to_json(), again.to_json());
+    }
+
+    #[test]
+    fn test_plot_cdf_compare() {
+        let old = vec![0.1, 0.2, 0.3, 0.4];
+        let new = vec![0.3, 0.4, 0.5, 0.6];
+
+        let plot = plot_cdf_compare(("v1", &old), ("v2", &new), "Score distributions", "Score").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["y"], json!([0.25, 0.5, 0.75, 1.0]));
+        assert_eq!(json["data"][2]["y"], json!([0.5, 0.0]));
+        assert!(json["layout"]["annotations"][0]["text"].as_str().unwrap().starts_with("KS D = 0.5000"));
+    }
+
+    #[test]
+    fn test_plot_roc_and_precision_recall() {
+        let scores = [0.9, 0.8, 0.7, 0.6];
+        let labels = [1, 1, -1, -1];
+
+        let json = to_json(&plot_roc(&scores, &labels, "ROC").unwrap());
+        assert_eq!(json["data"][0]["name"], "ROC (AUC = 1.000)");
+        assert_eq!(json["data"][0]["x"], json!([0.0, 0.0, 0.0, 0.5, 1.0]));
+        assert_eq!(json["data"][0]["text"][1], "Threshold: 0.9000");
+
+        let json = to_json(&plot_precision_recall(&scores, &labels, "PR").unwrap());
+        assert_eq!(json["data"][0]["name"], "Precision-recall (AP = 1.000)");
+        assert_eq!(json["data"][1]["y"], json!([0.5, 0.5]));
+
+        assert!(matches!(plot_roc(&scores, &[1, 1, -1], "ROC"), Err(ReportError::LengthMismatch { .. })));
+        assert!(matches!(plot_roc(&scores, &[1, 1, 0, -1], "ROC"), Err(ReportError::InvalidLabel(0))));
+        assert!(matches!(plot_roc(&scores, &[1, 1, 1, 1], "ROC"), Err(ReportError::EmptyData(_))));
+        assert!(matches!(plot_pp(&scores, &[1, 1, 1, 1], "P-P"), Err(ReportError::EmptyData(_))));
+    }
+
+    #[test]
+    fn test_plot_bland_altman() {
+        let x = vec![10.0, 20.0, 30.0, 40.0];
+        let y = vec![9.0, 21.0, 28.0, 38.0];
+
+        let plot = plot_bland_altman(&x, &y, "Method comparison").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["x"], json!([9.5, 20.5, 29.0, 39.0]));
+        assert_eq!(json["data"][0]["y"], json!([1.0, -1.0, 2.0, 2.0]));
+        assert_eq!(json["data"][1]["name"], "Bias = 1.000");
+        assert_eq!(json["data"][1]["x"], json!([9.5, 39.0]));
+
+        let plot = plot_ma(&[4.0, 8.0, 0.0], &[2.0, 8.0, 1.0], "Replicates").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["y"], json!([1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_plot_forest() {
+        let plot = plot_forest(&[0.5, -0.2], &[0.1, -0.6], &[0.9, 0.3], ["Protein A", "Protein B"], 0.0, "Fold changes", "log2 FC").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["y"], json!(["Protein B", "Protein A"]));
+        assert_eq!(json["data"][0]["error_x"]["arrayminus"], json!([0.4, 0.4]));
+        assert_eq!(json["layout"]["shapes"][0]["x0"], json!(0.0));
+        assert!(plot_forest(&[0.5], &[0.1], &[], ["A"], 0.0, "", "").is_err());
+    }
+
+    #[test]
+    fn test_plot_lines() {
+        let x = [0.0, 1.0, 2.0];
+        let y = vec![vec![1.0, 2.0, 3.0], vec![3.0, 2.0, 1.0]];
+        let bands = [Some(ErrorBand::symmetric(&y[0], &[0.5, 0.5, 1.0])), None];
+        let plot = plot_lines(&x, &y, ["Train", "Validation"], &bands, "Learning curve", "Epoch", "Loss").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"].as_array().unwrap().len(), 4);
+        assert_eq!(json["data"][0]["y"], json!([0.5, 1.5, 2.0]));
+        assert_eq!(json["data"][1]["fill"], "tonexty");
+        assert_eq!(json["data"][1]["fillcolor"], "rgba(31, 119, 180, 0.2)");
+        assert_eq!(json["data"][2]["name"], "Train");
+        assert_eq!(json["data"][3]["line"]["color"], PALETTE[1]);
+        assert!(plot_lines(&x, &y, ["Train", "Validation"], &bands[..1], "", "", "").is_err());
+        assert!(plot_lines(&x[..2], &y, ["Train", "Validation"], &[], "", "", "").is_err());
+    }
+
+    #[test]
+    fn test_plot_upset() {
+        let set = |elements: &[&str]| elements.iter().map(|e| e.to_string()).collect::<HashSet<String>>();
+        let sets = HashMap::from([
+            ("Run B".to_string(), set(&["c", "d", "e"])),
+            ("Run A".to_string(), set(&["a", "b", "c", "d"])),
+            ("Run C".to_string(), set(&["d", "f"])),
+        ]);
+        let plot = plot_upset(&sets, "Shared identifications").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["y"], json!([2, 1, 1, 1, 1]));
+        assert_eq!(json["data"][1]["x"], json!([4, 3, 2]));
+        assert_eq!(json["layout"]["yaxis2"]["ticktext"], json!(["Run A", "Run B", "Run C"]));
+        let names: Vec<&str> = (3..8).map(|i| json["data"][i]["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["Run A (2)", "Run B (1)", "Run C (1)", "Run A ∩ Run B (1)", "Run A ∩ Run B ∩ Run C (1)"]);
+        assert_eq!(json["data"][7]["y"], json!([2.0, 1.0, 0.0]));
+        assert!(plot_upset(&HashMap::from([("Empty".to_string(), HashSet::new())]), "").is_err());
+    }
+
+    #[test]
+    fn test_plot_kaplan_meier() {
+        let times = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 3.0, 5.0, 7.0, 8.0, 9.0, 10.0];
+        let events = [true, true, false, true, true, false, false, true, true, true, false, true];
+        let groups = ["A", "A", "A", "A", "A", "A", "B", "B", "B", "B", "B", "B"];
+
+        let plot = plot_kaplan_meier(&times, &events, &groups, true, "Survival", "Months").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["name"], "A (n=6)");
+        assert_eq!(json["data"][0]["x"], json!([0.0, 1.0, 2.0, 4.0, 5.0, 6.0]));
+        assert_eq!(json["data"][0]["line"]["shape"], "hv");
+        assert_eq!(json["data"][1]["x"], json!([3.0, 6.0]));
+        assert_eq!(json["layout"]["xaxis"]["tickvals"], json!([0.0, 2.0, 4.0, 6.0, 8.0, 10.0]));
+
+        let annotations = json["layout"]["annotations"].as_array().unwrap();
+        let at_risk: Vec<&str> = annotations.iter().filter(|a| a["xref"] == "x").map(|a| a["text"].as_str().unwrap()).collect();
+        assert_eq!(at_risk, vec!["6", "5", "3", "1", "0", "0", "6", "6", "5", "4", "3", "1"]);
+        assert_eq!(annotations.last().unwrap()["text"], "Log-rank p = 0.043");
+        assert!(plot_kaplan_meier(&times, &events[1..], &groups, false, "", "").is_err());
+    }
+
+    #[test]
+    fn test_plot_calibration_and_score_rank() {
+        let probs = [0.05, 0.15, 0.75, 1.0, 0.875, 1.5];
+        let labels = [-1, 1, 1, 1, -1, 1];
+
+        let plot = plot_calibration(&probs, &labels, 2, "Calibration").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["x"], json!([0.1, 0.875]));
+        assert_eq!(json["data"][0]["y"], json!([0.5, 2.0 / 3.0]));
+        assert_eq!(json["data"][0]["name"], "Calibration (ECE = 0.285)");
+        assert_eq!(json["data"][2]["marker"]["color"], TARGET_COLOR);
+        assert!(plot_calibration(&probs, &labels, 0, "").is_err());
+
+        let plot = plot_score_rank(&[1.0, 3.0, 2.0, f64::NAN], &[1, 1, -1, 1], "Scores").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["x"], json!([1, 3]));
+        assert_eq!(json["data"][0]["y"], json!([3.0, 1.0]));
+        assert_eq!(json["data"][1]["x"], json!([2]));
+        assert_eq!(json["data"][1]["marker"]["color"], DECOY_COLOR);
+    }
+
+    #[test]
+    fn test_plot_dose_response() {
+        let conc = [0.01, 0.03, 0.1, 0.3, 1.0, 3.0, 10.0, 30.0, 100.0];
+        let response = [2.3, 2.6, 5.2, 12.0, 40.0, 70.4, 90.1, 96.2, 97.5];
+
+        let plot = plot_dose_response(&conc, &response, "Dose response", "Concentration (nM)", "Signal").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][1]["fill"], "tonexty");
+        assert!(json["data"][2]["name"].as_str().unwrap().starts_with("4PL fit (EC50 = "));
+        assert_eq!(json["data"][3]["x"], json!(conc));
+        assert!(json["data"][4]["name"].as_str().unwrap().starts_with("EC50 = "));
+        assert_eq!(json["layout"]["xaxis"]["type"], "log");
+        assert!(plot_dose_response(&conc[..3], &response[..3], "", "", "").is_err());
+    }
+
+    #[test]
+    fn test_plot_score_histogram_bins() {
+        let scores = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let labels = vec![1, 1, 1, 1, -1, -1, -1, -1];
+
+        let plot = plot_score_histogram_with_bins(&scores, &labels, BinRule::Width(2.0), "Scores", "Score").unwrap();
+        let json = to_json(&plot);
+
+        assert_eq!(json["data"][0]["xbins"], json!({ "start": 0.0, "end": 9.0, "size": 2.0 }));
+        assert_eq!(json["data"][0]["xbins"], json["data"][1]["xbins"]);
+    }
+
+    #[test]
+    fn test_plot_score_histogram_opts() {
+        let scores = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let labels = vec![1, 1, 1, 1, -1, -1, -1, -1];
+
+        let json = to_json(&plot_score_histogram(&scores, &labels, "Scores", "Score").unwrap());
+        assert_eq!(json["layout"]["yaxis"]["title"]["text"], "Count");
+        assert!(json["data"][0].get("histnorm").is_none() && json["layout"].get("barmode").is_none());
+
+        let opts = HistogramOptions {
+            bins: BinRule::Count(4),
+            normalization: HistogramNormalization::Density,
+            mode: HistogramMode::Overlay,
+            cumulative: true,
+            ..Default::default()
+        };
+        let json = to_json(&plot_score_histogram_with_opts(&scores, &labels, opts, "Scores", "Score").unwrap());
+        assert_eq!(json["data"][1]["xbins"]["size"], 1.75);
+        assert_eq!(json["data"][1]["histnorm"], "probability density");
+        assert_eq!((json["data"][0]["opacity"].as_f64(), json["data"][0]["cumulative"]["enabled"].as_bool()), (Some(0.6), Some(true)));
+        assert_eq!(json["layout"]["barmode"], "overlay");
+        assert_eq!(json["layout"]["yaxis"]["title"]["text"], "Cumulative density");
+    }
+
+    #[test]
+    fn test_plot_binned_histogram() {
+        let mut histogram = BinnedHistogram::empty("Intensity", vec![0.0, 1.0, 2.0, 4.0]).unwrap();
+        histogram.accumulate(&[0.5, 1.5, 4.0, 9.0, f64::NAN], None).unwrap();
+        histogram.accumulate(&[3.0, 0.1], Some(&[2.5, 1.0])).unwrap();
+        assert_eq!(histogram.counts, vec![2.0, 1.0, 3.5]);
+        assert!(histogram.accumulate(&[1.0], Some(&[])).is_err());
+        assert!(BinnedHistogram::new("Intensity", vec![0.0, 2.0, 1.0], vec![1.0, 1.0]).is_err());
+        assert!(matches!(BinnedHistogram::empty("Intensity", vec![1.0]), Err(ReportError::InvalidArgument(_))));
+        let mut single_edge = BinnedHistogram { name: "Intensity".to_string(), edges: vec![1.0], counts: Vec::new() };
+        assert!(single_edge.accumulate(&[1.0], None).is_ok());
+
+        let plot = plot_binned_histogram(&[histogram], "Intensities", "Intensity", "Count").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["x"], json!([0.5, 1.5, 3.0]));
+        assert_eq!(json["data"][0]["width"], json!([1.0, 1.0, 2.0]));
+        assert_eq!(json["layout"]["barmode"], "overlay");
+    }
+
+    #[test]
+    fn test_plot_scatter_categorical_x() {
+        let samples = vec![vec!["1", "2", "3"], vec!["1", "2", "3"]];
+        let metric = vec![vec![0.9, 0.8, 0.95], vec![0.7, 0.75, 0.8]];
+        let plot = plot_scatter(&samples, &metric, ["Run A", "Run B"], "Per-sample metric", "Sample", "Score").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["x"], json!(["1", "2", "3"]));
+        assert_eq!(json["layout"]["xaxis"]["type"], "category");
+        assert!(json["layout"]["yaxis"]["type"].is_null());
+    }
+
+    #[test]
+    fn test_plot_handle() {
+        let plot = plot_boxplot(&[vec![1.0, 2.0], vec![3.0, 4.0]], ["a", "b"], "Box", "File", "Score").unwrap();
+        let mut handle = PlotHandle::from(plot);
+        assert_eq!(handle.trace_names(), vec!["a", "b"]);
+
+        handle.trace_named("b").unwrap().name("Run B").color("#ff0000").visible(false);
+        assert!(handle.trace(2).is_none());
+        let plot = Plot::from(handle);
+
+        let json = to_json(&plot);
+        assert_eq!(json["data"][1]["name"], "Run B");
+        assert_eq!(json["data"][1]["marker"]["color"], "#ff0000");
+        assert_eq!(json["data"][1]["visible"], "legendonly");
+        assert_eq!(json["layout"]["title"]["text"], "Box");
+    }
+
+    #[test]
+    fn test_add_watermark() {
+        let mut plot = plot_ma(&[4.0, 8.0], &[2.0, 8.0], "Replicates").unwrap();
+        add_watermark(&mut plot, "Redeem v1.0");
+        let json = to_json(&plot);
+        let annotations = json["layout"]["annotations"].as_array().unwrap();
+        assert_eq!(annotations.last().unwrap()["text"], "Redeem v1.0");
+        assert_eq!(annotations.last().unwrap()["xanchor"], "right");
+    }
+
+    #[test]
+    fn test_plot_compose() {
+        let scores: Vec<f64> = (0..100).map(|i| (i % 10) as f64).collect();
+        let labels: Vec<i32> = (0..100).map(|i| if i % 2 == 0 { 1 } else { -1 }).collect();
+        let histogram = plot_score_histogram(&scores, &labels, "Scores", "Score").unwrap();
+        let mut density = Plot::new();
+        density.add_trace(Scatter::new(vec![0.0, 5.0, 9.0], vec![0.05, 0.1, 0.05]).mode(Mode::Lines));
+        density.set_layout(Layout::new().y_axis(Axis::new().title("Density")));
+
+        let plot = PlotCompose::new().overlay(histogram).overlay_secondary(density).title("Scores with density").build().unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"].as_array().unwrap().len(), 3);
+        assert_eq!(json["data"][2]["yaxis"], "y2");
+        assert_eq!(json["layout"]["yaxis2"]["title"]["text"], "Density");
+        assert_eq!(json["layout"]["title"]["text"], "Scores with density");
+
+        assert!(PlotCompose::new().build().is_err());
+        assert!(PlotCompose::new().overlay(Plot::new()).layout(LayoutSource::Index(1)).build().is_err());
+    }
+
+    #[test]
+    fn test_numeric_inputs() {
+        let intensities: Vec<f32> = vec![1.5, 2.5, 4.0];
+        let reference: Vec<f32> = vec![1.0, 2.5, 4.0];
+        let plot = plot_bland_altman(&intensities, &reference, "f32 intensities").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["y"][0], 0.5);
+
+        let counts: Vec<Vec<u32>> = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert!(plot_boxplot(&counts, ["a", "b"], "Counts", "Sample", "Count").is_ok());
+        assert!(plot_score_histogram(&[1_i64, 2, 3, 4], &[1, 1, -1, -1], "Scores", "Score").is_ok());
+    }
+
+    #[test]
+    fn test_plot_group_summaries() {
+        let summaries = crate::aggregate::group_by(&["a", "a", "b"], &[1.0, 3.0, 5.0]).unwrap().data;
+        let plot = plot_group_summaries(&summaries, "Per group", "Group", "Value").unwrap();
+        let json = to_json(&plot);
+        assert_eq!(json["data"][0]["x"], json!(["a", "b"]));
+        assert_eq!(json["data"][0]["median"], json!([2.0, 5.0]));
+    }
+
+    #[test]
+    fn test_box_plot_builder_from_stream() {
+        let builder = BoxPlotBuilder::from_stream("a", (0..=1000).map(|i| i as f64))
+            .add_stream("b", (0..=1000).map(|i| (i * 2) as f64))
+            .violin(true);
+        assert_eq!(builder.aggregation().points(), 2002);
+
+        let plot = builder.build("Streams", "Group", "Value").unwrap();
+        let json = to_json(&plot);
+        let traces = json["data"].as_array().unwrap();
+        assert_eq!(traces.len(), 4);
+        assert_eq!(traces[1]["type"], "box");
+        assert!((traces[1]["median"][0].as_f64().unwrap() - 500.0).abs() < 5.0);
+        assert_eq!(json["layout"]["xaxis"]["ticktext"], json!(["a", "b"]));
+    }
+}
@@ -0,0 +1,130 @@
+//! Automatic alternative text for plots.
+//!
+//! Every plot added to a section is described in a visually hidden paragraph, linked to its
+//! container with `aria-describedby`, so screen readers and search can tell what it shows without
+//! manual effort. The description is built from the figure's Plotly JSON: the plot type and
+//! title, the traces, the axes with their ranges or categories, and the number of points, e.g.
+//!
+//! > Scatter plot "Score distribution" with 2 traces (Targets, Decoys). x axis "Score": -3.2 to
+//! > 8.1. y axis "Count": 0 to 412. 1200 points.
+
+use plotly::Plot;
+use serde_json::Value;
+
+use super::fallback::{format_tick, plain_title};
+
+/// The kind of plot a trace is drawn as
+fn trace_kind(trace: &Value) -> String {
+    match trace["type"].as_str().unwrap_or("scatter") {
+        "scatter" | "scattergl" => match trace["mode"].as_str() {
+            Some(mode) if mode.contains("lines") && !mode.contains("markers") => "line plot".to_string(),
+            _ => "scatter plot".to_string(),
+        },
+        "bar" => "bar chart".to_string(),
+        "pie" => "pie chart".to_string(),
+        kind @ ("histogram" | "heatmap") => kind.to_string(),
+        "box" => "box plot".to_string(),
+        "scatterpolar" => "polar plot".to_string(),
+        kind => format!("{} plot", kind),
+    }
+}
+
+/// The values of an axis across all traces: their numeric range, or their number of categories
+fn axis_extent(traces: &[Value], axis: &str) -> Option<String> {
+    let values: Vec<&Value> = traces.iter().filter_map(|trace| trace[axis].as_array()).flatten().collect();
+    if values.is_empty() {
+        return None;
+    }
+    let numbers: Vec<f64> = values.iter().filter_map(|v| v.as_f64()).filter(|v| v.is_finite()).collect();
+    if numbers.is_empty() {
+        let mut categories: Vec<String> = values.iter().map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string)).collect();
+        categories.sort();
+        categories.dedup();
+        return Some(format!("{} categories", categories.len()));
+    }
+    let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some(format!("{} to {}", format_tick(min), format_tick(max)))
+}
+
+/// Describe a figure from its Plotly JSON, see the [module documentation](self)
+pub(crate) fn describe(figure: &Value) -> String {
+    let traces: &[Value] = figure["data"].as_array().map_or(&[], Vec::as_slice);
+    let layout = &figure["layout"];
+
+    let mut kinds: Vec<String> = Vec::new();
+    for kind in traces.iter().map(trace_kind) {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    let mut kind = match kinds.len() {
+        0 => "empty plot".to_string(),
+        _ => kinds.join(" and "),
+    };
+    kind[..1].make_ascii_uppercase();
+
+    let mut description = kind;
+    if let Some(title) = plain_title(&layout["title"]).filter(|title| !title.is_empty()) {
+        description.push_str(&format!(" \"{}\"", title));
+    }
+    let names: Vec<&str> = traces.iter().filter_map(|trace| trace["name"].as_str()).filter(|name| !name.is_empty()).collect();
+    match traces.len() {
+        0 | 1 => {}
+        n if names.len() == n && n <= 5 => description.push_str(&format!(" with {} traces ({})", n, names.join(", "))),
+        n => description.push_str(&format!(" with {} traces", n)),
+    }
+    description.push('.');
+
+    for axis in ["x", "y"] {
+        let title = plain_title(&layout[format!("{}axis", axis)]["title"]).filter(|title| !title.is_empty());
+        let extent = axis_extent(traces, axis);
+        match (title, extent) {
+            (Some(title), Some(extent)) => description.push_str(&format!(" {} axis \"{}\": {}.", axis, title, extent)),
+            (Some(title), None) => description.push_str(&format!(" {} axis \"{}\".", axis, title)),
+            (None, Some(extent)) => description.push_str(&format!(" {} axis: {}.", axis, extent)),
+            (None, None) => {}
+        }
+    }
+
+    let points: usize = traces
+        .iter()
+        .map(|trace| {
+            let len = |key: &str| trace[key].as_array().map_or(0, Vec::len);
+            match trace["type"].as_str() {
+                Some("heatmap") => trace["z"].as_array().map_or(0, |rows| rows.iter().filter_map(Value::as_array).map(Vec::len).sum()),
+                Some("pie") => len("values"),
+                _ => len("x").max(len("y")),
+            }
+        })
+        .sum();
+    if points > 0 {
+        description.push_str(&format!(" {} {}.", points, if points == 1 { "point" } else { "points" }));
+    }
+    description
+}
+
+/// The alternative text of a plot, as attached to its container when it is added to a section,
+/// see the [module documentation](self).
+pub fn alt_text(plot: &Plot) -> String {
+    describe(&super::to_json(plot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alt_text() {
+        let plot = super::super::plot_score_histogram(&[1.5, 4.0, 8.1, -3.2, 0.4], &[1, 1, 1, -1, -1], "Score distribution", "Score").unwrap();
+        let text = alt_text(&plot);
+        assert!(text.starts_with("Histogram \"Score distribution\" with 2 traces ("), "{}", text);
+        assert!(text.contains("x axis \"Score\": -3.2 to 8.1.") && text.ends_with(" 5 points."), "{}", text);
+
+        let mut plot = Plot::new();
+        plot.add_trace(plotly::Bar::new(vec!["A", "B", "A"], vec![1, 2, 3]));
+        plot.add_trace(plotly::Scatter::new(vec!["A", "B"], vec![2, 2]).mode(plotly::common::Mode::Lines));
+        assert_eq!(alt_text(&plot), "Bar chart and line plot with 2 traces. x axis: 2 categories. y axis: 1 to 3. 5 points.");
+        assert_eq!(alt_text(&Plot::new()), "Empty plot.");
+    }
+}
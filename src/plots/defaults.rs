@@ -0,0 +1,151 @@
+//! Crate-wide default styling applied to every plot built by the helpers in [`crate::plots`].
+//!
+//! The defaults are attached to each figure as a Plotly layout template, so anything a helper or
+//! the caller sets explicitly on a plot (e.g. through
+//! [`PlotHandle::layout_mut`](crate::plots::PlotHandle::layout_mut)) still takes precedence.
+//!
+//! ```rust,ignore
+//! use report_builder::plots::defaults::{set_plot_defaults, LegendPlacement, PlotDefaults};
+//!
+//! set_plot_defaults(PlotDefaults {
+//!     margin: Some([60, 30, 60, 60]),
+//!     title_font_size: Some(20),
+//!     legend: Some(LegendPlacement::Bottom),
+//!     show_grid: Some(false),
+//!     ..Default::default()
+//! });
+//! ```
+
+use std::sync::{OnceLock, RwLock};
+
+use plotly::common::{Anchor, Font, Orientation, Title};
+use plotly::layout::{Axis, Layout, LayoutTemplate, Legend, Margin, Template};
+
+/// Where the legend is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegendPlacement {
+    /// To the right of the plot area (Plotly's default)
+    Right,
+    /// Horizontally, above the plot area
+    Top,
+    /// Horizontally, below the plot area
+    Bottom,
+    /// No legend
+    Hidden,
+}
+
+/// Default layout settings applied to all plots. Settings left as `None` keep Plotly's defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlotDefaults {
+    /// The left, right, top and bottom margins, in pixels
+    pub margin: Option<[usize; 4]>,
+    pub font_family: Option<String>,
+    pub font_size: Option<usize>,
+    pub title_font_size: Option<usize>,
+    /// The horizontal position of the title, from 0 (left) to 1 (right)
+    pub title_x: Option<f64>,
+    pub legend: Option<LegendPlacement>,
+    /// Whether to draw grid lines on the x and y axes
+    pub show_grid: Option<bool>,
+}
+
+impl PlotDefaults {
+    /// The defaults as a Plotly layout template.
+    pub fn template(&self) -> Template {
+        let mut layout = LayoutTemplate::new();
+
+        if let Some([left, right, top, bottom]) = self.margin {
+            layout = layout.margin(Margin::new().left(left).right(right).top(top).bottom(bottom));
+        }
+        if self.font_family.is_some() || self.font_size.is_some() {
+            let mut font = Font::new();
+            if let Some(family) = &self.font_family {
+                font = font.family(family.as_str());
+            }
+            if let Some(size) = self.font_size {
+                font = font.size(size);
+            }
+            layout = layout.font(font);
+        }
+        if self.title_font_size.is_some() || self.title_x.is_some() {
+            let mut title = Title::new();
+            if let Some(size) = self.title_font_size {
+                title = title.font(Font::new().size(size));
+            }
+            if let Some(x) = self.title_x {
+                title = title.x(x);
+            }
+            layout = layout.title(title);
+        }
+        match self.legend {
+            Some(LegendPlacement::Right) | None => {}
+            Some(LegendPlacement::Top) => {
+                layout = layout.legend(
+                    Legend::new().orientation(Orientation::Horizontal).x(0.0).y(1.02).y_anchor(Anchor::Bottom),
+                );
+            }
+            Some(LegendPlacement::Bottom) => {
+                layout = layout.legend(
+                    Legend::new().orientation(Orientation::Horizontal).x(0.0).y(-0.2).y_anchor(Anchor::Top),
+                );
+            }
+            Some(LegendPlacement::Hidden) => layout = layout.show_legend(false),
+        }
+        if let Some(show_grid) = self.show_grid {
+            layout = layout
+                .x_axis(Axis::new().show_grid(show_grid))
+                .y_axis(Axis::new().show_grid(show_grid));
+        }
+
+        Template::new().layout(layout)
+    }
+}
+
+fn defaults() -> &'static RwLock<PlotDefaults> {
+    static DEFAULTS: OnceLock<RwLock<PlotDefaults>> = OnceLock::new();
+    DEFAULTS.get_or_init(Default::default)
+}
+
+/// Sets the defaults applied to all plots built afterwards.
+pub fn set_plot_defaults(plot_defaults: PlotDefaults) {
+    *defaults().write().unwrap() = plot_defaults;
+}
+
+/// Returns the defaults currently applied to new plots.
+pub fn plot_defaults() -> PlotDefaults {
+    defaults().read().unwrap().clone()
+}
+
+/// A new layout carrying the current defaults, the starting point of every helper's layout.
+pub(crate) fn base_layout() -> Layout {
+    let plot_defaults = plot_defaults();
+    if plot_defaults == PlotDefaults::default() {
+        Layout::new()
+    } else {
+        Layout::new().template(plot_defaults.template())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_template() {
+        let plot_defaults = PlotDefaults {
+            margin: Some([10, 20, 30, 40]),
+            title_font_size: Some(22),
+            legend: Some(LegendPlacement::Hidden),
+            show_grid: Some(false),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(plot_defaults.template()).unwrap();
+        assert_eq!(json["layout"]["margin"], serde_json::json!({ "l": 10, "r": 20, "t": 30, "b": 40 }));
+        assert_eq!(json["layout"]["title"]["font"]["size"], 22);
+        assert_eq!(json["layout"]["showlegend"], false);
+        assert_eq!(json["layout"]["yaxis"]["showgrid"], false);
+
+        let layout = serde_json::to_value(Layout::new().template(plot_defaults.template())).unwrap();
+        assert_eq!(layout["template"]["layout"]["margin"]["t"], 30);
+    }
+}
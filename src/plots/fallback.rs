@@ -0,0 +1,248 @@
+//! Static SVG snapshots of plots, shown when JavaScript is disabled or Plotly fails to load.
+//!
+//! The snapshot is drawn directly from the figure's Plotly JSON, without a browser: scatter,
+//! line, bar and histogram traces with numeric data are drawn over linear axes, and other traces
+//! (e.g. box plots or polar charts) are left out.
+
+use maud::{html, Markup};
+use serde_json::Value;
+
+use super::PALETTE;
+
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const TICKS: usize = 5;
+
+/// How a trace is drawn
+enum Shape {
+    Lines,
+    Markers,
+    /// Bars centered on each x, with the given widths
+    Bars(Vec<f64>),
+}
+
+/// A trace reduced to the points to draw
+struct Series {
+    name: String,
+    color: String,
+    shape: Shape,
+    x: Vec<f64>,
+    y: Vec<f64>,
+}
+
+fn numbers(values: &Value) -> Option<Vec<f64>> {
+    values.as_array()?.iter().map(Value::as_f64).collect()
+}
+
+/// A title as plain text, with Plotly's line breaks replaced by spaces
+pub(super) fn plain_title(value: &Value) -> Option<String> {
+    let title = value.as_str().or_else(|| value["text"].as_str())?;
+    Some(title.replace("<br>", " "))
+}
+
+/// Bin the values of a histogram trace, with its explicit bins if any
+fn histogram(values: &[f64], bins: &Value) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let min = finite.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = finite.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if finite.is_empty() {
+        return (Vec::new(), Vec::new(), Vec::new());
+    }
+    let start = bins["start"].as_f64().unwrap_or(min);
+    let size = bins["size"]
+        .as_f64()
+        .filter(|size| *size > 0.0)
+        .unwrap_or(if max > min { (max - min) / 30.0 } else { 1.0 });
+    let n_bins = (((max - start) / size).floor() as usize + 1).min(10_000);
+
+    let mut counts = vec![0.0; n_bins];
+    for value in finite.iter().filter(|v| **v >= start) {
+        counts[(((value - start) / size) as usize).min(n_bins - 1)] += 1.0;
+    }
+    let centers = (0..n_bins).map(|i| start + (i as f64 + 0.5) * size).collect();
+    (centers, counts, vec![size; n_bins])
+}
+
+/// The drawable traces of a figure
+fn series(figure: &Value) -> Vec<Series> {
+    let Some(traces) = figure["data"].as_array() else {
+        return Vec::new();
+    };
+    traces
+        .iter()
+        .enumerate()
+        .filter(|(_, trace)| trace["visible"] != "legendonly" && trace["visible"] != false)
+        .filter_map(|(i, trace)| {
+            let kind = trace["type"].as_str().unwrap_or("scatter");
+            let (x, y, shape) = match kind {
+                "scatter" | "scattergl" => {
+                    let y = numbers(&trace["y"])?;
+                    let x = numbers(&trace["x"]).unwrap_or_else(|| (0..y.len()).map(|i| i as f64).collect());
+                    let markers_only = trace["mode"].as_str().is_some_and(|mode| !mode.contains("lines"));
+                    (x, y, if markers_only { Shape::Markers } else { Shape::Lines })
+                }
+                "bar" => {
+                    let (x, y) = (numbers(&trace["x"])?, numbers(&trace["y"])?);
+                    let widths = numbers(&trace["width"]).unwrap_or_else(|| {
+                        let spacing = x.windows(2).map(|w| (w[1] - w[0]).abs()).fold(f64::INFINITY, f64::min);
+                        vec![if spacing.is_finite() { spacing * 0.8 } else { 0.8 }; x.len()]
+                    });
+                    (x, y, Shape::Bars(widths))
+                }
+                "histogram" => {
+                    let (x, y, widths) = histogram(&numbers(&trace["x"])?, &trace["xbins"]);
+                    (x, y, Shape::Bars(widths))
+                }
+                _ => return None,
+            };
+            let color = [&trace["marker"]["color"], &trace["line"]["color"]]
+                .into_iter()
+                .find_map(Value::as_str)
+                .unwrap_or(PALETTE[i % PALETTE.len()])
+                .to_string();
+            let name = trace["name"].as_str().unwrap_or_default().to_string();
+            Some(Series { name, color, shape, x, y })
+        })
+        .collect()
+}
+
+/// A compact tick label
+pub(super) fn format_tick(value: f64) -> String {
+    if value != 0.0 && (value.abs() >= 1e5 || value.abs() < 1e-3) {
+        format!("{:.1e}", value)
+    } else {
+        let text = format!("{:.3}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// Render a static SVG snapshot of a figure
+///
+/// # Arguments
+///
+/// * `figure` - The figure's Plotly JSON, see [`to_json`](super::to_json)
+/// * `width` - The width of the image in pixels
+/// * `height` - The height of the image in pixels
+pub fn render_svg(figure: &Value, width: u32, height: u32) -> String {
+    let (width, height) = (width as f64, height as f64);
+    let series = series(figure);
+    let layout = &figure["layout"];
+
+    let finite = |values: &mut dyn Iterator<Item = f64>| -> (f64, f64) {
+        values
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)))
+    };
+    let (mut x_min, mut x_max) = finite(&mut series.iter().flat_map(|s| {
+        let widths: &[f64] = if let Shape::Bars(widths) = &s.shape { widths } else { &[] };
+        s.x.iter().enumerate().flat_map(move |(i, x)| {
+            let half = widths.get(i).map_or(0.0, |w| w / 2.0);
+            [x - half, x + half]
+        })
+    }));
+    let (mut y_min, mut y_max) = finite(&mut series.iter().flat_map(|s| s.y.iter().copied()));
+    if series.iter().any(|s| matches!(s.shape, Shape::Bars(_))) {
+        y_min = y_min.min(0.0);
+    }
+    let has_data = x_min <= x_max && y_min <= y_max;
+    if has_data {
+        let (x_pad, y_pad) = (((x_max - x_min) * 0.05).max(0.5), ((y_max - y_min) * 0.05).max(0.5));
+        x_min -= x_pad;
+        x_max += x_pad;
+        y_min = if y_min == 0.0 { 0.0 } else { y_min - y_pad };
+        y_max += y_pad;
+    }
+
+    let (left, right, top, bottom) = (MARGIN_LEFT, width - MARGIN_RIGHT, MARGIN_TOP, height - MARGIN_BOTTOM);
+    let sx = |x: f64| left + (x - x_min) / (x_max - x_min) * (right - left);
+    let sy = |y: f64| bottom - (y - y_min) / (y_max - y_min) * (bottom - top);
+    let ticks = |min: f64, max: f64| (0..=TICKS).map(move |i| min + (max - min) * i as f64 / TICKS as f64);
+
+    let svg: Markup = html! {
+        svg xmlns="http://www.w3.org/2000/svg" width=(width) height=(height) viewBox=(format!("0 0 {} {}", width, height))
+            font-family="Arial, sans-serif" font-size="12" {
+            rect width=(width) height=(height) fill="white" {}
+            @if let Some(title) = plain_title(&layout["title"]) {
+                text x=(width / 2.0) y="24" text-anchor="middle" font-size="16" { (title) }
+            }
+            @if has_data {
+                line x1=(left) y1=(bottom) x2=(right) y2=(bottom) stroke="#444" {}
+                line x1=(left) y1=(top) x2=(left) y2=(bottom) stroke="#444" {}
+                @for x in ticks(x_min, x_max) {
+                    text x=(sx(x)) y=(bottom + 16.0) text-anchor="middle" { (format_tick(x)) }
+                }
+                @for y in ticks(y_min, y_max) {
+                    line x1=(left) y1=(sy(y)) x2=(right) y2=(sy(y)) stroke="#eee" {}
+                    text x=(left - 6.0) y=(sy(y) + 4.0) text-anchor="end" { (format_tick(y)) }
+                }
+                @for s in &series {
+                    @let points = s.x.iter().zip(&s.y).filter(|(x, y)| x.is_finite() && y.is_finite());
+                    @match &s.shape {
+                        Shape::Lines => {
+                            polyline fill="none" stroke=(s.color) stroke-width="2"
+                                points=(points.map(|(x, y)| format!("{:.1},{:.1}", sx(*x), sy(*y))).collect::<Vec<_>>().join(" ")) {}
+                        }
+                        Shape::Markers => {
+                            @for (x, y) in points {
+                                circle cx=(format!("{:.1}", sx(*x))) cy=(format!("{:.1}", sy(*y))) r="3" fill=(s.color) fill-opacity="0.7" {}
+                            }
+                        }
+                        Shape::Bars(widths) => {
+                            @for ((x, y), w) in points.zip(widths) {
+                                rect x=(format!("{:.1}", sx(x - w / 2.0))) y=(format!("{:.1}", sy(y.max(0.0))))
+                                    width=(format!("{:.1}", sx(x + w / 2.0) - sx(x - w / 2.0)))
+                                    height=(format!("{:.1}", (sy(0.0_f64.max(y_min)) - sy(y.max(0.0))).abs()))
+                                    fill=(s.color) fill-opacity="0.6" {}
+                            }
+                        }
+                    }
+                }
+                @for (i, s) in series.iter().filter(|s| !s.name.is_empty()).enumerate() {
+                    rect x=(right - 150.0) y=(top + 4.0 + 16.0 * i as f64) width="10" height="10" fill=(s.color) {}
+                    text x=(right - 135.0) y=(top + 13.0 + 16.0 * i as f64) { (s.name) }
+                }
+            } @else {
+                text x=(width / 2.0) y=(height / 2.0) text-anchor="middle" fill="#666" {
+                    "Interactive plot: enable JavaScript to view"
+                }
+            }
+            @if let Some(x_title) = plain_title(&layout["xaxis"]["title"]) {
+                text x=((left + right) / 2.0) y=(height - 12.0) text-anchor="middle" { (x_title) }
+            }
+            @if let Some(y_title) = plain_title(&layout["yaxis"]["title"]) {
+                text x="16" y=((top + bottom) / 2.0) text-anchor="middle"
+                    transform=(format!("rotate(-90 16 {})", (top + bottom) / 2.0)) { (y_title) }
+            }
+        }
+    };
+    svg.into_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_svg() {
+        let figure = json!({
+            "data": [
+                { "type": "scatter", "x": [0.0, 1.0, 2.0], "y": [1.0, 3.0, 2.0], "mode": "lines", "name": "Run <1>" },
+                { "type": "histogram", "x": [0.5, 0.7, 1.5], "xbins": { "start": 0.0, "end": 2.0, "size": 1.0 } },
+                { "type": "box", "y": [1.0, 2.0] },
+            ],
+            "layout": { "title": { "text": "Scores" }, "xaxis": { "title": { "text": "Score" } } },
+        });
+        let svg = render_svg(&figure, 400, 300);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="300""#));
+        assert!(svg.contains(">Scores</text>"));
+        assert!(svg.contains("<polyline"));
+        assert_eq!(svg.matches("fill-opacity=\"0.6\"").count(), 2);
+        assert!(svg.contains("Run &lt;1&gt;"));
+
+        let svg = render_svg(&json!({ "data": [{ "type": "box", "y": [1.0] }], "layout": {} }), 400, 300);
+        assert!(svg.contains("enable JavaScript"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Target-decoy FDR diagnostics: q-values against the number of accepted targets, the estimated
+//! FDR along the score threshold, and Storey's π₀ estimates across λ.
+//!
+//! Like [`plot_pp`](super::plot_pp), each plot is computed from the scores of all targets and
+//! decoys, higher scores indicating targets, and their labels (1 for targets, -1 for decoys):
+//!
+//! ```rust,ignore
+//! use report_builder::plots::fdr;
+//!
+//! section.add_plot(fdr::plot_qvalue_vs_hits(&scores, &labels, "Identifications")?);
+//! section.add_plot(fdr::plot_fdr_vs_score_threshold(&scores, &labels, "FDR")?);
+//! section.add_plot(fdr::plot_pi0_lambda(&scores, &labels, "π₀ estimation")?);
+//! ```
+
+use plotly::common::{DashType, Line, LineShape, Mode};
+use plotly::layout::Axis;
+use plotly::{Plot, Scatter};
+
+use super::{defaults, to_f64_vec, Numeric, PALETTE, TARGET_COLOR};
+use crate::error::ReportError;
+use crate::stats;
+
+/// The λ values π₀ is estimated at
+const LAMBDAS: [f64; 19] = [
+    0.0, 0.05, 0.1, 0.15, 0.2, 0.25, 0.3, 0.35, 0.4, 0.45, 0.5, 0.55, 0.6, 0.65, 0.7, 0.75, 0.8, 0.85, 0.9,
+];
+
+/// The estimated FDR and q-value at each distinct score threshold, from the highest score down
+struct FdrCurve {
+    thresholds: Vec<f64>,
+    /// The number of targets scoring at least the threshold
+    targets: Vec<usize>,
+    /// The number of decoys over the number of targets scoring at least the threshold
+    fdr: Vec<f64>,
+    /// The lowest FDR at which each threshold's targets are accepted
+    q_values: Vec<f64>,
+}
+
+fn fdr_curve<T: Numeric>(scores: &[T], labels: &[i32]) -> Result<FdrCurve, ReportError> {
+    ReportError::check_labels(scores.len(), labels)?;
+    let counts = stats::confusion_counts(&to_f64_vec(scores), labels);
+    if counts.last().map_or(true, |&(_, targets, decoys)| targets == 0 || decoys == 0) {
+        return Err(ReportError::EmptyData("both targets and decoys must have finite scores"));
+    }
+
+    let fdr: Vec<f64> = counts
+        .iter()
+        .map(|&(_, targets, decoys)| if targets == 0 { 1.0 } else { (decoys as f64 / targets as f64).min(1.0) })
+        .collect();
+    let mut q_values = fdr.clone();
+    for i in (0..q_values.len().saturating_sub(1)).rev() {
+        q_values[i] = q_values[i].min(q_values[i + 1]);
+    }
+    Ok(FdrCurve {
+        thresholds: counts.iter().map(|&(threshold, _, _)| threshold).collect(),
+        targets: counts.iter().map(|&(_, targets, _)| targets).collect(),
+        fdr,
+        q_values,
+    })
+}
+
+/// Storey's estimate of the proportion of null targets, π₀(λ), at each of [`LAMBDAS`], with the
+/// p-value of each target computed from the decoy score distribution
+fn pi0_estimates(scores: &[f64], labels: &[i32]) -> Vec<f64> {
+    let decoys = stats::sorted_finite(&scores.iter().zip(labels).filter(|(_, &l)| l == -1).map(|(&s, _)| s).collect::<Vec<_>>());
+    let p_values: Vec<f64> = scores
+        .iter()
+        .zip(labels)
+        .filter(|(score, &label)| label == 1 && score.is_finite())
+        .map(|(&score, _)| {
+            let at_least = decoys.len() - decoys.partition_point(|&decoy| decoy < score);
+            (at_least + 1) as f64 / (decoys.len() + 1) as f64
+        })
+        .collect();
+    LAMBDAS
+        .iter()
+        .map(|&lambda| p_values.iter().filter(|&&p| p > lambda).count() as f64 / (p_values.len() as f64 * (1.0 - lambda)))
+        .collect()
+}
+
+/// Generate the number of accepted targets at each q-value, with the 1% and 5% cutoffs marked
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the q-value curve
+pub fn plot_qvalue_vs_hits<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    let curve = fdr_curve(scores, labels)?;
+    let max_hits = *curve.targets.last().unwrap() as f64;
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(curve.q_values, curve.targets)
+            .name("Targets")
+            .mode(Mode::Lines)
+            .line(Line::new().shape(LineShape::Hv).color(TARGET_COLOR)),
+    );
+    for cutoff in [0.01, 0.05] {
+        plot.add_trace(
+            Scatter::new(vec![cutoff, cutoff], vec![0.0, max_hits])
+                .name(format!("q = {}", cutoff))
+                .mode(Mode::Lines)
+                .line(Line::new().color("gray").dash(DashType::Dash)),
+        );
+    }
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("q-value"))
+            .y_axis(Axis::new().title("Accepted targets")),
+    );
+
+    Ok(plot)
+}
+
+/// Generate the estimated FDR (decoys over targets) and the q-value at each score threshold
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the FDR and q-value curves
+pub fn plot_fdr_vs_score_threshold<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    let curve = fdr_curve(scores, labels)?;
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(curve.thresholds.clone(), curve.fdr)
+            .name("Estimated FDR")
+            .mode(Mode::Lines)
+            .line(Line::new().color(PALETTE[0])),
+    );
+    plot.add_trace(
+        Scatter::new(curve.thresholds, curve.q_values)
+            .name("q-value")
+            .mode(Mode::Lines)
+            .line(Line::new().color(PALETTE[1]).dash(DashType::Dash)),
+    );
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("Score threshold"))
+            .y_axis(Axis::new().title("FDR").range(vec![0.0, 1.05])),
+    );
+
+    Ok(plot)
+}
+
+/// Generate Storey's π₀ estimates across λ values, with the estimate at λ = 0.5 for reference.
+/// The p-value of each target is the fraction of decoys scoring at least as high.
+///
+/// # Arguments
+///
+/// * `scores` - The scores of all targets and decoys, higher scores indicating targets
+/// * `labels` - 1 for targets and -1 for decoys, one per score
+/// * `title` - The title of the plot
+///
+/// # Returns
+///
+/// A Plot object containing the π₀(λ) estimates
+pub fn plot_pi0_lambda<T: Numeric>(scores: &[T], labels: &[i32], title: &str) -> Result<Plot, ReportError> {
+    fdr_curve(scores, labels)?;
+    let pi0 = pi0_estimates(&to_f64_vec(scores), labels);
+    let pi0_half = pi0[LAMBDAS.iter().position(|&lambda| lambda == 0.5).unwrap()];
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(LAMBDAS.to_vec(), pi0)
+            .name("π₀(λ)")
+            .mode(Mode::LinesMarkers)
+            .line(Line::new().color(PALETTE[0])),
+    );
+    plot.add_trace(
+        Scatter::new(vec![0.0, 1.0], vec![pi0_half, pi0_half])
+            .name(format!("π₀(0.5) = {:.3}", pi0_half))
+            .mode(Mode::Lines)
+            .line(Line::new().color("gray").dash(DashType::Dash)),
+    );
+    plot.set_layout(
+        defaults::base_layout()
+            .title(title)
+            .x_axis(Axis::new().title("λ").range(vec![0.0, 1.0]))
+            .y_axis(Axis::new().title("π₀").range(vec![0.0, 1.05])),
+    );
+
+    Ok(plot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fdr_curve() {
+        let scores = [10.0, 9.0, 8.0, 7.0, 6.0, 5.0];
+        let labels = [1, 1, -1, 1, -1, -1];
+        let curve = fdr_curve(&scores, &labels).unwrap();
+        assert_eq!(curve.targets, vec![1, 2, 2, 3, 3, 3]);
+        assert_eq!(curve.fdr, vec![0.0, 0.0, 0.5, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert_eq!(curve.q_values, vec![0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+
+        let pi0 = pi0_estimates(&scores, &labels);
+        assert_eq!(pi0[0], 1.0);
+        assert!(plot_qvalue_vs_hits(&scores, &labels, "Hits").is_ok());
+        assert!(plot_fdr_vs_score_threshold(&[1.0, 2.0], &[1, 1], "FDR").is_err());
+    }
+
+    #[test]
+    fn test_plot_pi0_lambda() {
+        let plot = plot_pi0_lambda(&[3.0, 2.0, 1.0, 0.5], &[1, 1, -1, -1], "π₀").unwrap();
+        let json = super::super::to_json(&plot);
+        assert_eq!(json["data"][0]["x"].as_array().unwrap().len(), LAMBDAS.len());
+        // Both targets outscore all decoys: p = 1/3, so none are null above λ = 1/3
+        assert_eq!(json["data"][0]["y"][10], 0.0);
+    }
+}
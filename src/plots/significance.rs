@@ -0,0 +1,175 @@
+//! Significance brackets on group comparison plots.
+//!
+//! [`annotate_significance`] tests the groups of a box, violin, strip or bar plot against each
+//! other and draws a bracket over each comparison, labelled with its (adjusted) p-value and stars:
+//!
+//! ```rust,ignore
+//! use report_builder::plots::significance::{annotate_significance, SignificanceTest};
+//! use report_builder::stats::PAdjust;
+//!
+//! let mut plot = plot_strip(&groups, 0.5, true, "Intensity", "Condition", "log2 intensity")?;
+//! annotate_significance(&mut plot, &groups, SignificanceTest::MannWhitney, Some(PAdjust::Holm))?;
+//! ```
+
+use plotly::common::Font;
+use plotly::layout::{Annotation, Shape, ShapeLine, ShapeType};
+use plotly::Plot;
+
+use crate::error::ReportError;
+use crate::stats::{self, PAdjust};
+
+/// The test run between groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignificanceTest {
+    /// Welch's t-test between each pair of groups
+    WelchT,
+    /// Mann–Whitney U test between each pair of groups
+    MannWhitney,
+    /// One-way ANOVA across all groups, drawn as a single bracket
+    Anova,
+}
+
+/// The result of a comparison drawn on a plot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// The indices of the first and last groups compared; all groups for ANOVA
+    pub groups: (usize, usize),
+    pub p_value: f64,
+    /// The p-value adjusted for multiple comparisons, or the p-value itself without adjustment
+    pub adjusted_p_value: f64,
+}
+
+/// The conventional stars of a p-value
+fn stars(p_value: f64) -> &'static str {
+    match p_value {
+        p if p < 0.001 => "***",
+        p if p < 0.01 => "**",
+        p if p < 0.05 => "*",
+        _ => "ns",
+    }
+}
+
+/// Test groups against each other and draw a bracket labelled with the p-value over each
+/// comparison, stacked above the data from the narrowest to the widest comparison.
+///
+/// Groups are expected at x positions 0, 1, 2, ... (or the categories of a categorical axis, in
+/// order), as drawn by [`plot_boxplot`](super::plot_boxplot), [`plot_strip`](super::plot_strip)
+/// and [`BoxPlotBuilder`](super::BoxPlotBuilder).
+///
+/// # Arguments
+///
+/// * `plot` - The plot to annotate
+/// * `groups` - The groups as (name, values) pairs, in the order they are drawn
+/// * `test` - The test to run, pairwise or across all groups
+/// * `adjustment` - How pairwise p-values are adjusted for multiple comparisons, if at all
+///
+/// # Returns
+///
+/// The comparisons drawn, or an error if there are fewer than two groups or no comparison could
+/// be tested (e.g. groups with fewer than two values)
+pub fn annotate_significance(plot: &mut Plot, groups: &[(String, Vec<f64>)], test: SignificanceTest, adjustment: Option<PAdjust>) -> Result<Vec<Comparison>, ReportError> {
+    if groups.len() < 2 {
+        return Err(ReportError::InvalidArgument(format!("At least two groups are needed for a comparison, got {}", groups.len())));
+    }
+
+    let mut comparisons: Vec<Comparison> = match test {
+        SignificanceTest::Anova => {
+            let values: Vec<&[f64]> = groups.iter().map(|(_, values)| values.as_slice()).collect();
+            stats::one_way_anova(&values)
+                .map(|result| Comparison { groups: (0, groups.len() - 1), p_value: result.p_value, adjusted_p_value: result.p_value })
+                .into_iter()
+                .collect()
+        }
+        SignificanceTest::WelchT | SignificanceTest::MannWhitney => {
+            let mut pairs: Vec<(usize, usize)> = (0..groups.len()).flat_map(|i| (i + 1..groups.len()).map(move |j| (i, j))).collect();
+            pairs.sort_by_key(|&(i, j)| (j - i, i));
+            pairs
+                .into_iter()
+                .filter_map(|(i, j)| {
+                    let (a, b) = (&groups[i].1, &groups[j].1);
+                    let result = if test == SignificanceTest::WelchT { stats::welch_t_test(a, b) } else { stats::mann_whitney_u(a, b) }?;
+                    Some(Comparison { groups: (i, j), p_value: result.p_value, adjusted_p_value: result.p_value })
+                })
+                .collect()
+        }
+    };
+    if comparisons.is_empty() {
+        return Err(ReportError::EmptyData("no pair of groups has enough values to be tested"));
+    }
+    if let (Some(method), true) = (adjustment, test != SignificanceTest::Anova) {
+        let p_values: Vec<f64> = comparisons.iter().map(|c| c.p_value).collect();
+        for (comparison, adjusted) in comparisons.iter_mut().zip(stats::adjust_p_values(&p_values, method)) {
+            comparison.adjusted_p_value = adjusted;
+        }
+    }
+
+    let finite = || groups.iter().flat_map(|(_, values)| values.iter().copied()).filter(|v| v.is_finite());
+    let (low, high) = (finite().fold(f64::INFINITY, f64::min), finite().fold(f64::NEG_INFINITY, f64::max));
+    let step = if high > low { (high - low) * 0.08 } else { high.abs().max(1.0) * 0.08 };
+
+    let mut layout = plot.layout().clone();
+    for (level, comparison) in comparisons.iter().enumerate() {
+        let y = high + step * (level as f64 + 1.0);
+        let (x0, x1) = (comparison.groups.0 as f64, comparison.groups.1 as f64);
+        for (xa, ya, xb, yb) in [(x0, y - step / 3.0, x0, y), (x0, y, x1, y), (x1, y, x1, y - step / 3.0)] {
+            layout.add_shape(
+                Shape::new()
+                    .shape_type(ShapeType::Line)
+                    .x_ref("x")
+                    .y_ref("y")
+                    .x0(xa)
+                    .y0(ya)
+                    .x1(xb)
+                    .y1(yb)
+                    .line(ShapeLine::new().color("#444444").width(1.0)),
+            );
+        }
+        let label = match (test, adjustment) {
+            (SignificanceTest::Anova, _) => "ANOVA p",
+            (_, Some(_)) => "adj. p",
+            (_, None) => "p",
+        };
+        let p_value = comparison.adjusted_p_value;
+        let value = if p_value < 0.001 { "< 0.001".to_string() } else { format!("= {:.3}", p_value) };
+        layout.add_annotation(
+            Annotation::new()
+                .text(format!("{} {} {}", label, value, stars(p_value)))
+                .x_ref("x")
+                .y_ref("y")
+                .x((x0 + x1) / 2.0)
+                .y(y)
+                .y_anchor(plotly::common::Anchor::Bottom)
+                .show_arrow(false)
+                .font(Font::new().size(11)),
+        );
+    }
+    plot.set_layout(layout);
+
+    Ok(comparisons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotate_significance() {
+        let groups = vec![
+            ("Control".to_string(), vec![5.1, 4.9, 6.2, 5.8, 6.0, 5.5, 5.3]),
+            ("Treated".to_string(), vec![6.8, 7.1, 6.5, 7.4, 6.9, 7.7]),
+            ("Rescue".to_string(), vec![5.9, 6.1, 6.4, 5.7, 6.3]),
+        ];
+        let mut plot = super::super::plot_strip(&groups, 0.5, false, "Intensity", "Condition", "Intensity").unwrap();
+        let comparisons = annotate_significance(&mut plot, &groups, SignificanceTest::WelchT, Some(PAdjust::Bonferroni)).unwrap();
+
+        assert_eq!(comparisons.iter().map(|c| c.groups).collect::<Vec<_>>(), vec![(0, 1), (1, 2), (0, 2)]);
+        assert!((comparisons[0].adjusted_p_value - 3.0 * comparisons[0].p_value).abs() < 1e-12);
+        let layout = &super::super::to_json(&plot)["layout"];
+        assert_eq!(layout["shapes"].as_array().unwrap().len(), 9);
+        assert!(layout["annotations"][0]["text"].as_str().unwrap().starts_with("adj. p < 0.001 ***"));
+
+        let comparisons = annotate_significance(&mut plot, &groups, SignificanceTest::Anova, None).unwrap();
+        assert_eq!(comparisons[0].groups, (0, 2));
+        assert!(annotate_significance(&mut plot, &groups[..1], SignificanceTest::Anova, None).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Structural comparison of plots, for testing plot-construction code without rendering HTML.
+//!
+//! Plots are compared through their Plotly JSON (see [`to_json`](super::to_json)), with numbers
+//! compared up to a small relative tolerance and volatile fields ignored. Paths name JSON fields
+//! with dots, and `*` matches any array index or key, e.g. `data.*.marker.color`.
+//!
+//! ```rust,ignore
+//! use report_builder::plots::{plot_ma, snapshot};
+//!
+//! let plot = plot_ma(&[4.0, 8.0], &[2.0, 8.0], "Replicates").unwrap();
+//! snapshot::assert_plot_snapshot(&plot, "tests/snapshots/ma.json", &[]);
+//! ```
+
+use std::path::Path;
+
+use plotly::Plot;
+use serde_json::Value;
+
+/// Fields ignored by every comparison: trace UIDs, and the layout template carrying the
+/// process-wide [`PlotDefaults`](super::defaults::PlotDefaults).
+pub const VOLATILE_FIELDS: &[&str] = &["data.*.uid", "layout.template"];
+
+/// Relative tolerance used when comparing numbers.
+const TOLERANCE: f64 = 1e-9;
+
+fn is_ignored(path: &[String], ignore: &[&str]) -> bool {
+    VOLATILE_FIELDS.iter().chain(ignore).any(|pattern| {
+        let parts: Vec<&str> = pattern.split('.').collect();
+        parts.len() == path.len() && parts.iter().zip(path).all(|(part, key)| *part == "*" || part == key)
+    })
+}
+
+fn numbers_equal(a: f64, b: f64) -> bool {
+    a == b || (a - b).abs() <= TOLERANCE * a.abs().max(b.abs())
+}
+
+fn collect_diffs(expected: &Value, actual: &Value, path: &mut Vec<String>, ignore: &[&str], diffs: &mut Vec<String>) {
+    if is_ignored(path, ignore) {
+        return;
+    }
+    let location = if path.is_empty() { "(root)".to_string() } else { path.join(".") };
+
+    match (expected, actual) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().collect();
+            keys.extend(b.keys().filter(|key| !a.contains_key(*key)));
+            for key in keys {
+                path.push(key.clone());
+                let (x, y) = (a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null));
+                collect_diffs(x, y, path, ignore, diffs);
+                path.pop();
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                diffs.push(format!("{}: expected {} items, found {}", location, a.len(), b.len()));
+                return;
+            }
+            for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                path.push(i.to_string());
+                collect_diffs(x, y, path, ignore, diffs);
+                path.pop();
+            }
+        }
+        (Value::Number(a), Value::Number(b)) if numbers_equal(a.as_f64().unwrap_or(f64::NAN), b.as_f64().unwrap_or(f64::NAN)) => {}
+        (a, b) if a == b => {}
+        (a, b) => diffs.push(format!("{}: expected {}, found {}", location, a, b)),
+    }
+}
+
+/// List the differences between two plot JSON documents.
+///
+/// # Arguments
+///
+/// * `expected` - The expected document
+/// * `actual` - The document to check
+/// * `ignore` - Paths of fields to ignore, in addition to [`VOLATILE_FIELDS`]
+///
+/// # Returns
+///
+/// One line per differing field, naming its path; empty if the documents are equal.
+pub fn diff(expected: &Value, actual: &Value, ignore: &[&str]) -> Vec<String> {
+    let mut diffs = Vec::new();
+    collect_diffs(expected, actual, &mut Vec::new(), ignore, &mut diffs);
+    diffs
+}
+
+/// Assert that two plots are structurally equal, panicking with the list of differences otherwise.
+pub fn assert_plot_eq(actual: &Plot, expected: &Plot, ignore: &[&str]) {
+    let diffs = diff(&super::to_json(expected), &super::to_json(actual), ignore);
+    assert!(diffs.is_empty(), "Plots differ:\n{}", diffs.join("\n"));
+}
+
+/// Assert that a plot matches the JSON snapshot stored at `path`.
+///
+/// The snapshot is (re)written instead when it does not exist yet or the `UPDATE_SNAPSHOTS`
+/// environment variable is set, so snapshots can be reviewed as part of the diff.
+pub fn assert_plot_snapshot<P: AsRef<Path>>(plot: &Plot, path: P, ignore: &[&str]) {
+    let path = path.as_ref();
+    let actual = super::to_json(plot);
+    if !path.exists() || std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create the snapshot directory");
+        }
+        let json = serde_json::to_string_pretty(&actual).expect("Failed to serialize the plot");
+        std::fs::write(path, json + "\n").expect("Failed to write the snapshot");
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(path).expect("Failed to read the snapshot");
+    let expected: Value = serde_json::from_str(&snapshot).expect("The snapshot is not valid JSON");
+    let diffs = diff(&expected, &actual, ignore);
+    assert!(
+        diffs.is_empty(),
+        "Plot does not match snapshot '{}' (set UPDATE_SNAPSHOTS=1 to update it):\n{}",
+        path.display(),
+        diffs.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff() {
+        let expected = json!({ "data": [{ "x": [1.0, 2.0], "uid": "a" }], "layout": { "title": { "text": "A" } } });
+        let actual = json!({ "data": [{ "x": [1.0, 2.0000000000001], "uid": "b" }], "layout": { "title": { "text": "B" } } });
+        assert_eq!(diff(&expected, &actual, &[]), vec![r#"layout.title.text: expected "A", found "B""#]);
+        assert!(diff(&expected, &actual, &["layout.title.*"]).is_empty());
+
+        let shorter = json!({ "data": [], "layout": { "title": { "text": "A" } } });
+        assert_eq!(diff(&expected, &shorter, &[]), vec!["data: expected 1 items, found 0"]);
+    }
+
+    #[test]
+    fn test_assert_plot_snapshot() {
+        let path = std::env::temp_dir().join(format!("report-builder-snapshot-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let plot = crate::plots::plot_ma(&[4.0, 8.0], &[2.0, 8.0], "Replicates").unwrap();
+
+        assert_plot_snapshot(&plot, &path, &[]);
+        assert_plot_snapshot(&plot, &path, &[]);
+        let other = crate::plots::plot_ma(&[4.0, 8.0], &[2.0, 8.0], "Other").unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| assert_plot_snapshot(&other, &path, &[])));
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Power analysis for study design.
+//!
+//! A [`PowerAnalysis`] computes the power of a two-sided two-sample t-test from the expected
+//! difference in means, its standard deviation and the significance level, and renders a
+//! "Power analysis" section with power curves and the sample size needed for each target power:
+//!
+//! ```rust,ignore
+//! use report_builder::power::PowerAnalysis;
+//!
+//! let analysis = PowerAnalysis::new(1.5, 3.0).alpha(0.01).target_powers(&[0.8, 0.9]);
+//! report.add_section(analysis.to_section());
+//! ```
+
+use maud::html;
+use plotly::common::{DashType, Line, Mode};
+use plotly::layout::Axis;
+use plotly::{Plot, Scatter};
+
+use crate::plots::defaults;
+use crate::stats;
+use crate::ReportSection;
+
+/// The effect sizes drawn next to the expected one, as multiples of it
+const EFFECT_MULTIPLIERS: [f64; 3] = [0.75, 1.0, 1.25];
+
+/// A power analysis of a two-sided two-sample t-test with equal group sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerAnalysis {
+    difference: f64,
+    sd: f64,
+    alpha: f64,
+    target_powers: Vec<f64>,
+    max_n: usize,
+}
+
+impl PowerAnalysis {
+    /// Creates an analysis at a significance level of 0.05, for target powers of 0.8, 0.9 and
+    /// 0.95 and up to 200 subjects per group.
+    ///
+    /// # Arguments
+    ///
+    /// * `difference` - The expected difference in means between the groups.
+    /// * `sd` - The standard deviation within each group.
+    pub fn new(difference: f64, sd: f64) -> Self {
+        PowerAnalysis {
+            difference,
+            sd,
+            alpha: 0.05,
+            target_powers: vec![0.8, 0.9, 0.95],
+            max_n: 200,
+        }
+    }
+
+    /// Sets the significance level.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets the powers a sample size is recommended for.
+    pub fn target_powers(mut self, powers: &[f64]) -> Self {
+        self.target_powers = powers.to_vec();
+        self
+    }
+
+    /// Sets the largest number of subjects per group considered.
+    pub fn max_n(mut self, max_n: usize) -> Self {
+        self.max_n = max_n.max(2);
+        self
+    }
+
+    /// The standardized effect size (Cohen's d).
+    pub fn effect_size(&self) -> f64 {
+        self.difference / self.sd
+    }
+
+    /// The power reached with the given number of subjects per group.
+    pub fn power(&self, n_per_group: usize) -> f64 {
+        stats::two_sample_t_power(self.effect_size(), n_per_group, self.alpha)
+    }
+
+    /// The recommended number of subjects per group for each target power, `None` when more than
+    /// the maximum would be needed.
+    pub fn sample_sizes(&self) -> Vec<(f64, Option<usize>)> {
+        self.target_powers
+            .iter()
+            .map(|&power| (power, stats::two_sample_t_sample_size(self.effect_size(), self.alpha, power, self.max_n)))
+            .collect()
+    }
+
+    /// The power against the number of subjects per group, for the expected effect size and
+    /// effect sizes 25% smaller and larger, with the target powers marked.
+    pub fn plot(&self) -> Plot {
+        let n: Vec<usize> = (2..=self.max_n).collect();
+        let mut plot = Plot::new();
+        for multiplier in EFFECT_MULTIPLIERS {
+            let effect_size = self.effect_size() * multiplier;
+            let power: Vec<f64> = n.iter().map(|&n| stats::two_sample_t_power(effect_size, n, self.alpha)).collect();
+            let mut trace = Scatter::new(n.clone(), power).name(format!("d = {:.2}", effect_size)).mode(Mode::Lines);
+            if multiplier != 1.0 {
+                trace = trace.line(Line::new().dash(DashType::Dot));
+            }
+            plot.add_trace(trace);
+        }
+        for &power in &self.target_powers {
+            plot.add_trace(
+                Scatter::new(vec![2, self.max_n], vec![power, power])
+                    .name(format!("Power = {}", power))
+                    .mode(Mode::Lines)
+                    .line(Line::new().color("gray").dash(DashType::Dash)),
+            );
+        }
+        plot.set_layout(
+            defaults::base_layout()
+                .title(format!("Power of a two-sample t-test (α = {})", self.alpha))
+                .x_axis(Axis::new().title("Subjects per group"))
+                .y_axis(Axis::new().title("Power").range(vec![0.0, 1.05])),
+        );
+        plot
+    }
+
+    /// Creates a "Power analysis" section with the inputs, the power curves and the recommended
+    /// sample sizes.
+    pub fn to_section(&self) -> ReportSection {
+        let mut section = ReportSection::new("Power analysis");
+        section.add_key_value_table([
+            ("Test", "Two-sided two-sample t-test, equal group sizes".to_string()),
+            ("Difference in means", self.difference.to_string()),
+            ("Standard deviation", self.sd.to_string()),
+            ("Effect size (Cohen's d)", format!("{:.3}", self.effect_size())),
+            ("Significance level (α)", self.alpha.to_string()),
+        ]);
+        section.add_plot(self.plot());
+        section.add_content(html! {
+            table class="key-value" {
+                thead { tr { th { "Target power" } th { "Subjects per group" } th { "Total subjects" } th { "Achieved power" } } }
+                tbody {
+                    @for (power, n) in self.sample_sizes() {
+                        tr {
+                            th scope="row" { (power) }
+                            @match n {
+                                Some(n) => { td { (n) } td { (2 * n) } td { (format!("{:.3}", self.power(n))) } },
+                                None => td colspan="3" { "More than " (self.max_n) " per group" },
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_analysis() {
+        let analysis = PowerAnalysis::new(1.5, 3.0).target_powers(&[0.8, 0.999]).max_n(100);
+        assert_eq!(analysis.effect_size(), 0.5);
+        assert_eq!(analysis.sample_sizes(), vec![(0.8, Some(64)), (0.999, None)]);
+
+        let json = crate::plots::to_json(&analysis.plot());
+        assert_eq!(json["data"].as_array().unwrap().len(), 5);
+        assert_eq!(json["data"][1]["name"], "d = 0.50");
+
+        let html = analysis.to_section().render_to_string();
+        assert!(html.contains("<th scope=\"row\">0.8</th><td>64</td><td>128</td><td>0.801</td>"));
+        assert!(html.contains("More than 100 per group"));
+    }
+}
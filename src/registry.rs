@@ -0,0 +1,164 @@
+//! Runtime registry of named component factories and input parsers.
+//!
+//! External crates can register their own components under a name, so that reports can
+//! instantiate them from plain JSON parameters without this crate depending on them:
+//!
+//! ```rust,ignore
+//! use report_builder::registry;
+//! use maud::html;
+//!
+//! registry::register_component("banner-note", |params| {
+//!     let text = params["text"].as_str().ok_or("missing 'text'")?;
+//!     Ok(html! { p class="note" { (text) } })
+//! });
+//!
+//! section.add_component("banner-note", &serde_json::json!({ "text": "Hello" }))?;
+//! ```
+//!
+//! Parsers turn raw text input (e.g. a tool-specific output file) into the JSON parameters
+//! expected by a component, and are registered and looked up the same way.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use maud::Markup;
+use serde_json::Value;
+
+use crate::ReportError;
+
+/// A function building a block of content from JSON parameters.
+pub type ComponentFactory = Arc<dyn Fn(&Value) -> Result<Markup, String> + Send + Sync>;
+
+/// A function parsing raw text input into JSON parameters.
+pub type Parser = Arc<dyn Fn(&str) -> Result<Value, String> + Send + Sync>;
+
+#[derive(Default)]
+struct Registry {
+    components: HashMap<String, ComponentFactory>,
+    parsers: HashMap<String, Parser>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::default()))
+}
+
+/// Registers a component factory under the given name, replacing any previous registration.
+///
+/// # Arguments
+///
+/// * `name` - The name the component is instantiated by.
+/// * `factory` - A function building the component's markup from JSON parameters.
+pub fn register_component<F>(name: &str, factory: F)
+where
+    F: Fn(&Value) -> Result<Markup, String> + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap()
+        .components
+        .insert(name.to_string(), Arc::new(factory));
+}
+
+/// Registers an input parser under the given name, replacing any previous registration.
+///
+/// # Arguments
+///
+/// * `name` - The name the parser is looked up by.
+/// * `parser` - A function turning raw text into JSON parameters.
+pub fn register_parser<F>(name: &str, parser: F)
+where
+    F: Fn(&str) -> Result<Value, String> + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap()
+        .parsers
+        .insert(name.to_string(), Arc::new(parser));
+}
+
+/// Instantiates a registered component.
+///
+/// # Arguments
+///
+/// * `name` - The name the component was registered under.
+/// * `params` - The JSON parameters passed to the component factory.
+///
+/// # Returns
+///
+/// The component's markup, or [`ReportError::InvalidArgument`] if no component of that name exists
+/// or the factory fails.
+pub fn build_component(name: &str, params: &Value) -> Result<Markup, ReportError> {
+    let factory = registry()
+        .read()
+        .unwrap()
+        .components
+        .get(name)
+        .cloned()
+        .ok_or_else(|| ReportError::InvalidArgument(format!("No component registered under '{}'", name)))?;
+    factory(params).map_err(|e| ReportError::InvalidArgument(format!("Component '{}' failed: {}", name, e)))
+}
+
+/// Runs a registered parser over raw text input.
+///
+/// # Arguments
+///
+/// * `name` - The name the parser was registered under.
+/// * `input` - The raw text to parse.
+///
+/// # Returns
+///
+/// The parsed JSON parameters, or [`ReportError::InvalidArgument`] if no parser of that name
+/// exists or parsing fails.
+pub fn parse(name: &str, input: &str) -> Result<Value, ReportError> {
+    let parser = registry()
+        .read()
+        .unwrap()
+        .parsers
+        .get(name)
+        .cloned()
+        .ok_or_else(|| ReportError::InvalidArgument(format!("No parser registered under '{}'", name)))?;
+    parser(input).map_err(|e| ReportError::InvalidArgument(format!("Parser '{}' failed: {}", name, e)))
+}
+
+/// Returns the names of all registered components, sorted alphabetically.
+pub fn registered_components() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap().components.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Returns the names of all registered parsers, sorted alphabetically.
+pub fn registered_parsers() -> Vec<String> {
+    let mut names: Vec<String> = registry().read().unwrap().parsers.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maud::html;
+    use serde_json::json;
+
+    #[test]
+    fn test_register_and_build_component() {
+        register_component("test-note", |params| {
+            let text = params["text"].as_str().ok_or("missing 'text'")?;
+            Ok(html! { p class="note" { (text) } })
+        });
+        register_parser("test-kv", |input| {
+            let (key, value) = input.split_once('=').ok_or("expected key=value")?;
+            Ok(json!({ key.trim(): value.trim() }))
+        });
+
+        let params = parse("test-kv", "text = Hello").unwrap();
+        let markup = build_component("test-note", &params).unwrap();
+        assert_eq!(markup.into_string(), r#"<p class="note">Hello</p>"#);
+
+        assert!(registered_components().contains(&"test-note".to_string()));
+        let e = build_component("test-note", &json!({})).unwrap_err();
+        assert_eq!(e.to_string(), "Invalid argument: Component 'test-note' failed: missing 'text'");
+        assert!(build_component("does-not-exist", &json!({})).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Versioned serialization of report definitions.
+//!
+//! Reports are serialized inside an envelope carrying a `schema_version`. When the layout of the
+//! serialized document changes, [`SCHEMA_VERSION`] is bumped and a migration step is appended to
+//! [`MIGRATIONS`], so documents written by any older version of this crate can still be loaded,
+//! upgraded step by step, and re-rendered.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Report, ReportError};
+
+/// The schema version written by this version of the crate.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A single migration step, upgrading a serialized report by exactly one schema version.
+pub type Migration = fn(Value) -> Result<Value, ReportError>;
+
+/// Migration steps, where `MIGRATIONS[i]` upgrades a report from version `i + 1` to `i + 2`.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// Version 2 turned content blocks from plain HTML strings into objects carrying data lineage.
+fn migrate_v1_to_v2(mut document: Value) -> Result<Value, ReportError> {
+    let sections = document["report"]["sections"]
+        .as_array_mut()
+        .ok_or_else(|| ReportError::Serialization("Report document is missing 'sections'".to_string()))?;
+    for section in sections {
+        let blocks = section["content_blocks"]
+            .as_array_mut()
+            .ok_or_else(|| ReportError::Serialization("Section is missing 'content_blocks'".to_string()))?;
+        for block in blocks.iter_mut() {
+            let markup = block.take();
+            *block = serde_json::json!({ "markup": markup, "lineage": [] });
+        }
+    }
+    Ok(document)
+}
+
+/// The data behind a content block, serialized next to its rendered HTML so tools reading a
+/// report document do not have to parse the markup.
+///
+/// Text blocks carry no data, their HTML is their content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlockData {
+    /// The Plotly figure (data, layout and config) of a plot
+    Plot { figure: Value },
+    /// The headers and cells of a table, with cells as HTML
+    Table {
+        id: String,
+        caption: Option<String>,
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+#[derive(Serialize)]
+struct VersionedReportRef<'a> {
+    schema_version: u32,
+    report: &'a Report,
+}
+
+#[derive(Deserialize)]
+struct VersionedReport {
+    report: Report,
+}
+
+/// Upgrades a serialized report document to the current [`SCHEMA_VERSION`].
+///
+/// # Arguments
+///
+/// * `document` - The serialized envelope, containing `schema_version` and `report`.
+///
+/// # Returns
+///
+/// The upgraded envelope, or [`ReportError::Serialization`] if the document has no version or
+/// was written by a newer version of the crate.
+pub fn migrate(mut document: Value) -> Result<Value, ReportError> {
+    let version = document
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| ReportError::Serialization("Report document is missing 'schema_version'".to_string()))? as u32;
+
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(ReportError::Serialization(format!(
+            "Unsupported schema version {} (this version of report-builder supports 1 to {})",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        document = migration(document)?;
+        document["schema_version"] = Value::from(step as u32 + 2);
+    }
+
+    Ok(document)
+}
+
+impl Report {
+    /// Serializes the report definition to JSON, tagged with the current schema version.
+    ///
+    /// Besides its rendered HTML, each plot carries its Plotly figure and each table its cells,
+    /// see [`BlockData`].
+    ///
+    /// # Returns
+    ///
+    /// The JSON document as a string, or [`ReportError::Serialization`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        Ok(serde_json::to_string(&VersionedReportRef {
+            schema_version: SCHEMA_VERSION,
+            report: self,
+        })?)
+    }
+
+    /// Loads a report definition from JSON, migrating it from older schema versions if needed.
+    ///
+    /// The placeholder element IDs saved with the report are renumbered, so blocks added to the
+    /// loaded report cannot be given the same IDs as the saved ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A JSON document previously produced by [`Report::to_json`].
+    ///
+    /// # Returns
+    ///
+    /// The loaded Report, or [`ReportError::Serialization`] if the document is invalid or its
+    /// version is unsupported.
+    pub fn from_json(json: &str) -> Result<Report, ReportError> {
+        let document: Value = serde_json::from_str(&crate::ids::renew(json))?;
+        let document = migrate(document)?;
+        let versioned: VersionedReport = serde_json::from_value(document)?;
+        Ok(versioned.report)
+    }
+}
+
+/// Serde helpers for storing `Markup` as an HTML string.
+pub(crate) mod markup {
+    use alloc::string::String;
+    use maud::{Markup, PreEscaped};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(markup: &Markup, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&markup.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Markup, D::Error> {
+        Ok(PreEscaped(String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReportSection;
+    use maud::html;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut report = Report::new("Redeem", "1.0", Some("logo.png"), "My Report");
+        let mut section = ReportSection::new("Section 1");
+        section.add_content(html! { p { "Hello" } });
+        report.add_section(section);
+
+        let json = report.to_json().unwrap();
+        let document: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document["schema_version"], SCHEMA_VERSION);
+
+        let loaded = Report::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+    }
+
+    #[cfg(feature = "plots")]
+    #[test]
+    fn test_json_round_trip_ids() {
+        let plot = || {
+            let mut plot = plotly::Plot::new();
+            plot.add_trace(plotly::Scatter::new(vec![1, 2], vec![3, 4]));
+            plot
+        };
+        let mut section = ReportSection::new("A");
+        section.add_plot(plot());
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_section(section);
+
+        let mut loaded = Report::from_json(&report.to_json().unwrap()).unwrap();
+        let mut section = ReportSection::new("B");
+        section.add_plot(plot());
+        loaded.add_section(section);
+        loaded.get_section_mut("A").unwrap().add_plot(plot());
+
+        let html = loaded.to_string();
+        let mut ids: Vec<&str> = html.split(" id=\"").skip(1).filter_map(|rest| rest.split('"').next()).collect();
+        assert!(ids.contains(&"plot-a-1") && ids.contains(&"plot-a-2") && ids.contains(&"plot-b-1"));
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[cfg(all(feature = "plots", feature = "tables"))]
+    #[test]
+    fn test_json_block_data() {
+        use crate::table::Table;
+
+        let mut section = ReportSection::new("Results");
+        let mut plot = plotly::Plot::new();
+        plot.add_trace(plotly::Scatter::new(vec![1, 2], vec![3, 4]));
+        section.add_plot(plot);
+        section.add_table(Table::new().id("psms").headers(["Peptide", "Score"]).add_row(["PEPTIDE", "0.9"]));
+        section.add_content(html! { p { "Notes" } });
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.add_section(section);
+
+        let document: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        let blocks = &document["report"]["sections"][0]["content_blocks"];
+        assert_eq!(blocks[0]["data"]["kind"], "plot");
+        assert_eq!(blocks[0]["data"]["figure"]["data"][0]["y"], serde_json::json!([3, 4]));
+        assert_eq!(
+            blocks[1]["data"],
+            serde_json::json!({ "kind": "table", "id": "psms", "caption": null, "headers": ["Peptide", "Score"], "rows": [["PEPTIDE", "0.9"]] })
+        );
+        assert!(blocks[2].get("data").is_none());
+
+        // The placeholder IDs are renumbered when loading, so the documents are compared with IDs
+        let with_ids = |report: &Report| crate::ids::ElementIds::default().assign(&report.to_json().unwrap(), "");
+        let loaded = Report::from_json(&report.to_json().unwrap()).unwrap();
+        assert_eq!(with_ids(&loaded), with_ids(&report));
+    }
+
+    #[test]
+    fn test_load_v1_document() {
+        let v1 = r#"{
+            "schema_version": 1,
+            "report": {
+                "software_name": "Redeem",
+                "version": "1.0",
+                "software_logo": null,
+                "title": "My Report",
+                "sections": [{ "title": "Section 1", "content_blocks": ["<p>Hello</p>"] }]
+            }
+        }"#;
+
+        let report = Report::from_json(v1).unwrap();
+        let document: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(document["schema_version"], SCHEMA_VERSION);
+        assert_eq!(
+            document["report"]["sections"][0]["content_blocks"][0],
+            serde_json::json!({ "markup": "<p>Hello</p>", "lineage": [] })
+        );
+    }
+
+    #[test]
+    fn test_migrate_rejects_unknown_versions() {
+        assert!(migrate(serde_json::json!({ "report": {} })).is_err());
+        assert!(migrate(serde_json::json!({ "schema_version": SCHEMA_VERSION + 1, "report": {} })).is_err());
+    }
+}
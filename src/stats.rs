@@ -0,0 +1,1141 @@
+//! Statistical routines backing the plot helpers.
+//!
+//! These are computed in Rust so that the numbers annotated on figures (test statistics,
+//! p-values, fitted parameters) are exact and reproducible rather than left to the browser.
+
+/// The result of a two-sample Kolmogorov–Smirnov test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KsResult {
+    /// The maximum absolute difference between the two empirical CDFs.
+    pub statistic: f64,
+    /// The asymptotic p-value of the statistic.
+    pub p_value: f64,
+    /// The value at which the maximum difference occurs.
+    pub location: f64,
+    /// The value of the first sample's ECDF at `location`.
+    pub cdf_a: f64,
+    /// The value of the second sample's ECDF at `location`.
+    pub cdf_b: f64,
+}
+
+/// Sort the finite values of a sample in ascending order, dropping NaN and infinite values.
+pub(crate) fn sorted_finite(values: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+}
+
+/// The fraction of a sorted sample that is less than or equal to `x`.
+pub(crate) fn ecdf_at(sorted: &[f64], x: f64) -> f64 {
+    sorted.partition_point(|&v| v <= x) as f64 / sorted.len() as f64
+}
+
+/// The arithmetic mean of a sample, or NaN if it is empty.
+pub fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// The sample standard deviation (n - 1 denominator), or NaN if there are fewer than two values.
+pub fn std_dev(values: &[f64]) -> f64 {
+    let m = mean(values);
+    let ss: f64 = values.iter().map(|v| (v - m).powi(2)).sum();
+    (ss / (values.len() as f64 - 1.0)).sqrt()
+}
+
+/// The `q`-th quantile (0 to 1) of a sorted sample, linearly interpolating between values.
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// How the bin width of a histogram is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinRule {
+    /// Freedman–Diaconis rule, `2 * IQR / n^(1/3)`, robust to outliers and heavy tails.
+    FreedmanDiaconis,
+    /// Sturges' rule, `range / (log2(n) + 1)`, suited to small, roughly normal samples.
+    Sturges,
+    /// A fixed bin width.
+    Width(f64),
+    /// A fixed number of bins spanning the data range.
+    Count(usize),
+}
+
+/// Computes the bin width for a sample according to a binning rule.
+///
+/// Non-finite values are ignored. The Freedman–Diaconis rule falls back to Sturges' rule when the
+/// interquartile range is zero, and a width of 1 is used when all values are identical.
+///
+/// # Arguments
+///
+/// * `values` - The sample to bin
+/// * `rule` - The binning rule
+///
+/// # Returns
+///
+/// The bin width, always strictly positive.
+pub fn bin_width(values: &[f64], rule: BinRule) -> f64 {
+    let sorted = sorted_finite(values);
+    let n = sorted.len() as f64;
+    let range = match (sorted.first(), sorted.last()) {
+        (Some(min), Some(max)) => max - min,
+        _ => 0.0,
+    };
+
+    let width = match rule {
+        BinRule::Width(width) => width,
+        BinRule::Count(count) => range / count.max(1) as f64,
+        BinRule::Sturges => range / (n.log2().ceil() + 1.0),
+        BinRule::FreedmanDiaconis => {
+            let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+            if iqr > 0.0 {
+                2.0 * iqr / n.cbrt()
+            } else {
+                range / (n.log2().ceil() + 1.0)
+            }
+        }
+    };
+
+    if width.is_finite() && width > 0.0 {
+        width
+    } else {
+        1.0
+    }
+}
+
+/// The points of a classifier performance curve, with the area under it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// The score threshold of each point; the first point lies above all scores.
+    pub thresholds: Vec<f64>,
+    /// The area under the curve (the average precision for precision-recall curves).
+    pub auc: f64,
+}
+
+/// Count the true and false positives at each distinct score threshold, from the highest score
+/// down. Labels are 1 for positives (targets) and -1 for negatives (decoys).
+pub(crate) fn confusion_counts(scores: &[f64], labels: &[i32]) -> Vec<(f64, usize, usize)> {
+    let mut pairs: Vec<(f64, bool)> = scores
+        .iter()
+        .zip(labels)
+        .filter(|(score, _)| score.is_finite())
+        .map(|(&score, &label)| (score, label == 1))
+        .collect();
+    pairs.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+    let mut counts: Vec<(f64, usize, usize)> = Vec::new();
+    let (mut tp, mut fp) = (0, 0);
+    for (i, &(score, positive)) in pairs.iter().enumerate() {
+        if positive {
+            tp += 1;
+        } else {
+            fp += 1;
+        }
+        // Tied scores are a single threshold
+        if pairs.get(i + 1).map_or(true, |next| next.0 < score) {
+            counts.push((score, tp, fp));
+        }
+    }
+    counts
+}
+
+/// Computes the receiver operating characteristic (ROC) curve of a scoring classifier.
+///
+/// Higher scores are taken to indicate positives. Non-finite scores are ignored and tied scores
+/// form a single point.
+///
+/// # Arguments
+///
+/// * `scores` - The classifier scores
+/// * `labels` - 1 for positives (targets) and -1 for negatives (decoys), one per score
+///
+/// # Returns
+///
+/// The false positive rates (x) and true positive rates (y) from (0, 0) to (1, 1), with the
+/// trapezoidal area under the curve, or `None` if there are no positives or no negatives.
+pub fn roc_curve(scores: &[f64], labels: &[i32]) -> Option<Curve> {
+    let counts = confusion_counts(scores, labels);
+    let &(_, positives, negatives) = counts.last()?;
+    if positives == 0 || negatives == 0 {
+        return None;
+    }
+
+    let mut curve = Curve { x: vec![0.0], y: vec![0.0], thresholds: vec![f64::INFINITY], auc: 0.0 };
+    for (threshold, tp, fp) in counts {
+        let (fpr, tpr) = (fp as f64 / negatives as f64, tp as f64 / positives as f64);
+        let (last_fpr, last_tpr) = (*curve.x.last().unwrap(), *curve.y.last().unwrap());
+        curve.auc += (fpr - last_fpr) * (tpr + last_tpr) / 2.0;
+        curve.x.push(fpr);
+        curve.y.push(tpr);
+        curve.thresholds.push(threshold);
+    }
+    Some(curve)
+}
+
+/// Computes the precision-recall curve of a scoring classifier.
+///
+/// Higher scores are taken to indicate positives. Non-finite scores are ignored and tied scores
+/// form a single point.
+///
+/// # Arguments
+///
+/// * `scores` - The classifier scores
+/// * `labels` - 1 for positives (targets) and -1 for negatives (decoys), one per score
+///
+/// # Returns
+///
+/// The recalls (x) and precisions (y), starting at (0, 1), with the average precision (the
+/// precisions weighted by the recall gained at each threshold) as the area, or `None` if there
+/// are no positives.
+pub fn precision_recall_curve(scores: &[f64], labels: &[i32]) -> Option<Curve> {
+    let counts = confusion_counts(scores, labels);
+    let &(_, positives, _) = counts.last()?;
+    if positives == 0 {
+        return None;
+    }
+
+    let mut curve = Curve { x: vec![0.0], y: vec![1.0], thresholds: vec![f64::INFINITY], auc: 0.0 };
+    for (threshold, tp, fp) in counts {
+        let (recall, precision) = (tp as f64 / positives as f64, tp as f64 / (tp + fp) as f64);
+        curve.auc += (recall - curve.x.last().unwrap()) * precision;
+        curve.x.push(recall);
+        curve.y.push(precision);
+        curve.thresholds.push(threshold);
+    }
+    Some(curve)
+}
+
+/// The survival function of the Kolmogorov distribution, P(K > lambda).
+fn kolmogorov_survival(lambda: f64) -> f64 {
+    if lambda < 1e-3 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    for j in 1..=100 {
+        let term = (-2.0 * (j * j) as f64 * lambda * lambda).exp();
+        sum += if j % 2 == 1 { term } else { -term };
+        if term < 1e-12 {
+            break;
+        }
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+/// Performs a two-sample Kolmogorov–Smirnov test.
+///
+/// Non-finite values are ignored. The p-value uses the asymptotic Kolmogorov distribution with the
+/// small-sample correction of Stephens (1970).
+///
+/// # Arguments
+///
+/// * `a` - The first sample
+/// * `b` - The second sample
+///
+/// # Returns
+///
+/// The test statistic, p-value and location of the maximum ECDF difference, or `None` if either
+/// sample has no finite values.
+pub fn ks_two_sample(a: &[f64], b: &[f64]) -> Option<KsResult> {
+    let a = sorted_finite(a);
+    let b = sorted_finite(b);
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let mut best = KsResult {
+        statistic: 0.0,
+        p_value: 1.0,
+        location: a[0].min(b[0]),
+        cdf_a: 0.0,
+        cdf_b: 0.0,
+    };
+    for &x in a.iter().chain(b.iter()) {
+        let (cdf_a, cdf_b) = (ecdf_at(&a, x), ecdf_at(&b, x));
+        let d = (cdf_a - cdf_b).abs();
+        if d > best.statistic {
+            best = KsResult { statistic: d, location: x, cdf_a, cdf_b, ..best };
+        }
+    }
+
+    let (n, m) = (a.len() as f64, b.len() as f64);
+    let effective_n = (n * m / (n + m)).sqrt();
+    best.p_value = kolmogorov_survival((effective_n + 0.12 + 0.11 / effective_n) * best.statistic);
+
+    Some(best)
+}
+
+/// The result of a hypothesis test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic: t for Welch's t-test, U for the Mann–Whitney test, F for ANOVA,
+    /// chi-squared for the log-rank test.
+    pub statistic: f64,
+    /// The two-sided p-value of the statistic.
+    pub p_value: f64,
+}
+
+/// The natural logarithm of the gamma function, with the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series: f64 = COEFFICIENTS.iter().enumerate().map(|(j, c)| c / (x + 1.0 + j as f64)).sum();
+    -tmp + (2.5066282746310005 * (1.000000000190015 + series) / x).ln()
+}
+
+/// The continued fraction of the regularized incomplete beta function (Numerical Recipes' betacf).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    d = 1.0 / if d.abs() < TINY { TINY } else { d };
+    let mut h = d;
+    for m in 1..=200 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        for aa in [m * (b - m) * x / ((qam + m2) * (a + m2)), -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2))] {
+            d = 1.0 + aa * d;
+            d = 1.0 / if d.abs() < TINY { TINY } else { d };
+            c = 1.0 + aa / c;
+            c = if c.abs() < TINY { TINY } else { c };
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// The regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// The two-sided p-value of a t statistic with `df` degrees of freedom.
+fn t_test_p_value(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
+/// The complementary error function, with a Chebyshev approximation accurate to 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418 + t * (-0.18628806 + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let value = t * poly.exp();
+    if x >= 0.0 {
+        value
+    } else {
+        2.0 - value
+    }
+}
+
+/// Performs Welch's two-sample t-test, which does not assume equal variances.
+///
+/// Non-finite values are ignored.
+///
+/// # Returns
+///
+/// The t statistic and its two-sided p-value, or `None` if either sample has fewer than two values
+/// or both have no variance.
+pub fn welch_t_test(a: &[f64], b: &[f64]) -> Option<TestResult> {
+    let (a, b) = (sorted_finite(a), sorted_finite(b));
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let (var_a, var_b) = (std_dev(&a).powi(2) / a.len() as f64, std_dev(&b).powi(2) / b.len() as f64);
+    if var_a + var_b == 0.0 {
+        return None;
+    }
+    let t = (mean(&a) - mean(&b)) / (var_a + var_b).sqrt();
+    let df = (var_a + var_b).powi(2) / (var_a.powi(2) / (a.len() as f64 - 1.0) + var_b.powi(2) / (b.len() as f64 - 1.0));
+    Some(TestResult { statistic: t, p_value: t_test_p_value(t, df) })
+}
+
+/// Performs the Mann–Whitney U test (Wilcoxon rank-sum test).
+///
+/// Non-finite values are ignored. The p-value uses the normal approximation with tie and
+/// continuity corrections.
+///
+/// # Returns
+///
+/// The U statistic of the first sample and its two-sided p-value, or `None` if either sample is
+/// empty or all values are tied.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<TestResult> {
+    let (a, b) = (sorted_finite(a), sorted_finite(b));
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let mut pooled: Vec<(f64, bool)> = a.iter().map(|&v| (v, true)).chain(b.iter().map(|&v| (v, false))).collect();
+    pooled.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+
+    // Tied values share the average of their ranks
+    let (mut rank_sum, mut tie_term, mut i) = (0.0, 0.0, 0);
+    while i < pooled.len() {
+        let j = pooled[i..].iter().position(|&(v, _)| v != pooled[i].0).map_or(pooled.len(), |offset| i + offset);
+        let rank = (i + j + 1) as f64 / 2.0;
+        rank_sum += rank * pooled[i..j].iter().filter(|(_, first)| *first).count() as f64;
+        let ties = (j - i) as f64;
+        tie_term += ties.powi(3) - ties;
+        i = j;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return None;
+    }
+    let z = ((u - n1 * n2 / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+    Some(TestResult { statistic: u, p_value: erfc(z / core::f64::consts::SQRT_2).min(1.0) })
+}
+
+/// Performs a one-way analysis of variance (ANOVA) across groups.
+///
+/// Non-finite values are ignored.
+///
+/// # Returns
+///
+/// The F statistic and its p-value, or `None` if there are fewer than two groups with values,
+/// no more values than groups, or no variance within the groups.
+pub fn one_way_anova(groups: &[&[f64]]) -> Option<TestResult> {
+    let groups: Vec<Vec<f64>> = groups.iter().map(|g| sorted_finite(g)).filter(|g| !g.is_empty()).collect();
+    let k = groups.len() as f64;
+    let n: f64 = groups.iter().map(|g| g.len() as f64).sum();
+    if k < 2.0 || n <= k {
+        return None;
+    }
+    let grand_mean = groups.iter().flatten().sum::<f64>() / n;
+    let between: f64 = groups.iter().map(|g| g.len() as f64 * (mean(g) - grand_mean).powi(2)).sum();
+    let within: f64 = groups.iter().map(|g| {
+        let m = mean(g);
+        g.iter().map(|v| (v - m).powi(2)).sum::<f64>()
+    }).sum();
+    if within == 0.0 {
+        return None;
+    }
+    let (df_between, df_within) = (k - 1.0, n - k);
+    let f = (between / df_between) / (within / df_within);
+    let p_value = incomplete_beta(df_within / 2.0, df_between / 2.0, df_within / (df_within + df_between * f));
+    Some(TestResult { statistic: f, p_value: p_value.clamp(0.0, 1.0) })
+}
+
+/// How p-values are adjusted for multiple comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PAdjust {
+    /// Multiply each p-value by the number of comparisons (family-wise error rate).
+    Bonferroni,
+    /// Holm's step-down procedure, controlling the family-wise error rate with more power than
+    /// Bonferroni.
+    Holm,
+    /// Benjamini–Hochberg procedure, controlling the false discovery rate.
+    BenjaminiHochberg,
+}
+
+/// Adjusts p-values for multiple comparisons.
+///
+/// # Returns
+///
+/// The adjusted p-values, in the order of the input, capped at 1.
+pub fn adjust_p_values(p_values: &[f64], method: PAdjust) -> Vec<f64> {
+    let m = p_values.len() as f64;
+    let mut order: Vec<usize> = (0..p_values.len()).collect();
+    order.sort_by(|&i, &j| p_values[i].total_cmp(&p_values[j]));
+
+    let mut adjusted = vec![0.0; p_values.len()];
+    match method {
+        PAdjust::Bonferroni => {
+            for (i, p) in p_values.iter().enumerate() {
+                adjusted[i] = (p * m).min(1.0);
+            }
+        }
+        PAdjust::Holm => {
+            let mut running_max: f64 = 0.0;
+            for (rank, &i) in order.iter().enumerate() {
+                running_max = running_max.max((p_values[i] * (m - rank as f64)).min(1.0));
+                adjusted[i] = running_max;
+            }
+        }
+        PAdjust::BenjaminiHochberg => {
+            let mut running_min: f64 = 1.0;
+            for (rank, &i) in order.iter().enumerate().rev() {
+                running_min = running_min.min(p_values[i] * m / (rank as f64 + 1.0));
+                adjusted[i] = running_min;
+            }
+        }
+    }
+    adjusted
+}
+
+/// The regularized upper incomplete gamma function Q(a, x), by its series for small `x` and its
+/// continued fraction otherwise (Numerical Recipes' gammq).
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let front = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * front
+    } else {
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = 1.0 / if d.abs() < TINY { TINY } else { d };
+            c = b + an / c;
+            c = if c.abs() < TINY { TINY } else { c };
+            h *= d * c;
+            if (d * c - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        front * h
+    }
+}
+
+/// Solves the linear system `matrix * x = rhs` by Gaussian elimination with partial pivoting.
+///
+/// Returns `None` if the matrix is singular.
+fn solve_linear(mut matrix: Vec<Vec<f64>>, mut x: Vec<f64>) -> Option<Vec<f64>> {
+    let m = x.len();
+    let scale = matrix.iter().flatten().fold(0.0, |max: f64, v| max.max(v.abs()));
+    for col in 0..m {
+        let pivot = (col..m).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        let magnitude = matrix[pivot][col].abs();
+        if magnitude.is_nan() || magnitude <= 1e-12 * scale {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        x.swap(col, pivot);
+        for row in col + 1..m {
+            let factor = matrix[row][col] / matrix[col][col];
+            let (upper, lower) = matrix.split_at_mut(row);
+            for (value, pivot) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *value -= factor * pivot;
+            }
+            x[row] -= factor * x[col];
+        }
+    }
+    for row in (0..m).rev() {
+        let tail: f64 = (row + 1..m).map(|j| matrix[row][j] * x[j]).sum();
+        x[row] = (x[row] - tail) / matrix[row][row];
+    }
+    Some(x)
+}
+
+/// A Kaplan–Meier estimate of a survival function.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SurvivalCurve {
+    /// The start (0) and each distinct event time, in increasing order
+    pub times: Vec<f64>,
+    /// The estimated probability of surviving past each time
+    pub survival: Vec<f64>,
+    /// The censoring times, with the estimated survival at each
+    pub censored: Vec<(f64, f64)>,
+}
+
+/// Estimates the survival function of subjects followed until an event or censoring.
+///
+/// Subjects with a non-finite time are ignored. A subject censored at the time of an event is
+/// still at risk for that event.
+///
+/// # Arguments
+///
+/// * `times` - The follow-up time of each subject
+/// * `events` - Whether each subject's follow-up ended with the event (`true`) or was censored
+pub fn kaplan_meier(times: &[f64], events: &[bool]) -> SurvivalCurve {
+    let mut subjects: Vec<(f64, bool)> = times.iter().copied().zip(events.iter().copied()).filter(|(t, _)| t.is_finite()).collect();
+    subjects.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut curve = SurvivalCurve { times: vec![0.0], survival: vec![1.0], censored: Vec::new() };
+    let mut survival = 1.0;
+    let mut start = 0;
+    while start < subjects.len() {
+        let time = subjects[start].0;
+        let end = start + subjects[start..].iter().take_while(|(t, _)| *t == time).count();
+        let deaths = subjects[start..end].iter().filter(|(_, event)| *event).count();
+        let at_risk = subjects.len() - start;
+        if deaths > 0 {
+            survival *= 1.0 - deaths as f64 / at_risk as f64;
+            curve.times.push(time);
+            curve.survival.push(survival);
+        }
+        curve.censored.extend(subjects[start..end].iter().filter(|(_, event)| !event).map(|_| (time, survival)));
+        start = end;
+    }
+    curve
+}
+
+/// Performs the log-rank test of whether groups of subjects have the same survival function.
+///
+/// Subjects with a non-finite time are ignored.
+///
+/// # Arguments
+///
+/// * `times` - The follow-up time of each subject
+/// * `events` - Whether each subject's follow-up ended with the event (`true`) or was censored
+/// * `groups` - The group of each subject
+///
+/// # Returns
+///
+/// The chi-squared statistic (with one degree of freedom less than the number of groups) and its
+/// p-value, or `None` if there are fewer than two groups or no events.
+pub fn log_rank_test<S: AsRef<str>>(times: &[f64], events: &[bool], groups: &[S]) -> Option<TestResult> {
+    let mut names: Vec<&str> = Vec::new();
+    let mut subjects: Vec<(f64, bool, usize)> = Vec::new();
+    for ((&time, &event), group) in times.iter().zip(events).zip(groups).filter(|((t, _), _)| t.is_finite()) {
+        let group = match names.iter().position(|name| *name == group.as_ref()) {
+            Some(i) => i,
+            None => {
+                names.push(group.as_ref());
+                names.len() - 1
+            }
+        };
+        subjects.push((time, event, group));
+    }
+    let k = names.len();
+    if k < 2 || !subjects.iter().any(|s| s.1) {
+        return None;
+    }
+    subjects.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Observed minus expected events and their covariance, over the first k - 1 groups
+    let mut at_risk = vec![0.0; k];
+    for s in &subjects {
+        at_risk[s.2] += 1.0;
+    }
+    let mut difference = vec![0.0; k - 1];
+    let mut covariance = vec![vec![0.0; k - 1]; k - 1];
+    let mut start = 0;
+    while start < subjects.len() {
+        let time = subjects[start].0;
+        let end = start + subjects[start..].iter().take_while(|s| s.0 == time).count();
+        let mut deaths = vec![0.0; k];
+        for s in subjects[start..end].iter().filter(|s| s.1) {
+            deaths[s.2] += 1.0;
+        }
+        let (d, n): (f64, f64) = (deaths.iter().sum(), at_risk.iter().sum());
+        if d > 0.0 {
+            let spread = if n > 1.0 { d * (n - d) / (n - 1.0) } else { 0.0 };
+            for i in 0..k - 1 {
+                difference[i] += deaths[i] - d * at_risk[i] / n;
+                for j in 0..k - 1 {
+                    let delta = if i == j { 1.0 } else { 0.0 };
+                    covariance[i][j] += spread * at_risk[i] / n * (delta - at_risk[j] / n);
+                }
+            }
+        }
+        for s in &subjects[start..end] {
+            at_risk[s.2] -= 1.0;
+        }
+        start = end;
+    }
+
+    let x = solve_linear(covariance, difference.clone())?;
+    let statistic: f64 = difference.iter().zip(&x).map(|(d, x)| d * x).sum();
+    let df = (k - 1) as f64;
+    Some(TestResult { statistic, p_value: upper_incomplete_gamma(df / 2.0, statistic / 2.0).clamp(0.0, 1.0) })
+}
+
+/// The quantile of Student's t distribution with `df` degrees of freedom leaving `alpha / 2` in
+/// each tail, found by bisection of [`t_test_p_value`].
+fn t_quantile(alpha: f64, df: f64) -> f64 {
+    let (mut low, mut high) = (0.0, 1e3);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if t_test_p_value(mid, df) > alpha {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// The cumulative distribution function of the noncentral t distribution with `df` degrees of
+/// freedom and noncentrality `delta` (Lenth's algorithm AS 243).
+fn noncentral_t_cdf(t: f64, df: f64, delta: f64) -> f64 {
+    let (t, delta, negative) = if t < 0.0 { (-t, -delta, true) } else { (t, delta, false) };
+    let x = t * t / (t * t + df);
+    let mut cdf = 0.0;
+    if x > 0.0 {
+        let lambda = delta * delta;
+        let mut p = 0.5 * (-0.5 * lambda).exp();
+        let mut q = (2.0 / core::f64::consts::PI).sqrt() * p * delta;
+        let mut s = 0.5 - p;
+        let (mut a, b) = (0.5, 0.5 * df);
+        let rxb = (1.0 - x).powf(b);
+        let log_beta = 0.5 * core::f64::consts::PI.ln() + ln_gamma(b) - ln_gamma(0.5 + b);
+        let mut x_odd = incomplete_beta(a, b, x);
+        let mut g_odd = 2.0 * rxb * (a * x.ln() - log_beta).exp();
+        let mut x_even = 1.0 - rxb;
+        let mut g_even = b * x * rxb;
+        cdf = p * x_odd + q * x_even;
+        for n in 1..=1000 {
+            a += 1.0;
+            x_odd -= g_odd;
+            x_even -= g_even;
+            g_odd *= x * (a + b - 1.0) / a;
+            g_even *= x * (a + b - 0.5) / (a + 0.5);
+            p *= lambda / (2.0 * n as f64);
+            q *= lambda / (2.0 * n as f64 + 1.0);
+            s -= p;
+            cdf += p * x_odd + q * x_even;
+            if (2.0 * s * (x_odd - g_odd)).abs() < 1e-12 {
+                break;
+            }
+        }
+    }
+    cdf += 0.5 * erfc(delta / core::f64::consts::SQRT_2);
+    let cdf = cdf.clamp(0.0, 1.0);
+    if negative {
+        1.0 - cdf
+    } else {
+        cdf
+    }
+}
+
+/// The power of a two-sided two-sample t-test with equal group sizes.
+///
+/// # Arguments
+///
+/// * `effect_size` - The difference in means divided by the common standard deviation (Cohen's d)
+/// * `n_per_group` - The number of subjects in each group, at least 2
+/// * `alpha` - The significance level
+pub fn two_sample_t_power(effect_size: f64, n_per_group: usize, alpha: f64) -> f64 {
+    if n_per_group < 2 {
+        return alpha;
+    }
+    let n = n_per_group as f64;
+    let df = 2.0 * n - 2.0;
+    let noncentrality = effect_size * (n / 2.0).sqrt();
+    let critical = t_quantile(alpha, df);
+    1.0 - noncentral_t_cdf(critical, df, noncentrality) + noncentral_t_cdf(-critical, df, noncentrality)
+}
+
+/// The smallest number of subjects per group for a two-sided two-sample t-test to reach the
+/// given power, see [`two_sample_t_power`].
+///
+/// # Returns
+///
+/// The sample size per group, or `None` if the effect size is zero or more than `max_n` subjects
+/// per group would be needed.
+pub fn two_sample_t_sample_size(effect_size: f64, alpha: f64, power: f64, max_n: usize) -> Option<usize> {
+    if effect_size == 0.0 || !effect_size.is_finite() {
+        return None;
+    }
+    (2..=max_n).find(|&n| two_sample_t_power(effect_size, n, alpha) >= power)
+}
+
+/// A four-parameter logistic (4PL) dose-response curve,
+/// `response = bottom + (top - bottom) / (1 + (ec50 / concentration)^hill)`.
+///
+/// `bottom` is the response at zero concentration and `hill` is positive, so `top` is below
+/// `bottom` for inhibition curves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogisticFit {
+    pub bottom: f64,
+    pub top: f64,
+    /// The concentration giving a response halfway between bottom and top
+    pub ec50: f64,
+    /// The Hill slope
+    pub hill: f64,
+    /// The standard deviation of the residuals, with four degrees of freedom spent on the fit
+    pub residual_sd: f64,
+    /// The covariance of (bottom, top, log10 ec50, hill)
+    covariance: Vec<Vec<f64>>,
+    degrees_of_freedom: f64,
+}
+
+impl LogisticFit {
+    /// The parameters as fitted: (bottom, top, log10 ec50, hill)
+    fn parameters(&self) -> [f64; 4] {
+        [self.bottom, self.top, self.ec50.log10(), self.hill]
+    }
+
+    /// The fitted response and its gradient with respect to the parameters at a concentration
+    fn evaluate(parameters: &[f64; 4], concentration: f64) -> (f64, [f64; 4]) {
+        let [bottom, top, log_ec50, hill] = *parameters;
+        let distance = log_ec50 - concentration.log10();
+        let u = 10f64.powf(distance * hill);
+        let f = 1.0 / (1.0 + u);
+        let slope = -(top - bottom) * f * f * u * core::f64::consts::LN_10;
+        (bottom + (top - bottom) * f, [1.0 - f, f, slope * hill, slope * distance])
+    }
+
+    /// The fitted response at a concentration.
+    pub fn predict(&self, concentration: f64) -> f64 {
+        Self::evaluate(&self.parameters(), concentration).0
+    }
+
+    /// The 95% confidence interval of the fitted response at a concentration, from the
+    /// covariance of the parameters (delta method).
+    pub fn confidence_interval(&self, concentration: f64) -> (f64, f64) {
+        let (response, gradient) = Self::evaluate(&self.parameters(), concentration);
+        let variance: f64 = (0..4).map(|i| (0..4).map(|j| gradient[i] * self.covariance[i][j] * gradient[j]).sum::<f64>()).sum();
+        let half_width = t_quantile(0.05, self.degrees_of_freedom) * variance.max(0.0).sqrt();
+        (response - half_width, response + half_width)
+    }
+
+    /// The concentration giving a response, or `None` if the response is outside the curve.
+    pub fn inverse(&self, response: f64) -> Option<f64> {
+        let ratio = (self.top - self.bottom) / (response - self.bottom);
+        (ratio > 1.0).then(|| self.ec50 / (ratio - 1.0).powf(1.0 / self.hill))
+    }
+
+    /// The limit of detection: the concentration at which the fitted response departs from the
+    /// zero-concentration response by three residual standard deviations.
+    pub fn lod(&self) -> Option<f64> {
+        let direction = if self.top >= self.bottom { 1.0 } else { -1.0 };
+        self.inverse(self.bottom + direction * 3.0 * self.residual_sd)
+    }
+}
+
+/// Fits a four-parameter logistic curve to dose-response data with the Levenberg–Marquardt
+/// algorithm.
+///
+/// Points with a non-positive or non-finite concentration or a non-finite response are ignored.
+///
+/// # Returns
+///
+/// The fit, or `None` if fewer than five points remain, the concentrations are all equal or the
+/// fit does not converge.
+pub fn fit_four_parameter_logistic(concentrations: &[f64], responses: &[f64]) -> Option<LogisticFit> {
+    let mut points: Vec<(f64, f64)> = concentrations
+        .iter()
+        .zip(responses)
+        .filter(|(c, r)| c.is_finite() && **c > 0.0 && r.is_finite())
+        .map(|(c, r)| (*c, *r))
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (first, last) = (*points.first()?, *points.last()?);
+    if points.len() < 5 || first.0 == last.0 {
+        return None;
+    }
+
+    let rss = |parameters: &[f64; 4]| -> f64 {
+        points.iter().map(|&(c, r)| (r - LogisticFit::evaluate(parameters, c).0).powi(2)).sum()
+    };
+    let normal_equations = |parameters: &[f64; 4]| {
+        let mut jtj = vec![vec![0.0; 4]; 4];
+        let mut jtr = vec![0.0; 4];
+        for &(c, r) in &points {
+            let (fitted, gradient) = LogisticFit::evaluate(parameters, c);
+            for i in 0..4 {
+                jtr[i] += gradient[i] * (r - fitted);
+                for j in 0..4 {
+                    jtj[i][j] += gradient[i] * gradient[j];
+                }
+            }
+        }
+        (jtj, jtr)
+    };
+
+    let log_mid = (first.0.log10() + last.0.log10()) / 2.0;
+    let mut parameters = [first.1, last.1, log_mid, 1.0];
+    let mut current = rss(&parameters);
+    let mut lambda = 1e-3;
+    for _ in 0..500 {
+        let (jtj, jtr) = normal_equations(&parameters);
+        let mut damped = jtj.clone();
+        for (i, row) in damped.iter_mut().enumerate() {
+            row[i] += lambda * jtj[i][i].max(1e-12);
+        }
+        let Some(step) = solve_linear(damped, jtr) else {
+            lambda *= 10.0;
+            continue;
+        };
+        let candidate = core::array::from_fn(|i| parameters[i] + step[i]);
+        let candidate_rss = rss(&candidate);
+        if candidate_rss.is_finite() && candidate_rss <= current {
+            let converged = current - candidate_rss <= 1e-12 * current.max(1e-300);
+            parameters = candidate;
+            current = candidate_rss;
+            lambda = (lambda / 10.0).max(1e-12);
+            if converged {
+                break;
+            }
+        } else {
+            lambda *= 10.0;
+            if lambda > 1e12 {
+                break;
+            }
+        }
+    }
+    if !parameters.iter().all(|p| p.is_finite()) {
+        return None;
+    }
+    // A negative slope is the same curve with bottom and top swapped
+    if parameters[3] < 0.0 {
+        parameters = [parameters[1], parameters[0], parameters[2], -parameters[3]];
+    }
+
+    let degrees_of_freedom = (points.len() - 4) as f64;
+    let variance = current / degrees_of_freedom;
+    let (jtj, _) = normal_equations(&parameters);
+    // The inverse of the symmetric J'J, one column (equal to its row) at a time
+    let covariance = (0..4)
+        .map(|i| {
+            let unit: Vec<f64> = (0..4).map(|j| if i == j { 1.0 } else { 0.0 }).collect();
+            Some(solve_linear(jtj.clone(), unit)?.into_iter().map(|value| value * variance).collect())
+        })
+        .collect::<Option<Vec<Vec<f64>>>>()?;
+    Some(LogisticFit {
+        bottom: parameters[0],
+        top: parameters[1],
+        ec50: 10f64.powf(parameters[2]),
+        hill: parameters[3],
+        residual_sd: variance.sqrt(),
+        covariance,
+        degrees_of_freedom,
+    })
+}
+
+/// How out-of-trend values of a series (e.g. a QC metric across runs) are detected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnomalyRule {
+    /// Flag values more than `threshold` standard deviations from the mean of the `window`
+    /// values before them. The first three values have too little history to be flagged.
+    RollingZScore { window: usize, threshold: f64 },
+    /// Flag values more than `factor` interquartile ranges below the first or above the third
+    /// quartile of the whole series (Tukey's fences).
+    Iqr { factor: f64 },
+}
+
+impl Default for AnomalyRule {
+    /// A rolling z-score over the last 10 values, flagging values beyond 3 standard deviations
+    fn default() -> Self {
+        AnomalyRule::RollingZScore { window: 10, threshold: 3.0 }
+    }
+}
+
+impl AnomalyRule {
+    /// Finds the out-of-trend values of a series, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The series, in chronological order. Non-finite values are never flagged.
+    ///
+    /// # Returns
+    ///
+    /// The index of each flagged value, with its deviation: the z-score for
+    /// [`AnomalyRule::RollingZScore`], or the signed distance from the nearest quartile in
+    /// interquartile ranges for [`AnomalyRule::Iqr`].
+    pub fn detect(&self, values: &[f64]) -> Vec<(usize, f64)> {
+        let deviations: Vec<Option<f64>> = match *self {
+            AnomalyRule::RollingZScore { window, threshold } => (0..values.len())
+                .map(|i| {
+                    let history: Vec<f64> = values[i.saturating_sub(window.max(3))..i].iter().copied().filter(|v| v.is_finite()).collect();
+                    if history.len() < 3 {
+                        return None;
+                    }
+                    let (m, sd) = (mean(&history), std_dev(&history));
+                    let z = (values[i] - m) / sd;
+                    (sd > 0.0 && z.abs() > threshold).then_some(z)
+                })
+                .collect(),
+            AnomalyRule::Iqr { factor } => {
+                let sorted = sorted_finite(values);
+                let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+                let iqr = q3 - q1;
+                values
+                    .iter()
+                    .map(|&value| {
+                        let distance = if value < q1 { value - q1 } else { (value - q3).max(0.0) };
+                        (iqr > 0.0 && (distance / iqr).abs() > factor).then(|| distance / iqr)
+                    })
+                    .collect()
+            }
+        };
+        deviations
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, deviation)| Some((i, deviation.filter(|d| d.is_finite())?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ks_two_sample() {
+        let a: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let same = ks_two_sample(&a, &a).unwrap();
+        assert_eq!(same.statistic, 0.0);
+        assert_eq!(same.p_value, 1.0);
+
+        let shifted: Vec<f64> = a.iter().map(|v| v + 50.0).collect();
+        let result = ks_two_sample(&a, &shifted).unwrap();
+        assert!((result.statistic - 0.5).abs() < 1e-12);
+        assert!(result.p_value < 1e-8);
+        assert!(ks_two_sample(&a, &[f64::NAN]).is_none());
+    }
+
+    #[test]
+    fn test_roc_and_precision_recall() {
+        let scores = [0.9, 0.8, 0.7, 0.6, 0.6, f64::NAN];
+        let labels = [1, 1, -1, 1, -1, 1];
+        let roc = roc_curve(&scores, &labels).unwrap();
+        assert_eq!(roc.x, vec![0.0, 0.0, 0.0, 0.5, 1.0]);
+        assert_eq!(roc.y, vec![0.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert!((roc.auc - (2.0 / 3.0 * 0.5 + 5.0 / 6.0 * 0.5)).abs() < 1e-12);
+
+        let pr = precision_recall_curve(&scores, &labels).unwrap();
+        assert_eq!(pr.y, vec![1.0, 1.0, 1.0, 2.0 / 3.0, 0.6]);
+        assert!((pr.auc - (2.0 / 3.0 + 0.6 / 3.0)).abs() < 1e-12);
+
+        assert!(roc_curve(&[0.5, 0.4], &[1, 1]).is_none());
+        assert!(precision_recall_curve(&[0.5], &[-1]).is_none());
+    }
+
+    #[test]
+    fn test_group_comparisons() {
+        // Reference p-values integrated numerically from the t and F densities
+        let a = [5.1, 4.9, 6.2, 5.8, 6.0, 5.5, 5.3];
+        let b = [6.8, 7.1, 6.5, 7.4, 6.9, 7.7];
+        let c = [5.9, 6.1, 6.4, 5.7, 6.3];
+        let t = welch_t_test(&a, &b).unwrap();
+        assert!((t.statistic - -6.02809).abs() < 1e-5, "{:?}", t);
+        assert!((t.p_value - 8.7253e-5).abs() < 1e-8, "{:?}", t);
+
+        let u = mann_whitney_u(&a, &b).unwrap();
+        assert_eq!(u.statistic, 0.0);
+        assert!((u.p_value - 0.0034052).abs() < 1e-6, "{:?}", u);
+
+        let anova = one_way_anova(&[&a, &b, &c]).unwrap();
+        assert!((anova.statistic - 21.60771).abs() < 1e-5, "{:?}", anova);
+        assert!((anova.p_value - 3.8274e-5).abs() < 1e-8, "{:?}", anova);
+        assert!(welch_t_test(&[1.0], &b).is_none());
+
+        let p = [0.01, 0.04, 0.03];
+        assert_eq!(adjust_p_values(&p, PAdjust::Bonferroni), vec![0.03, 0.12, 0.09]);
+        assert_eq!(adjust_p_values(&p, PAdjust::Holm), vec![0.03, 0.06, 0.06]);
+        assert_eq!(adjust_p_values(&p, PAdjust::BenjaminiHochberg), vec![0.03, 0.04, 0.04]);
+    }
+
+    #[test]
+    fn test_survival() {
+        let times = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 3.0, 5.0, 7.0, 8.0, 9.0, 10.0];
+        let events = [true, true, false, true, true, false, false, true, true, true, false, true];
+        let curve = kaplan_meier(&times[..6], &events[..6]);
+        assert_eq!(curve.times, vec![0.0, 1.0, 2.0, 4.0, 5.0]);
+        let expected = [1.0, 5.0 / 6.0, 4.0 / 6.0, 4.0 / 9.0, 2.0 / 9.0];
+        assert!(curve.survival.iter().zip(expected).all(|(s, e)| (s - e).abs() < 1e-12));
+        assert_eq!(curve.censored.len(), 2);
+        assert_eq!(curve.censored[0].0, 3.0);
+
+        // Reference statistics from the textbook formulas, chi-squared p-values in closed form
+        let groups = ["A", "A", "A", "A", "A", "A", "B", "B", "B", "B", "B", "B"];
+        let result = log_rank_test(&times, &events, &groups).unwrap();
+        assert!((result.statistic - 4.1082855).abs() < 1e-6, "{:?}", result);
+        assert!((result.p_value - 0.0426736).abs() < 1e-6, "{:?}", result);
+
+        let times = [&times[..], &[2.0, 4.0, 6.0, 11.0]].concat();
+        let events = [&events[..], &[true, false, true, true]].concat();
+        let groups = [&groups[..], &["C"; 4]].concat();
+        let result = log_rank_test(&times, &events, &groups).unwrap();
+        assert!((result.statistic - 3.4662629).abs() < 1e-6, "{:?}", result);
+        assert!((result.p_value - 0.1767301).abs() < 1e-6, "{:?}", result);
+        assert!(log_rank_test(&times, &events, &["A"; 16]).is_none());
+    }
+
+    #[test]
+    fn test_four_parameter_logistic() {
+        let truth = |c: f64| 2.0 + (98.0 - 2.0) / (1.0 + (1.5f64 / c).powf(1.2));
+        let concentrations = [0.01, 0.03, 0.1, 0.3, 1.0, 3.0, 10.0, 30.0, 100.0];
+        let noise = [0.4, -0.3, 0.2, -0.5, 0.3, -0.2, 0.4, -0.1, -0.3];
+        let responses: Vec<f64> = concentrations.iter().zip(noise).map(|(&c, e)| truth(c) + e).collect();
+
+        let fit = fit_four_parameter_logistic(&concentrations, &responses).unwrap();
+        assert!((fit.ec50 - 1.5).abs() < 0.1, "{:?}", fit);
+        assert!((fit.hill - 1.2).abs() < 0.1, "{:?}", fit);
+        assert!((fit.bottom - 2.0).abs() < 1.0 && (fit.top - 98.0).abs() < 1.0, "{:?}", fit);
+        assert!((fit.inverse(fit.predict(0.7)).unwrap() - 0.7).abs() < 1e-9);
+        let (low, high) = fit.confidence_interval(1.5);
+        assert!(low < fit.predict(1.5) && fit.predict(1.5) < high);
+        assert!(fit.lod().unwrap() < fit.ec50);
+
+        let decreasing: Vec<f64> = responses.iter().map(|r| 100.0 - r).collect();
+        let fit = fit_four_parameter_logistic(&concentrations, &decreasing).unwrap();
+        assert!(fit.hill > 0.0 && fit.top < fit.bottom, "{:?}", fit);
+        assert!(fit_four_parameter_logistic(&concentrations[..4], &responses[..4]).is_none());
+    }
+
+    #[test]
+    fn test_power() {
+        // Sample sizes and powers as given by G*Power for two-tailed tests at alpha = 0.05
+        assert_eq!(two_sample_t_sample_size(0.5, 0.05, 0.8, 1000), Some(64));
+        assert_eq!(two_sample_t_sample_size(0.8, 0.05, 0.8, 1000), Some(26));
+        assert_eq!(two_sample_t_sample_size(0.5, 0.05, 0.9, 1000), Some(86));
+        assert!((two_sample_t_power(0.5, 64, 0.05) - 0.8015).abs() < 1e-3);
+        assert!((two_sample_t_power(-0.5, 64, 0.05) - 0.8015).abs() < 1e-3);
+        assert_eq!(two_sample_t_sample_size(0.1, 0.05, 0.8, 100), None);
+    }
+
+    #[test]
+    fn test_anomaly_rules() {
+        let values = [10.0, 11.0, 10.0, 11.0, 10.0, 30.0, 10.5, f64::NAN];
+        let flagged = AnomalyRule::default().detect(&values);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].0, 5);
+        assert!(flagged[0].1 > 3.0);
+
+        let flagged = AnomalyRule::Iqr { factor: 1.5 }.detect(&values);
+        assert_eq!(flagged, vec![(5, (30.0 - 11.0) / 1.0)]);
+        assert!(AnomalyRule::default().detect(&[1.0, 1.0, 1.0, 1.0]).is_empty());
+    }
+
+    #[test]
+    fn test_bin_width() {
+        let values: Vec<f64> = (0..=64).map(|i| i as f64).collect();
+        assert_eq!(quantile(&values, 0.25), 16.0);
+        assert_eq!(bin_width(&values, BinRule::Sturges), 64.0 / 8.0);
+        assert!((bin_width(&values, BinRule::FreedmanDiaconis) - 2.0 * 32.0 / 65f64.cbrt()).abs() < 1e-12);
+        assert_eq!(bin_width(&values, BinRule::Count(16)), 4.0);
+        assert_eq!(bin_width(&[3.0, 3.0], BinRule::FreedmanDiaconis), 1.0);
+    }
+}
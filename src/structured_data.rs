@@ -0,0 +1,136 @@
+//! Structured data (JSON-LD) describing a report, for intranet search engines to index.
+//!
+//! When enabled with [`Report::with_structured_data`], the report's `<head>` embeds a schema.org
+//! `Dataset` with the report's title, description, author, generating software, tags, header
+//! fields, sections and key metrics:
+//!
+//! ```json
+//! {
+//!   "@context": "https://schema.org",
+//!   "@type": "Dataset",
+//!   "name": "QC Report",
+//!   "creator": { "@type": "SoftwareApplication", "name": "Redeem", "softwareVersion": "1.0" },
+//!   "hasPart": [{ "@type": "CreativeWork", "name": "Summary", "url": "#summary" }],
+//!   "variableMeasured": [{ "@type": "PropertyValue", "name": "median_cv", "value": 0.24 }]
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use maud::{html, Markup, PreEscaped};
+use serde_json::{json, Map, Value};
+
+use crate::narrative::Metrics;
+use crate::Report;
+
+/// A schema.org `PropertyValue`
+fn property_value(name: &str, value: Value) -> Value {
+    json!({ "@type": "PropertyValue", "name": name, "value": value })
+}
+
+impl Report {
+    /// Embeds a schema.org JSON-LD description of the report in its `<head>`, see
+    /// [`structured_data`](crate::structured_data). Off by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to embed the description.
+    pub fn with_structured_data(mut self, enabled: bool) -> Self {
+        self.structured_data = enabled;
+        self
+    }
+
+    /// Sets the key metrics of the run (e.g. the median CV or the number of identifications),
+    /// listed as the measured variables of the report's structured data.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - The metrics, e.g. those the report's narrative is generated from.
+    pub fn with_key_metrics(mut self, metrics: Metrics) -> Self {
+        self.key_metrics = metrics;
+        self
+    }
+
+    /// The JSON-LD description of the report
+    pub(crate) fn json_ld(&self) -> Value {
+        let mut data = Map::new();
+        data.insert("@context".into(), "https://schema.org".into());
+        data.insert("@type".into(), "Dataset".into());
+        data.insert("name".into(), self.title.clone().into());
+        if let Some(description) = &self.description {
+            data.insert("description".into(), description.clone().into());
+        }
+        if let Some(author) = &self.author {
+            data.insert("author".into(), json!({ "@type": "Person", "name": author }));
+        }
+        #[cfg(feature = "std")]
+        data.insert("dateCreated".into(), self.generated_at().to_rfc3339().into());
+        data.insert(
+            "creator".into(),
+            json!({ "@type": "SoftwareApplication", "name": self.software_name, "softwareVersion": self.version }),
+        );
+        if !self.tags.is_empty() {
+            let keywords: Vec<String> = self.tags.iter().map(|(key, value)| format!("{}: {}", key, value)).collect();
+            data.insert("keywords".into(), keywords.into());
+        }
+        if !self.header_fields.is_empty() {
+            let fields: Vec<Value> = self.header_fields.iter().map(|(key, value)| property_value(key, value.clone().into())).collect();
+            data.insert("additionalProperty".into(), fields.into());
+        }
+        let sections: Vec<Value> = self
+            .sections
+            .iter()
+            .zip(self.section_anchors())
+            .map(|(section, anchors)| json!({ "@type": "CreativeWork", "name": section.title, "url": format!("#{}", anchors.anchor) }))
+            .collect();
+        if !sections.is_empty() {
+            data.insert("hasPart".into(), sections.into());
+        }
+        let metrics: Vec<Value> = self.key_metrics.iter().map(|(name, value)| property_value(name, value.into())).collect();
+        if !metrics.is_empty() {
+            data.insert("variableMeasured".into(), metrics.into());
+        }
+        Value::Object(data)
+    }
+
+    /// Render the JSON-LD description for the `<head>`, if enabled
+    pub(crate) fn render_structured_data(&self) -> Markup {
+        html! {
+            @if self.structured_data {
+                script type="application/ld+json" {
+                    (PreEscaped(serde_json::to_string(&self.json_ld()).unwrap_or_default().replace("</", "<\\/")))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReportSection;
+
+    #[test]
+    fn test_structured_data() {
+        let report = Report::new("Redeem", "1.0", None, "QC Report");
+        assert!(!report.to_string().contains("application/ld+json"));
+
+        let mut metrics = Metrics::new();
+        metrics.record("median_cv", 0.24);
+        let mut report = report.with_structured_data(true).with_key_metrics(metrics).with_description("Weekly </script> QC");
+        report.add_header_field("Project", "PXD000001");
+        report.add_section(ReportSection::new("Summary"));
+
+        let json_ld = report.json_ld();
+        assert_eq!(json_ld["@type"], "Dataset");
+        assert_eq!(json_ld["creator"], json!({ "@type": "SoftwareApplication", "name": "Redeem", "softwareVersion": "1.0" }));
+        assert_eq!(json_ld["hasPart"], json!([{ "@type": "CreativeWork", "name": "Summary", "url": "#summary" }]));
+        assert_eq!(json_ld["additionalProperty"][0], json!({ "@type": "PropertyValue", "name": "Project", "value": "PXD000001" }));
+        assert_eq!(json_ld["variableMeasured"][0], json!({ "@type": "PropertyValue", "name": "median_cv", "value": 0.24 }));
+
+        let html = report.to_string();
+        assert!(html.contains(r#"<script type="application/ld+json">{"@context":"https://schema.org","@type":"Dataset","name":"QC Report","description":"Weekly <\/script> QC""#));
+    }
+}
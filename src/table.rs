@@ -0,0 +1,789 @@
+//! Interactive (sortable, searchable) tables.
+//!
+//! Each [`Table`] gets its own element ID and DataTables init script, so any number of tables can
+//! be added to a report with [`ReportSection::add_table`](crate::ReportSection::add_table).
+//!
+//! Beyond sorting, searching and CSV export, tables can:
+//!
+//! - take DataTables settings such as the page length, initial sort order, fixed header or column
+//!   visibility toggles, see [`TableOptions`]
+//! - show numbers in scientific, fixed, thousands or percent notation while still sorting by value
+//!   ([`Table::col_format`])
+//! - color a column by value ([`Table::col_color_scale`]) and highlight rows matching a condition,
+//!   e.g. a q-value below 0.01 ([`Table::highlight_rows`])
+//! - be built from serializable records ([`Table::from_serializable`]), structs deriving
+//!   [`ReportTable`] (`derive` feature) or CSV/TSV files (`csv` feature)
+
+use alloc::{format, vec};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use maud::{html, Markup, PreEscaped};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::assets::Library;
+use crate::components::Component;
+use crate::narrative::Condition;
+use crate::schema::BlockData;
+use crate::ReportError;
+
+/// A table rendered with DataTables, built up row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    id: String,
+    caption: Option<String>,
+    headers: Vec<String>,
+    /// The cells of each row, as HTML
+    rows: Vec<Vec<String>>,
+    options: TableOptions,
+    /// Number formats of columns, by header
+    formats: Vec<(String, Format)>,
+    /// Background color scales of columns, by header
+    color_scales: Vec<(String, ColorScale)>,
+    /// Background colors of rows whose value in a column meets a condition
+    highlights: Vec<(String, Condition, String)>,
+}
+
+/// A sequence of colors mapping the numbers of a column to cell backgrounds, see
+/// [`Table::col_color_scale`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScale {
+    /// Red for the lowest values, yellow in the middle and green for the highest
+    RdYlGn,
+    /// Green for the lowest values, yellow in the middle and red for the highest, e.g. for q-values
+    GnYlRd,
+    /// White for the lowest values to blue for the highest
+    Blues,
+    /// The perceptually uniform viridis scale, purple to yellow
+    Viridis,
+}
+
+impl ColorScale {
+    /// The colors at the start, middle and end of the scale
+    fn stops(&self) -> [(u8, u8, u8); 3] {
+        match self {
+            ColorScale::RdYlGn => [(0xfc, 0x8d, 0x59), (0xff, 0xff, 0xbf), (0x91, 0xcf, 0x60)],
+            ColorScale::GnYlRd => [(0x91, 0xcf, 0x60), (0xff, 0xff, 0xbf), (0xfc, 0x8d, 0x59)],
+            ColorScale::Blues => [(0xf7, 0xfb, 0xff), (0xc6, 0xdb, 0xef), (0x6b, 0xae, 0xd6)],
+            ColorScale::Viridis => [(0x44, 0x01, 0x54), (0x21, 0x91, 0x8c), (0xfd, 0xe7, 0x25)],
+        }
+    }
+
+    /// The color at a position of the scale, from 0 (start) to 1 (end), as "#rrggbb"
+    pub fn color(&self, position: f64) -> String {
+        let position = if position.is_nan() { 0.5 } else { position.clamp(0.0, 1.0) };
+        let stops = self.stops();
+        let (from, to, t) = if position < 0.5 { (stops[0], stops[1], position * 2.0) } else { (stops[1], stops[2], position * 2.0 - 1.0) };
+        // `f64::round` needs std; the mix is never negative, so adding 0.5 and truncating rounds it
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t + 0.5) as u8;
+        format!("#{:02x}{:02x}{:02x}", mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+/// The style of a cell with the given background, with white text on dark backgrounds
+fn background_style(color: &str) -> String {
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap_or(255) as f64;
+    let luminance = 0.299 * channel(1) + 0.587 * channel(3) + 0.114 * channel(5);
+    if luminance < 128.0 {
+        format!("background-color: {}; color: #fff;", color)
+    } else {
+        format!("background-color: {};", color)
+    }
+}
+
+/// How the numbers of a column are shown, see [`Table::col_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Scientific notation with the given number of decimals, e.g. "1.23e-5"
+    Scientific(usize),
+    /// Fixed-point notation with the given number of decimals, e.g. "0.123"
+    Fixed(usize),
+    /// Rounded to an integer with thousands separators, e.g. "12,345"
+    Thousands,
+    /// A fraction as a percentage with the given number of decimals, e.g. 0.123 as "12.3%"
+    Percent(usize),
+}
+
+impl Format {
+    /// Format a number; non-finite values are shown as they are
+    pub fn apply(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        match *self {
+            Format::Scientific(decimals) => format!("{:.*e}", decimals, value),
+            Format::Fixed(decimals) => format!("{:.*}", decimals, value),
+            Format::Thousands => {
+                let digits = format!("{:.0}", value.abs());
+                let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+                for (i, digit) in digits.chars().enumerate() {
+                    if i > 0 && (digits.len() - i) % 3 == 0 {
+                        grouped.push(',');
+                    }
+                    grouped.push(digit);
+                }
+                if value < 0.0 && digits.chars().any(|digit| digit != '0') {
+                    grouped.insert(0, '-');
+                }
+                grouped
+            }
+            Format::Percent(decimals) => format!("{:.*}%", decimals, value * 100.0),
+        }
+    }
+}
+
+/// The direction a column is sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// DataTables settings of a table, see [`ReportSection::add_table_with_opts`](crate::ReportSection::add_table_with_opts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableOptions {
+    /// The number of rows per page.
+    pub page_length: usize,
+    /// Split the rows into pages; all rows are shown at once otherwise.
+    pub paging: bool,
+    /// The index of the column the table is sorted by when it loads, and in which direction.
+    /// `None` keeps the order the rows were added in.
+    pub order: Option<(usize, SortDirection)>,
+    /// Keep the header in view by scrolling the rows within this height in pixels.
+    pub fixed_header: Option<u32>,
+    /// Scroll wide tables horizontally instead of overflowing the page.
+    pub scroll_x: bool,
+    /// Show a checkbox per column above the table, hiding or showing the column.
+    pub column_toggles: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            page_length: 10,
+            paging: true,
+            order: None,
+            fixed_header: None,
+            scroll_x: true,
+            column_toggles: false,
+        }
+    }
+}
+
+impl TableOptions {
+    /// The DataTables initialization settings
+    fn settings(&self) -> Value {
+        let mut settings = json!({
+            "paging": self.paging,
+            "pageLength": self.page_length,
+            "searching": true,
+            "ordering": true,
+            "order": self.order.map_or(json!([]), |(column, direction)| {
+                json!([[column, if direction == SortDirection::Ascending { "asc" } else { "desc" }]])
+            }),
+            "scrollX": self.scroll_x,
+            "autoWidth": false,
+            "colResize": { "enable": true, "resizeTable": true },
+        });
+        if let Some(height) = self.fixed_header {
+            settings["scrollY"] = json!(format!("{}px", height));
+            settings["scrollCollapse"] = json!(true);
+        }
+        settings
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Table {
+    /// Creates an empty table with a unique ID, numbered within its section when the report is
+    /// rendered.
+    pub fn new() -> Self {
+        Table {
+            id: format!("table-{}", crate::ids::placeholder()),
+            caption: None,
+            headers: Vec::new(),
+            rows: Vec::new(),
+            options: TableOptions::default(),
+            formats: Vec::new(),
+            color_scales: Vec::new(),
+            highlights: Vec::new(),
+        }
+    }
+
+    /// Creates a table from serializable records, one row per record.
+    ///
+    /// The field names become the column headers, in declaration order. Fields missing from some
+    /// records (e.g. skipped `None`s) are left empty, and nested values are shown as JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - The records, each serializing to a map (e.g. a struct with named fields).
+    ///
+    /// # Returns
+    ///
+    /// The table, or [`ReportError::Serialization`] if a record cannot be serialized and
+    /// [`ReportError::InvalidArgument`] if it does not serialize to a map.
+    pub fn from_serializable<T: Serialize>(records: &[T]) -> Result<Self, ReportError> {
+        let mut headers: Vec<String> = Vec::new();
+        let mut objects = Vec::with_capacity(records.len());
+        for record in records {
+            let Value::Object(object) = serde_json::to_value(record)? else {
+                return Err(ReportError::InvalidArgument(
+                    "Records must serialize to maps (e.g. structs with named fields)".to_string(),
+                ));
+            };
+            for key in object.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+            objects.push(object);
+        }
+
+        let rows = objects.iter().map(|object| {
+            headers.iter().map(|header| match object.get(header) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(text)) => text.clone(),
+                Some(value) => value.to_string(),
+            })
+        });
+        Ok(Table::new().headers(headers.clone()).add_rows(rows))
+    }
+
+    /// Creates a table from a delimited text file, e.g. a CSV or TSV pipeline output.
+    ///
+    /// Fields may be quoted with `"` (doubling quotes inside), so they can contain delimiters and
+    /// line breaks. The first record becomes the column headers, unless all its fields are numbers,
+    /// in which case it is kept as data and the columns are named "Column 1", "Column 2", etc.
+    ///
+    /// Records are streamed from the file, which is only read up to `max_rows`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `delimiter` - The field delimiter, an ASCII character such as `','` or `'\t'`.
+    /// * `max_rows` - The maximum number of data rows to read, e.g. to preview large files.
+    ///
+    /// # Returns
+    ///
+    /// The table, or [`ReportError::Io`] if the file cannot be read and
+    /// [`ReportError::InvalidArgument`] if the delimiter is not ASCII, a record is not valid UTF-8
+    /// or does not have one field per column.
+    #[cfg(feature = "csv")]
+    pub fn from_csv_path<P: AsRef<std::path::Path>>(path: P, delimiter: char, max_rows: Option<usize>) -> Result<Self, ReportError> {
+        let delimiter = u8::try_from(delimiter)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or_else(|| ReportError::InvalidArgument(format!("Delimiter {:?} is not an ASCII character", delimiter)))?;
+        let reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .map_err(csv_error)?;
+
+        let mut records = reader
+            .into_records()
+            .map(|record| record.map(|record| record.iter().map(str::to_string).collect::<Vec<String>>()).map_err(csv_error));
+        let Some(first) = records.next().transpose()? else {
+            return Ok(Table::new());
+        };
+        let (headers, first_row) = if first.iter().all(|field| field.trim().parse::<f64>().is_ok()) {
+            ((1..=first.len()).map(|i| format!("Column {}", i)).collect(), Some(Ok(first)))
+        } else {
+            (first, None)
+        };
+
+        let mut table = Table::new().headers(headers);
+        for (i, record) in first_row.into_iter().chain(records).take(max_rows.unwrap_or(usize::MAX)).enumerate() {
+            let record = record?;
+            if record.len() != table.headers.len() {
+                let message = format!("row {} has {} fields, expected {}", i + 1, record.len(), table.headers.len());
+                return Err(ReportError::InvalidArgument(message));
+            }
+            table = table.add_row(record);
+        }
+        Ok(table)
+    }
+
+    /// Sets the element ID of the table, e.g. to link to it or style it.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Sets the DataTables settings of the table, e.g. its page length or initial sort order.
+    pub fn options(mut self, options: TableOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets how the numbers of a column are shown, e.g. q-values in scientific notation.
+    ///
+    /// Cells that are numbers are formatted when the table is rendered and sorted by their value,
+    /// so "1e-5" sorts before "0.01"; other cells are shown as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the column; formats of unknown columns are ignored.
+    /// * `format` - The number format, e.g. `Format::Scientific(2)`.
+    pub fn col_format(mut self, header: &str, format: Format) -> Self {
+        self.formats.retain(|(h, _)| h != header);
+        self.formats.push((header.to_string(), format));
+        self
+    }
+
+    /// Colors the background of a column's cells by their value, from the lowest to the highest
+    /// number of the column, e.g. to spot low scores at a glance. Cells that are not numbers are
+    /// left uncolored.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the column; scales of unknown columns are ignored.
+    /// * `scale` - The colors, e.g. `ColorScale::RdYlGn`.
+    pub fn col_color_scale(mut self, header: &str, scale: ColorScale) -> Self {
+        self.color_scales.retain(|(h, _)| h != header);
+        self.color_scales.push((header.to_string(), scale));
+        self
+    }
+
+    /// Highlights the rows whose value in a column meets a condition, e.g. rows with a q-value
+    /// below 0.01. When several rules match a row, the first one added sets its color.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the column tested; rules on unknown columns are ignored.
+    /// * `condition` - The condition on the cell's number; cells that are not numbers never match.
+    /// * `color` - The CSS background color of matching rows, e.g. "#d4edda".
+    pub fn highlight_rows(mut self, header: &str, condition: Condition, color: &str) -> Self {
+        self.highlights.push((header.to_string(), condition, color.to_string()));
+        self
+    }
+
+    /// The number of a cell, if it is one
+    fn cell_value(cell: &str) -> Option<f64> {
+        cell.trim().parse::<f64>().ok()
+    }
+
+    /// The background style of each cell of each row, from the column color scales
+    fn cell_styles(&self) -> Vec<Vec<Option<String>>> {
+        let mut styles = vec![vec![None; self.headers.len()]; self.rows.len()];
+        for (column, header) in self.headers.iter().enumerate() {
+            let Some(&(_, scale)) = self.color_scales.iter().find(|(h, _)| h == header) else {
+                continue;
+            };
+            let values: Vec<Option<f64>> = self.rows.iter().map(|row| Self::cell_value(&row[column]).filter(|v| v.is_finite())).collect();
+            let (low, high) = values.iter().flatten().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &v| (low.min(v), high.max(v)));
+            for (row, value) in values.into_iter().enumerate() {
+                if let Some(value) = value {
+                    let position = if high > low { (value - low) / (high - low) } else { 0.5 };
+                    styles[row][column] = Some(background_style(&scale.color(position)));
+                }
+            }
+        }
+        styles
+    }
+
+    /// The background style of a row, from the first highlight rule it matches
+    fn row_style(&self, row: &[String]) -> Option<String> {
+        self.highlights.iter().find_map(|(header, condition, color)| {
+            let column = self.headers.iter().position(|h| h == header)?;
+            let value = Self::cell_value(&row[column])?;
+            condition.holds(value).then(|| format!("background-color: {};", color))
+        })
+    }
+
+    /// The number format of each column, if any
+    fn column_formats(&self) -> Vec<Option<Format>> {
+        self.headers.iter().map(|header| self.formats.iter().find(|(h, _)| h == header).map(|&(_, format)| format)).collect()
+    }
+
+    /// Sets a caption shown above the table.
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = Some(caption.to_string());
+        self
+    }
+
+    /// Sets the column headers.
+    pub fn headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a row, with one cell per header.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cell values, formatted with their `Display` implementation.
+    pub fn add_row<I, T>(self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        self.add_html_row(cells.into_iter().map(text_cell))
+    }
+
+    /// Adds a row of HTML cells (e.g. links), with one cell per header.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cell contents, see [`text_cell`] and [`link_cell`].
+    pub fn add_html_row<I>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = Markup>,
+    {
+        let row: Vec<String> = cells.into_iter().map(Markup::into_string).collect();
+        assert_eq!(row.len(), self.headers.len(), "Each row must have one cell per header");
+        self.rows.push(row);
+        self
+    }
+
+    /// Creates a table from records, one row per record, with the columns given by their
+    /// [`ReportTable`] implementation (usually derived with `#[derive(ReportTable)]`).
+    pub fn from_records<T: ReportTable>(records: &[T]) -> Self {
+        records
+            .iter()
+            .fold(Table::new().headers(T::headers()), |table, record| table.add_html_row(record.cells()))
+    }
+
+    /// Adds several rows, see [`Table::add_row`].
+    pub fn add_rows<R, I, T>(self, rows: R) -> Self
+    where
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        rows.into_iter().fold(self, |table, row| table.add_row(row))
+    }
+
+    /// The element ID of the table.
+    ///
+    /// Unless set with [`Table::id`], it is a placeholder until the report is rendered, replaced
+    /// along with every reference to it in the table's section.
+    pub fn table_id(&self) -> &str {
+        &self.id
+    }
+
+    /// The number of rows in the table.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The headers and cells of the table, serialized with the report definition
+    pub(crate) fn data(&self) -> BlockData {
+        BlockData::Table {
+            id: self.id.clone(),
+            caption: self.caption.clone(),
+            headers: self.headers.clone(),
+            rows: self.rows.clone(),
+        }
+    }
+}
+
+/// A table cell showing a value as text.
+pub fn text_cell<T: ToString>(value: T) -> Markup {
+    html! { (value.to_string()) }
+}
+
+/// A table cell linking to a URL.
+pub fn link_cell<T: ToString>(href: &str, text: T) -> Markup {
+    html! { a href=(href) { (text.to_string()) } }
+}
+
+/// Records shown as the rows of a table.
+///
+/// Usually derived with `#[derive(ReportTable)]` (`derive` feature), with `#[report(...)]` field
+/// attributes: `rename = "Column"`, `format = "{:.3}"` (a format string for the value),
+/// `link = "https://example.org/{}"` (a format string for the link target) and `skip`.
+///
+/// ```rust,ignore
+/// #[derive(ReportTable)]
+/// struct Protein {
+///     #[report(rename = "Accession", link = "https://www.uniprot.org/uniprot/{}")]
+///     accession: String,
+///     #[report(format = "{:.2e}")]
+///     q_value: f64,
+/// }
+///
+/// section.add_table(Table::from_records(&proteins));
+/// ```
+pub trait ReportTable {
+    /// The column headers.
+    fn headers() -> Vec<String>;
+
+    /// The cells of the record's row, one per header.
+    fn cells(&self) -> Vec<Markup>;
+}
+
+/// Records shown as a section of their own, listing each field and its value.
+///
+/// Usually derived with `#[derive(ReportSectionData)]` (`derive` feature), with the same field
+/// attributes as [`ReportTable`] and an optional `#[report(title = "...")]` on the struct
+/// (defaulting to the struct name).
+pub trait ReportSectionData: ReportTable {
+    /// The title of the section.
+    fn title(&self) -> String;
+
+    /// A section with a two-column "Field"/"Value" table of the record.
+    fn to_section(&self) -> crate::ReportSection {
+        let rows = Self::headers().into_iter().zip(self.cells()).map(|(field, value)| vec![text_cell(field), value]);
+        let table = rows.fold(Table::new().headers(["Field", "Value"]), |table, row| table.add_html_row(row));
+        let mut section = crate::ReportSection::new(&self.title());
+        section.add_table(table);
+        section
+    }
+}
+
+impl Component for Table {
+    fn render(&self) -> Markup {
+        let formats = self.column_formats();
+        let mut settings = self.options.settings();
+        let numeric: Vec<usize> = formats.iter().enumerate().filter(|(_, format)| format.is_some()).map(|(i, _)| i).collect();
+        if !numeric.is_empty() {
+            settings["columnDefs"] = json!([{ "targets": numeric, "type": "num" }]);
+        }
+        html! {
+            @if self.options.column_toggles {
+                div class="column-toggles" data-table=(self.id) {
+                    @for (i, header) in self.headers.iter().enumerate() {
+                        label { input type="checkbox" data-column=(i) checked; " " (header) }
+                    }
+                }
+            }
+            table class="display" id=(self.id) {
+                @if let Some(caption) = &self.caption {
+                    caption { (caption) }
+                }
+                thead {
+                    tr {
+                        @for header in &self.headers {
+                            th { (header) }
+                        }
+                    }
+                }
+                tbody {
+                    @for (row, row_styles) in self.rows.iter().zip(self.cell_styles()) {
+                        tr style=[self.row_style(row)] {
+                            @for ((cell, format), style) in row.iter().zip(&formats).zip(row_styles) {
+                                @match (format, Self::cell_value(cell)) {
+                                    (Some(format), Some(value)) => td data-order=(value) style=[style] { (format.apply(value)) },
+                                    _ => td style=[style] { (PreEscaped(cell)) },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            script {
+                (PreEscaped(format!(r#"
+                    jQuery(function($) {{
+                        const table = $('#{id}').DataTable({settings});
+                        if (window.addExpandButton) {{
+                            window.addExpandButton(table.table().container());
+                        }}
+                        const announce = function(message) {{
+                            if (window.announceStatus) {{
+                                window.announceStatus(message);
+                            }}
+                        }};
+                        table.on('search.dt', function() {{
+                            const rows = table.rows({{ search: 'applied' }}).count();
+                            announce(rows + (rows === 1 ? ' row matches' : ' rows match'));
+                        }});
+                        $('.column-toggles[data-table="{id}"] input').on('change', function() {{
+                            table.column($(this).data('column')).visible(this.checked);
+                            announce('Column ' + $(this).parent().text().trim() + (this.checked ? ' shown' : ' hidden'));
+                        }});
+                    }});
+                "#, id = self.id, settings = settings)))
+            }
+        }
+    }
+
+    fn libraries(&self) -> Vec<Library> {
+        vec![
+            Library::JQuery,
+            Library::DataTables,
+            Library::DataTablesStyle,
+            Library::ColResize,
+            Library::ColResizeStyle,
+        ]
+    }
+}
+
+/// Convert an error reading a delimited file, see [`Table::from_csv_path`]
+#[cfg(feature = "csv")]
+fn csv_error(e: csv::Error) -> ReportError {
+    if e.is_io_error() {
+        ReportError::Io(e.into())
+    } else {
+        ReportError::InvalidArgument(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_render() {
+        let table = Table::new()
+            .id("scores")
+            .headers(["Run", "Score"])
+            .add_row(["run1", "0.9"])
+            .add_rows(vec![vec!["run2".to_string(), 0.8.to_string()]]);
+        assert_eq!(table.len(), 2);
+
+        let html = table.render().into_string();
+        assert!(html.contains(r#"<table class="display" id="scores">"#));
+        assert!(html.contains("<td>run2</td><td>0.8</td>"));
+        assert!(html.contains("$('#scores').DataTable("));
+        assert!(html.contains("announce(rows + (rows === 1 ? ' row matches' : ' rows match'));"));
+        assert_ne!(Table::new().table_id(), Table::new().table_id());
+    }
+
+    #[test]
+    fn test_table_options() {
+        let html = Table::new().id("scores").headers(["Run", "Score"]).render().into_string();
+        assert!(html.contains(r#"DataTable({"paging":true,"pageLength":10,"searching":true,"ordering":true,"order":[],"scrollX":true,"autoWidth":false,"colResize":{"enable":true,"resizeTable":true}})"#));
+        assert!(!html.contains(r#"<div class="column-toggles""#));
+
+        let options = TableOptions {
+            page_length: 25,
+            order: Some((1, SortDirection::Descending)),
+            fixed_header: Some(400),
+            column_toggles: true,
+            ..Default::default()
+        };
+        let html = Table::new().id("scores").headers(["Run", "Score"]).options(options).render().into_string();
+        assert!(html.contains(r#""order":[[1,"desc"]]"#) && html.contains(r#""pageLength":25"#));
+        assert!(html.contains(r#""scrollY":"400px","scrollCollapse":true"#));
+        assert!(html.contains(r#"<div class="column-toggles" data-table="scores"><label><input type="checkbox" data-column="0" checked> Run</label>"#));
+    }
+
+    #[test]
+    fn test_col_format() {
+        let table = Table::new()
+            .id("psms")
+            .headers(["Peptide", "q-value", "Intensity", "Coverage"])
+            .col_format("q-value", Format::Scientific(2))
+            .col_format("Intensity", Format::Thousands)
+            .col_format("Coverage", Format::Percent(1))
+            .add_row(["PEPTIDE", "0.0000123", "-1234567.8", "0.1234"])
+            .add_row(["PEPTIDER", "n/a", "999", "1"]);
+
+        let html = table.render().into_string();
+        assert!(html.contains(r#"<td>PEPTIDE</td><td data-order="0.0000123">1.23e-5</td><td data-order="-1234567.8">-1,234,568</td><td data-order="0.1234">12.3%</td>"#));
+        assert!(html.contains(r#"<td>n/a</td><td data-order="999">999</td><td data-order="1">100.0%</td>"#));
+        assert!(html.contains(r#""columnDefs":[{"targets":[1,2,3],"type":"num"}]"#));
+        assert_eq!(Format::Thousands.apply(-0.4), "0");
+        assert_eq!(Format::Fixed(2).apply(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn test_color_scale_and_highlights() {
+        assert_eq!(ColorScale::RdYlGn.color(0.0), "#fc8d59");
+        assert_eq!(ColorScale::RdYlGn.color(0.75), "#c8e790");
+        assert_eq!(background_style(&ColorScale::Viridis.color(0.0)), "background-color: #440154; color: #fff;");
+
+        let table = Table::new()
+            .headers(["Run", "Score", "q-value"])
+            .col_color_scale("Score", ColorScale::RdYlGn)
+            .highlight_rows("q-value", Condition::Below(0.01), "#d4edda")
+            .add_row(["run1", "1", "0.001"])
+            .add_row(["run2", "3", "0.2"])
+            .add_row(["run3", "failed", "n/a"]);
+
+        let html = table.render().into_string();
+        assert!(html.contains(r#"<tr style="background-color: #d4edda;"><td>run1</td><td style="background-color: #fc8d59;">1</td>"#));
+        assert!(html.contains(r#"<tr><td>run2</td><td style="background-color: #91cf60;">3</td>"#));
+        assert!(html.contains("<tr><td>run3</td><td>failed</td>"));
+    }
+
+    #[test]
+    fn test_from_serializable() {
+        #[derive(Serialize)]
+        struct Row {
+            sample: &'static str,
+            score: f64,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            note: Option<&'static str>,
+        }
+
+        let rows = [
+            Row { sample: "A", score: 0.5, note: None },
+            Row { sample: "B", score: 1.0, note: Some("outlier") },
+        ];
+        let table = Table::from_serializable(&rows).unwrap();
+        assert_eq!(table.headers, vec!["sample", "score", "note"]);
+        assert_eq!(table.rows[0], vec!["A", "0.5", ""]);
+        assert_eq!(table.rows[1], vec!["B", "1.0", "outlier"]);
+
+        assert!(Table::from_serializable(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_derive_report_table() {
+        use report_builder_derive::{ReportSectionData, ReportTable};
+
+        #[derive(ReportTable, ReportSectionData)]
+        #[report(title = "Proteins")]
+        struct Protein {
+            #[report(rename = "Accession", link = "https://www.uniprot.org/uniprot/{}")]
+            accession: &'static str,
+            #[report(format = "{:.2}")]
+            score: f64,
+            #[report(skip)]
+            #[allow(dead_code)]
+            peptides: Vec<String>,
+        }
+
+        let proteins = [Protein { accession: "P<1>", score: 0.123, peptides: Vec::new() }];
+        let table = Table::from_records(&proteins);
+        assert_eq!(table.headers, vec!["Accession", "score"]);
+        assert_eq!(table.rows[0], vec![r#"<a href="https://www.uniprot.org/uniprot/P&lt;1&gt;">P&lt;1&gt;</a>"#, "0.12"]);
+
+        let html = proteins[0].to_section().render_to_string();
+        assert!(html.contains("<h2>Proteins</h2>"));
+        assert!(html.contains("<td>score</td><td>0.12</td>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Each row must have one cell per header")]
+    fn test_table_row_length() {
+        let _ = Table::new().headers(["Run", "Score"]).add_row(["run1"]);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_from_csv_path() {
+        let path = std::env::temp_dir().join(format!("report-builder-table-{}.tsv", std::process::id()));
+        std::fs::write(&path, "run\tscore\tnote\r\nrun1\t0.9\t\"a\tb\"\n\nrun2\t0.8\t\"say \"\"hi\"\"\nthere\"\nrun3\t0.7\t\n").unwrap();
+        let table = Table::from_csv_path(&path, '\t', None).unwrap();
+        assert_eq!(table.headers, vec!["run", "score", "note"]);
+        assert_eq!(table.rows[0], vec!["run1", "0.9", "a\tb"]);
+        assert_eq!(table.rows[1], vec!["run2", "0.8", "say &quot;hi&quot;\nthere"]);
+        assert_eq!(Table::from_csv_path(&path, '\t', Some(1)).unwrap().len(), 1);
+        assert!(matches!(Table::from_csv_path(&path, '§', None), Err(ReportError::InvalidArgument(_))));
+
+        std::fs::write(&path, "1,2\n3,4,5\n").unwrap();
+        let error = Table::from_csv_path(&path, ',', None).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid argument: row 2 has 3 fields, expected 2");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+//! Reusable report skeletons, so every tool of a suite lays out its reports the same way.
+//!
+//! A [`ReportTemplate`] defines named slots in a fixed order, each with a description of what
+//! belongs there. Callers fill the slots with sections as their results become available, and
+//! slots left empty render a standard "not available" panel with the description:
+//!
+//! ```rust,ignore
+//! use report_builder::template::ReportTemplate;
+//!
+//! let mut template = ReportTemplate::new()
+//!     .slot("Summary", "Key results of the run.")
+//!     .slot("QC", "Quality control metrics of each raw file.")
+//!     .slot("Results", "Identified and quantified peptides.")
+//!     .slot("Parameters", "The search parameters.");
+//! template.fill("QC", qc_section)?;
+//! report.apply_template(template);
+//! ```
+//!
+//! Templates serialize to JSON without their filled sections, so a suite can share one definition.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use maud::html;
+use serde::{Deserialize, Serialize};
+
+use crate::{Report, ReportError, ReportSection};
+
+/// A named place for a section in a [`ReportTemplate`].
+#[derive(Serialize, Deserialize)]
+struct Slot {
+    title: String,
+    description: String,
+    #[serde(skip)]
+    section: Option<ReportSection>,
+}
+
+impl Slot {
+    /// The slot's section, or the "not available" panel if it was not filled
+    fn into_section(self) -> ReportSection {
+        if let Some(section) = self.section {
+            return section;
+        }
+        let mut section = ReportSection::new(&self.title);
+        section.add_content(html! {
+            div class="slot-unavailable" role="note" {
+                p class="slot-unavailable-title" { "Not available" }
+                @if !self.description.is_empty() {
+                    p { (self.description) }
+                }
+            }
+        });
+        section
+    }
+}
+
+/// A report skeleton of named slots, filled with sections, see the [module documentation](self).
+#[derive(Default, Serialize, Deserialize)]
+pub struct ReportTemplate {
+    slots: Vec<Slot>,
+}
+
+impl ReportTemplate {
+    /// Creates a template without slots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a slot after the existing ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The name of the slot, used as the title of its section.
+    /// * `description` - What belongs in the slot, shown when it is not filled.
+    pub fn slot(mut self, title: &str, description: &str) -> Self {
+        self.slots.push(Slot {
+            title: title.to_string(),
+            description: description.to_string(),
+            section: None,
+        });
+        self
+    }
+
+    /// Fills a slot with a section, replacing any section it was filled with before.
+    ///
+    /// The section takes the title of the slot, so reports built from the template share their
+    /// section titles.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - The name of the slot.
+    /// * `section` - The section to place in the slot.
+    ///
+    /// # Returns
+    ///
+    /// An error if the template has no slot with the name.
+    pub fn fill(&mut self, slot: &str, mut section: ReportSection) -> Result<(), ReportError> {
+        let Some(target) = self.slots.iter_mut().find(|s| s.title == slot) else {
+            return Err(ReportError::InvalidArgument(format!("The template has no slot named '{}'", slot)));
+        };
+        section.title = target.title.clone();
+        target.section = Some(section);
+        Ok(())
+    }
+
+    /// The names of the slots, in order.
+    pub fn slots(&self) -> impl Iterator<Item = &str> {
+        self.slots.iter().map(|slot| slot.title.as_str())
+    }
+
+    /// Whether a slot has been filled; `false` for unknown slots.
+    pub fn is_filled(&self, slot: &str) -> bool {
+        self.slots.iter().any(|s| s.title == slot && s.section.is_some())
+    }
+
+    /// The sections of the slots in order, with a "not available" panel for each slot not filled.
+    pub fn into_sections(self) -> Vec<ReportSection> {
+        self.slots.into_iter().map(Slot::into_section).collect()
+    }
+}
+
+impl Report {
+    /// Adds the sections of a template, in the order of its slots, see
+    /// [`ReportTemplate::into_sections`].
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The filled template.
+    pub fn apply_template(&mut self, template: ReportTemplate) {
+        for section in template.into_sections() {
+            self.add_section(section);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_template() {
+        let mut template = ReportTemplate::new()
+            .slot("Summary", "Key results of the run.")
+            .slot("QC", "Quality control metrics of each raw file.")
+            .slot("Parameters", "");
+        let mut qc = ReportSection::new("QC metrics");
+        qc.add_content(html! { p { "All files passed." } });
+        template.fill("QC", qc).unwrap();
+        assert!(template.fill("Results", ReportSection::new("Results")).is_err());
+        assert!(template.is_filled("QC") && !template.is_filled("Summary"));
+
+        let json = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            json,
+            r#"{"slots":[{"title":"Summary","description":"Key results of the run."},{"title":"QC","description":"Quality control metrics of each raw file."},{"title":"Parameters","description":""}]}"#
+        );
+        let loaded: ReportTemplate = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.slots().collect::<Vec<_>>(), ["Summary", "QC", "Parameters"]);
+
+        let mut report = Report::new("Redeem", "1.0", None, "My Report");
+        report.apply_template(template);
+        assert_eq!(report.sections.iter().map(|s| s.title.as_str()).collect::<Vec<_>>(), ["Summary", "QC", "Parameters"]);
+        assert!(report.sections[1].render_to_string().contains("<p>All files passed.</p>"));
+        assert!(report.sections[0].render_to_string().contains(
+            r#"<div class="slot-unavailable" role="note"><p class="slot-unavailable-title">Not available</p><p>Key results of the run.</p></div>"#
+        ));
+        assert!(!report.sections[2].render_to_string().contains("<p></p>"));
+    }
+}
@@ -0,0 +1,102 @@
+//! Deterministic output for snapshot tests.
+//!
+//! Element IDs depend only on the content of a report, but reports are stamped with the time they
+//! are rendered, so the same report never renders twice to the same HTML. In deterministic mode
+//! the timestamp is fixed to [`FIXED_TIMESTAMP`], so downstream crates can compare rendered reports
+//! against stored snapshots:
+//!
+//! ```rust,ignore
+//! use report_builder::testing;
+//!
+//! let html = testing::snapshot(|| {
+//!     let mut report = Report::new("Redeem", "1.0", None, "QC");
+//!     report.add_section(qc_section(&run));
+//!     report
+//! });
+//! insta::assert_snapshot!(html);
+//! ```
+//!
+//! The mode applies to the current thread only, so tests running in parallel do not affect each
+//! other.
+
+use std::cell::Cell;
+
+use chrono::{DateTime, FixedOffset, Local};
+
+use crate::Report;
+
+/// The time reports are stamped with in deterministic mode, 2000-01-01 00:00:00 UTC, in seconds
+/// since the Unix epoch
+pub const FIXED_TIMESTAMP: i64 = 946_684_800;
+
+thread_local! {
+    /// Whether the current thread is in deterministic mode
+    static DETERMINISTIC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the mode of the thread when a deterministic run ends, even if it panics
+struct Restore(bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        DETERMINISTIC.with(|mode| mode.set(self.0));
+    }
+}
+
+/// Runs a closure in deterministic mode on the current thread, see the [module documentation](self).
+pub fn deterministic<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(DETERMINISTIC.with(|mode| mode.replace(true)));
+    f()
+}
+
+/// Builds a report and renders it to HTML in deterministic mode, ready to compare against a
+/// stored snapshot.
+///
+/// # Arguments
+///
+/// * `build` - Builds the report, including its plots and tables.
+pub fn snapshot(build: impl FnOnce() -> Report) -> String {
+    deterministic(|| build().render_to_string())
+}
+
+/// Whether the current thread is in deterministic mode.
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.with(|mode| mode.get())
+}
+
+/// The current local time, or [`FIXED_TIMESTAMP`] in UTC in deterministic mode
+pub(crate) fn now() -> DateTime<FixedOffset> {
+    match is_deterministic() {
+        true => DateTime::from_timestamp(FIXED_TIMESTAMP, 0).unwrap_or_default().fixed_offset(),
+        false => Local::now().fixed_offset(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReportSection;
+
+    #[test]
+    fn test_snapshot() {
+        let build = || {
+            let mut section = ReportSection::new("Summary");
+            section.add_content(maud::html! { p { "All runs passed." } });
+            #[cfg(feature = "tables")]
+            section.add_table(crate::table::Table::new().headers(["Run"]).add_row(["run1"]));
+            #[cfg(feature = "plots")]
+            section.add_plot(plotly::Plot::new());
+            let mut report = Report::new("Redeem", "1.0", None, "My Report");
+            report.add_section(section);
+            report
+        };
+        let html = snapshot(build);
+        assert_eq!(html, snapshot(build));
+        assert!(html.contains("Generated on: 2000-01-01 00:00:00"));
+        #[cfg(feature = "tables")]
+        assert!(html.contains(r#"id="table-summary-1""#));
+
+        assert!(!is_deterministic());
+        assert_ne!(build().render_to_string(), html);
+    }
+}
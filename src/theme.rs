@@ -0,0 +1,188 @@
+//! Colors and fonts of the report, with an optional light/dark toggle.
+//!
+//! A [`Theme`] is rendered as CSS custom properties, so the report's own styles (banner, tabs,
+//! tables) and any custom CSS can refer to them, e.g. `color: var(--primary-color)`.
+//!
+//! ```rust,ignore
+//! use report_builder::{theme::Theme, Report};
+//!
+//! let report = Report::new("Redeem", "1.0", None, "Run QC")
+//!     .with_theme(Theme { primary_color: "#2e7d32".to_string(), ..Theme::default() })
+//!     .with_dark_mode_toggle(true);
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use maud::{html, Markup, PreEscaped};
+#[cfg(feature = "plots")]
+use plotly::layout::themes::{PLOTLY_DARK, PLOTLY_WHITE};
+use serde::{Deserialize, Serialize};
+
+/// The Plotly template applied to the report's plots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlotTemplate {
+    /// Keep each plot's own template (e.g. from [`PlotDefaults`](crate::plots::defaults::PlotDefaults))
+    #[default]
+    Inherit,
+    /// Plotly's "plotly_white" template
+    PlotlyWhite,
+    /// Plotly's "plotly_dark" template
+    PlotlyDark,
+}
+
+impl PlotTemplate {
+    /// The template as JSON, or `null` to keep each plot's own template
+    #[cfg(feature = "plots")]
+    fn to_json(self) -> String {
+        let template = match self {
+            PlotTemplate::Inherit => return "null".to_string(),
+            PlotTemplate::PlotlyWhite => &*PLOTLY_WHITE,
+            PlotTemplate::PlotlyDark => &*PLOTLY_DARK,
+        };
+        serde_json::to_string(template).unwrap_or_else(|_| "null".to_string())
+    }
+
+    /// Without the `plots` feature there are no plots to apply a template to
+    #[cfg(not(feature = "plots"))]
+    fn to_json(self) -> String {
+        "null".to_string()
+    }
+}
+
+/// The colors and fonts of a report. All colors are CSS colors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    /// The color of active tabs, links and highlights
+    pub primary_color: String,
+    /// The start and end colors of the banner's gradient
+    pub banner_gradient: [String; 2],
+    pub font_family: String,
+    pub background_color: String,
+    pub text_color: String,
+    /// The background colors of odd and even table rows
+    pub table_stripe_colors: [String; 2],
+    pub plot_template: PlotTemplate,
+}
+
+impl Default for Theme {
+    /// The light theme the report has always used.
+    fn default() -> Self {
+        Theme {
+            primary_color: "#007bff".to_string(),
+            banner_gradient: ["#4a90e2".to_string(), "#145da0".to_string()],
+            font_family: "Arial, sans-serif".to_string(),
+            background_color: "#ffffff".to_string(),
+            text_color: "#222222".to_string(),
+            table_stripe_colors: ["#f9f9f9".to_string(), "#ffffff".to_string()],
+            plot_template: PlotTemplate::Inherit,
+        }
+    }
+}
+
+impl Theme {
+    /// The built-in light theme, see [`Theme::default`].
+    pub fn light() -> Self {
+        Self::default()
+    }
+
+    /// The built-in dark theme, with Plotly's "plotly_dark" template.
+    pub fn dark() -> Self {
+        Theme {
+            primary_color: "#4dabf7".to_string(),
+            banner_gradient: ["#1e3a5f".to_string(), "#0b1d33".to_string()],
+            font_family: "Arial, sans-serif".to_string(),
+            background_color: "#121212".to_string(),
+            text_color: "#e0e0e0".to_string(),
+            table_stripe_colors: ["#1e1e1e".to_string(), "#262626".to_string()],
+            plot_template: PlotTemplate::PlotlyDark,
+        }
+    }
+
+    /// The theme as CSS custom properties
+    fn css_variables(&self) -> String {
+        format!(
+            "--primary-color: {}; --banner-start: {}; --banner-end: {}; --font-family: {}; \
+             --background-color: {}; --text-color: {}; --table-stripe-odd: {}; --table-stripe-even: {};",
+            self.primary_color,
+            self.banner_gradient[0],
+            self.banner_gradient[1],
+            self.font_family,
+            self.background_color,
+            self.text_color,
+            self.table_stripe_colors[0],
+            self.table_stripe_colors[1],
+        )
+    }
+}
+
+/// Render the theme's CSS custom properties and, with a toggle, the dark theme's under
+/// `body.dark-mode` plus the script switching between them (and the plots' templates)
+pub(crate) fn render_theme(theme: &Theme, dark_mode_toggle: bool) -> Markup {
+    let dark = Theme::dark();
+    html! {
+        style {
+            (PreEscaped(format!(":root {{ {} }}", theme.css_variables())))
+            @if dark_mode_toggle {
+                (PreEscaped(format!(" body.dark-mode {{ {} }}", dark.css_variables())))
+            }
+        }
+        script {
+            (PreEscaped(format!(r#"
+                const plotTemplates = {{ light: {}, dark: {} }};
+
+                function applyPlotTemplates(mode) {{
+                    if (!window.Plotly) {{
+                        return;
+                    }}
+                    document.querySelectorAll('.js-plotly-plot').forEach(function(plot) {{
+                        if (plot.originalTemplate === undefined) {{
+                            plot.originalTemplate = plot.layout.template || null;
+                        }}
+                        let template = plotTemplates[mode] || plot.originalTemplate;
+                        Plotly.relayout(plot, {{ template: template }});
+                    }});
+                }}
+
+                function setDarkMode(dark) {{
+                    document.body.classList.toggle('dark-mode', dark);
+                    try {{
+                        localStorage.setItem('report-dark-mode', dark ? '1' : '0');
+                    }} catch (e) {{}}
+                    applyPlotTemplates(dark ? 'dark' : 'light');
+                }}
+
+                window.addEventListener('load', function() {{
+                    let dark = false;
+                    try {{
+                        dark = localStorage.getItem('report-dark-mode') === '1';
+                    }} catch (e) {{}}
+                    if (dark && document.querySelector('.theme-toggle')) {{
+                        setDarkMode(true);
+                    }} else if (plotTemplates.light) {{
+                        applyPlotTemplates('light');
+                    }}
+                }});
+            "#, theme.plot_template.to_json(), if dark_mode_toggle { dark.plot_template.to_json() } else { "null".to_string() })))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_theme() {
+        let css = render_theme(&Theme::default(), false).into_string();
+        assert!(css.contains("--primary-color: #007bff;"));
+        assert!(!css.contains("body.dark-mode"));
+        assert!(css.contains("const plotTemplates = { light: null, dark: null };"));
+
+        let css = render_theme(&Theme::dark(), true).into_string();
+        assert!(css.contains(":root { --primary-color: #4dabf7;"));
+        assert!(css.contains("body.dark-mode { --primary-color: #4dabf7;"));
+        #[cfg(feature = "plots")]
+        assert!(!css.contains("light: null"));
+    }
+}
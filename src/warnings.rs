@@ -0,0 +1,172 @@
+//! Collection of non-fatal issues encountered while building a report.
+//!
+//! Anything that silently alters what ends up in the report (values Plotly will not draw,
+//! truncated tables, downsampled plots, assets that cannot be found) is recorded in a
+//! [`ReportBuilderLog`]. The collected warnings are rendered in a "Build warnings" appendix and
+//! returned from [`Report::save_to_file`](crate::Report::save_to_file).
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use maud::{html, Markup};
+use serde::{Deserialize, Serialize};
+
+/// The category of a build warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarningKind {
+    /// Non-finite values (NaN/inf) were dropped or will not be drawn.
+    DroppedNaN,
+    /// A table was truncated to a maximum number of rows.
+    TruncatedTable,
+    /// A plot was downsampled or aggregated to reduce its size.
+    DownsampledPlot,
+    /// A referenced asset (image, file) could not be found.
+    MissingAsset,
+    /// Any other non-fatal issue.
+    Other,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            WarningKind::DroppedNaN => "Dropped NaN",
+            WarningKind::TruncatedTable => "Truncated table",
+            WarningKind::DownsampledPlot => "Downsampled plot",
+            WarningKind::MissingAsset => "Missing asset",
+            WarningKind::Other => "Other",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A single non-fatal issue recorded while building a report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildWarning {
+    pub kind: WarningKind,
+    pub message: String,
+    /// The title of the section the warning was raised in, if any.
+    pub section: Option<String>,
+}
+
+impl fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.section {
+            Some(ref section) => write!(f, "[{}] {}: {}", self.kind, section, self.message),
+            None => write!(f, "[{}] {}", self.kind, self.message),
+        }
+    }
+}
+
+/// Collects the non-fatal issues raised while building a report or section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportBuilderLog {
+    warnings: Vec<BuildWarning>,
+}
+
+impl ReportBuilderLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The category of the warning.
+    /// * `message` - A description of what was changed or could not be done.
+    pub fn warn(&mut self, kind: WarningKind, message: &str) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(kind = %kind, "{}", message);
+        self.warnings.push(BuildWarning {
+            kind,
+            message: message.to_string(),
+            section: None,
+        });
+    }
+
+    /// Returns the recorded warnings, in the order they were raised.
+    pub fn warnings(&self) -> &[BuildWarning] {
+        &self.warnings
+    }
+
+    /// Returns true if no warnings were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Moves all warnings from another log into this one, tagging those without a section.
+    pub(crate) fn absorb(&mut self, other: &mut ReportBuilderLog, section: &str) {
+        for mut warning in other.warnings.drain(..) {
+            if warning.section.is_none() {
+                warning.section = Some(section.to_string());
+            }
+            self.warnings.push(warning);
+        }
+    }
+}
+
+/// Render the "Build warnings" appendix listing the given warnings.
+pub(crate) fn render_warnings(warnings: &[BuildWarning]) -> Markup {
+    html! {
+        div class="build-warnings" {
+            h2 { "Build warnings" }
+            p { "The following non-fatal issues were encountered while building this report." }
+            table {
+                thead {
+                    tr {
+                        th { "Kind" }
+                        th { "Section" }
+                        th { "Message" }
+                    }
+                }
+                tbody {
+                    @for warning in warnings {
+                        tr {
+                            td { (warning.kind) }
+                            td { (warning.section.as_deref().unwrap_or("-")) }
+                            td { (warning.message) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Count the non-finite values in the `x`/`y`/`z` arrays of a serialized Plotly figure.
+///
+/// Plotly serializes NaN and infinite values as `null`, which are then silently skipped when the
+/// figure is drawn.
+#[cfg(feature = "plots")]
+pub(crate) fn count_plot_nulls(plot_json: &serde_json::Value) -> usize {
+    let Some(traces) = plot_json["data"].as_array() else {
+        return 0;
+    };
+    traces
+        .iter()
+        .flat_map(|trace| ["x", "y", "z"].into_iter().filter_map(move |key| trace[key].as_array()))
+        .map(|values| values.iter().filter(|v| v.is_null()).count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absorb_tags_section() {
+        let mut section_log = ReportBuilderLog::new();
+        section_log.warn(WarningKind::TruncatedTable, "Kept first 1000 of 5000 rows");
+
+        let mut report_log = ReportBuilderLog::new();
+        report_log.absorb(&mut section_log, "Results");
+
+        assert!(section_log.is_empty());
+        assert_eq!(
+            report_log.warnings()[0].to_string(),
+            "[Truncated table] Results: Kept first 1000 of 5000 rows"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Streaming output of reports too large to hold in memory.
+//!
+//! A [`ReportWriter`] writes the head and banner of a report as soon as it is created, then each
+//! section as it is finished, so a long-running pipeline can drop its plots once they are written
+//! instead of keeping hundreds of them around until the end:
+//!
+//! ```rust,ignore
+//! use report_builder::writer::ReportWriter;
+//!
+//! let report = Report::new("Redeem", "1.0", None, "Cohort QC");
+//! let mut writer = ReportWriter::create(report, "cohort.html")?;
+//! for run in runs {
+//!     writer.write_section(qc_section(&run))?;
+//! }
+//! let warnings = writer.finalize()?;
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use maud::{html, PreEscaped};
+
+use crate::assets::{Asset, AssetMode};
+use crate::anchors::Anchors;
+use crate::appendix;
+use crate::error::ReportError;
+use crate::ids::ElementIds;
+use crate::numbering::{self, RefTarget};
+use crate::warnings::{self, BuildWarning};
+use crate::{Report, ReportSection};
+
+/// Writes a report to a file section by section, see the [module documentation](self).
+///
+/// The report passed to the writer provides everything above the sections (title, banner,
+/// metadata, theme, custom CSS and JavaScript); any sections it already holds are written first.
+/// Since later sections are not known when the page starts, every component asset is included
+/// up front and the report has no table of contents. With
+/// [section numbering](Report::with_section_numbering), cross-references are labelled with the
+/// numbers of the sections written so far; references to later sections keep their titles.
+pub struct ReportWriter {
+    report: Report,
+    path: PathBuf,
+    out: BufWriter<File>,
+    titles: Vec<String>,
+    anchors: Anchors,
+    targets: BTreeMap<String, RefTarget>,
+    /// The number of appendices written so far, lettering the next one
+    appendices: usize,
+    ids: ElementIds,
+}
+
+impl ReportWriter {
+    /// Creates the output file and writes the head and banner of the report, referencing the
+    /// third-party libraries on their CDNs.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The report providing the head and banner.
+    /// * `filename` - The name of the file to write the report to.
+    ///
+    /// # Returns
+    ///
+    /// The writer, or [`ReportError::Io`] if the file cannot be created or written.
+    pub fn create<P: AsRef<Path>>(report: Report, filename: P) -> Result<Self, ReportError> {
+        Self::create_with_assets(report, filename, &AssetMode::Cdn)
+    }
+
+    /// Creates the output file and writes the head and banner of the report, including the
+    /// third-party libraries as given.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The report providing the head and banner.
+    /// * `filename` - The name of the file to write the report to.
+    /// * `asset_mode` - Whether to reference the libraries on CDNs or embed them.
+    ///
+    /// # Returns
+    ///
+    /// The writer, or [`ReportError::Io`] if a library cannot be loaded or the file cannot be
+    /// created or written.
+    pub fn create_with_assets<P: AsRef<Path>>(mut report: Report, filename: P, asset_mode: &AssetMode) -> Result<Self, ReportError> {
+        let libraries = asset_mode.render_libraries()?;
+        let assets: BTreeSet<Asset> = Asset::ALL.into_iter().collect();
+        let sections = std::mem::take(&mut report.sections);
+
+        let mut writer = ReportWriter {
+            out: BufWriter::new(File::create(filename.as_ref())?),
+            path: filename.as_ref().to_path_buf(),
+            titles: Vec::new(),
+            anchors: Anchors::default(),
+            targets: BTreeMap::new(),
+            appendices: 0,
+            ids: ElementIds::default(),
+            report,
+        };
+        let output_dir = writer.path.parent().unwrap_or(Path::new(""));
+        let images = writer.report.embed_logos(output_dir);
+        let start = html! {
+            (maud::DOCTYPE)
+            (PreEscaped("<html><head>"))
+            (writer.report.render_head(libraries, &assets))
+            (PreEscaped("</head><body>"))
+            (writer.report.render_top(&images))
+            // Filled with one button per section by `finalize`
+            div class="tabs" id="report-tabs" {}
+        };
+        writer.out.write_all(start.into_string().as_bytes())?;
+        for section in sections {
+            writer.write_section(section)?;
+        }
+        Ok(writer)
+    }
+
+    /// Writes a section to the file, as the next tab of the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `section` - The section to write; its warnings are kept for the "Build warnings" tab.
+    ///
+    /// # Returns
+    ///
+    /// A Result indicating success or [`ReportError::Io`] if the file cannot be written.
+    pub fn write_section(&mut self, mut section: ReportSection) -> Result<(), ReportError> {
+        let i = self.titles.len();
+        let number = match section.is_appendix() {
+            true => appendix::appendix_letter(self.appendices),
+            false => (i - self.appendices + 1).to_string(),
+        };
+        let anchors = self.anchors.section(&section, i + 1);
+        section.reference_targets(0, &number, &anchors, self.report.numbering.as_ref(), &mut self.targets);
+        let mut rendered = section.render_at(0, &number, &anchors, self.report.numbering.as_ref()).into_string();
+        rendered = self.ids.assign(&rendered, &anchors.anchor);
+        rendered = numbering::resolve_references(&rendered, &self.targets);
+        let markup = html! {
+            div id=(format!("tab{}", i)) class={@if i == 0 { "tab-content active" } @else { "tab-content" }} {
+                (PreEscaped(rendered))
+            }
+        };
+        self.out.write_all(markup.into_string().as_bytes())?;
+        self.report.log.absorb(&mut section.log, &section.title);
+        if section.is_appendix() {
+            self.titles.push(format!("Appendix {}: {}", number, section.title));
+            self.appendices += 1;
+        } else {
+            self.titles.push(section.title);
+        }
+        Ok(())
+    }
+
+    /// The number of sections written so far.
+    pub fn sections_written(&self) -> usize {
+        self.titles.len()
+    }
+
+    /// Writes the tab bar, the "Build warnings" tab and the closing scripts, and flushes the file.
+    ///
+    /// # Returns
+    ///
+    /// All non-fatal issues raised while building and saving the report, or [`ReportError::Io`].
+    pub fn finalize(mut self) -> Result<Vec<BuildWarning>, ReportError> {
+        let output_dir = self.path.parent().unwrap_or(Path::new(""));
+        let mut build_warnings = self.report.log.warnings().to_vec();
+        build_warnings.extend(self.report.check_assets(output_dir));
+
+        let end = html! {
+            @if !build_warnings.is_empty() {
+                div id="tab-warnings" class="tab-content" {
+                    (warnings::render_warnings(&build_warnings))
+                }
+            }
+            template id="report-tab-buttons" {
+                @for (i, title) in self.titles.iter().enumerate() {
+                    button class="tab" data-tab=(format!("tab{}", i)) onclick=(format!("showTab('tab{}')", i)) { (title) }
+                }
+                @if !build_warnings.is_empty() {
+                    button class="tab" data-tab="tab-warnings" onclick="showTab('tab-warnings')" {
+                        "Build warnings (" (build_warnings.len()) ")"
+                    }
+                }
+            }
+            script {
+                (PreEscaped("document.getElementById('report-tabs').appendChild(document.getElementById('report-tab-buttons').content);"))
+            }
+            (self.report.render_scripts())
+            (PreEscaped("</body></html>"))
+        };
+        self.out.write_all(end.into_string().as_bytes())?;
+        self.out.flush()?;
+        Ok(build_warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_writer() {
+        let path = std::env::temp_dir().join(format!("report-writer-{}.html", std::process::id()));
+        let mut report = Report::new("Redeem", "1.0", None, "Streamed");
+        report.add_section(ReportSection::new("First"));
+
+        let mut writer = ReportWriter::create(report, &path).unwrap();
+        let mut section = ReportSection::new("Second");
+        section.warn(crate::warnings::WarningKind::DroppedNaN, "1 value");
+        writer.write_section(section).unwrap();
+        writer.write_section(ReportSection::new("First")).unwrap();
+        assert_eq!(writer.sections_written(), 3);
+        let warnings = writer.finalize().unwrap();
+
+        let html = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(html.starts_with("<!DOCTYPE html><html><head><title>Streamed</title>"));
+        assert!(html.contains(r#"<div id="tab0" class="tab-content active">"#));
+        assert!(html.contains(r#"<div id="first"><h2>First</h2>"#) && html.contains(r#"<div id="first-2"><h2>First</h2>"#));
+        assert!(html.contains(r#"<button class="tab" data-tab="tab1" onclick="showTab('tab1')">Second</button>"#));
+        assert!(html.contains("Build warnings (1)"));
+        assert!(html.ends_with("</body></html>"));
+    }
+}